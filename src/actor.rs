@@ -0,0 +1,69 @@
+//! Identifies who is running rblxsync, for "last synced by" tracking.
+//!
+//! Resolution order: `RBLXSYNC_ACTOR`, then CI metadata (GitHub Actions,
+//! GitLab CI, or a generic `CI` flag), then the OS username.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Actor string for the current process environment
+pub fn current() -> String {
+    detect(|key| std::env::var(key).ok().filter(|v| !v.is_empty()))
+}
+
+/// Seconds since the Unix epoch, for stamping state entries
+pub fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+fn detect(env: impl Fn(&str) -> Option<String>) -> String {
+    if let Some(actor) = env("RBLXSYNC_ACTOR") {
+        return actor;
+    }
+
+    if env("GITHUB_ACTIONS").is_some() {
+        let user = env("GITHUB_ACTOR").unwrap_or_else(|| "unknown".to_string());
+        let mut actor = format!("github-actions:{}", user);
+        if let (Some(workflow), Some(run)) = (env("GITHUB_WORKFLOW"), env("GITHUB_RUN_ID")) {
+            actor.push_str(&format!(" ({} #{})", workflow, run));
+        }
+        return actor;
+    }
+
+    if env("GITLAB_CI").is_some() {
+        let user = env("GITLAB_USER_LOGIN").unwrap_or_else(|| "unknown".to_string());
+        let mut actor = format!("gitlab-ci:{}", user);
+        if let Some(job) = env("CI_JOB_ID") {
+            actor.push_str(&format!(" (job #{})", job));
+        }
+        return actor;
+    }
+
+    let user = env("USER").or_else(|| env("USERNAME")).unwrap_or_else(|| "unknown".to_string());
+    if env("CI").is_some() {
+        format!("ci:{}", user)
+    } else {
+        user
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn detect_with(vars: &[(&str, &str)]) -> String {
+        let vars: HashMap<&str, &str> = vars.iter().copied().collect();
+        detect(|key| vars.get(key).map(|v| v.to_string()))
+    }
+
+    #[test]
+    fn test_detect_actor() {
+        assert_eq!(detect_with(&[("RBLXSYNC_ACTOR", "release-bot"), ("USER", "alex")]), "release-bot");
+        assert_eq!(
+            detect_with(&[("GITHUB_ACTIONS", "true"), ("GITHUB_ACTOR", "octocat"), ("GITHUB_WORKFLOW", "Deploy"), ("GITHUB_RUN_ID", "42")]),
+            "github-actions:octocat (Deploy #42)"
+        );
+        assert_eq!(detect_with(&[("USER", "alex")]), "alex");
+        assert_eq!(detect_with(&[]), "unknown");
+    }
+}
@@ -0,0 +1,123 @@
+//! Synchronous wrapper around [`RobloxClient`](super::RobloxClient), for
+//! scripts and build tools without a tokio runtime. Enabled by the
+//! `blocking` feature.
+//!
+//! Each client owns a single-threaded runtime and blocks on it for every
+//! call, like `reqwest::blocking`. It must not be used from inside another
+//! async runtime, where blocking on a request would panic.
+
+use super::{ClientOptions, IconThumbnail, ListResponse, LuauTask};
+use crate::config::{AssetType, CreatorConfig};
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use std::path::Path;
+use std::pin::Pin;
+use tokio::runtime::Runtime;
+use tokio_stream::{Stream, StreamExt};
+
+pub struct RobloxClient {
+    inner: super::RobloxClient,
+    runtime: Runtime,
+}
+
+/// Blocking versions of the async client's methods, with the same arguments
+macro_rules! blocking {
+    ($($name:ident($($arg:ident: $ty:ty),*) -> $ret:ty;)*) => {
+        $(
+            pub fn $name(&self, $($arg: $ty),*) -> Result<$ret> {
+                self.runtime.block_on(self.inner.$name($($arg),*))
+            }
+        )*
+    };
+}
+
+impl RobloxClient {
+    pub fn new(api_key: String) -> Result<Self> {
+        Self::with_options(api_key, ClientOptions::default())
+    }
+
+    pub fn with_options(api_key: String, options: ClientOptions) -> Result<Self> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .context("Failed to start the blocking client's runtime")?;
+        Ok(Self { inner: super::RobloxClient::with_options(api_key, options), runtime })
+    }
+
+    /// The async client this one wraps
+    pub fn inner(&self) -> &super::RobloxClient {
+        &self.inner
+    }
+
+    blocking! {
+        list_game_passes(universe_id: u64, cursor: Option<String>) -> ListResponse<serde_json::Value>;
+        get_game_pass(universe_id: u64, game_pass_id: u64) -> serde_json::Value;
+        create_game_pass(universe_id: u64, data: &serde_json::Value) -> serde_json::Value;
+        update_game_pass(universe_id: u64, game_pass_id: u64, data: &serde_json::Value) -> serde_json::Value;
+        update_game_pass_with_icon(universe_id: u64, game_pass_id: u64, data: &serde_json::Value, image_data: Option<(Vec<u8>, String)>) -> serde_json::Value;
+        list_developer_products(universe_id: u64, page_token: Option<String>) -> ListResponse<serde_json::Value>;
+        get_developer_product(universe_id: u64, product_id: u64) -> serde_json::Value;
+        create_developer_product(universe_id: u64, data: &serde_json::Value) -> serde_json::Value;
+        update_developer_product(universe_id: u64, product_id: u64, data: &serde_json::Value) -> serde_json::Value;
+        update_developer_product_with_icon(universe_id: u64, product_id: u64, data: &serde_json::Value, image_data: Option<(Vec<u8>, String)>) -> serde_json::Value;
+        list_badges(universe_id: u64, cursor: Option<String>) -> ListResponse<serde_json::Value>;
+        get_badge(badge_id: u64) -> serde_json::Value;
+        create_badge(universe_id: u64, name: &str, description: &str, image_data: Option<(Vec<u8>, String)>, payment_source_type: Option<&str>) -> serde_json::Value;
+        update_badge(badge_id: u64, data: &serde_json::Value) -> serde_json::Value;
+        update_badge_icon(badge_id: u64, image_data: Vec<u8>, filename: &str) -> serde_json::Value;
+        upload_asset(file_path: &Path, name: &str, creator: &CreatorConfig) -> String;
+        upload_asset_bytes(file_content: Vec<u8>, filename: &str, name: &str, creator: &CreatorConfig) -> String;
+        upload_typed_asset(file_content: Vec<u8>, filename: &str, name: &str, description: Option<&str>, asset_type: AssetType, creator: &CreatorConfig) -> String;
+        update_asset(asset_id: u64, file_path: &Path, asset_type: AssetType) -> u64;
+        update_asset_bytes(asset_id: u64, file_content: Vec<u8>, filename: &str, asset_type: AssetType, description: Option<&str>) -> u64;
+        archive_asset(asset_id: u64) -> ();
+        get_asset_moderation_state(asset_id: u64) -> Option<String>;
+        get_universe_name(universe_id: u64) -> Option<String>;
+        get_universe_details(universe_id: u64) -> Option<serde_json::Value>;
+        get_universe_votes(universe_id: u64) -> Option<serde_json::Value>;
+        get_datastore_entry(universe_id: u64, datastore: &str, scope: Option<&str>, key: &str) -> Option<serde_json::Value>;
+        set_datastore_entry(universe_id: u64, datastore: &str, scope: Option<&str>, key: &str, value: &serde_json::Value, etag: Option<&str>) -> serde_json::Value;
+        create_datastore_entry(universe_id: u64, datastore: &str, scope: Option<&str>, key: &str, value: &serde_json::Value) -> serde_json::Value;
+        delete_datastore_entry(universe_id: u64, datastore: &str, scope: Option<&str>, key: &str) -> ();
+        list_datastore_entries(universe_id: u64, datastore: &str, scope: Option<&str>, prefix: Option<&str>, page_token: Option<String>) -> ListResponse<serde_json::Value>;
+        get_icon_thumbnails(path: &str, id_param: &str, ids: &[u64]) -> Vec<IconThumbnail>;
+        download_image(url: &str) -> Vec<u8>;
+        restart_servers(universe_id: u64) -> ();
+        publish_message(universe_id: u64, topic: &str, message: &str) -> ();
+        list_user_restrictions(universe_id: u64, place_id: Option<u64>, page_token: Option<String>) -> ListResponse<serde_json::Value>;
+        get_user_restriction(universe_id: u64, place_id: Option<u64>, user_id: u64) -> serde_json::Value;
+        update_user_restriction(universe_id: u64, place_id: Option<u64>, user_id: u64, restriction: &serde_json::Value) -> serde_json::Value;
+        send_notification(universe_id: u64, user_id: u64, payload: &serde_json::Value) -> serde_json::Value;
+        introspect_api_key() -> serde_json::Value;
+        get_group(group_id: u64) -> serde_json::Value;
+        get_group_membership(group_id: u64, user_id: u64) -> Option<serde_json::Value>;
+        get_group_role(role_path: &str) -> serde_json::Value;
+        run_luau(universe_id: u64, place_id: u64, version: Option<u64>, script: &str, timeout_secs: u64) -> LuauTask;
+        get_luau_task_logs(task_path: &str) -> Vec<String>;
+        publish_place(universe_id: u64, place_id: u64, file_path: &Path) -> serde_json::Value;
+        get_latest_place_version(place_id: u64) -> Option<u64>;
+    }
+
+    /// Every game pass of a universe as a `T` (e.g. `serde_json::Value`),
+    /// fetching pages as the iterator advances
+    pub fn game_passes<T: DeserializeOwned + Send + 'static>(&self, universe_id: u64) -> impl Iterator<Item = Result<T>> + '_ {
+        self.iter(self.inner.game_passes(universe_id).stream())
+    }
+
+    pub fn developer_products<T: DeserializeOwned + Send + 'static>(&self, universe_id: u64) -> impl Iterator<Item = Result<T>> + '_ {
+        self.iter(self.inner.developer_products(universe_id).stream())
+    }
+
+    pub fn badges<T: DeserializeOwned + Send + 'static>(&self, universe_id: u64) -> impl Iterator<Item = Result<T>> + '_ {
+        self.iter(self.inner.badges(universe_id).stream())
+    }
+
+    pub fn user_restrictions<T: DeserializeOwned + Send + 'static>(&self, universe_id: u64, place_id: Option<u64>) -> impl Iterator<Item = Result<T>> + '_ {
+        self.iter(self.inner.user_restrictions(universe_id, place_id).stream())
+    }
+
+    fn iter<'a, T, S: Stream<Item = Result<T>> + 'a>(&'a self, stream: S) -> impl Iterator<Item = Result<T>> + 'a {
+        let mut stream: Pin<Box<S>> = Box::pin(stream);
+        std::iter::from_fn(move || self.runtime.block_on(stream.next()))
+    }
+}
@@ -0,0 +1,258 @@
+//! Recording and replay of API traffic (`--record` and `--replay`).
+//!
+//! [`Recorder`] writes each request and its response to a directory, one
+//! JSON file per call named `<sequence>-<method>-<path>.json`. Request
+//! headers aren't stored and the API key is scrubbed from URLs and bodies,
+//! so fixtures can be committed. [`Replayer`] answers requests from such a
+//! directory without sending them: calls with the same method and URL are
+//! answered in recorded order, the last one repeating once they run out.
+
+use super::{ApiResponse, Middleware};
+use anyhow::{anyhow, Context, Result};
+use reqwest::header::{HeaderValue, CONTENT_TYPE};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+const REDACTED: &str = "<redacted>";
+
+/// One recorded call
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Fixture {
+    method: String,
+    url: String,
+    /// Request body, when it was JSON
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    request: Option<serde_json::Value>,
+    status: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    content_type: Option<String>,
+    /// Response body, when it was JSON
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    body: Option<serde_json::Value>,
+    /// Response body, when it was other text
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    body_text: Option<String>,
+    /// Response body as hex, when it wasn't UTF-8 (e.g. images)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    body_hex: Option<String>,
+}
+
+impl Fixture {
+    fn key(&self) -> String {
+        format!("{} {}", self.method, self.url)
+    }
+
+    fn response_body(&self) -> Result<Vec<u8>> {
+        match (&self.body, &self.body_text, &self.body_hex) {
+            (Some(json), _, _) => Ok(json.to_string().into_bytes()),
+            (_, Some(text), _) => Ok(text.clone().into_bytes()),
+            (_, _, Some(hex)) => hex_decode(hex),
+            _ => Ok(Vec::new()),
+        }
+    }
+}
+
+/// Writes every call to a fixtures directory
+pub struct Recorder {
+    dir: PathBuf,
+    sequence: AtomicUsize,
+    /// JSON request bodies waiting for their response, by method and URL
+    pending: Mutex<HashMap<String, VecDeque<Option<serde_json::Value>>>>,
+    /// API key seen on requests, to scrub it from responses too
+    key: Mutex<String>,
+}
+
+impl Recorder {
+    pub fn new(dir: &Path) -> Result<Self> {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+        // Continue numbering after fixtures already in the directory
+        let existing = fs::read_dir(dir)?.filter(|entry| entry.as_ref().is_ok_and(|e| e.path().extension().is_some_and(|ext| ext == "json"))).count();
+        Ok(Self { dir: dir.to_path_buf(), sequence: AtomicUsize::new(existing), pending: Mutex::new(HashMap::new()), key: Mutex::new(String::new()) })
+    }
+
+    fn write(&self, fixture: &Fixture) -> Result<()> {
+        let sequence = self.sequence.fetch_add(1, Ordering::SeqCst) + 1;
+        let path = self.dir.join(format!("{:04}-{}-{}.json", sequence, fixture.method.to_lowercase(), slug(&fixture.url)));
+        fs::write(&path, serde_json::to_string_pretty(fixture)?).with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+impl Middleware for Recorder {
+    fn on_request(&self, request: &mut reqwest::Request) -> Option<ApiResponse> {
+        let key = request.headers().get("x-api-key").and_then(|v| v.to_str().ok()).unwrap_or_default();
+        let body = request.body().and_then(|b| b.as_bytes()).and_then(|bytes| serde_json::from_str(&scrub(&String::from_utf8_lossy(bytes), key)).ok());
+        let id = format!("{} {}", request.method(), scrub(request.url().as_str(), key));
+        self.pending.lock().unwrap().entry(id).or_default().push_back(body);
+        if !key.is_empty() {
+            *self.key.lock().unwrap() = key.to_string();
+        }
+        None
+    }
+
+    fn on_response(&self, response: &mut ApiResponse) {
+        let key = self.key.lock().unwrap().clone();
+        let url = scrub(response.url.as_str(), &key);
+        let id = format!("{} {}", response.method, url);
+        let request = self.pending.lock().unwrap().get_mut(&id).and_then(|queue| queue.pop_front()).flatten();
+        let (mut body, mut body_text, mut body_hex) = (None, None, None);
+        match std::str::from_utf8(&response.body) {
+            Ok("") => {}
+            Ok(text) => {
+                let text = scrub(text, &key);
+                match serde_json::from_str(&text) {
+                    Ok(json) => body = Some(json),
+                    Err(_) => body_text = Some(text),
+                }
+            }
+            Err(_) => body_hex = Some(hex_encode(&response.body)),
+        }
+        let fixture = Fixture {
+            method: response.method.to_string(),
+            url,
+            request,
+            status: response.status.as_u16(),
+            content_type: response.headers.get(CONTENT_TYPE).and_then(|v| v.to_str().ok()).map(str::to_string),
+            body,
+            body_text,
+            body_hex,
+        };
+        if let Err(e) = self.write(&fixture) {
+            tracing::warn!("Failed to record {}: {:#}", id, e);
+        }
+    }
+}
+
+/// Answers every call from a fixtures directory
+pub struct Replayer {
+    dir: PathBuf,
+    responses: Mutex<HashMap<String, VecDeque<Fixture>>>,
+}
+
+impl Replayer {
+    pub fn load(dir: &Path) -> Result<Self> {
+        let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+            .with_context(|| format!("Failed to read fixtures from {}", dir.display()))?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .collect();
+        paths.sort();
+        let mut responses: HashMap<String, VecDeque<Fixture>> = HashMap::new();
+        for path in paths {
+            let content = fs::read_to_string(&path)?;
+            let fixture: Fixture = serde_json::from_str(&content).with_context(|| format!("Failed to parse fixture {}", path.display()))?;
+            responses.entry(fixture.key()).or_default().push_back(fixture);
+        }
+        if responses.is_empty() {
+            return Err(anyhow!("No fixtures found in {}", dir.display()));
+        }
+        Ok(Self { dir: dir.to_path_buf(), responses: Mutex::new(responses) })
+    }
+}
+
+impl Middleware for Replayer {
+    fn on_request(&self, request: &mut reqwest::Request) -> Option<ApiResponse> {
+        let key = request.headers().get("x-api-key").and_then(|v| v.to_str().ok()).unwrap_or_default().to_string();
+        let id = format!("{} {}", request.method(), scrub(request.url().as_str(), &key));
+        let mut responses = self.responses.lock().unwrap();
+        let fixture = responses.get_mut(&id).and_then(|queue| if queue.len() > 1 { queue.pop_front() } else { queue.front().cloned() });
+        let Some(fixture) = fixture else {
+            tracing::debug!("No recorded response for {}", id);
+            let message = format!("No recorded response for {} in {}", id, self.dir.display());
+            return Some(ApiResponse::new(request, StatusCode::NOT_IMPLEMENTED, message.into_bytes()));
+        };
+        let (status, body) = match (StatusCode::from_u16(fixture.status), fixture.response_body()) {
+            (Ok(status), Ok(body)) => (status, body),
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, format!("Invalid fixture for {}", id).into_bytes()),
+        };
+        let mut response = ApiResponse::new(request, status, body);
+        if let Some(content_type) = fixture.content_type.as_deref().and_then(|c| HeaderValue::from_str(c).ok()) {
+            response.headers.insert(CONTENT_TYPE, content_type);
+        }
+        Some(response)
+    }
+}
+
+fn scrub(text: &str, key: &str) -> String {
+    if key.is_empty() {
+        text.to_string()
+    } else {
+        text.replace(key, REDACTED)
+    }
+}
+
+/// File name part for a URL: its path, with anything but letters and digits as `-`
+fn slug(url: &str) -> String {
+    let path = reqwest::Url::parse(url).map(|u| u.path().to_string()).unwrap_or_default();
+    let slug: String = path.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '-' }).collect();
+    let slug = slug.split('-').filter(|part| !part.is_empty()).collect::<Vec<_>>().join("-");
+    slug.chars().take(80).collect()
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn hex_decode(hex: &str) -> Result<Vec<u8>> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| hex.get(i..i + 2).and_then(|pair| u8::from_str_radix(pair, 16).ok()).ok_or_else(|| anyhow!("Invalid hex in fixture")))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::Method;
+
+    fn request(method: Method, url: &str, body: Option<&str>) -> reqwest::Request {
+        let mut request = reqwest::Request::new(method, url.parse().unwrap());
+        request.headers_mut().insert("x-api-key", HeaderValue::from_static("secret-key"));
+        *request.body_mut() = body.map(|b| b.to_string().into());
+        request
+    }
+
+    /// Send `request` through the recorder, answered with `status` and `body`
+    fn record(recorder: &Recorder, mut request: reqwest::Request, status: StatusCode, body: &[u8]) {
+        assert!(recorder.on_request(&mut request).is_none());
+        let mut response = ApiResponse::new(&request, status, body.to_vec());
+        recorder.on_response(&mut response);
+    }
+
+    #[test]
+    fn test_record_and_replay() {
+        let dir = std::env::temp_dir().join(format!("rblxsync-fixtures-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let list = "https://apis.roblox.com/game-passes/v1/universes/1/game-passes?limit=100";
+        let poll = "https://apis.roblox.com/assets/v1/operations/7";
+
+        let recorder = Recorder::new(&dir).unwrap();
+        record(&recorder, request(Method::GET, list, None), StatusCode::OK, br#"{"gamePasses":[{"id":5,"name":"VIP"}]}"#);
+        record(&recorder, request(Method::GET, poll, None), StatusCode::OK, br#"{"done":false}"#);
+        record(&recorder, request(Method::GET, poll, None), StatusCode::OK, br#"{"done":true}"#);
+        record(&recorder, request(Method::POST, "https://apis.roblox.com/echo", Some(r#"{"key":"secret-key"}"#)), StatusCode::OK, b"secret-key");
+        record(&recorder, request(Method::GET, "https://tr.rbxcdn.com/icon.png", None), StatusCode::OK, &[0x89, 0xff, 0x00]);
+
+        let files: Vec<String> = fs::read_dir(&dir).unwrap().map(|e| fs::read_to_string(e.unwrap().path()).unwrap()).collect();
+        assert_eq!(files.len(), 5);
+        assert!(files.iter().all(|content| !content.contains("secret-key")));
+        assert!(dir.join("0001-get-game-passes-v1-universes-1-game-passes.json").exists());
+
+        let replayer = Replayer::load(&dir).unwrap();
+        let answer = |method: Method, url: &str| replayer.on_request(&mut request(method, url, None)).unwrap();
+        assert_eq!(answer(Method::GET, list).text(), r#"{"gamePasses":[{"id":5,"name":"VIP"}]}"#);
+        assert_eq!(answer(Method::GET, poll).text(), r#"{"done":false}"#);
+        // The last response repeats
+        assert_eq!(answer(Method::GET, poll).text(), r#"{"done":true}"#);
+        assert_eq!(answer(Method::GET, poll).text(), r#"{"done":true}"#);
+        assert_eq!(answer(Method::POST, "https://apis.roblox.com/echo").text(), REDACTED);
+        assert_eq!(answer(Method::GET, "https://tr.rbxcdn.com/icon.png").body, [0x89, 0xff, 0x00]);
+        assert_eq!(answer(Method::DELETE, list).status, StatusCode::NOT_IMPLEMENTED);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
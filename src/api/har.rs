@@ -0,0 +1,181 @@
+//! HTTP Archive (HAR 1.2) log of a client's traffic (`--debug-http`), for
+//! bug reports about API failures. Credentials are redacted from headers,
+//! and registered secrets from bodies; bodies are otherwise kept, except for
+//! uploads (multipart icons and assets, place files), which are streamed.
+//!
+//! The file is rewritten after every response, so it's complete even when
+//! the command fails halfway.
+
+use super::{ApiResponse, Middleware};
+use anyhow::{Context, Result};
+use reqwest::header::{HeaderMap, CONTENT_TYPE};
+use serde_json::{json, Value};
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Instant, SystemTime};
+
+/// Headers whose values are replaced with `<redacted>`
+const SECRET_HEADERS: &[&str] = &["x-api-key", "cookie", "authorization", "x-csrf-token"];
+
+/// Writes every call to a HAR file
+pub struct HarLog {
+    path: PathBuf,
+    /// Requests waiting for their response, by method and URL
+    pending: Mutex<HashMap<String, VecDeque<Pending>>>,
+    entries: Mutex<Vec<Value>>,
+}
+
+/// A request's HAR entry and when it was sent
+struct Pending {
+    request: Value,
+    started: SystemTime,
+    start: Instant,
+}
+
+impl HarLog {
+    pub fn new(path: &Path) -> Result<Self> {
+        let log = Self { path: path.to_path_buf(), pending: Mutex::new(HashMap::new()), entries: Mutex::new(Vec::new()) };
+        log.save(&[])?;
+        Ok(log)
+    }
+
+    fn save(&self, entries: &[Value]) -> Result<()> {
+        let har = json!({
+            "log": {
+                "version": "1.2",
+                "creator": { "name": "rblxsync", "version": env!("CARGO_PKG_VERSION") },
+                "entries": entries,
+            }
+        });
+        std::fs::write(&self.path, serde_json::to_string_pretty(&har)?).with_context(|| format!("Failed to write {}", self.path.display()))
+    }
+}
+
+impl Middleware for HarLog {
+    fn on_request(&self, request: &mut reqwest::Request) -> Option<ApiResponse> {
+        let query: Vec<Value> = request.url().query_pairs().map(|(name, value)| json!({ "name": name, "value": value })).collect();
+        let mut entry = json!({
+            "method": request.method().as_str(),
+            "url": request.url().as_str(),
+            "httpVersion": "HTTP/1.1",
+            "cookies": [],
+            "headers": headers(request.headers()),
+            "queryString": query,
+            "headersSize": -1,
+            "bodySize": -1,
+        });
+        if let Some(body) = request.body().and_then(|b| b.as_bytes()) {
+            let mime_type = request.headers().get(CONTENT_TYPE).and_then(|v| v.to_str().ok()).unwrap_or("application/octet-stream");
+            entry["postData"] = json!({ "mimeType": mime_type, "text": crate::redact::redact(&String::from_utf8_lossy(body)) });
+            entry["bodySize"] = body.len().into();
+        }
+        let id = format!("{} {}", request.method(), request.url());
+        self.pending.lock().unwrap().entry(id).or_default().push_back(Pending { request: entry, started: SystemTime::now(), start: Instant::now() });
+        None
+    }
+
+    fn on_response(&self, response: &mut ApiResponse) {
+        let id = format!("{} {}", response.method, response.url);
+        let Some(Pending { request, started, start }) = self.pending.lock().unwrap().get_mut(&id).and_then(|queue| queue.pop_front()) else {
+            return;
+        };
+        let elapsed = start.elapsed().as_secs_f64() * 1000.0;
+        let mime_type = response.headers.get(CONTENT_TYPE).and_then(|v| v.to_str().ok()).unwrap_or_default();
+        let mut content = json!({ "size": response.body.len(), "mimeType": mime_type });
+        match std::str::from_utf8(&response.body) {
+            Ok(text) => content["text"] = crate::redact::redact(text).into(),
+            Err(_) => {
+                content["text"] = base64(&response.body).into();
+                content["encoding"] = "base64".into();
+            }
+        }
+        let entry = json!({
+            "startedDateTime": crate::logging::timestamp(started),
+            "time": elapsed,
+            "request": request,
+            "response": {
+                "status": response.status.as_u16(),
+                "statusText": response.status.canonical_reason().unwrap_or_default(),
+                "httpVersion": "HTTP/1.1",
+                "cookies": [],
+                "headers": headers(&response.headers),
+                "content": content,
+                "redirectURL": "",
+                "headersSize": -1,
+                "bodySize": response.body.len(),
+            },
+            "cache": {},
+            "timings": { "send": 0, "wait": elapsed, "receive": 0 },
+        });
+        let mut entries = self.entries.lock().unwrap();
+        entries.push(entry);
+        if let Err(e) = self.save(&entries) {
+            tracing::warn!("Failed to write HTTP log: {:#}", e);
+        }
+    }
+}
+
+fn headers(headers: &HeaderMap) -> Vec<Value> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let value = match SECRET_HEADERS.contains(&name.as_str()) {
+                true => "<redacted>".to_string(),
+                false => String::from_utf8_lossy(value.as_bytes()).into_owned(),
+            };
+            json!({ "name": name.as_str(), "value": value })
+        })
+        .collect()
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = (chunk[0] as u32) << 16 | (*chunk.get(1).unwrap_or(&0) as u32) << 8 | *chunk.get(2).unwrap_or(&0) as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+    use reqwest::{Method, StatusCode};
+
+    #[test]
+    fn test_har_log() {
+        let path = std::env::temp_dir().join(format!("rblxsync-har-{}.har", std::process::id()));
+        let har = HarLog::new(&path).unwrap();
+
+        let mut request = reqwest::Request::new(Method::PATCH, "https://apis.roblox.com/badges/v1/badges/9?x=1".parse().unwrap());
+        request.headers_mut().insert("x-api-key", HeaderValue::from_static("secret-key"));
+        request.headers_mut().insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        *request.body_mut() = Some(r#"{"enabled":false}"#.into());
+        assert!(har.on_request(&mut request).is_none());
+        let mut response = ApiResponse::new(&request, StatusCode::FORBIDDEN, b"\x89PNG".to_vec());
+        har.on_response(&mut response);
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(!content.contains("secret-key"));
+        let log: Value = serde_json::from_str(&content).unwrap();
+        let entry = &log["log"]["entries"][0];
+        assert_eq!(entry["request"]["method"], "PATCH");
+        assert_eq!(entry["request"]["queryString"][0]["value"], "1");
+        assert_eq!(entry["request"]["postData"]["text"], r#"{"enabled":false}"#);
+        assert_eq!(entry["response"]["status"], 403);
+        assert_eq!(entry["response"]["content"]["encoding"], "base64");
+        assert_eq!(entry["response"]["content"]["text"], "iVBORw==");
+        assert!(entry["startedDateTime"].as_str().unwrap().ends_with('Z'));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
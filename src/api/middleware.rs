@@ -0,0 +1,112 @@
+//! Hooks that see every request a [`RobloxClient`](super::RobloxClient)
+//! sends and every response it gets back, e.g. to add headers, collect
+//! metrics or answer requests from a cache.
+//!
+//! ```no_run
+//! use rblxsync::api::{ApiResponse, ClientOptions, Middleware, RobloxClient};
+//!
+//! struct Tag;
+//!
+//! impl Middleware for Tag {
+//!     fn on_request(&self, request: &mut reqwest::Request) -> Option<ApiResponse> {
+//!         request.headers_mut().insert("x-build", "1234".parse().unwrap());
+//!         None
+//!     }
+//! }
+//!
+//! let client = RobloxClient::with_options("key".to_string(), ClientOptions::new().with_middleware(Tag));
+//! ```
+
+use reqwest::header::HeaderMap;
+use reqwest::{Method, StatusCode, Url};
+use std::sync::Arc;
+
+/// Observes or changes the requests of a client and their responses
+pub trait Middleware: Send + Sync {
+    /// Called before `request` is sent. Returning a response answers the
+    /// request without sending it, and skips the middleware after this one.
+    fn on_request(&self, request: &mut reqwest::Request) -> Option<ApiResponse> {
+        let _ = request;
+        None
+    }
+
+    /// Called with each response before the client reads it, in the reverse
+    /// order the middleware was added
+    fn on_response(&self, response: &mut ApiResponse) {
+        let _ = response;
+    }
+}
+
+/// A response with its body already read
+#[derive(Debug, Clone)]
+pub struct ApiResponse {
+    /// Method and URL of the request this answers
+    pub method: Method,
+    pub url: Url,
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Vec<u8>,
+}
+
+impl ApiResponse {
+    /// A response to `request`, e.g. one served from a cache
+    pub fn new(request: &reqwest::Request, status: StatusCode, body: Vec<u8>) -> Self {
+        Self { method: request.method().clone(), url: request.url().clone(), status, headers: HeaderMap::new(), body }
+    }
+
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+}
+
+/// Settings for [`RobloxClient::with_options`](super::RobloxClient::with_options)
+#[derive(Clone, Default)]
+pub struct ClientOptions {
+    pub(super) middleware: Vec<Arc<dyn Middleware>>,
+}
+
+impl ClientOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run `middleware` on every request, after any added before it
+    pub fn with_middleware(mut self, middleware: impl Middleware + 'static) -> Self {
+        self.middleware.push(Arc::new(middleware));
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::RobloxClient;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct Cached;
+
+    impl Middleware for Cached {
+        fn on_request(&self, request: &mut reqwest::Request) -> Option<ApiResponse> {
+            Some(ApiResponse::new(request, StatusCode::OK, br#"{"data":[{"name":"Cached Game"}]}"#.to_vec()))
+        }
+    }
+
+    #[derive(Clone, Default)]
+    struct Counter(Arc<AtomicUsize>);
+
+    impl Middleware for Counter {
+        fn on_response(&self, response: &mut ApiResponse) {
+            assert_eq!(response.url.path(), "/v1/games");
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_middleware_answers_and_observes() {
+        let counter = Counter::default();
+        let options = ClientOptions::new().with_middleware(counter.clone()).with_middleware(Cached);
+        let client = RobloxClient::with_options("key".to_string(), options);
+        assert_eq!(client.get_universe_name(1).await.unwrap().as_deref(), Some("Cached Game"));
+        assert_eq!(counter.0.load(Ordering::SeqCst), 1);
+    }
+}
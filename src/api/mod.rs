@@ -1,22 +1,60 @@
 use anyhow::{anyhow, Context, Result};
 use reqwest::{Client, Method, RequestBuilder};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use crate::config::AssetType;
 use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::RwLock;
+use tracing::Instrument;
+use tokio_stream::StreamExt;
+use tokio_util::io::ReaderStream;
+use crate::progress::Progress;
+
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod fixtures;
+pub mod har;
+mod middleware;
+mod pages;
+pub use middleware::{ApiResponse, ClientOptions, Middleware};
+pub use pages::Pages;
 
 const BASE_URL: &str = "https://apis.roblox.com";
+const THUMBNAILS_URL: &str = "https://thumbnails.roblox.com";
+const GAMES_URL: &str = "https://games.roblox.com";
+/// Size of the pieces a place file is sent in
+const UPLOAD_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Requests sent to Roblox by this process, for run summaries
+static API_CALLS: AtomicU64 = AtomicU64::new(0);
+
+/// Number of Roblox API requests sent so far
+pub fn api_calls() -> u64 {
+    API_CALLS.load(Ordering::Relaxed)
+}
+
+fn count_call() {
+    API_CALLS.fetch_add(1, Ordering::Relaxed);
+}
 
 #[derive(Clone)]
 pub struct RobloxClient {
     client: Client,
     api_key: String,
+    middleware: Vec<std::sync::Arc<dyn Middleware>>,
 }
 
 impl RobloxClient {
     pub fn new(api_key: String) -> Self {
+        Self::with_options(api_key, ClientOptions::default())
+    }
+
+    pub fn with_options(api_key: String, options: ClientOptions) -> Self {
+        crate::redact::register(&api_key);
         Self {
             client: Client::new(),
             api_key,
+            middleware: options.middleware,
         }
     }
 
@@ -26,19 +64,48 @@ impl RobloxClient {
             .header("x-api-key", &self.api_key)
     }
 
+    /// Send a request through the middleware and read its response
+    async fn send(&self, builder: RequestBuilder) -> Result<ApiResponse> {
+        let mut request = builder.build()?;
+        let span = tracing::debug_span!("http", method = %request.method(), url = %request.url(), status = tracing::field::Empty);
+        let call = span.clone();
+        async move {
+            let started = std::time::Instant::now();
+            let answered = self.middleware.iter().find_map(|m| m.on_request(&mut request));
+            let mut response = match answered {
+                Some(response) => response,
+                None => {
+                    count_call();
+                    let (method, url) = (request.method().clone(), request.url().clone());
+                    let response = self.client.execute(request).await?;
+                    let (status, headers) = (response.status(), response.headers().clone());
+                    ApiResponse { method, url, status, headers, body: response.bytes().await?.to_vec() }
+                }
+            };
+            for m in self.middleware.iter().rev() {
+                m.on_response(&mut response);
+            }
+            call.record("status", response.status.as_u16());
+            #[cfg(feature = "otel")]
+            crate::telemetry::record_request(&response.method, &response.url, response.status.as_u16(), started.elapsed());
+            tracing::debug!(elapsed_ms = started.elapsed().as_millis() as u64, "Response received");
+            Ok(response)
+        }
+        .instrument(span)
+        .await
+    }
+
     async fn execute<T: DeserializeOwned>(&self, builder: RequestBuilder) -> Result<T> {
-        let response = builder.send().await?;
-        let status = response.status();
-        let text = response.text().await.unwrap_or_default();
+        let response = self.send(builder).await?;
+        let status = response.status;
+        let text = response.text();
         
-        log::debug!("API response status: {}, body: {}", status, text);
+        tracing::debug!("API response status: {}, body: {}", status, text);
         
         if !status.is_success() {
-            return Err(anyhow!("API request failed: {} - {}", status, text));
+            return Err(ApiError { status, body: text }.into());
         }
 
-        let text = text;
-        
         // Handle empty response (common for PATCH/PUT endpoints)
         if text.is_empty() || text.trim().is_empty() {
             // Try to deserialize from empty JSON object or null
@@ -54,7 +121,7 @@ impl RobloxClient {
             }
         }
         
-        serde_json::from_str(&text).context(format!("Failed to parse response: {}", text))
+        serde_json::from_str(&text).context(format!("Failed to parse response: {}", crate::redact::redact(&text)))
     }
 
     // --- Game Passes ---
@@ -68,18 +135,24 @@ impl RobloxClient {
         self.execute(req).await
     }
 
+    /// A game pass as its creator sees it, including `updatedTimestamp`
+    pub async fn get_game_pass(&self, universe_id: u64, game_pass_id: u64) -> Result<serde_json::Value> {
+        let url = format!("{}/game-passes/v1/universes/{}/game-passes/{}/creator", BASE_URL, universe_id, game_pass_id);
+        self.execute(self.request(Method::GET, &url)).await
+    }
+
     pub async fn create_game_pass(&self, universe_id: u64, data: &serde_json::Value) -> Result<serde_json::Value> {
         let url = format!("{}/game-passes/v1/universes/{}/game-passes", BASE_URL, universe_id);
         let form = json_to_multipart(data);
-        log::debug!("Creating game pass at: {}", url);
+        tracing::debug!("Creating game pass at: {}", url);
         let result: serde_json::Value = self.execute(self.request(Method::POST, &url).multipart(form)).await?;
-        log::info!("Create game pass response: {}", result);
+        tracing::info!("Create game pass response: {}", result);
         Ok(result)
     }
 
     pub async fn update_game_pass(&self, universe_id: u64, game_pass_id: u64, data: &serde_json::Value) -> Result<serde_json::Value> {
         let url = format!("{}/game-passes/v1/universes/{}/game-passes/{}", BASE_URL, universe_id, game_pass_id);
-        log::debug!("Updating game pass at URL: {} with data: {}", url, data);
+        tracing::debug!("Updating game pass at URL: {} with data: {}", url, data);
         let form = json_to_multipart(data);
         self.execute(self.request(Method::PATCH, &url).multipart(form)).await
     }
@@ -93,13 +166,13 @@ impl RobloxClient {
         image_data: Option<(Vec<u8>, String)>
     ) -> Result<serde_json::Value> {
         let url = format!("{}/game-passes/v1/universes/{}/game-passes/{}", BASE_URL, universe_id, game_pass_id);
-        log::debug!("Updating game pass with icon at URL: {} with data: {}", url, data);
+        tracing::debug!("Updating game pass with icon at URL: {} with data: {}", url, data);
         
         let mut form = json_to_multipart(data);
         
         // Add image file if provided (game passes API uses "file" field name)
         if let Some((file_bytes, filename)) = image_data {
-            log::debug!("Adding file to form: {} ({} bytes)", filename, file_bytes.len());
+            tracing::debug!("Adding file to form: {} ({} bytes)", filename, file_bytes.len());
             let file_part = reqwest::multipart::Part::bytes(file_bytes)
                 .file_name(filename)
                 .mime_str("image/png")?;
@@ -120,18 +193,24 @@ impl RobloxClient {
         self.execute(req).await
     }
 
+    /// A developer product as its creator sees it, including `updatedTimestamp`
+    pub async fn get_developer_product(&self, universe_id: u64, product_id: u64) -> Result<serde_json::Value> {
+        let url = format!("{}/developer-products/v2/universes/{}/developer-products/{}/creator", BASE_URL, universe_id, product_id);
+        self.execute(self.request(Method::GET, &url)).await
+    }
+
     pub async fn create_developer_product(&self, universe_id: u64, data: &serde_json::Value) -> Result<serde_json::Value> {
         let url = format!("{}/developer-products/v2/universes/{}/developer-products", BASE_URL, universe_id);
-        log::debug!("Creating developer product at: {}", url);
+        tracing::debug!("Creating developer product at: {}", url);
         let form = json_to_multipart(data);
         let result: serde_json::Value = self.execute(self.request(Method::POST, &url).multipart(form)).await?;
-        log::info!("Create developer product response: {}", result);
+        tracing::info!("Create developer product response: {}", result);
         Ok(result)
     }
 
     pub async fn update_developer_product(&self, universe_id: u64, product_id: u64, data: &serde_json::Value) -> Result<serde_json::Value> {
         let url = format!("{}/developer-products/v2/universes/{}/developer-products/{}", BASE_URL, universe_id, product_id);
-        log::debug!("Updating developer product at URL: {} with data: {}", url, data);
+        tracing::debug!("Updating developer product at URL: {} with data: {}", url, data);
         let form = json_to_multipart(data);
         self.execute(self.request(Method::PATCH, &url).multipart(form)).await
     }
@@ -145,13 +224,13 @@ impl RobloxClient {
         image_data: Option<(Vec<u8>, String)>
     ) -> Result<serde_json::Value> {
         let url = format!("{}/developer-products/v2/universes/{}/developer-products/{}", BASE_URL, universe_id, product_id);
-        log::debug!("Updating developer product with icon at URL: {} with data: {}", url, data);
+        tracing::debug!("Updating developer product with icon at URL: {} with data: {}", url, data);
         
         let mut form = json_to_multipart(data);
         
         // Add image file if provided
         if let Some((file_bytes, filename)) = image_data {
-            log::debug!("Adding imageFile to form: {} ({} bytes)", filename, file_bytes.len());
+            tracing::debug!("Adding imageFile to form: {} ({} bytes)", filename, file_bytes.len());
             let file_part = reqwest::multipart::Part::bytes(file_bytes)
                 .file_name(filename)
                 .mime_str("image/png")?;
@@ -181,6 +260,12 @@ impl RobloxClient {
         self.execute(req).await
     }
 
+    /// A badge, including its `updated` timestamp
+    pub async fn get_badge(&self, badge_id: u64) -> Result<serde_json::Value> {
+        let url = format!("https://badges.roblox.com/v1/badges/{}", badge_id);
+        self.execute(self.request(Method::GET, &url)).await
+    }
+
     pub async fn create_badge(
         &self, 
         universe_id: u64, 
@@ -190,7 +275,7 @@ impl RobloxClient {
         payment_source_type: Option<&str>
     ) -> Result<serde_json::Value> {
         let url = format!("{}/legacy-badges/v1/universes/{}/badges", BASE_URL, universe_id);
-        log::debug!("Creating badge at: {}", url);
+        tracing::debug!("Creating badge at: {}", url);
         
         let mut form = reqwest::multipart::Form::new()
             .text("name", name.to_string())
@@ -220,14 +305,14 @@ impl RobloxClient {
     pub async fn update_badge(&self, badge_id: u64, data: &serde_json::Value) -> Result<serde_json::Value> {
         // Update badge config
         let url = format!("{}/legacy-badges/v1/badges/{}", BASE_URL, badge_id);
-        log::debug!("Updating badge at URL: {} with data: {}", url, data);
+        tracing::debug!("Updating badge at URL: {} with data: {}", url, data);
         self.execute(self.request(Method::PATCH, &url).json(data)).await
     }
 
     pub async fn update_badge_icon(&self, badge_id: u64, image_data: Vec<u8>, filename: &str) -> Result<serde_json::Value> {
         // Update badge icon uses legacy-publish endpoint
         let url = format!("{}/legacy-publish/v1/badges/{}/icon", BASE_URL, badge_id);
-        log::debug!("Updating badge icon at URL: {}", url);
+        tracing::debug!("Updating badge icon at URL: {}", url);
         
         let file_part = reqwest::multipart::Part::bytes(image_data)
             .file_name(filename.to_string())
@@ -242,44 +327,88 @@ impl RobloxClient {
     // --- Assets (Images) ---
 
     pub async fn upload_asset(&self, file_path: &Path, name: &str, creator: &crate::config::CreatorConfig) -> Result<String> {
-        // 1. Prepare Multipart
-        let url = format!("{}/assets/v1/assets", BASE_URL);
-        
-        // Check file extension for content type
-        let extension = file_path.extension().and_then(|s| s.to_str()).unwrap_or("png");
-        let content_type = match extension {
-            "png" => "image/png",
-            "jpg" | "jpeg" => "image/jpeg",
-            "bmp" => "image/bmp",
-            "tga" => "image/tga",
-            _ => "image/png", // Default fallback
-        };
-
         let file_content = tokio::fs::read(file_path).await?;
         let filename = file_path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        self.upload_asset_bytes(file_content, &filename, name, creator).await
+    }
 
-        // Create the request struct following Asphalt's approach
-        let creator_web = if creator.creator_type == "group" {
-            WebAssetCreator::Group(WebAssetGroupCreator {
-                group_id: creator.id.clone(),
-            })
-        } else {
-            WebAssetCreator::User(WebAssetUserCreator {
-                user_id: creator.id.clone(),
-            })
-        };
+    /// Upload an image asset from in-memory bytes and return its asset ID
+    pub async fn upload_asset_bytes(&self, file_content: Vec<u8>, filename: &str, name: &str, creator: &crate::config::CreatorConfig) -> Result<String> {
+        // Check file extension for content type
+        let extension = Path::new(filename).extension().and_then(|s| s.to_str()).unwrap_or("png");
+        let content_type = AssetType::Image.content_type(extension).unwrap_or("image/png"); // Default fallback
 
         let request = WebAssetRequest {
             asset_type: "Image".to_string(),
             display_name: name.to_string(),
             description: format!("Uploaded by rblxsync from {}", filename),
             creation_context: WebAssetRequestCreationContext {
-                creator: creator_web,
+                creator: web_asset_creator(creator),
                 expected_price: None, // Not used for image assets
             },
         };
 
-        let request_json = serde_json::to_string(&request)?;
+        self.create_asset(request, file_content, filename, content_type).await
+    }
+
+    /// Upload an asset of any supported type from in-memory bytes and return its asset ID
+    pub async fn upload_typed_asset(&self, file_content: Vec<u8>, filename: &str, name: &str, description: Option<&str>, asset_type: AssetType, creator: &crate::config::CreatorConfig) -> Result<String> {
+        let extension = Path::new(filename).extension().and_then(|s| s.to_str()).unwrap_or_default();
+        let content_type = asset_type.content_type(extension)
+            .ok_or_else(|| anyhow!("Unsupported file type for {} asset: {}", asset_type.api_name(), filename))?;
+
+        let request = WebAssetRequest {
+            asset_type: asset_type.api_name().to_string(),
+            display_name: name.to_string(),
+            description: description
+                .map(|d| d.to_string())
+                .unwrap_or_else(|| format!("Uploaded by rblxsync from {}", filename)),
+            creation_context: WebAssetRequestCreationContext {
+                creator: web_asset_creator(creator),
+                expected_price: None,
+            },
+        };
+
+        self.create_asset(request, file_content, filename, content_type).await
+    }
+
+    /// Replace the content of an existing asset with the file at `file_path`,
+    /// keeping its ID so places and scripts referencing it pick up the change
+    pub async fn update_asset(&self, asset_id: u64, file_path: &Path, asset_type: AssetType) -> Result<u64> {
+        let file_content = tokio::fs::read(file_path).await?;
+        let filename = file_path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        self.update_asset_bytes(asset_id, file_content, &filename, asset_type, None).await
+    }
+
+    /// Replace the content (and optionally the description) of an existing
+    /// asset from in-memory bytes. Roblox only allows this for some asset
+    /// types, e.g. models.
+    pub async fn update_asset_bytes(&self, asset_id: u64, file_content: Vec<u8>, filename: &str, asset_type: AssetType, description: Option<&str>) -> Result<u64> {
+        let extension = Path::new(filename).extension().and_then(|s| s.to_str()).unwrap_or_default();
+        let content_type = asset_type.content_type(extension)
+            .ok_or_else(|| anyhow!("Unsupported file type for {} asset: {}", asset_type.api_name(), filename))?;
+
+        let mut request = serde_json::json!({ "assetId": asset_id.to_string() });
+        let mut url = format!("{}/assets/v1/assets/{}", BASE_URL, asset_id);
+        if let Some(description) = description {
+            request["description"] = description.into();
+            url.push_str("?updateMask=description");
+        }
+        let id = self.send_asset(Method::PATCH, &url, request.to_string(), file_content, filename, content_type).await?;
+        id.parse().with_context(|| format!("Invalid asset ID '{}' in update response", id))
+    }
+
+    /// POST a new asset to the Assets API and wait for the operation to return its ID
+    async fn create_asset(&self, request: WebAssetRequest, file_content: Vec<u8>, filename: &str, content_type: &str) -> Result<String> {
+        let url = format!("{}/assets/v1/assets", BASE_URL);
+        self.send_asset(Method::POST, &url, serde_json::to_string(&request)?, file_content, filename, content_type).await
+    }
+
+    /// Send an asset's metadata and file content to the Assets API and wait
+    /// for the operation to return the asset's ID
+    async fn send_asset(&self, method: Method, url: &str, request_json: String, file_content: Vec<u8>, filename: &str, content_type: &str) -> Result<String> {
+        // 1. Prepare Multipart
+        let filename = filename.to_string();
 
         // Try Part::bytes instead of stream_with_length
         // Use stream_with_length like Asphalt does
@@ -295,18 +424,12 @@ impl RobloxClient {
             .text("request", request_json.clone())
             .part("fileContent", file_part);
 
-        log::debug!("Asset upload URL: {}", url);
-        log::debug!("Asset upload request JSON: {}", request_json);
+        tracing::debug!("Asset upload URL: {}", url);
+        tracing::debug!("Asset upload request JSON: {}", request_json);
 
-        let response = self.client
-            .request(Method::POST, &url)
-            .header("x-api-key", &self.api_key)
-            .multipart(form)
-            .send()
-            .await?;
-        
-        let status = response.status();
-        let text = response.text().await?;
+        let response = self.send(self.request(method, url).multipart(form)).await?;
+        let status = response.status;
+        let text = response.text();
 
         if status.is_success() {
             // Parse operation response
@@ -327,7 +450,7 @@ impl RobloxClient {
             let operation: OperationResponse = serde_json::from_str(&text)
                 .context("Failed to parse operation response")?;
 
-            log::debug!("Initial operation response: {}", text);
+            tracing::debug!("Initial operation response: {}", text);
 
             // If the operation is already done, extract the asset ID
             if operation.done.unwrap_or(false) {
@@ -345,7 +468,7 @@ impl RobloxClient {
             // Poll the operation until it completes
             self.poll_operation(&operation_path).await
         } else {
-            Err(anyhow!("Asset upload failed: {} - {}", status, text))
+            Err(anyhow!("Asset upload failed: {} - {}", status, crate::redact::redact(&text)))
         }
     }
 
@@ -374,18 +497,22 @@ impl RobloxClient {
         let max_attempts = 30;
         let poll_interval = std::time::Duration::from_secs(2);
 
+        let progress = Progress::spinner("Waiting for Roblox to process the asset");
         for attempt in 1..=max_attempts {
-            log::debug!("Polling operation (attempt {}): {}", attempt, url);
+            tracing::debug!("Polling operation (attempt {}): {}", attempt, url);
+            if attempt > 1 {
+                progress.set_message(format!("attempt {}/{}", attempt, max_attempts));
+            }
 
-            let response = self.request(Method::GET, &url).send().await?;
-            let status = response.status();
-            let text = response.text().await?;
+            let response = self.send(self.request(Method::GET, &url)).await?;
+            let status = response.status;
+            let text = response.text();
 
             if !status.is_success() {
                 return Err(anyhow!("Failed to poll operation: {} - {}", status, text));
             }
 
-            log::debug!("Poll response: {}", text);
+            tracing::debug!("Poll response: {}", text);
 
             let operation: OperationResponse = serde_json::from_str(&text)
                 .context("Failed to parse operation poll response")?;
@@ -398,7 +525,7 @@ impl RobloxClient {
             if operation.done.unwrap_or(false) {
                 if let Some(resp) = operation.response {
                     if let Some(asset_id) = resp.asset_id {
-                        log::info!("Asset uploaded successfully with ID: {}", asset_id);
+                        tracing::info!("Asset uploaded successfully with ID: {}", asset_id);
                         return Ok(asset_id);
                     }
                 }
@@ -411,22 +538,339 @@ impl RobloxClient {
         Err(anyhow!("Operation polling timed out after {} attempts", max_attempts))
     }
 
+    /// Archive an asset so it no longer counts towards the creator's
+    /// inventory; archived assets can be restored from the Creator Dashboard
+    pub async fn archive_asset(&self, asset_id: u64) -> Result<()> {
+        let url = format!("{}/assets/v1/assets/{}:archive", BASE_URL, asset_id);
+        let req = self.request(Method::POST, &url).json(&serde_json::json!({}));
+        let _: serde_json::Value = self.execute(req).await?;
+        Ok(())
+    }
+
+    /// Get the moderation state of an asset ("Approved", "Reviewing" or "Rejected")
+    pub async fn get_asset_moderation_state(&self, asset_id: u64) -> Result<Option<String>> {
+        let url = format!("{}/assets/v1/assets/{}", BASE_URL, asset_id);
+        let req = self.request(Method::GET, &url).query(&[("readMask", "moderationResult")]);
+        let asset: serde_json::Value = self.execute(req).await?;
+        Ok(asset["moderationResult"]["moderationState"].as_str().map(|s| s.to_string()))
+    }
+
+    // --- Universes ---
+
+    /// Public name of a universe, from the games API
+    pub async fn get_universe_name(&self, universe_id: u64) -> Result<Option<String>> {
+        let details = self.get_universe_details(universe_id).await?;
+        Ok(details.and_then(|game| game["name"].as_str().map(|s| s.to_string())))
+    }
+
+    /// Public details of a universe (name, description, max players, ...) from the games API
+    pub async fn get_universe_details(&self, universe_id: u64) -> Result<Option<serde_json::Value>> {
+        let url = format!("{}/v1/games", GAMES_URL);
+        let req = self.client.get(&url).query(&[("universeIds", universe_id.to_string())]);
+        let mut games: serde_json::Value = self.execute(req).await?;
+        Ok(games["data"].get_mut(0).map(serde_json::Value::take))
+    }
+
+    // --- DataStores ---
+
+    /// URL of a standard data store's entries, or of one entry when `key` is set
+    fn datastore_url(&self, universe_id: u64, datastore: &str, scope: Option<&str>, key: Option<&str>) -> Result<reqwest::Url> {
+        let mut url = reqwest::Url::parse(BASE_URL)?;
+        {
+            let universe = universe_id.to_string();
+            let mut segments = url.path_segments_mut().map_err(|_| anyhow!("Invalid base URL"))?;
+            segments.extend(["cloud", "v2", "universes", universe.as_str(), "data-stores", datastore]);
+            if let Some(scope) = scope {
+                segments.extend(["scopes", scope]);
+            }
+            segments.push("entries");
+            if let Some(key) = key {
+                segments.push(key);
+            }
+        }
+        Ok(url)
+    }
+
+    /// Read a data store entry; `None` if the key doesn't exist
+    pub async fn get_datastore_entry(&self, universe_id: u64, datastore: &str, scope: Option<&str>, key: &str) -> Result<Option<serde_json::Value>> {
+        let url = self.datastore_url(universe_id, datastore, scope, Some(key))?;
+        let response = self.send(self.request(Method::GET, url.as_str())).await?;
+        if response.status == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let status = response.status;
+        let text = response.text();
+        if !status.is_success() {
+            return Err(ApiError { status, body: text }.into());
+        }
+        Ok(Some(serde_json::from_str(&text).context(format!("Failed to parse response: {}", crate::redact::redact(&text)))?))
+    }
+
+    /// Create or overwrite a data store entry. With `etag`, the write fails
+    /// if the entry changed since it was read.
+    pub async fn set_datastore_entry(&self, universe_id: u64, datastore: &str, scope: Option<&str>, key: &str, value: &serde_json::Value, etag: Option<&str>) -> Result<serde_json::Value> {
+        let url = self.datastore_url(universe_id, datastore, scope, Some(key))?;
+        let mut body = serde_json::json!({ "value": value });
+        if let Some(etag) = etag {
+            body["etag"] = serde_json::json!(etag);
+        }
+        let req = self.request(Method::PATCH, url.as_str()).query(&[("allowMissing", "true")]).json(&body);
+        self.execute(req).await
+    }
+
+    /// Create a data store entry, failing with 409 Conflict if it exists
+    pub async fn create_datastore_entry(&self, universe_id: u64, datastore: &str, scope: Option<&str>, key: &str, value: &serde_json::Value) -> Result<serde_json::Value> {
+        let url = self.datastore_url(universe_id, datastore, scope, None)?;
+        let req = self.request(Method::POST, url.as_str()).query(&[("id", key)]).json(&serde_json::json!({ "value": value }));
+        self.execute(req).await
+    }
+
+    pub async fn delete_datastore_entry(&self, universe_id: u64, datastore: &str, scope: Option<&str>, key: &str) -> Result<()> {
+        let url = self.datastore_url(universe_id, datastore, scope, Some(key))?;
+        let _: serde_json::Value = self.execute(self.request(Method::DELETE, url.as_str())).await?;
+        Ok(())
+    }
+
+    /// List one page of entry keys, optionally only those starting with `prefix`
+    pub async fn list_datastore_entries(
+        &self,
+        universe_id: u64,
+        datastore: &str,
+        scope: Option<&str>,
+        prefix: Option<&str>,
+        page_token: Option<String>,
+    ) -> Result<ListResponse<serde_json::Value>> {
+        let url = self.datastore_url(universe_id, datastore, scope, None)?;
+        let mut req = self.request(Method::GET, url.as_str()).query(&[("maxPageSize", "256")]);
+        if let Some(prefix) = prefix {
+            req = req.query(&[("filter", format!("id.startsWith({})", filter_string(prefix)))]);
+        }
+        if let Some(token) = page_token {
+            req = req.query(&[("pageToken", token)]);
+        }
+        self.execute(req).await
+    }
+
+    /// Up and down votes of a universe, from the games API
+    pub async fn get_universe_votes(&self, universe_id: u64) -> Result<Option<serde_json::Value>> {
+        let url = format!("{}/v1/games/votes", GAMES_URL);
+        let req = self.client.get(&url).query(&[("universeIds", universe_id.to_string())]);
+        let mut votes: serde_json::Value = self.execute(req).await?;
+        Ok(votes["data"].get_mut(0).map(serde_json::Value::take))
+    }
+
+    // --- Thumbnails ---
+
+    /// Look up rendered icon URLs on the public thumbnails API, e.g.
+    /// `path = "v1/badges/icons"`, `id_param = "badgeIds"`. Returns each
+    /// target's render state and, once rendered, its image URL.
+    pub async fn get_icon_thumbnails(&self, path: &str, id_param: &str, ids: &[u64]) -> Result<Vec<IconThumbnail>> {
+        let url = format!("{}/{}", THUMBNAILS_URL, path);
+        let ids: Vec<String> = ids.iter().map(|id| id.to_string()).collect();
+        let req = self.client.get(&url).query(&[(id_param, ids.join(",").as_str()), ("size", "150x150"), ("format", "Png")]);
+        let response: ThumbnailResponse = self.execute(req).await?;
+        Ok(response.data)
+    }
+
+    /// Download a rendered thumbnail image
+    pub async fn download_image(&self, url: &str) -> Result<Vec<u8>> {
+        let response = self.send(self.client.get(url)).await?;
+        if !response.status.is_success() {
+            return Err(anyhow!("Image download failed: {}", response.status));
+        }
+        Ok(response.body)
+    }
+
+    /// Shut down every running server of a universe so players rejoin on
+    /// the latest published version
+    pub async fn restart_servers(&self, universe_id: u64) -> Result<()> {
+        let url = format!("{}/cloud/v2/universes/{}:restartServers", BASE_URL, universe_id);
+        let req = self.request(Method::POST, &url).json(&serde_json::json!({}));
+        let _: serde_json::Value = self.execute(req).await?;
+        Ok(())
+    }
+
+    /// Publish a message to every live server subscribed to `topic` through MessagingService
+    pub async fn publish_message(&self, universe_id: u64, topic: &str, message: &str) -> Result<()> {
+        let url = format!("{}/cloud/v2/universes/{}:publishMessage", BASE_URL, universe_id);
+        let req = self.request(Method::POST, &url).json(&serde_json::json!({ "topic": topic, "message": message }));
+        let _: serde_json::Value = self.execute(req).await?;
+        Ok(())
+    }
+
+    // --- User Restrictions ---
+
+    /// URL of the universe's (or one place's) user restrictions, or of one user's
+    fn restrictions_url(universe_id: u64, place_id: Option<u64>, user_id: Option<u64>) -> String {
+        let mut url = format!("{}/cloud/v2/universes/{}", BASE_URL, universe_id);
+        if let Some(place_id) = place_id {
+            url.push_str(&format!("/places/{}", place_id));
+        }
+        url.push_str("/user-restrictions");
+        if let Some(user_id) = user_id {
+            url.push_str(&format!("/{}", user_id));
+        }
+        url
+    }
+
+    pub async fn list_user_restrictions(&self, universe_id: u64, place_id: Option<u64>, page_token: Option<String>) -> Result<ListResponse<serde_json::Value>> {
+        let url = Self::restrictions_url(universe_id, place_id, None);
+        let mut req = self.request(Method::GET, &url).query(&[("maxPageSize", "100")]);
+        if let Some(token) = page_token {
+            req = req.query(&[("pageToken", token)]);
+        }
+        self.execute(req).await
+    }
+
+    /// A user's restriction; inactive when the user was never banned
+    pub async fn get_user_restriction(&self, universe_id: u64, place_id: Option<u64>, user_id: u64) -> Result<serde_json::Value> {
+        let url = Self::restrictions_url(universe_id, place_id, Some(user_id));
+        self.execute(self.request(Method::GET, &url)).await
+    }
+
+    /// Set a user's `gameJoinRestriction`, banning them or lifting their ban
+    pub async fn update_user_restriction(&self, universe_id: u64, place_id: Option<u64>, user_id: u64, restriction: &serde_json::Value) -> Result<serde_json::Value> {
+        let url = Self::restrictions_url(universe_id, place_id, Some(user_id));
+        let req = self
+            .request(Method::PATCH, &url)
+            .query(&[("updateMask", "gameJoinRestriction")])
+            .json(&serde_json::json!({ "gameJoinRestriction": restriction }));
+        self.execute(req).await
+    }
+
+    // --- Experience Notifications ---
+
+    /// Send an experience notification to a user who opted in to them
+    pub async fn send_notification(&self, universe_id: u64, user_id: u64, payload: &serde_json::Value) -> Result<serde_json::Value> {
+        let url = format!("{}/cloud/v2/users/{}/notifications", BASE_URL, user_id);
+        let body = serde_json::json!({
+            "source": { "universe": format!("universes/{}", universe_id) },
+            "payload": payload,
+        });
+        self.execute(self.request(Method::POST, &url).json(&body)).await
+    }
+
+    // --- API Keys & Groups ---
+
+    /// Details of the API key this client uses: its owner, scopes and
+    /// expiration
+    pub async fn introspect_api_key(&self) -> Result<serde_json::Value> {
+        let url = format!("{}/api-keys/v1/introspect", BASE_URL);
+        let req = self.request(Method::POST, &url).json(&serde_json::json!({ "apiKey": self.api_key }));
+        self.execute(req).await
+    }
+
+    pub async fn get_group(&self, group_id: u64) -> Result<serde_json::Value> {
+        let url = format!("{}/cloud/v2/groups/{}", BASE_URL, group_id);
+        self.execute(self.request(Method::GET, &url)).await
+    }
+
+    /// A user's membership in a group, or None when they are not a member
+    pub async fn get_group_membership(&self, group_id: u64, user_id: u64) -> Result<Option<serde_json::Value>> {
+        let url = format!("{}/cloud/v2/groups/{}/memberships", BASE_URL, group_id);
+        let filter = format!("user == 'users/{}'", user_id);
+        let resp: ListResponse<serde_json::Value> = self.execute(self.request(Method::GET, &url).query(&[("filter", filter.as_str())])).await?;
+        Ok(resp.data.into_iter().next())
+    }
+
+    /// A group role by its path (`groups/<id>/roles/<id>`), including its
+    /// permissions
+    pub async fn get_group_role(&self, role_path: &str) -> Result<serde_json::Value> {
+        let url = format!("{}/cloud/v2/{}", BASE_URL, role_path);
+        self.execute(self.request(Method::GET, &url)).await
+    }
+
+    // --- Luau Execution ---
+
+    /// Run `script` in a fresh server of `place_id` (at `version`, or the
+    /// latest) and wait for it to finish, up to `timeout_secs`
+    pub async fn run_luau(&self, universe_id: u64, place_id: u64, version: Option<u64>, script: &str, timeout_secs: u64) -> Result<LuauTask> {
+        let mut url = format!("{}/cloud/v2/universes/{}/places/{}", BASE_URL, universe_id, place_id);
+        if let Some(version) = version {
+            url.push_str(&format!("/versions/{}", version));
+        }
+        url.push_str("/luau-execution-session-tasks");
+        let body = serde_json::json!({ "script": script, "timeout": format!("{}s", timeout_secs) });
+        let mut task: LuauTask = self.execute(self.request(Method::POST, &url).json(&body)).await?;
+        tracing::debug!("Created Luau execution task {}", task.path);
+
+        // Tasks can sit in the queue before the timeout starts counting
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(timeout_secs + 120);
+        while !task.is_finished() {
+            if std::time::Instant::now() > deadline {
+                return Err(anyhow!("Luau execution task {} did not finish in time (state: {})", task.path, task.state));
+            }
+            tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+            let url = format!("{}/cloud/v2/{}", BASE_URL, task.path);
+            task = self.execute(self.request(Method::GET, &url)).await?;
+        }
+        Ok(task)
+    }
+
+    /// Log lines printed by a Luau execution task
+    pub async fn get_luau_task_logs(&self, task_path: &str) -> Result<Vec<String>> {
+        let url = format!("{}/cloud/v2/{}/logs", BASE_URL, task_path);
+        let mut lines = Vec::new();
+        let mut page_token: Option<String> = None;
+        loop {
+            let mut req = self.request(Method::GET, &url);
+            if let Some(token) = &page_token {
+                req = req.query(&[("pageToken", token)]);
+            }
+            let page: serde_json::Value = self.execute(req).await?;
+            for log in page["luauExecutionSessionTaskLogs"].as_array().into_iter().flatten() {
+                lines.extend(log["messages"].as_array().into_iter().flatten().filter_map(|m| m.as_str().map(|m| m.to_string())));
+            }
+            page_token = page["nextPageToken"].as_str().filter(|t| !t.is_empty()).map(|t| t.to_string());
+            if page_token.is_none() {
+                return Ok(lines);
+            }
+        }
+    }
+
     // --- Places ---
 
     pub async fn publish_place(&self, universe_id: u64, place_id: u64, file_path: &Path) -> Result<serde_json::Value> {
         let url = format!("{}/v1/universes/{}/places/{}/versions", BASE_URL, universe_id, place_id);
         
-        let file_content = tokio::fs::read(file_path).await?;
         let _version_type = "Published"; // or Saved
-        
-        self.client.post(&url)
-            .header("x-api-key", &self.api_key)
+
+        // Streamed from disk in chunks, so large places don't have to fit in
+        // memory, counting each chunk as it's sent
+        let file = tokio::fs::File::open(file_path).await.with_context(|| format!("Failed to open {}", file_path.display()))?;
+        let length = file.metadata().await?.len();
+        let progress = Progress::bytes(format!("Uploading place {}", place_id), length);
+        let sent = progress.clone();
+        let body = ReaderStream::with_capacity(file, UPLOAD_CHUNK_SIZE).map(move |chunk| {
+            if let Ok(chunk) = &chunk {
+                sent.inc(chunk.len() as u64);
+            }
+            chunk
+        });
+        let req = self.request(Method::POST, &url)
             .query(&[("versionType", "Published")])
             .header("Content-Type", "application/octet-stream")
-            .body(file_content)
-            .send()
-            .await?
-            .json().await.map_err(|e| anyhow::anyhow!(e))
+            .header("Content-Length", length)
+            .body(reqwest::Body::wrap_stream(body));
+        let response = self.send(req).await?;
+        progress.finish();
+        if !response.status.is_success() {
+            return Err(PublishError { status: response.status, body: response.text() }.into());
+        }
+        serde_json::from_slice(&response.body).map_err(|e| anyhow::anyhow!(e))
+    }
+
+    /// Number of the newest version of a place, from the Assets API (a
+    /// place is an asset), or `None` when it has no versions yet
+    pub async fn get_latest_place_version(&self, place_id: u64) -> Result<Option<u64>> {
+        let url = format!("{}/assets/v1/assets/{}/versions", BASE_URL, place_id);
+        let page: serde_json::Value = self.execute(self.request(Method::GET, &url).query(&[("maxPageSize", "1")])).await?;
+        // Newest first; paths look like `assets/123/versions/4`
+        Ok(page["assetVersions"]
+            .get(0)
+            .and_then(|version| version["path"].as_str())
+            .and_then(|path| path.rsplit('/').next())
+            .and_then(|number| number.parse().ok()))
     }
 }
 
@@ -440,6 +884,7 @@ pub struct RobloxCookieClient {
 
 impl RobloxCookieClient {
     pub fn new(cookie: String) -> Self {
+        crate::redact::register(&cookie);
         Self {
             client: Client::new(),
             cookie,
@@ -462,7 +907,8 @@ impl RobloxCookieClient {
             // Get the CSRF token from the response header
             if let Some(token) = response.headers().get("x-csrf-token") {
                 let token_str = token.to_str().unwrap_or_default().to_string();
-                log::debug!("Got CSRF token from 403 response: {}", token_str);
+                crate::redact::register(&token_str);
+                tracing::debug!("Got CSRF token from 403 response: {}", token_str);
                 
                 // Store the token
                 if let Ok(mut csrf) = self.csrf_token.write() {
@@ -470,6 +916,8 @@ impl RobloxCookieClient {
                 }
                 
                 // Retry the request with the token
+                #[cfg(feature = "otel")]
+                crate::telemetry::count_retry("csrf_token");
                 let retry_response = self.send_request(method, url, body).await?;
                 return self.handle_response(retry_response).await;
             }
@@ -484,6 +932,7 @@ impl RobloxCookieClient {
         url: &str,
         body: Option<&serde_json::Value>,
     ) -> Result<reqwest::Response> {
+        count_call();
         let mut req = self.client
             .request(method, url)
             .header("Cookie", format!(".ROBLOSECURITY={}", self.cookie))
@@ -507,10 +956,10 @@ impl RobloxCookieClient {
         let status = response.status();
         let text = response.text().await.unwrap_or_default();
         
-        log::debug!("Cookie API response status: {}, body: {}", status, text);
+        tracing::debug!("Cookie API response status: {}, body: {}", status, text);
         
         if !status.is_success() {
-            return Err(anyhow!("API request failed: {} - {}", status, text));
+            return Err(ApiError { status, body: text }.into());
         }
         
         if text.is_empty() || text.trim().is_empty() {
@@ -519,7 +968,7 @@ impl RobloxCookieClient {
             }
         }
         
-        serde_json::from_str(&text).context(format!("Failed to parse response: {}", text))
+        serde_json::from_str(&text).context(format!("Failed to parse response: {}", crate::redact::redact(&text)))
     }
 
     /// Update universe configuration via develop.roblox.com API
@@ -530,30 +979,42 @@ impl RobloxCookieClient {
         settings: &serde_json::Value,
     ) -> Result<serde_json::Value> {
         let url = format!("https://develop.roblox.com/v2/universes/{}/configuration", universe_id);
-        log::debug!("Making PATCH request to: {}", url);
-        log::debug!("Request body: {}", settings);
+        tracing::debug!("Making PATCH request to: {}", url);
+        tracing::debug!("Request body: {}", settings);
         
         self.request_with_csrf(Method::PATCH, &url, Some(settings)).await
     }
+
+    /// Make a universe public (`activate`) or private (`deactivate`)
+    pub async fn set_universe_active(&self, universe_id: u64, active: bool) -> Result<serde_json::Value> {
+        let action = if active { "activate" } else { "deactivate" };
+        let url = format!("https://develop.roblox.com/v1/universes/{}/{}", universe_id, action);
+        self.request_with_csrf(Method::POST, &url, None).await
+    }
+
+    /// Turn spatial voice chat on or off for a universe
+    pub async fn update_universe_voice(&self, universe_id: u64, enabled: bool) -> Result<serde_json::Value> {
+        let url = format!("https://voice.roblox.com/v1/settings/universe/{}", universe_id);
+        self.request_with_csrf(Method::POST, &url, Some(&serde_json::json!({ "optIn": enabled }))).await
+    }
 }
 
-/// Converts a JSON object to a HashMap suitable for form encoding
-fn json_to_form(json: &serde_json::Value) -> std::collections::HashMap<String, String> {
-    let mut form = std::collections::HashMap::new();
-    if let Some(obj) = json.as_object() {
-        for (key, value) in obj {
-            let str_value = match value {
-                serde_json::Value::String(s) => s.clone(),
-                serde_json::Value::Number(n) => n.to_string(),
-                serde_json::Value::Bool(b) => b.to_string(),
-                serde_json::Value::Null => String::new(),
-                // For arrays/objects, serialize to JSON string
-                _ => value.to_string(),
-            };
-            form.insert(key.clone(), str_value);
-        }
+/// Quote `value` as a string literal in an Open Cloud `filter` expression
+fn filter_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Build the Assets API creator payload from config
+fn web_asset_creator(creator: &crate::config::CreatorConfig) -> WebAssetCreator {
+    if creator.creator_type == "group" {
+        WebAssetCreator::Group(WebAssetGroupCreator {
+            group_id: creator.id.clone(),
+        })
+    } else {
+        WebAssetCreator::User(WebAssetUserCreator {
+            user_id: creator.id.clone(),
+        })
     }
-    form
 }
 
 /// Converts a JSON object to multipart form data
@@ -574,11 +1035,117 @@ fn json_to_multipart(json: &serde_json::Value) -> reqwest::multipart::Form {
     form
 }
 
+/// A request Roblox answered with an error status
+#[derive(Debug)]
+pub struct ApiError {
+    pub status: reqwest::StatusCode,
+    pub body: String,
+}
+
+impl ApiError {
+    /// The status of a failed request, when `error` is an ApiError
+    pub fn status_of(error: &anyhow::Error) -> Option<reqwest::StatusCode> {
+        error.downcast_ref::<Self>().map(|e| e.status)
+    }
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "API request failed: {} - {}", self.status, crate::redact::redact(&self.body))
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+/// A place publish Roblox answered with an error status
+#[derive(Debug)]
+pub struct PublishError {
+    pub status: reqwest::StatusCode,
+    pub body: String,
+}
+
+impl PublishError {
+    /// Whether the same upload may succeed later: server errors, rate
+    /// limits and conflicts with a version still being processed
+    pub fn is_transient(&self) -> bool {
+        self.status.is_server_error() || self.status == reqwest::StatusCode::TOO_MANY_REQUESTS || self.is_processing()
+    }
+
+    /// Whether Roblox is still processing an earlier version of the place.
+    /// Other conflicts won't resolve by waiting, so they aren't retried.
+    pub fn is_processing(&self) -> bool {
+        let body = self.body.to_lowercase();
+        self.status == reqwest::StatusCode::CONFLICT && (body.contains("being processed") || body.contains("already in progress"))
+    }
+}
+
+impl std::fmt::Display for PublishError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Place publish failed: {} - {}", self.status, crate::redact::redact(&self.body))
+    }
+}
+
+impl std::error::Error for PublishError {}
+
+/// A Luau execution session task
+#[derive(Debug, Deserialize)]
+pub struct LuauTask {
+    /// Resource path, e.g. `universes/1/places/2/luau-execution-session-tasks/...`
+    pub path: String,
+    /// `QUEUED`, `PROCESSING`, `COMPLETE`, `FAILED` or `CANCELLED`
+    #[serde(default)]
+    pub state: String,
+    #[serde(default)]
+    pub output: Option<LuauTaskOutput>,
+    #[serde(default)]
+    pub error: Option<LuauTaskError>,
+}
+
+impl LuauTask {
+    pub fn is_finished(&self) -> bool {
+        matches!(self.state.as_str(), "COMPLETE" | "FAILED" | "CANCELLED")
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LuauTaskOutput {
+    /// Values the script returned
+    #[serde(default)]
+    pub results: Vec<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LuauTaskError {
+    #[serde(default)]
+    pub code: String,
+    #[serde(default)]
+    pub message: String,
+}
+
+/// One entry of a thumbnails API response
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IconThumbnail {
+    pub target_id: u64,
+    /// "Completed", "Pending", "Blocked" or "Error"
+    pub state: String,
+    pub image_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ThumbnailResponse {
+    data: Vec<IconThumbnail>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct ListResponse<T> {
     #[serde(alias = "gamePasses")]
     #[serde(alias = "developerProducts")]
     #[serde(alias = "badges")]
+    #[serde(alias = "dataStoreEntries")]
+    #[serde(alias = "userRestrictions")]
+    #[serde(alias = "groupMemberships")]
+    #[serde(default = "Vec::new")]
     pub data: Vec<T>,
     #[serde(alias = "nextPageCursor")]
     #[serde(alias = "nextPageToken")]
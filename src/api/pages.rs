@@ -0,0 +1,174 @@
+//! Paginated list endpoints as async streams.
+//!
+//! `client.game_passes(universe_id).stream()` yields each item as its page
+//! arrives, fetching the next page only once the previous one has been
+//! consumed, so callers can work through large catalogs without collecting
+//! them first. Items are deserialized into whatever type the caller asks
+//! for, e.g. `stream::<GamePass>()`, or `serde_json::Value` for the raw
+//! objects the endpoint returns.
+
+use super::{ListResponse, RobloxClient};
+use anyhow::{Context as _, Result};
+use serde::de::DeserializeOwned;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio_stream::Stream;
+
+type PageFuture<'a> = Pin<Box<dyn Future<Output = Result<ListResponse<serde_json::Value>>> + Send + 'a>>;
+
+#[derive(Debug, Clone, Copy)]
+enum Endpoint {
+    GamePasses { universe_id: u64 },
+    DeveloperProducts { universe_id: u64 },
+    Badges { universe_id: u64 },
+    UserRestrictions { universe_id: u64, place_id: Option<u64> },
+}
+
+/// A list endpoint of one universe, read page by page
+#[derive(Clone, Copy)]
+pub struct Pages<'a> {
+    client: &'a RobloxClient,
+    endpoint: Endpoint,
+}
+
+impl<'a> Pages<'a> {
+    /// Every item across all pages as a `T`, in the order the API returns
+    /// them. The stream ends after the first error, including an item that
+    /// doesn't deserialize.
+    pub fn stream<T: DeserializeOwned + Send + 'a>(self) -> impl Stream<Item = Result<T>> + Send + 'a {
+        PageStream { pages: self, items: Vec::new().into_iter(), pending: None, cursor: None, done: false, item: PhantomData }
+    }
+
+    fn fetch(self, cursor: Option<String>) -> PageFuture<'a> {
+        let client = self.client;
+        Box::pin(async move {
+            match self.endpoint {
+                Endpoint::GamePasses { universe_id } => client.list_game_passes(universe_id, cursor).await,
+                Endpoint::DeveloperProducts { universe_id } => client.list_developer_products(universe_id, cursor).await,
+                Endpoint::Badges { universe_id } => client.list_badges(universe_id, cursor).await,
+                Endpoint::UserRestrictions { universe_id, place_id } => client.list_user_restrictions(universe_id, place_id, cursor).await,
+            }
+        })
+    }
+}
+
+impl RobloxClient {
+    pub fn game_passes(&self, universe_id: u64) -> Pages<'_> {
+        Pages { client: self, endpoint: Endpoint::GamePasses { universe_id } }
+    }
+
+    pub fn developer_products(&self, universe_id: u64) -> Pages<'_> {
+        Pages { client: self, endpoint: Endpoint::DeveloperProducts { universe_id } }
+    }
+
+    pub fn badges(&self, universe_id: u64) -> Pages<'_> {
+        Pages { client: self, endpoint: Endpoint::Badges { universe_id } }
+    }
+
+    /// Restrictions of the universe, or of one of its places
+    pub fn user_restrictions(&self, universe_id: u64, place_id: Option<u64>) -> Pages<'_> {
+        Pages { client: self, endpoint: Endpoint::UserRestrictions { universe_id, place_id } }
+    }
+}
+
+struct PageStream<'a, T> {
+    pages: Pages<'a>,
+    /// Rest of the last fetched page
+    items: std::vec::IntoIter<serde_json::Value>,
+    pending: Option<PageFuture<'a>>,
+    cursor: Option<String>,
+    done: bool,
+    item: PhantomData<fn() -> T>,
+}
+
+impl<T: DeserializeOwned> Stream for PageStream<'_, T> {
+    type Item = Result<T>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            if let Some(item) = this.items.next() {
+                let item = serde_json::from_value(item).with_context(|| format!("Unexpected item from the {:?} listing", this.pages.endpoint));
+                if item.is_err() {
+                    this.done = true;
+                    this.items = Vec::new().into_iter();
+                }
+                return Poll::Ready(Some(item));
+            }
+            if this.done {
+                return Poll::Ready(None);
+            }
+            let (pages, cursor) = (this.pages, &mut this.cursor);
+            let pending = this.pending.get_or_insert_with(|| pages.fetch(cursor.take()));
+            let page = match pending.as_mut().poll(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(page) => page,
+            };
+            this.pending = None;
+            match page {
+                Ok(page) => {
+                    this.cursor = page.next_page_cursor.filter(|c| !c.is_empty());
+                    this.done = this.cursor.is_none();
+                    this.items = page.data.into_iter();
+                }
+                Err(e) => {
+                    this.done = true;
+                    return Poll::Ready(Some(Err(e)));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{ApiResponse, ClientOptions, Middleware};
+    use serde::Deserialize;
+    use serde_json::json;
+    use std::sync::{Arc, Mutex};
+    use tokio_stream::StreamExt;
+
+    /// Answers listing requests with the pages keyed by their page token
+    #[derive(Clone, Default)]
+    struct PagedListing(Arc<Mutex<Vec<String>>>);
+
+    impl Middleware for PagedListing {
+        fn on_request(&self, request: &mut reqwest::Request) -> Option<ApiResponse> {
+            let token = request.url().query_pairs().find(|(key, _)| key == "pageToken").map(|(_, value)| value.into_owned());
+            self.0.lock().unwrap().push(token.clone().unwrap_or_default());
+            let page = match token.as_deref() {
+                None => json!({ "developerProducts": [{ "productId": 1 }, { "productId": 2 }], "nextPageToken": "page-2" }),
+                Some("page-2") => json!({ "developerProducts": [{ "productId": 3 }], "nextPageToken": "page-3" }),
+                Some("page-3") => json!({ "developerProducts": [{ "productId": 4 }, { "productName": "no id" }], "nextPageToken": "" }),
+                Some(other) => panic!("unexpected page token {}", other),
+            };
+            Some(ApiResponse::new(request, reqwest::StatusCode::OK, page.to_string().into_bytes()))
+        }
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serde(rename_all = "camelCase")]
+    struct Product {
+        product_id: u64,
+    }
+
+    #[tokio::test]
+    async fn test_stream_follows_page_tokens() {
+        let listing = PagedListing::default();
+        let client = RobloxClient::with_options("fake-api-key".to_string(), ClientOptions::new().with_middleware(listing.clone()));
+
+        let raw: Vec<serde_json::Value> = client.developer_products(1).stream().collect::<Result<_>>().await.unwrap();
+        assert_eq!(raw.len(), 5);
+        assert_eq!(*listing.0.lock().unwrap(), ["", "page-2", "page-3"]);
+
+        // Typed items; the one that doesn't fit ends the stream with an error
+        let typed: Vec<Result<Product>> = client.developer_products(1).stream().collect().await;
+        let ids: Vec<u64> = typed.iter().filter_map(|p| p.as_ref().ok()).map(|p| p.product_id).collect();
+        assert_eq!(ids, [1, 2, 3, 4]);
+        assert_eq!(typed.len(), 5);
+        assert!(format!("{:#}", typed[4].as_ref().unwrap_err()).contains("productId"));
+    }
+}
@@ -0,0 +1,149 @@
+//! Cache of uploaded icons shared by every project on this machine.
+//!
+//! The lock file already keeps a project from uploading the same icon twice.
+//! This cache does the same across projects: when several games use the
+//! same art, it maps each icon's content hash to the asset it was uploaded
+//! as. Entries are kept per creator, as a user's asset can't be used by a
+//! group's game. `run --no-cache` bypasses it, e.g. after the cached asset
+//! was deleted or moderated, and replaces the entry with the new upload.
+//! Updates hold an OS file lock on `assets.json.lock`, so runs of several
+//! projects at once don't drop each other's entries.
+//!
+//! The cache only saves uploads, so failing to read or write it is logged
+//! and otherwise ignored.
+
+use crate::config::{user_cache_dir, CreatorConfig};
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+
+/// Asset IDs by content hash, by creator (`user:123`, `group:456`)
+type Entries = BTreeMap<String, BTreeMap<String, u64>>;
+
+/// Path of the cache: `RBLXSYNC_ASSET_CACHE`, or `rblxsync/assets.json` in
+/// the user's cache directory
+pub fn cache_path() -> Option<PathBuf> {
+    match std::env::var_os("RBLXSYNC_ASSET_CACHE") {
+        Some(path) => Some(PathBuf::from(path)),
+        None => Some(user_cache_dir()?.join("rblxsync").join("assets.json")),
+    }
+}
+
+fn creator_key(creator: &CreatorConfig) -> String {
+    format!("{}:{}", creator.creator_type, creator.id)
+}
+
+fn read(path: &Path) -> Result<Entries> {
+    if !path.exists() {
+        return Ok(Entries::new());
+    }
+    let content = fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+/// Asset a previous upload by `creator` of the icon with `hash` created
+pub fn lookup(creator: &CreatorConfig, hash: &str) -> Option<u64> {
+    let path = cache_path()?;
+    match read(&path) {
+        Ok(entries) => entries.get(&creator_key(creator))?.get(hash).copied(),
+        Err(e) => {
+            tracing::warn!("Ignoring the asset cache: {:#}", e);
+            None
+        }
+    }
+}
+
+/// Remember that `creator` uploaded the icon with `hash` as `asset_id`
+pub fn record(creator: &CreatorConfig, hash: &str, asset_id: u64) {
+    let Some(path) = cache_path() else { return };
+    if let Err(e) = write(&path, creator, hash, asset_id) {
+        tracing::warn!("Failed to update the asset cache: {:#}", e);
+    }
+}
+
+/// Stop reusing `asset_id` for the icon with `hash`, e.g. once it was archived
+pub fn forget(hash: &str, asset_id: u64) {
+    let Some(path) = cache_path() else { return };
+    if !path.exists() {
+        return;
+    }
+    let result = locked(&path, || {
+        let mut entries = read(&path)?;
+        for assets in entries.values_mut() {
+            assets.retain(|h, id| !(h == hash && *id == asset_id));
+        }
+        save(&path, &entries)
+    });
+    if let Err(e) = result {
+        tracing::warn!("Failed to update the asset cache: {:#}", e);
+    }
+}
+
+fn write(path: &Path, creator: &CreatorConfig, hash: &str, asset_id: u64) -> Result<()> {
+    locked(path, || {
+        let mut entries = read(path).unwrap_or_default();
+        entries.entry(creator_key(creator)).or_default().insert(hash.to_string(), asset_id);
+        save(path, &entries)
+    })
+}
+
+/// Run a read-modify-write of the cache at `path` holding its lock file.
+/// Readers need no lock, as the cache is only ever replaced whole.
+fn locked<T>(path: &Path, update: impl FnOnce() -> Result<T>) -> Result<T> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let lock_path = path.with_extension("json.lock");
+    let lock = File::create(&lock_path).with_context(|| format!("Failed to open {}", lock_path.display()))?;
+    lock.lock().with_context(|| format!("Failed to lock {}", lock_path.display()))?;
+    // Released when `lock` is closed
+    update()
+}
+
+fn save(path: &Path, entries: &Entries) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    // Renamed into place so another project's run never reads half a file
+    let tmp = path.with_extension(format!("json.{}.tmp", std::process::id()));
+    fs::write(&tmp, serde_json::to_string_pretty(entries)?)?;
+    fs::rename(&tmp, path).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_asset_cache_per_creator() {
+        let path = std::env::temp_dir().join(format!("rblxsync-asset-cache-{}", std::process::id())).join("assets.json");
+        let user = CreatorConfig { id: "1".to_string(), creator_type: "user".to_string() };
+        let group = CreatorConfig { id: "1".to_string(), creator_type: "group".to_string() };
+
+        write(&path, &user, "abc", 10).unwrap();
+        write(&path, &group, "abc", 20).unwrap();
+        write(&path, &user, "def", 30).unwrap();
+        let entries = read(&path).unwrap();
+        assert_eq!(entries["user:1"]["abc"], 10);
+        assert_eq!(entries["user:1"]["def"], 30);
+        assert_eq!(entries["group:1"]["abc"], 20);
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_asset_cache_concurrent_writes() {
+        let path = std::env::temp_dir().join(format!("rblxsync-asset-cache-threads-{}", std::process::id())).join("assets.json");
+        let user = CreatorConfig { id: "1".to_string(), creator_type: "user".to_string() };
+        std::thread::scope(|scope| {
+            for i in 0..8u64 {
+                let (path, user) = (&path, &user);
+                scope.spawn(move || write(path, user, &format!("hash{}", i), i).unwrap());
+            }
+        });
+        assert_eq!(read(&path).unwrap()["user:1"].len(), 8);
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,118 @@
+//! Reads the lockfiles of Tarmac (`tarmac-manifest.toml`) and Asphalt
+//! (`asphalt.lock.toml`), so icons those tools already uploaded can be
+//! recorded in the lock file instead of uploaded again (`icons import`).
+//!
+//! Both map an image's path, relative to the lockfile, to its asset ID:
+//! Tarmac as `[inputs."<path>"] id = ...` and Asphalt before 1.0 as
+//! `[entries."<path>"] asset_id = ...`. Entries are found by shape rather
+//! than by format version, so a table with an `id` or `asset_id` counts as
+//! long as its key or `path` field names a file. Asphalt 1.x keys entries
+//! by content hash only; those can't be tied to a file and are skipped.
+
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use toml::{Table, Value};
+
+/// An image uploaded by another tool
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedAsset {
+    /// Image path, resolved against the lockfile's directory
+    pub path: PathBuf,
+    pub asset_id: u64,
+}
+
+/// Assets listed in a lockfile
+#[derive(Debug, Default)]
+pub struct Lockfile {
+    pub assets: Vec<ImportedAsset>,
+    /// Entries with an asset ID but no file path
+    pub unlinked: usize,
+}
+
+impl Lockfile {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let table: Table = toml::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))?;
+        let base = path.parent().unwrap_or(Path::new("."));
+        let mut lockfile = Self::default();
+        lockfile.collect(&table, None, base);
+        Ok(lockfile)
+    }
+
+    fn collect(&mut self, table: &Table, key: Option<&str>, base: &Path) {
+        let asset_id = ["id", "asset_id"].iter().find_map(|field| table.get(*field).and_then(Value::as_integer)).and_then(|id| u64::try_from(id).ok());
+        if let Some(asset_id) = asset_id {
+            let path = table.get("path").and_then(Value::as_str).or(key.filter(|k| looks_like_file(k)));
+            match path {
+                Some(path) => self.assets.push(ImportedAsset { path: base.join(path.replace('\\', "/")), asset_id }),
+                None => self.unlinked += 1,
+            }
+            return;
+        }
+        for (key, value) in table {
+            if let Value::Table(child) = value {
+                self.collect(child, Some(key), base);
+            }
+        }
+    }
+}
+
+fn looks_like_file(key: &str) -> bool {
+    Path::new(key).extension().is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(content: &str) -> Lockfile {
+        let dir = std::env::temp_dir().join(format!("rblxsync-asset-import-{}-{}", std::process::id(), content.len()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("lock.toml");
+        std::fs::write(&path, content).unwrap();
+        let lockfile = Lockfile::load(&path).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        lockfile
+    }
+
+    #[test]
+    fn test_lockfile_formats() {
+        let tarmac = parse(
+            r#"
+[inputs."assets/vip.png"]
+hash = "1f2e"
+id = 100
+packable = false
+
+[inputs."assets\\coins.png"]
+hash = "3c4d"
+id = 200
+"#,
+        );
+        let mut found: Vec<_> = tarmac.assets.iter().map(|a| (a.path.file_name().unwrap().to_string_lossy().to_string(), a.asset_id)).collect();
+        found.sort();
+        assert_eq!(found, [("coins.png".to_string(), 200), ("vip.png".to_string(), 100)]);
+
+        let asphalt = parse(
+            r#"
+[entries."icons/badge.png"]
+hash = "abcd"
+asset_id = 300
+"#,
+        );
+        assert_eq!(asphalt.assets.len(), 1);
+        assert!(asphalt.assets[0].path.ends_with("icons/badge.png"));
+        assert_eq!(asphalt.assets[0].asset_id, 300);
+
+        // Asphalt 1.x only has the content hash
+        let hashed = parse(
+            r#"
+version = 1
+[inputs.icons.af10c3]
+asset_id = 400
+"#,
+        );
+        assert!(hashed.assets.is_empty());
+        assert_eq!(hashed.unlinked, 1);
+    }
+}
@@ -0,0 +1,319 @@
+//! Local asset handling for icons uploaded by rblxsync.
+//!
+//! Icons are read from `assets_dir`, optionally validated and normalised
+//! according to the `image_processing` config block, and hashed so that
+//! uploads only happen when the resulting bytes change. A single source
+//! image can back several resource types; each type gets its own sized
+//! variant, cached on disk by (source hash, size).
+
+use crate::config::ImageProcessingConfig;
+use anyhow::{anyhow, Context, Result};
+use image::{imageops::FilterType, DynamicImage, GenericImageView, ImageFormat, Rgb, RgbImage, RgbaImage};
+use sha2::{Digest, Sha256};
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+/// Largest image dimension accepted by Roblox for uploaded images
+pub const MAX_IMAGE_DIMENSION: u32 = 1024;
+
+/// Default badge icon size when `image_processing.sizes.badge` is not set
+pub const DEFAULT_BADGE_ICON_SIZE: u32 = 150;
+
+/// Directory (relative to the project root) holding processed icon variants
+const ICON_CACHE_DIR: &str = ".rblxsync/cache/icons";
+
+/// The kind of resource an icon is uploaded for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IconKind {
+    GamePass,
+    DeveloperProduct,
+    Badge,
+}
+
+/// An icon ready for upload
+#[derive(Debug, Clone)]
+pub struct IconFile {
+    /// File contents (PNG when image processing is enabled)
+    pub data: Vec<u8>,
+    /// File name sent with the upload
+    pub filename: String,
+    /// SHA-256 hash of `data`
+    pub hash: String,
+}
+
+/// Target icon size for a resource type
+pub fn icon_size(opts: &ImageProcessingConfig, kind: IconKind) -> u32 {
+    match kind {
+        IconKind::GamePass => opts.sizes.game_pass.unwrap_or(opts.size),
+        IconKind::DeveloperProduct => opts.sizes.developer_product.unwrap_or(opts.size),
+        IconKind::Badge => opts.sizes.badge.unwrap_or(DEFAULT_BADGE_ICON_SIZE),
+    }
+}
+
+/// Read an icon from disk, applying image processing if configured.
+/// Processed variants are cached under `project_root`.
+pub async fn load_icon(project_root: &Path, path: &Path, processing: Option<&ImageProcessingConfig>, kind: IconKind) -> Result<IconFile> {
+    if !path.exists() {
+        return Err(anyhow!("Icon file not found: {:?}", path));
+    }
+
+    let raw = tokio::fs::read(path).await?;
+    prepare_icon(project_root, raw, path, processing, kind).await
+}
+
+/// Turn an icon's contents into an upload, applying image processing if
+/// configured. `path` names the icon in file names and errors.
+pub async fn prepare_icon(project_root: &Path, raw: Vec<u8>, path: &Path, processing: Option<&ImageProcessingConfig>, kind: IconKind) -> Result<IconFile> {
+    let filename = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+
+    let (data, filename) = match processing {
+        Some(opts) => {
+            let size = icon_size(opts, kind);
+            let data = load_variant(project_root, &raw, opts, size)
+                .await
+                .with_context(|| format!("Failed to process icon {:?}", path))?;
+            let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+            (data, format!("{}_{}.png", stem, size))
+        }
+        None => (raw, filename),
+    };
+
+    let hash = hash_bytes(&data);
+    Ok(IconFile { data, filename, hash })
+}
+
+/// Return the processed variant of `raw` at `size`, reusing a cached copy when available
+async fn load_variant(project_root: &Path, raw: &[u8], opts: &ImageProcessingConfig, size: u32) -> Result<Vec<u8>> {
+    // The limit may have changed since the variant was cached
+    check_file_size(raw, opts)?;
+    let cache_path = project_root.join(variant_cache_path(&hash_bytes(raw), size, opts.resize));
+    if let Ok(cached) = tokio::fs::read(&cache_path).await {
+        tracing::debug!("Using cached icon variant {:?}", cache_path);
+        return Ok(cached);
+    }
+
+    let data = process_image(raw, opts, size)?;
+
+    if let Some(parent) = cache_path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    if let Err(e) = tokio::fs::write(&cache_path, &data).await {
+        tracing::debug!("Failed to cache icon variant {:?}: {}", cache_path, e);
+    }
+    Ok(data)
+}
+
+fn variant_cache_path(source_hash: &str, size: u32, resize: bool) -> PathBuf {
+    let suffix = if resize { size.to_string() } else { "original".to_string() };
+    Path::new(ICON_CACHE_DIR).join(format!("{}-{}.png", source_hash, suffix))
+}
+
+/// SHA-256 of the file at `path` as a hex string, read in chunks so large
+/// place files don't have to fit in memory
+pub fn hash_file(path: &Path) -> Result<String> {
+    let mut file = std::fs::File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher).with_context(|| format!("Failed to read {}", path.display()))?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Calculate the SHA-256 hash of a byte slice as a hex string
+pub fn hash_bytes(data: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(data);
+    format!("{:x}", hasher.finalize())
+}
+
+/// Fail for a source image larger than `max_file_size_kb`
+fn check_file_size(raw: &[u8], opts: &ImageProcessingConfig) -> Result<()> {
+    if raw.len() as u64 > opts.max_file_size_kb.saturating_mul(1024) {
+        return Err(anyhow!(
+            "Image is {} KB, which exceeds the {} KB limit",
+            raw.len() / 1024,
+            opts.max_file_size_kb
+        ));
+    }
+    Ok(())
+}
+
+/// Validate an image against Roblox limits and convert it to PNG,
+/// resizing and padding it to a `size`x`size` square when `resize` is enabled.
+pub fn process_image(raw: &[u8], opts: &ImageProcessingConfig, size: u32) -> Result<Vec<u8>> {
+    check_file_size(raw, opts)?;
+
+    let format = image::guess_format(raw).context("Unrecognised image format")?;
+    if !matches!(format, ImageFormat::Png | ImageFormat::Jpeg | ImageFormat::WebP | ImageFormat::Bmp) {
+        return Err(anyhow!("Unsupported image format {:?} (use PNG, JPEG, WebP or BMP)", format));
+    }
+
+    let img = image::load_from_memory_with_format(raw, format).context("Failed to decode image")?;
+    let (width, height) = img.dimensions();
+
+    let output = if opts.resize {
+        fit_to_square(&img, size)
+    } else {
+        if width > MAX_IMAGE_DIMENSION || height > MAX_IMAGE_DIMENSION {
+            return Err(anyhow!(
+                "Image is {}x{}, which exceeds the {}x{} limit (enable image_processing.resize to scale it down)",
+                width, height, MAX_IMAGE_DIMENSION, MAX_IMAGE_DIMENSION
+            ));
+        }
+        img
+    };
+
+    let mut buf = Cursor::new(Vec::new());
+    output.write_to(&mut buf, ImageFormat::Png).context("Failed to encode PNG")?;
+    Ok(buf.into_inner())
+}
+
+/// Visual fingerprint of an image, for telling whether a live icon shows
+/// the same picture as a local file despite Roblox re-encoding and
+/// resizing it (so the content hash differs)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImageFingerprint {
+    /// Difference hash: one bit per neighbouring pixel pair of a 9x8 grayscale copy
+    dhash: u64,
+    /// Average colour, which the difference hash ignores (e.g. two flat icons)
+    mean: [u8; 3],
+}
+
+/// Most differing hash bits, and most difference per colour channel, of
+/// fingerprints of the same picture
+const FINGERPRINT_TOLERANCE: (u32, u8) = (6, 12);
+
+impl ImageFingerprint {
+    pub fn of(data: &[u8]) -> Result<Self> {
+        let img = image::load_from_memory(data).context("Failed to decode image")?.to_rgba8();
+        // Transparent areas are compared as white, as thumbnails may flatten them
+        let flattened = RgbImage::from_fn(img.width(), img.height(), |x, y| {
+            let [r, g, b, a] = img.get_pixel(x, y).0;
+            let over_white = |c: u8| ((u32::from(c) * u32::from(a) + 255 * (255 - u32::from(a))) / 255) as u8;
+            Rgb([over_white(r), over_white(g), over_white(b)])
+        });
+
+        let small = image::imageops::resize(&flattened, 9, 8, FilterType::Triangle);
+        let luma = |x: u32, y: u32| {
+            let [r, g, b] = small.get_pixel(x, y).0;
+            299 * u32::from(r) + 587 * u32::from(g) + 114 * u32::from(b)
+        };
+        let mut dhash = 0u64;
+        for y in 0..8 {
+            for x in 0..8 {
+                dhash = (dhash << 1) | u64::from(luma(x, y) > luma(x + 1, y));
+            }
+        }
+
+        let pixels = small.pixels().count() as u32;
+        let mut sums = [0u32; 3];
+        for pixel in small.pixels() {
+            for (sum, c) in sums.iter_mut().zip(pixel.0) {
+                *sum += u32::from(c);
+            }
+        }
+        Ok(Self { dhash, mean: sums.map(|sum| (sum / pixels) as u8) })
+    }
+
+    pub fn matches(&self, other: &Self) -> bool {
+        let (max_bits, max_channel) = FINGERPRINT_TOLERANCE;
+        (self.dhash ^ other.dhash).count_ones() <= max_bits
+            && self.mean.iter().zip(other.mean).all(|(a, b)| a.abs_diff(b) <= max_channel)
+    }
+}
+
+/// Whether two encoded images show the same picture
+pub fn looks_identical(a: &[u8], b: &[u8]) -> Result<bool> {
+    if a == b {
+        return Ok(true);
+    }
+    Ok(ImageFingerprint::of(a)?.matches(&ImageFingerprint::of(b)?))
+}
+
+/// Scale an image to fit within `size`x`size` and pad it with transparency
+pub(crate) fn fit_to_square(img: &DynamicImage, size: u32) -> DynamicImage {
+    let scaled = img.resize(size, size, FilterType::Lanczos3);
+    let (w, h) = scaled.dimensions();
+    if w == size && h == size {
+        return DynamicImage::ImageRgba8(scaled.to_rgba8());
+    }
+
+    let mut canvas = RgbaImage::new(size, size);
+    let x = (size - w) / 2;
+    let y = (size - h) / 2;
+    image::imageops::overlay(&mut canvas, &scaled.to_rgba8(), x as i64, y as i64);
+    DynamicImage::ImageRgba8(canvas)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(img: DynamicImage, format: ImageFormat) -> Vec<u8> {
+        let mut buf = Cursor::new(Vec::new());
+        img.write_to(&mut buf, format).unwrap();
+        buf.into_inner()
+    }
+
+    fn opts(resize: bool) -> ImageProcessingConfig {
+        ImageProcessingConfig { resize, size: 512, sizes: Default::default(), max_file_size_kb: 20480 }
+    }
+
+    #[test]
+    fn test_process_image_pads_to_square_png() {
+        let raw = encode(DynamicImage::new_rgb8(300, 150), ImageFormat::Jpeg);
+        let out = process_image(&raw, &opts(true), 512).unwrap();
+        assert_eq!(image::guess_format(&out).unwrap(), ImageFormat::Png);
+        let img = image::load_from_memory(&out).unwrap();
+        assert_eq!(img.dimensions(), (512, 512));
+    }
+
+    #[test]
+    fn test_icon_size_per_kind() {
+        let mut config = opts(true);
+        assert_eq!(icon_size(&config, IconKind::GamePass), 512);
+        assert_eq!(icon_size(&config, IconKind::Badge), DEFAULT_BADGE_ICON_SIZE);
+        config.sizes.developer_product = Some(256);
+        assert_eq!(icon_size(&config, IconKind::DeveloperProduct), 256);
+    }
+
+    #[test]
+    fn test_looks_identical() {
+        let picture = DynamicImage::ImageRgb8(RgbImage::from_fn(512, 512, |x, y| {
+            if (x / 64 + y / 128) % 2 == 0 { Rgb([200, 40, 40]) } else { Rgb([20, 20, 120]) }
+        }));
+        let original = encode(picture.clone(), ImageFormat::Png);
+        // As the thumbnails API serves it: smaller and re-encoded
+        let thumbnail = encode(picture.resize(150, 150, FilterType::Lanczos3), ImageFormat::Jpeg);
+        assert!(looks_identical(&original, &thumbnail).unwrap());
+
+        let other = encode(picture.rotate90(), ImageFormat::Png);
+        assert!(!looks_identical(&original, &other).unwrap());
+        let red = encode(DynamicImage::ImageRgb8(RgbImage::from_pixel(64, 64, Rgb([255, 0, 0]))), ImageFormat::Png);
+        let blue = encode(DynamicImage::ImageRgb8(RgbImage::from_pixel(64, 64, Rgb([0, 0, 255]))), ImageFormat::Png);
+        assert!(!looks_identical(&red, &blue).unwrap());
+    }
+
+    #[test]
+    fn test_process_image_rejects_oversized_without_resize() {
+        let raw = encode(DynamicImage::new_rgba8(2048, 2048), ImageFormat::Png);
+        assert!(process_image(&raw, &opts(false), 512).is_err());
+    }
+
+    #[test]
+    fn test_process_image_rejects_large_files() {
+        let raw = encode(DynamicImage::new_rgba8(64, 64), ImageFormat::Png);
+        let limits = ImageProcessingConfig { max_file_size_kb: 0, ..opts(true) };
+        assert!(process_image(&raw, &limits, 512).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_cached_variants_are_per_project_and_size_checked() {
+        let root = std::env::temp_dir().join(format!("rblxsync-assets-{}", std::process::id()));
+        let raw = encode(DynamicImage::new_rgba8(64, 64), ImageFormat::Png);
+        load_variant(&root, &raw, &opts(true), 128).await.unwrap();
+        assert!(root.join(variant_cache_path(&hash_bytes(&raw), 128, true)).is_file());
+
+        let limits = ImageProcessingConfig { max_file_size_kb: 0, ..opts(true) };
+        assert!(load_variant(&root, &raw, &limits, 128).await.is_err());
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
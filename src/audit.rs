@@ -0,0 +1,175 @@
+//! Local audit log of applied changes.
+//!
+//! After every command that changes something on Roblox, each changed
+//! field is appended to `.rblxsync/audit.log` as one JSON line: when, what,
+//! old and new value, who ran it and with which API key. Unlike the run
+//! manifest, which only holds the last run, the log keeps growing, so
+//! `rblxsync history` can answer who changed a price and when. Dry runs and
+//! commands that only touch local files (`pull`, `state`) aren't logged.
+
+use crate::report::{ChangeAction, RunReport};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Commands whose changes stay on disk
+const LOCAL_COMMANDS: [&str; 2] = ["pull", "state"];
+
+/// One applied field change
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// Unix timestamp (seconds)
+    pub timestamp: u64,
+    pub command: String,
+    pub actor: Option<String>,
+    /// Fingerprint of the API key used (see `credentials::fingerprint`)
+    pub key_fingerprint: Option<String>,
+    pub resource_type: String,
+    pub name: String,
+    pub id: Option<u64>,
+    pub action: ChangeAction,
+    /// None for changes without field detail, like a place publish
+    pub field: Option<String>,
+    pub old: Value,
+    pub new: Value,
+}
+
+/// Which entries `history` shows
+#[derive(Debug, Clone, Default)]
+pub struct HistoryFilter {
+    /// Resource name or ID
+    pub name: Option<String>,
+    pub resource_type: Option<String>,
+    pub field: Option<String>,
+    /// Substring of the actor
+    pub actor: Option<String>,
+}
+
+impl HistoryFilter {
+    pub fn matches(&self, entry: &AuditEntry) -> bool {
+        let name = self.name.as_ref().is_none_or(|name| {
+            entry.name.eq_ignore_ascii_case(name) || entry.id.is_some_and(|id| id.to_string() == *name)
+        });
+        let resource_type = self.resource_type.as_ref().is_none_or(|t| entry.resource_type == *t);
+        let field = self.field.as_ref().is_none_or(|f| entry.field.as_ref() == Some(f));
+        let actor = self.actor.as_ref().is_none_or(|a| entry.actor.as_deref().unwrap_or_default().contains(a.as_str()));
+        name && resource_type && field && actor
+    }
+}
+
+pub fn log_path(project_root: &Path) -> PathBuf {
+    project_root.join(".rblxsync").join("audit.log")
+}
+
+/// Entries for the changes `report` applied
+pub fn entries(report: &RunReport, timestamp: u64) -> Vec<AuditEntry> {
+    if report.dry_run || LOCAL_COMMANDS.contains(&report.command.as_str()) {
+        return Vec::new();
+    }
+    let applied = report.changes.iter().filter(|c| !matches!(c.action, ChangeAction::Drift | ChangeAction::Download));
+    let mut entries = Vec::new();
+    for change in applied {
+        let entry = |field: Option<&str>, old: &Value, new: &Value| AuditEntry {
+            timestamp,
+            command: report.command.clone(),
+            actor: report.actor.clone(),
+            key_fingerprint: report.key_fingerprint.clone(),
+            resource_type: change.resource_type.clone(),
+            name: change.name.clone(),
+            id: change.id,
+            action: change.action,
+            field: field.map(|f| f.to_string()),
+            old: old.clone(),
+            new: new.clone(),
+        };
+        if change.fields.is_empty() {
+            entries.push(entry(None, &Value::Null, &Value::Null));
+        }
+        for field in &change.fields {
+            entries.push(entry(Some(&field.field), &field.old, &field.new));
+        }
+    }
+    entries
+}
+
+/// Append the changes `report` applied to the audit log, returning how many
+/// entries were written
+pub fn append(project_root: &Path, report: &RunReport) -> Result<usize> {
+    let entries = entries(report, crate::actor::now());
+    if entries.is_empty() {
+        return Ok(0);
+    }
+    let path = log_path(project_root);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut lines = String::new();
+    for entry in &entries {
+        lines.push_str(&serde_json::to_string(entry)?);
+        lines.push('\n');
+    }
+    let mut file = crate::security::private_file().create(true).append(true).open(&path).with_context(|| format!("Failed to open {}", path.display()))?;
+    // One write per run keeps concurrent appends from interleaving lines
+    file.write_all(lines.as_bytes()).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(entries.len())
+}
+
+/// Every entry in the audit log, oldest first. Unreadable lines (e.g. from a
+/// run killed mid-write) are skipped with a warning.
+pub fn read(project_root: &Path) -> Result<Vec<AuditEntry>> {
+    let path = log_path(project_root);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let mut entries = Vec::new();
+    for (i, line) in content.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+        match serde_json::from_str(line) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => tracing::warn!("Skipping line {} of {}: {}", i + 1, path.display(), e),
+        }
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::ChangeSet;
+
+    #[test]
+    fn test_audit_log() {
+        let root = std::env::temp_dir().join(format!("rblxsync-audit-{}", std::process::id()));
+        let mut report = RunReport::new("run");
+        report.actor = Some("github-actions:octocat".to_string());
+        report.key_fingerprint = Some("ab12cd34ef56".to_string());
+        let mut changes = ChangeSet::default();
+        changes.push("price", 100, 150);
+        changes.push("description", "Old", "New");
+        report.record("game_pass", "VIP", Some(42), ChangeAction::Update, changes);
+        report.record("place", "place.rbxl", Some(7), ChangeAction::Publish, ChangeSet::default());
+        report.record("badge", "Winner", None, ChangeAction::Drift, ChangeSet::default());
+
+        assert_eq!(append(&root, &report).unwrap(), 3);
+        assert_eq!(append(&root, &report).unwrap(), 3);
+        let entries = read(&root).unwrap();
+        assert_eq!(entries.len(), 6);
+        assert_eq!(entries[0].field.as_deref(), Some("price"));
+        assert_eq!(entries[0].old, serde_json::json!(100));
+        assert_eq!(entries[0].new, serde_json::json!(150));
+        assert_eq!(entries[2].field, None);
+
+        let filter = HistoryFilter { name: Some("vip".to_string()), field: Some("price".to_string()), ..Default::default() };
+        assert_eq!(entries.iter().filter(|e| filter.matches(e)).count(), 2);
+        let filter = HistoryFilter { name: Some("7".to_string()), actor: Some("octocat".to_string()), ..Default::default() };
+        assert_eq!(entries.iter().filter(|e| filter.matches(e)).count(), 2);
+
+        report.dry_run = true;
+        assert!(super::entries(&report, 0).is_empty());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -1,496 +1,2631 @@
-use crate::api::{RobloxClient, RobloxCookieClient};
-use crate::config::{RblxSyncConfig, PrivateServerCost};
+use crate::actor;
+use crate::api::{Pages, RobloxClient, RobloxCookieClient};
+use crate::asset_cache;
+use crate::asset_import;
+use crate::assets::{self, IconFile, IconKind};
+use crate::audit;
+use crate::datastore;
+use crate::credentials;
+use crate::config::{AssetConfig, AssetType, AvatarScales, ConfigFormat, RestrictionConfig, CreatorConfig, RblxSyncConfig, PrivateServerCost, SplitOutputConfig, user_config_path};
+use crate::fetch::{ResourceKind, ResumableFetch};
+use crate::groups;
+use crate::i18n::{self, Msg, Section};
+use crate::init;
+use crate::mantle;
+use crate::merge::{self, MergeField};
+use crate::filter::{NameFilter, ResourceAddress, SyncSection};
+use crate::hooks::{self, HookStage};
+use crate::icon_template;
+use crate::naming;
 use crate::output;
-use crate::state::{SyncState, ResourceState, UniverseState};
-use anyhow::{anyhow, Result};
-use log::{info, warn, error};
-use sha2::{Digest, Sha256};
-use std::path::Path;
+use crate::progress::Progress;
+use crate::prompt::{self, Confirmation};
+use crate::pull;
+use crate::report::{ChangeAction, ChangeSet, OutputMode, RunReport};
+use crate::scopes;
+use crate::snapshot::{self, Snapshot, SnapshotWriter};
+use crate::template;
+use crate::text_check::{self, PlannedText};
+use crate::state::{SyncState, ResourceState, UniverseState, AssetState, EntryKind, IconSource, PendingCheck, PromotionState, SyncStamp, TestPublish, TimedBan};
+use anyhow::{anyhow, Context, Result};
+use tracing::{info, info_span, warn, error, Instrument};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use tokio_stream::StreamExt;
 use std::collections::{HashMap, HashSet};
 
-/// Validate the configuration for errors (including case-insensitive duplicate names)
+/// Guard against syncing the wrong game when `--universe-id` overrides the
+/// configured universe. The override must be repeated with
+/// `--confirm-universe`, or confirmed by typing the universe's name.
+pub async fn confirm_universe_override(client: &RobloxClient, configured: u64, target: u64, confirmation: Option<u64>, interactive: bool) -> Result<()> {
+    match confirmation {
+        Some(id) if id == target => return Ok(()),
+        Some(id) => return Err(anyhow!("--confirm-universe {} does not match --universe-id {}", id, target)),
+        None => {}
+    }
+    if !interactive {
+        return Err(anyhow!(
+            "--universe-id {} overrides configured universe {}; pass --confirm-universe {} to apply changes to it",
+            target,
+            configured,
+            target
+        ));
+    }
+
+    let expected = match client.get_universe_name(target).await {
+        Ok(Some(name)) => name,
+        Ok(None) | Err(_) => target.to_string(),
+    };
+    warn!("--universe-id {} overrides configured universe {}", target, configured);
+    if prompt::confirm_typed(&format!("Type '{}' to continue:", expected), &expected)? {
+        Ok(())
+    } else {
+        Err(anyhow!("Universe confirmation did not match; aborting"))
+    }
+}
+
+/// Refuse to change universe `target` with a lock file holding another
+/// universe's IDs, which would be applied to the wrong resources. A named
+/// universe (`--universe`) keeps a lock file of its own.
+pub fn check_lock_universe(state: &SyncState, configured: u64, target: u64) -> Result<()> {
+    match state.owner(configured) {
+        Some(owner) if owner != target => Err(anyhow!(
+            "The lock file holds IDs from universe {}, not {}; declare the universe under `universes` and select it with --universe to give it its own lock file",
+            owner,
+            target
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Longest name Roblox accepts for passes, products, badges and assets
+const MAX_NAME_LENGTH: usize = 50;
+/// Longest description Roblox accepts for universes, passes, products and badges
+const MAX_DESCRIPTION_LENGTH: usize = 1000;
+/// Highest price Roblox accepts for game passes and developer products
+const MAX_PRICE: u32 = 1_000_000_000;
+/// Largest server size Roblox allows
+const MAX_SERVER_SIZE: u32 = 700;
+/// Prices Roblox accepts for paid access
+const PAID_ACCESS_PRICES: std::ops::RangeInclusive<u32> = 25..=1000;
+/// Lowest price for paid private servers
+const MIN_PRIVATE_SERVER_PRICE: u32 = 10;
+/// Longest ban reason shown to the user
+const MAX_DISPLAY_REASON_LENGTH: usize = 400;
+/// Longest ban reason kept for moderators
+const MAX_PRIVATE_REASON_LENGTH: usize = 1000;
+
+/// Validate the configuration for errors (including case-insensitive duplicate names, aliases and naming conventions)
 pub fn validate(config: &RblxSyncConfig) -> Result<()> {
+    let problems = config_problems(config);
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow!("{}", problems.join("\n")))
+    }
+}
+
+/// Every semantic problem in the config, so they can be reported at once
+pub fn config_problems(config: &RblxSyncConfig) -> Vec<String> {
+    let mut problems = Vec::new();
+    let mut check = |result: Result<()>| {
+        if let Err(e) = result {
+            problems.push(e.to_string());
+        }
+    };
+
     // Check for duplicate game pass names (case-insensitive)
-    let game_pass_names: Vec<&str> = config.game_passes.iter().map(|p| p.name.as_str()).collect();
-    check_for_duplicates(&game_pass_names, "game pass")?;
+    let game_pass_names: Vec<&str> = config.game_passes.iter()
+        .flat_map(|p| std::iter::once(p.name.as_str()).chain(p.aliases.iter().map(|a| a.as_str())))
+        .collect();
+    check(check_for_duplicates(&game_pass_names, "game pass"));
     
     // Check for duplicate developer product names (case-insensitive)
-    let product_names: Vec<&str> = config.developer_products.iter().map(|p| p.name.as_str()).collect();
-    check_for_duplicates(&product_names, "developer product")?;
+    let product_names: Vec<&str> = config.developer_products.iter()
+        .flat_map(|p| std::iter::once(p.name.as_str()).chain(p.aliases.iter().map(|a| a.as_str())))
+        .collect();
+    check(check_for_duplicates(&product_names, "developer product"));
     
     // Check for duplicate badge names (case-insensitive)
-    let badge_names: Vec<&str> = config.badges.iter().map(|b| b.name.as_str()).collect();
-    check_for_duplicates(&badge_names, "badge")?;
-    
-    Ok(())
-}
+    let badge_names: Vec<&str> = config.badges.iter()
+        .flat_map(|b| std::iter::once(b.name.as_str()).chain(b.aliases.iter().map(|a| a.as_str())))
+        .collect();
+    check(check_for_duplicates(&badge_names, "badge"));
 
-pub async fn run(config: RblxSyncConfig, mut state: SyncState, client: RobloxClient, cookie_client: Option<RobloxCookieClient>, dry_run: bool) -> Result<()> {
-    info!("Starting sync... (dry_run: {})", dry_run);
+    // Check that pinned IDs are unique per resource type
+    check(check_for_duplicate_ids(&config.game_passes.iter().filter_map(|p| p.id).collect::<Vec<_>>(), "game pass"));
+    check(check_for_duplicate_ids(&config.developer_products.iter().filter_map(|p| p.id).collect::<Vec<_>>(), "developer product"));
+    check(check_for_duplicate_ids(&config.badges.iter().filter_map(|b| b.id).collect::<Vec<_>>(), "badge"));
 
-    // Validate config before proceeding
-    validate(&config)?;
-    
-    let universe_id = config.universe.id;
-
-    // Update Universe Settings (requires cookie client)
-    if config.universe.has_settings() {
-        if let Some(ref cookie_client) = cookie_client {
-            sync_universe_settings(universe_id, &config, &mut state, cookie_client, dry_run).await?;
-        }
-    }
+    // Check for duplicate asset names (case-insensitive)
+    let asset_names: Vec<&str> = config.assets.iter().map(|a| a.name.as_str()).collect();
+    check(check_for_duplicates(&asset_names, "asset"));
 
-    // 2. Sync Resources
-    sync_game_passes(universe_id, &config, &mut state, &client, dry_run).await?;
-    sync_developer_products(universe_id, &config, &mut state, &client, dry_run).await?;
-    sync_badges(universe_id, &config, &mut state, &client, dry_run).await?;
+    check(naming::check(config));
 
-    // Save state
-    if !dry_run {
-        let root = std::env::current_dir()?;
-        state.save(&root)?;
-    } else {
-        info!("Dry Run: Would save state.");
+    if let Some(split) = &config.split_output {
+        for field in split.visibility.keys() {
+            if !SplitOutputConfig::FIELDS.contains(&field.as_str()) {
+                problems.push(format!(
+                    "Unknown split_output visibility field '{}'. Valid fields: {}",
+                    field,
+                    SplitOutputConfig::FIELDS.join(", ")
+                ));
+            }
+        }
     }
 
-    // Generate output config file if output_path is specified
-    if let Some(output_path) = &config.output_path {
-        if dry_run {
-            info!("Dry Run: Would generate config file at {}", output_path);
-        } else {
-            output::generate_config(&state, config.universe.id, output_path)?;
+    if let Some(max) = config.max_price_change_percent {
+        if max.is_nan() || max < 0.0 {
+            problems.push(format!("max_price_change_percent must be a non-negative number, got {}", max));
         }
     }
 
-    info!("Sync complete!");
-    Ok(())
-}
-
-pub async fn publish(config: RblxSyncConfig, client: RobloxClient) -> Result<()> {
-    let universe_id = config.universe.id;
-
-    for place in config.places {
-        if place.publish {
-            info!("Publishing place {} from {}", place.place_id, place.file_path);
-            let path = Path::new(&place.file_path);
-            if !path.exists() {
-                error!("File not found: {}", place.file_path);
-                continue;
+    if let Some(hooks) = &config.hooks {
+        for (stage, commands) in [("pre_sync", &hooks.pre_sync), ("post_sync", &hooks.post_sync), ("post_publish", &hooks.post_publish)] {
+            if commands.iter().any(|c| c.trim().is_empty()) {
+                problems.push(format!("hooks.{} contains an empty command", stage));
             }
-            match client.publish_place(universe_id, place.place_id, path).await {
-                Ok(_) => info!("Published place {}", place.place_id),
-                Err(e) => error!("Failed to publish place {}: {}", place.place_id, e),
+        }
+        if let Some(hook) = &hooks.publish_message {
+            if let Err(e) = check_message(&hook.topic, "") {
+                problems.push(format!("hooks.publish_message: {}", e));
             }
         }
     }
-    Ok(())
-}
 
-async fn sync_universe_settings(universe_id: u64, config: &RblxSyncConfig, state: &mut SyncState, cookie_client: &RobloxCookieClient, dry_run: bool) -> Result<()> {
-    info!("Syncing Universe Settings...");
-    
-    // Build the current desired state from config
-    // Convert private_server_cost to state string for comparison
-    let private_server_cost_state = config.universe.private_server_cost.as_ref().map(|c| match c {
-        PrivateServerCost::Disabled => "disabled".to_string(),
-        PrivateServerCost::Free => "0".to_string(),
-        PrivateServerCost::Paid(cost) => cost.to_string(),
-    });
-    
-    let desired_state = UniverseState {
-        name: config.universe.name.clone(),
-        description: config.universe.description.clone(),
-        genre: config.universe.genre.clone(),
-        playable_devices: config.universe.playable_devices.clone(),
-        max_players: config.universe.max_players,
-        private_server_cost: private_server_cost_state.clone(),
-    };
-    
-    // Check for diffs against stored state
-    let stored_state = state.universe.as_ref();
-    let mut changes: Vec<&str> = Vec::new();
-    
-    if stored_state.map(|s| &s.name) != Some(&desired_state.name) && desired_state.name.is_some() {
-        changes.push("name");
+    if let Some(creator) = &config.creator {
+        if creator.creator_type != "user" && creator.creator_type != "group" {
+            problems.push(format!("creator.type must be \"user\" or \"group\", got \"{}\"", creator.creator_type));
+        }
+        if creator.id.parse::<u64>().is_err() {
+            problems.push(format!("creator.id must be a numeric user or group ID, got \"{}\"", creator.id));
+        }
     }
-    if stored_state.map(|s| &s.description) != Some(&desired_state.description) && desired_state.description.is_some() {
-        changes.push("description");
+    if let Some(source) = &config.badge_payment_source {
+        if source != "user" && source != "group" {
+            problems.push(format!("badge_payment_source must be \"user\" or \"group\", got \"{}\"", source));
+        }
     }
-    if stored_state.map(|s| &s.playable_devices) != Some(&desired_state.playable_devices) && desired_state.playable_devices.is_some() {
-        changes.push("playable_devices");
+
+    for pass in &config.game_passes {
+        match pass.price {
+            Some(0) if pass.is_for_sale != Some(false) => problems.push(format!("game pass '{}': price must be at least 1 Robux while it is for sale", pass.name)),
+            None if pass.is_for_sale == Some(true) => problems.push(format!("game pass '{}': a price is required while it is for sale", pass.name)),
+            Some(price) if price > MAX_PRICE => problems.push(format!("game pass '{}': price {} exceeds the maximum of {} Robux", pass.name, price, MAX_PRICE)),
+            _ => {}
+        }
     }
-    if stored_state.map(|s| &s.private_server_cost) != Some(&desired_state.private_server_cost) && desired_state.private_server_cost.is_some() {
-        changes.push("private_server_cost");
+    for product in &config.developer_products {
+        if product.price == 0 || product.price > MAX_PRICE {
+            problems.push(format!("developer product '{}': price must be between 1 and {} Robux, got {}", product.name, MAX_PRICE, product.price));
+        }
     }
-    
-    let has_changes = !changes.is_empty();
-    
-    if !has_changes {
-        info!("  [SKIP] Universe Settings - no changes detected");
-        return Ok(());
+
+    if let Some(processing) = &config.image_processing {
+        if processing.size == 0 || processing.size > assets::MAX_IMAGE_DIMENSION {
+            problems.push(format!("image_processing.size must be between 1 and {}, got {}", assets::MAX_IMAGE_DIMENSION, processing.size));
+        }
+        let sizes = [("game_pass", processing.sizes.game_pass), ("developer_product", processing.sizes.developer_product), ("badge", processing.sizes.badge)];
+        for (field, size) in sizes {
+            if let Some(size) = size.filter(|&s| s == 0 || s > assets::MAX_IMAGE_DIMENSION) {
+                problems.push(format!("image_processing.sizes.{} must be between 1 and {}, got {}", field, assets::MAX_IMAGE_DIMENSION, size));
+            }
+        }
     }
-    
-    // Build the request body for develop.roblox.com/v2/universes/{id}/configuration
-    let mut body = serde_json::Map::new();
-    
-    // Add fields that are changing
-    if changes.contains(&"name") {
-        if let Some(name) = &desired_state.name {
-            body.insert("name".to_string(), name.clone().into());
+
+    let mut template_names: Vec<&String> = config.icon_templates.keys().collect();
+    template_names.sort();
+    for name in template_names {
+        let template = &config.icon_templates[name];
+        if template.svg.is_some() == template.base.is_some() {
+            problems.push(format!("icon_templates.{}: set exactly one of svg and base", name));
+        }
+        if template.size == 0 || template.size > assets::MAX_IMAGE_DIMENSION {
+            problems.push(format!("icon_templates.{}: size must be between 1 and {}, got {}", name, assets::MAX_IMAGE_DIMENSION, template.size));
         }
     }
-    if changes.contains(&"description") {
-        if let Some(desc) = &desired_state.description {
-            body.insert("description".to_string(), desc.clone().into());
+    let templated = config.game_passes.iter().map(|p| ("game pass", &p.name, &p.icon, &p.icon_template))
+        .chain(config.developer_products.iter().map(|p| ("developer product", &p.name, &p.icon, &p.icon_template)))
+        .chain(config.badges.iter().map(|b| ("badge", &b.name, &b.icon, &b.icon_template)));
+    for (resource_type, name, icon, template) in templated {
+        let Some(template) = template else { continue };
+        if icon.is_some() {
+            problems.push(format!("{} '{}': set either icon or icon_template, not both", resource_type, name));
+        }
+        if !config.icon_templates.contains_key(template) {
+            problems.push(format!("{} '{}': unknown icon template '{}'", resource_type, name, template));
         }
     }
-    
-    // Map playable devices to numeric array (1=Computer, 2=Phone, 3=Tablet, 4=Console, 5=VR)
-    if changes.contains(&"playable_devices") {
-        if let Some(devices) = &desired_state.playable_devices {
-            let device_ids: Vec<u8> = devices.iter().filter_map(|d| {
-                match d.to_lowercase().as_str() {
-                    "computer" => Some(1),
-                    "phone" => Some(2),
-                    "tablet" => Some(3),
-                    "console" => Some(4),
-                    "vr" => Some(5),
-                    _ => None,
-                }
-            }).collect();
-            body.insert("playableDevices".to_string(), serde_json::json!(device_ids));
+
+    let universe = &config.universe;
+    if let Some(max_players) = universe.max_players {
+        if !(1..=MAX_SERVER_SIZE).contains(&max_players) {
+            problems.push(format!("universe.max_players must be between 1 and {}, got {}", MAX_SERVER_SIZE, max_players));
         }
     }
-    
-    // Handle private server cost
-    if changes.contains(&"private_server_cost") {
-        if let Some(cost) = &config.universe.private_server_cost {
-            match cost {
-                PrivateServerCost::Disabled => {
-                    body.insert("allowPrivateServers".to_string(), serde_json::json!(false));
-                }
-                PrivateServerCost::Free => {
-                    body.insert("allowPrivateServers".to_string(), serde_json::json!(true));
-                    body.insert("privateServerPrice".to_string(), serde_json::json!(0));
-                }
-                PrivateServerCost::Paid(price) => {
-                    body.insert("allowPrivateServers".to_string(), serde_json::json!(true));
-                    body.insert("privateServerPrice".to_string(), serde_json::json!(price));
-                }
-            }
+    if let Some(price) = universe.paid_access {
+        if price != 0 && !PAID_ACCESS_PRICES.contains(&price) {
+            problems.push(format!(
+                "universe.paid_access must be 0 (off) or between {} and {} Robux, got {}",
+                PAID_ACCESS_PRICES.start(), PAID_ACCESS_PRICES.end(), price
+            ));
         }
     }
-    
-    if dry_run {
-        info!("  [UPDATE] Universe Settings - would update: {}", changes.join(", "));
-        info!("  Dry Run: Would PATCH to https://develop.roblox.com/v2/universes/{}/configuration", universe_id);
-    } else {
-        info!("  Request URL: https://develop.roblox.com/v2/universes/{}/configuration", universe_id);
-        info!("  Request Body: {}", serde_json::to_string_pretty(&serde_json::Value::Object(body.clone())).unwrap_or_default());
-        let response = cookie_client.update_universe_configuration(universe_id, &serde_json::Value::Object(body)).await?;
-        
-        // Output raw response
-        info!("  Universe API Response: {}", serde_json::to_string_pretty(&response).unwrap_or_else(|_| response.to_string()));
-        
-        // Update state after successful sync
-        state.update_universe(
-            desired_state.name.clone(),
-            desired_state.description.clone(),
-            desired_state.genre.clone(),
-            desired_state.playable_devices.clone(),
-            desired_state.max_players,
-            desired_state.private_server_cost.clone(),
-        );
-        
-        info!("  [UPDATED] Universe Settings - updated: {}", changes.join(", "));
+    if let Some(PrivateServerCost::Paid(cost)) = universe.private_server_cost {
+        if !(MIN_PRIVATE_SERVER_PRICE..=MAX_PRICE).contains(&cost) {
+            problems.push(format!("universe.private_server_cost must be 0 (free) or between {} and {} Robux, got {}", MIN_PRIVATE_SERVER_PRICE, MAX_PRICE, cost));
+        }
     }
-    
-    Ok(())
-}
-
-async fn sync_game_passes(universe_id: u64, config: &RblxSyncConfig, state: &mut SyncState, client: &RobloxClient, dry_run: bool) -> Result<()> {
-    info!("Syncing Game Passes...");
-    
-    let mut created_count = 0;
-    let mut updated_count = 0;
-    let mut skipped_count = 0;
-    
-    // Fetch existing to handle initial discovery
-    let existing = if !dry_run {
-         client.list_game_passes(universe_id, None).await?
-    } else {
-        match client.list_game_passes(universe_id, None).await {
-            Ok(r) => r,
-            Err(e) => {
-                warn!("Dry Run: Failed to list game passes (likely due to invalid credentials/universe): {}", e);
-                crate::api::ListResponse { data: vec![], next_page_cursor: None }
+    if let Some(scales) = &universe.avatar_scales {
+        for (field, _, range) in scales.ranges() {
+            let Some(range) = range else { continue };
+            let (low, high) = AvatarScales::limits(field);
+            if range.min > range.max || range.min < low || range.max > high {
+                problems.push(format!(
+                    "universe.avatar_scales.{}: min and max must satisfy {} <= min <= max <= {}, got {}..{}",
+                    field, low, high, range.min, range.max
+                ));
             }
         }
-    };
+    }
 
-    let mut remote_map: HashMap<String, (String, u64)> = HashMap::new();
-    for item in &existing.data {
-        log::debug!("Game pass item from API: {}", item);
-        let id = item["id"].as_u64()
-            .or_else(|| item["gamePassId"].as_u64())
-            .or_else(|| item["id"].as_str().and_then(|s| s.parse().ok()))
-            .or_else(|| item["gamePassId"].as_str().and_then(|s| s.parse().ok()));
-        
-        if let (Some(name), Some(id)) = (item["name"].as_str(), id) {
-            log::debug!("Found game pass: {} with ID: {}", name, id);
-            remote_map.insert(name.to_lowercase(), (name.to_string(), id));
+    let mut restricted = HashSet::new();
+    for restriction in &config.restrictions {
+        let name = restriction_name(restriction);
+        if !restricted.insert((restriction.user_id, restriction.place_id)) {
+            problems.push(format!("restrictions: {} is listed more than once", name));
+        }
+        for (field, reason, max) in [("display_reason", &restriction.display_reason, MAX_DISPLAY_REASON_LENGTH), ("private_reason", &restriction.private_reason, MAX_PRIVATE_REASON_LENGTH)] {
+            let length = reason.as_deref().map(|r| r.chars().count()).unwrap_or(0);
+            if length > max {
+                problems.push(format!("restriction for {}: {} is {} characters; the maximum is {}", name, field, length, max));
+            }
         }
     }
 
-    for pass in &config.game_passes {
-        // Case-insensitive state lookup by name
-        let state_lookup = state.find_game_pass_by_name(&pass.name);
-        let state_entry = state_lookup.map(|(_, s)| s);
-        let mut asset_id = None;
-        let mut icon_hash = None;
-        let mut icon_changed = false;
-        let mut changes: Vec<&str> = Vec::new();
-
-        // Check for metadata changes (name, description, price, is_for_sale)
-        if let Some(entry) = state_entry {
-            if entry.name != pass.name {
-                changes.push("name");
-            }
-            if entry.description.as_ref() != pass.description.as_ref() {
-                changes.push("description");
-            }
-            if entry.price != pass.price.map(|p| p as u64) {
-                changes.push("price");
-            }
-            if entry.is_for_sale != pass.is_for_sale {
-                changes.push("is_for_sale");
-            }
-        }
-
-        // Handle Icon - calculate hash and check for changes
-        if let Some(icon_path_str) = &pass.icon {
-            let icon_path = Path::new(&config.assets_dir).join(icon_path_str);
-            let current_hash = calculate_file_hash(&icon_path).await?;
-            let stored_hash = state_entry.and_then(|s| s.icon_hash.as_ref());
-            
-            if stored_hash == Some(&current_hash) && state_entry.and_then(|s| s.icon_asset_id).is_some() {
-                asset_id = state_entry.and_then(|s| s.icon_asset_id);
-                icon_hash = Some(current_hash);
-                icon_changed = false;
-            } else if dry_run {
-                asset_id = Some(0); 
-                icon_hash = Some(current_hash);
-                icon_changed = true;
-                changes.push("icon");
-            } else {
-                let creator = config.creator.as_ref().ok_or_else(|| anyhow!("Creator configuration is required for asset uploads"))?;
-                let (aid, hash) = ensure_icon(client, &icon_path, state_entry, creator).await?;
-                asset_id = Some(aid);
-                icon_hash = Some(hash);
-                icon_changed = true;
-                changes.push("icon");
-            }
+    for place in &config.places {
+        if place.test_place_id == Some(place.place_id) {
+            problems.push(format!("places: place {} is its own test_place_id", place.place_id));
+        }
+        if place.test_universe_id.is_some() && place.test_place_id.is_none() {
+            problems.push(format!("places: place {} sets test_universe_id without test_place_id", place.place_id));
         }
+    }
 
-        // Determine ID (State -> Remote -> Create) - case-insensitive matching
-        let state_id = state_lookup.map(|(id, _)| id);
-        let remote_entry = remote_map.get(&pass.name.to_lowercase());
-        let is_new = state_id.is_none() && remote_entry.is_none();
-        let has_changes = !changes.is_empty();
-        
-        let id = if let Some(sid) = state_id {
-            sid
-        } else if let Some((_, rid)) = remote_entry {
-            *rid
-        } else {
-            if dry_run {
-                info!("  [CREATE] Game Pass '{}' - would create with: name, description, price{}", 
-                    pass.name, 
-                    if pass.icon.is_some() { ", icon" } else { "" });
-                created_count += 1;
-                0
-            } else {
-                let mut body = serde_json::json!({
-                    "name": pass.name,
-                    "description": pass.description.clone().unwrap_or_default(),
-                    "price": pass.price.unwrap_or(0), 
-                });
-                if let Some(aid) = asset_id {
-                    body["iconAssetId"] = aid.into();
-                }
-                
-                let resp = client.create_game_pass(universe_id, &body).await?;
-                let new_id = resp["id"].as_u64().ok_or(anyhow!("Created game pass has no ID"))?;
-                info!("  [CREATED] Game Pass '{}' (ID: {}) - created with: name, description, price{}", 
-                    pass.name, new_id,
-                    if pass.icon.is_some() { ", icon" } else { "" });
-                created_count += 1;
-                new_id
-            }
-        };
+    problems.extend(text_limit_problems(config));
 
-        // Update Remote (Idempotent PATCH) - only if newly created or has changes
-        if is_new {
-            // Already created above
-        } else if dry_run {
-            if has_changes {
-                info!("  [UPDATE] Game Pass '{}' (ID: {}) - would update: {}", 
-                    pass.name, id, changes.join(", "));
-                updated_count += 1;
-            } else {
-                info!("  [SKIP] Game Pass '{}' (ID: {}) - no changes detected", pass.name, id);
-                skipped_count += 1;
-            }
-        } else if has_changes {
-            let mut patch = serde_json::Map::new();
-            patch.insert("name".to_string(), pass.name.clone().into());
-            if let Some(d) = &pass.description { patch.insert("description".to_string(), d.clone().into()); }
-            if let Some(p) = pass.price { patch.insert("price".to_string(), p.into()); }
-            if let Some(s) = pass.is_for_sale { patch.insert("isForSale".to_string(), s.into()); }
-            
-            // Read image file if icon changed
-            let image_data = if icon_changed {
-                if let Some(icon_path_str) = &pass.icon {
-                    let icon_path = Path::new(&config.assets_dir).join(icon_path_str);
-                    if icon_path.exists() {
-                        let data = tokio::fs::read(&icon_path).await?;
-                        let filename = icon_path.file_name().unwrap_or_default().to_string_lossy().to_string();
-                        Some((data, filename))
-                    } else {
-                        warn!("Game pass icon not found: {:?}", icon_path);
-                        None
-                    }
-                } else {
-                    None
-                }
-            } else {
-                None
-            };
-            
-            client.update_game_pass_with_icon(universe_id, id, &serde_json::Value::Object(patch), image_data).await?;
-            info!("  [UPDATED] Game Pass '{}' (ID: {}) - updated: {}", 
-                pass.name, id, changes.join(", "));
-            updated_count += 1;
-        } else {
-            info!("  [SKIP] Game Pass '{}' (ID: {}) - no changes detected", pass.name, id);
-            skipped_count += 1;
+    problems
+}
+
+/// Names and descriptions Roblox would reject with a bare 400, each pointing
+/// at its config entry
+fn text_limit_problems(config: &RblxSyncConfig) -> Vec<String> {
+    let mut problems = Vec::new();
+    let universe_length = config.universe.description.as_deref().map(|d| d.chars().count()).unwrap_or(0);
+    if universe_length > MAX_DESCRIPTION_LENGTH {
+        problems.push(format!("universe.description is {} characters; the maximum is {}", universe_length, MAX_DESCRIPTION_LENGTH));
+    }
+
+    let entries = config.game_passes.iter().enumerate().map(|(i, p)| ("game_passes", i, &p.name, &p.description))
+        .chain(config.developer_products.iter().enumerate().map(|(i, p)| ("developer_products", i, &p.name, &p.description)))
+        .chain(config.badges.iter().enumerate().map(|(i, b)| ("badges", i, &b.name, &b.description)))
+        .chain(config.assets.iter().enumerate().map(|(i, a)| ("assets", i, &a.name, &a.description)));
+    for (section, index, name, description) in entries {
+        let entry = format!("{}[{}] ('{}')", section, index, name);
+        let length = name.chars().count();
+        if name.trim().is_empty() {
+            problems.push(format!("{}: name is empty", entry));
+        } else if length > MAX_NAME_LENGTH {
+            problems.push(format!("{}: name is {} characters; the maximum is {}", entry, length, MAX_NAME_LENGTH));
+        } else if name.trim() != name {
+            problems.push(format!("{}: name starts or ends with whitespace, which Roblox trims", entry));
+        } else if name.chars().any(char::is_control) {
+            problems.push(format!("{}: name contains a control character such as a newline or tab", entry));
         }
 
-        // Update State after successful sync
-        if !dry_run && id != 0 {
-            state.update_game_pass(
-                id,
-                pass.name.clone(), 
-                pass.description.clone(),
-                pass.price.map(|p| p as u64),
-                pass.is_for_sale,
-                icon_hash.clone(), 
-                asset_id
-            );
+        let Some(description) = description else { continue };
+        let length = description.chars().count();
+        if length > MAX_DESCRIPTION_LENGTH {
+            problems.push(format!("{}: description is {} characters; the maximum is {}", entry, length, MAX_DESCRIPTION_LENGTH));
+        } else if description.chars().any(|c| c.is_control() && !matches!(c, '\n' | '\r' | '\t')) {
+            problems.push(format!("{}: description contains a control character", entry));
         }
     }
-    
-    info!("Game Passes Summary: {} created, {} updated, {} skipped (unchanged)", 
-        created_count, updated_count, skipped_count);
-    Ok(())
+    problems
 }
 
-async fn sync_developer_products(universe_id: u64, config: &RblxSyncConfig, state: &mut SyncState, client: &RobloxClient, dry_run: bool) -> Result<()> {
-    info!("Syncing Developer Products...");
-    
-    let mut created_count = 0;
-    let mut updated_count = 0;
-    let mut skipped_count = 0;
-    
-    let existing = if !dry_run {
-        client.list_developer_products(universe_id, None).await?
-    } else {
-        match client.list_developer_products(universe_id, None).await {
-            Ok(r) => r,
-            Err(e) => {
-                warn!("Dry Run: Failed to list developer products: {}", e);
-                crate::api::ListResponse { data: vec![], next_page_cursor: None }
-            }
+/// Problems `validate` reports beyond `config_problems`: unknown config keys
+/// and icon or asset files missing from `assets_dir`
+pub fn lint(config_path: &Path) -> Result<Vec<String>> {
+    let (config, unknown) = RblxSyncConfig::load_strict(config_path)?;
+    let mut problems: Vec<String> = unknown.into_iter().map(|key| format!("Unknown config key '{}'", key)).collect();
+    // Length limits apply to the rendered text, as in `run`
+    match template::render_config(config.clone(), config_path.parent().unwrap_or(Path::new("."))) {
+        Ok(rendered) => problems.extend(config_problems(&rendered)),
+        Err(e) => {
+            problems.push(e.to_string());
+            problems.extend(config_problems(&config));
         }
-    };
+    }
 
-    let mut remote_map: HashMap<String, (String, u64)> = HashMap::new();
-    for item in &existing.data {
-        log::debug!("Developer product item from API: {}", item);
-        let id = item["id"].as_u64()
-            .or_else(|| item["productId"].as_u64())
-            .or_else(|| item["developerProductId"].as_u64())
-            .or_else(|| item["id"].as_str().and_then(|s| s.parse().ok()))
-            .or_else(|| item["productId"].as_str().and_then(|s| s.parse().ok()));
-        
-        if let (Some(name), Some(id)) = (item["name"].as_str(), id) {
-            log::debug!("Found developer product: {} with ID: {}", name, id);
-            remote_map.insert(name.to_lowercase(), (name.to_string(), id));
+    let assets_dir = Path::new(&config.assets_dir);
+    let icons = config.game_passes.iter().map(|p| ("game pass", &p.name, &p.icon))
+        .chain(config.developer_products.iter().map(|p| ("developer product", &p.name, &p.icon)))
+        .chain(config.badges.iter().map(|b| ("badge", &b.name, &b.icon)));
+    for (resource_type, name, icon) in icons {
+        if let Some(icon) = icon {
+            if !assets_dir.join(icon).is_file() {
+                problems.push(format!("{} '{}': icon file {} not found", resource_type, name, assets_dir.join(icon).display()));
+            }
         }
     }
-
-    for prod in &config.developer_products {
-        // Case-insensitive state lookup by name
-        let state_lookup = state.find_developer_product_by_name(&prod.name);
-        let state_entry = state_lookup.map(|(_, s)| s);
-        let mut asset_id = None;
-        let mut icon_hash = None;
-        let mut icon_changed = false;
-        let mut changes: Vec<&str> = Vec::new();
-
-        // Check for metadata changes (name, description, price)
-        if let Some(entry) = state_entry {
-            if entry.name != prod.name {
-                changes.push("name");
-            }
-            if entry.description.as_ref() != prod.description.as_ref() {
-                changes.push("description");
-            }
-            if entry.price != Some(prod.price as u64) {
-                changes.push("price");
-            }
-        }
-
-        if let Some(icon_path_str) = &prod.icon {
-            let icon_path = Path::new(&config.assets_dir).join(icon_path_str);
-            let current_hash = calculate_file_hash(&icon_path).await?;
-            let stored_hash = state_entry.and_then(|s| s.icon_hash.as_ref());
-            
-            if stored_hash == Some(&current_hash) && state_entry.and_then(|s| s.icon_asset_id).is_some() {
-                asset_id = state_entry.and_then(|s| s.icon_asset_id);
-                icon_hash = Some(current_hash);
-                icon_changed = false;
-            } else if dry_run {
-                asset_id = Some(0);
-                icon_hash = Some(current_hash);
-                icon_changed = true;
-                changes.push("icon");
-            } else {
-                let creator = config.creator.as_ref().ok_or_else(|| anyhow!("Creator configuration is required for asset uploads"))?;
-                let (aid, hash) = ensure_icon(client, &icon_path, state_entry, creator).await?;
-                asset_id = Some(aid);
-                icon_hash = Some(hash);
-                icon_changed = true;
-                changes.push("icon");
+    let mut template_names: Vec<&String> = config.icon_templates.keys().collect();
+    template_names.sort();
+    for name in template_names {
+        let template = &config.icon_templates[name];
+        for file in template.svg.iter().chain(&template.base) {
+            if !assets_dir.join(file).is_file() {
+                problems.push(format!("icon_templates.{}: file {} not found", name, assets_dir.join(file).display()));
             }
         }
+    }
+    for asset in &config.assets {
+        if !assets_dir.join(&asset.path).is_file() {
+            problems.push(format!("asset '{}': file {} not found", asset.name, assets_dir.join(&asset.path).display()));
+        }
+    }
 
-        // Case-insensitive matching for ID lookup
-        let state_id = state_lookup.map(|(id, _)| id);
-        let remote_entry = remote_map.get(&prod.name.to_lowercase());
-        let is_new = state_id.is_none() && remote_entry.is_none();
-        let has_changes = !changes.is_empty();
+    Ok(problems)
+}
 
-        let id = if let Some(sid) = state_id {
-            sid
-        } else if let Some((_, rid)) = remote_entry {
-            *rid
+/// Options controlling how `run` applies changes
+#[derive(Debug, Clone, Default)]
+pub struct SyncOptions {
+    /// Preview changes without applying them
+    pub dry_run: bool,
+    /// Only update/adopt existing resources; never create new ones
+    pub no_create: bool,
+    /// Who is running the sync, recorded on each changed resource
+    pub actor: String,
+    /// Remote IDs to use when several remote resources share a configured name
+    pub use_ids: Vec<u64>,
+    /// How to confirm costly or destructive changes; also whether
+    /// ambiguities may be resolved by prompting on the terminal
+    pub confirm: Confirmation,
+    /// Sections to sync (all when empty)
+    pub only: Vec<SyncSection>,
+    /// Only sync resources whose name matches
+    pub filter: Option<NameFilter>,
+    /// Only sync these resources (all when empty)
+    pub targets: Vec<ResourceAddress>,
+    /// Allow price and for-sale changes to `protected` resources
+    pub allow_protected: bool,
+    /// Allow price changes beyond `max_price_change_percent`
+    pub confirm_price_changes: bool,
+    /// Replace edits made outside rblxsync since the last sync without asking
+    pub overwrite_remote_changes: bool,
+    /// Skip the cross-project asset cache and upload icons the lock file doesn't know
+    pub no_cache: bool,
+    /// Project root holding `.rblxsync/`, e.g. for processed icon variants
+    pub root: PathBuf,
+}
+
+impl SyncOptions {
+    /// Whether `run` should sync `section`. A name filter implies resources
+    /// only, so universe settings then need an explicit `--only universe`.
+    pub fn syncs(&self, section: SyncSection) -> bool {
+        if !self.targets.is_empty() && !self.targets.iter().any(|t| t.section == section) {
+            return false;
+        }
+        match section {
+            SyncSection::Universe if self.filter.is_some() => self.only.contains(&section),
+            _ => self.only.is_empty() || self.only.contains(&section),
+        }
+    }
+
+    /// Whether the resource called `name` in `section` passes `--filter` and `--target`
+    pub fn selects(&self, section: SyncSection, name: &str) -> bool {
+        self.filter.as_ref().is_none_or(|f| f.matches(name))
+            && (self.targets.is_empty() || self.targets.iter().any(|t| t.matches(section, name)))
+    }
+}
+
+/// A price or sale-status change `run` would make to a tracked pass or product
+struct PlannedPriceChange<'a> {
+    resource_type: &'static str,
+    name: &'a str,
+    protected: bool,
+    old: Option<u64>,
+    /// New price, or None when only the sale status changes
+    new: Option<u64>,
+    off_sale: bool,
+}
+
+impl PlannedPriceChange<'_> {
+    fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(new) = self.new {
+            parts.push(Msg::PriceChange { old: self.old, new }.text());
+        }
+        if self.off_sale {
+            parts.push("would be taken off sale".to_string());
+        }
+        format!("{} '{}': {}", self.resource_type, self.name, parts.join(", "))
+    }
+
+    /// Relative price change in percent, when there is a non-zero old price
+    fn percent(&self) -> Option<f64> {
+        match (self.old, self.new) {
+            (Some(old), Some(new)) if old > 0 => Some((new as f64 - old as f64).abs() / old as f64 * 100.0),
+            _ => None,
+        }
+    }
+}
+
+/// Price and sale-status changes to selected passes and products, compared
+/// against the lock file (as the sync itself does)
+fn planned_price_changes<'a>(config: &'a RblxSyncConfig, state: &SyncState, opts: &SyncOptions) -> Vec<PlannedPriceChange<'a>> {
+    let mut planned = Vec::new();
+
+    for pass in config.game_passes.iter().filter(|p| opts.syncs(SyncSection::GamePasses) && opts.selects(SyncSection::GamePasses, &p.name)) {
+        let entry = match pass.id {
+            Some(pinned) => state.game_passes.get(&pinned),
+            None => state.find_game_pass_by_name(&pass.name)
+                .or_else(|| pass.aliases.iter().find_map(|a| state.find_game_pass_by_name(a)))
+                .map(|(_, s)| s),
+        };
+        let Some(entry) = entry else { continue };
+        let new = pass.price.map(u64::from).filter(|p| entry.price != Some(*p));
+        let off_sale = pass.is_for_sale == Some(false) && entry.is_for_sale != Some(false);
+        if new.is_some() || off_sale {
+            planned.push(PlannedPriceChange { resource_type: "game pass", name: &pass.name, protected: pass.protected, old: entry.price, new, off_sale });
+        }
+    }
+
+    for prod in config.developer_products.iter().filter(|p| opts.syncs(SyncSection::DeveloperProducts) && opts.selects(SyncSection::DeveloperProducts, &p.name)) {
+        let entry = match prod.id {
+            Some(pinned) => state.developer_products.get(&pinned),
+            None => state.find_developer_product_by_name(&prod.name)
+                .or_else(|| prod.aliases.iter().find_map(|a| state.find_developer_product_by_name(a)))
+                .map(|(_, s)| s),
+        };
+        let Some(entry) = entry else { continue };
+        if entry.price != Some(u64::from(prod.price)) {
+            planned.push(PlannedPriceChange { resource_type: "developer product", name: &prod.name, protected: prod.protected, old: entry.price, new: Some(u64::from(prod.price)), off_sale: false });
+        }
+    }
+
+    planned
+}
+
+/// Names and descriptions of selected passes, products and badges that are
+/// new or differ from the lock file, i.e. text the sync is about to send
+fn planned_text_changes<'a>(config: &'a RblxSyncConfig, state: &SyncState, opts: &SyncOptions) -> Vec<PlannedText<'a>> {
+    let mut planned = Vec::new();
+    let mut plan = |resource_type: &'static str, entry: Option<&ResourceState>, name: &'a str, description: &'a Option<String>| {
+        if entry.is_none_or(|e| e.name != name) {
+            planned.push(PlannedText { resource_type, name, field: "name", text: name });
+        }
+        if let Some(text) = description.as_deref().filter(|_| entry.is_none_or(|e| e.description != *description)) {
+            planned.push(PlannedText { resource_type, name, field: "description", text });
+        }
+    };
+
+    for pass in config.game_passes.iter().filter(|p| opts.syncs(SyncSection::GamePasses) && opts.selects(SyncSection::GamePasses, &p.name)) {
+        let entry = match pass.id {
+            Some(pinned) => state.game_passes.get(&pinned),
+            None => state.find_game_pass_by_name(&pass.name)
+                .or_else(|| pass.aliases.iter().find_map(|a| state.find_game_pass_by_name(a)))
+                .map(|(_, s)| s),
+        };
+        plan("game pass", entry, &pass.name, &pass.description);
+    }
+    for prod in config.developer_products.iter().filter(|p| opts.syncs(SyncSection::DeveloperProducts) && opts.selects(SyncSection::DeveloperProducts, &p.name)) {
+        let entry = match prod.id {
+            Some(pinned) => state.developer_products.get(&pinned),
+            None => state.find_developer_product_by_name(&prod.name)
+                .or_else(|| prod.aliases.iter().find_map(|a| state.find_developer_product_by_name(a)))
+                .map(|(_, s)| s),
+        };
+        plan("developer product", entry, &prod.name, &prod.description);
+    }
+    for badge in config.badges.iter().filter(|b| opts.syncs(SyncSection::Badges) && opts.selects(SyncSection::Badges, &b.name)) {
+        let entry = match badge.id {
+            Some(pinned) => state.badges.get(&pinned),
+            None => state.find_badge_by_name(&badge.name)
+                .or_else(|| badge.aliases.iter().find_map(|a| state.find_badge_by_name(a)))
+                .map(|(_, s)| s),
+        };
+        plan("badge", entry, &badge.name, &badge.description);
+    }
+
+    planned
+}
+
+/// Fail before syncing if a `protected` resource would change price or go off sale
+fn check_protected(planned: &[PlannedPriceChange], opts: &SyncOptions) -> Result<()> {
+    if opts.allow_protected {
+        return Ok(());
+    }
+    let violations: Vec<String> = planned.iter().filter(|c| c.protected).map(|c| c.describe()).collect();
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow!("Refusing to change protected resources (pass --allow-protected to apply):\n  {}", violations.join("\n  ")))
+    }
+}
+
+/// Fail before syncing if a price moves by more than `max_price_change_percent`
+fn check_price_threshold(config: &RblxSyncConfig, planned: &[PlannedPriceChange], opts: &SyncOptions) -> Result<()> {
+    let Some(max) = config.max_price_change_percent else {
+        return Ok(());
+    };
+    if opts.confirm_price_changes {
+        return Ok(());
+    }
+    let violations: Vec<String> = planned
+        .iter()
+        .filter_map(|c| c.percent().filter(|pct| *pct > max).map(|pct| format!("{} ({:.0}%)", c.describe(), pct)))
+        .collect();
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "Price changes exceed max_price_change_percent ({}%); pass --confirm-price-changes if intended:\n  {}",
+            max,
+            violations.join("\n  ")
+        ))
+    }
+}
+
+/// Price drops and items going off sale lose revenue, so they need confirmation
+fn confirm_price_drops(planned: &[PlannedPriceChange], opts: &SyncOptions) -> Result<()> {
+    let drops: Vec<&PlannedPriceChange> = planned
+        .iter()
+        .filter(|c| c.off_sale || matches!((c.old, c.new), (Some(old), Some(new)) if new < old))
+        .collect();
+    if drops.is_empty() || opts.dry_run {
+        return Ok(());
+    }
+    for change in &drops {
+        warn!("  {}", change.describe());
+    }
+    opts.confirm.require(&format!("Lower the price of or stop selling {} item(s)", drops.len()))
+}
+
+/// Fail if a `--target` names a resource that isn't in the config
+fn check_targets(config: &RblxSyncConfig, targets: &[ResourceAddress]) -> Result<()> {
+    let user_ids: Vec<String> = config.restrictions.iter().map(|r| r.user_id.to_string()).collect();
+    for target in targets {
+        let names: Vec<&str> = match target.section {
+            SyncSection::Universe => continue,
+            SyncSection::GamePasses => config.game_passes.iter().map(|p| p.name.as_str()).collect(),
+            SyncSection::DeveloperProducts => config.developer_products.iter().map(|p| p.name.as_str()).collect(),
+            SyncSection::Badges => config.badges.iter().map(|b| b.name.as_str()).collect(),
+            SyncSection::Assets => config.assets.iter().map(|a| a.name.as_str()).collect(),
+            SyncSection::Restrictions => user_ids.iter().map(|id| id.as_str()).collect(),
+        };
+        if !names.iter().any(|name| target.matches(target.section, name)) {
+            return Err(anyhow!("Target {} matches no resource in config", target));
+        }
+    }
+    Ok(())
+}
+
+/// Fail before syncing when the API key's owner lacks the group role
+/// permissions a group-owned universe needs. Lookups the key can't make
+/// (e.g. without the `group:read` scope) only skip the check.
+async fn check_group_permissions(config: &RblxSyncConfig, client: &RobloxClient) -> Result<()> {
+    let Some(creator) = config.creator.as_ref().filter(|c| c.creator_type == "group") else { return Ok(()) };
+    let required = groups::required_permissions(config);
+    if required.is_empty() {
+        return Ok(());
+    }
+    let group_id: u64 = creator.id.parse().map_err(|_| anyhow!("creator.id must be a numeric group ID, got \"{}\"", creator.id))?;
+
+    let key = match client.introspect_api_key().await {
+        Ok(key) => key,
+        Err(e) => {
+            warn!("Skipping group permission check: could not introspect API key: {}", e);
+            return Ok(());
+        }
+    };
+    let owner = &key["authorizedUserId"];
+    let Some(user_id) = owner.as_u64().or_else(|| owner.as_str().and_then(|s| s.parse().ok())) else {
+        warn!("Skipping group permission check: API key has no owning user");
+        return Ok(());
+    };
+    let (group, membership) = match (client.get_group(group_id).await, client.get_group_membership(group_id, user_id).await) {
+        (Ok(group), Ok(membership)) => (group, membership),
+        (Err(e), _) | (_, Err(e)) => {
+            warn!("Skipping group permission check: could not read group {} (needs group:read): {}", group_id, e);
+            return Ok(());
+        }
+    };
+    let group_name = group["displayName"].as_str().unwrap_or("?");
+    if group["owner"].as_str().and_then(groups::user_id_from_path) == Some(user_id) {
+        info!("API key owner (user {}) owns group '{}'", user_id, group_name);
+        return Ok(());
+    }
+    let Some(membership) = membership else {
+        return Err(anyhow!(
+            "API key owner (user {}) is not a member of group '{}' ({}), which owns universe {}. Create the key as a group API key or join the group.",
+            user_id, group_name, group_id, config.universe.id
+        ));
+    };
+    let Some(role_path) = membership["role"].as_str() else {
+        warn!("Skipping group permission check: membership of user {} has no role", user_id);
+        return Ok(());
+    };
+    let role = match client.get_group_role(role_path).await {
+        Ok(role) => role,
+        Err(e) => {
+            warn!("Skipping group permission check: could not read role {}: {}", role_path, e);
+            return Ok(());
+        }
+    };
+    let missing = groups::missing_permissions(&role["permissions"], &required);
+    if missing.is_empty() {
+        info!("Group role '{}' has the permissions this sync needs", role["displayName"].as_str().unwrap_or(role_path));
+        return Ok(());
+    }
+    for req in &missing {
+        error!("  {:<24} {}", req.permission, req.reason);
+    }
+    Err(anyhow!(
+        "Role '{}' of user {} in group '{}' is missing {} permission(s) listed above; grant them in the group's Roles settings",
+        role["displayName"].as_str().unwrap_or(role_path),
+        user_id,
+        group_name,
+        missing.len()
+    ))
+}
+
+/// Print the effective config: the user config's defaults with the project
+/// config, its includes and the selected universe layered on top
+pub fn show_config(config_path: &Path, report: &RunReport) -> Result<()> {
+    match user_config_path().filter(|p| p.exists()) {
+        Some(path) => info!("User config:    {}", path.display()),
+        None => info!("User config:    none"),
+    }
+    info!("Project config: {}", config_path.display());
+
+    let config = RblxSyncConfig::load(config_path)?;
+    let mut value = serde_yaml::to_value(&config)?;
+    strip_nulls(&mut value);
+    match report.output() {
+        OutputMode::Text => print!("{}", serde_yaml::to_string(&value)?),
+        OutputMode::Json => println!("{}", serde_json::to_string(&value)?),
+    }
+    Ok(())
+}
+
+/// Drop unset (null) settings, recursively
+fn strip_nulls(value: &mut serde_yaml::Value) {
+    match value {
+        serde_yaml::Value::Mapping(map) => {
+            map.retain(|_, v| !v.is_null());
+            map.values_mut().for_each(strip_nulls);
+        }
+        serde_yaml::Value::Sequence(items) => items.iter_mut().for_each(strip_nulls),
+        _ => {}
+    }
+}
+
+/// Print the API key scopes and IP restrictions needed to run the config
+pub fn scopes(config: &RblxSyncConfig, read_only: bool) {
+    let mode = if read_only { "read-only (export, run --dry-run)" } else { "full sync" };
+    info!("API key scopes required for {}:", mode);
+    for req in scopes::required_scopes(config, read_only) {
+        info!("  {:<24} {:<46} {}", req.api, req.scope, req.reason);
+    }
+    info!("");
+    info!("Restrict the key to universe {} when creating it.", config.universe.id);
+    info!("IP restrictions: add the public IP of every machine that runs rblxsync.");
+    info!("  GitHub-hosted runners use changing IPs; allow 0.0.0.0/0 or use a self-hosted runner.");
+    if config.universe.has_settings() && !read_only {
+        info!("");
+        info!("Universe settings are synced via develop.roblox.com and need ROBLOX_COOKIE, not an API key scope.");
+    }
+}
+
+/// Print every entry in the lock file
+pub fn state_list(state: &SyncState) {
+    let entries = state.entries();
+    if entries.is_empty() {
+        info!("State is empty.");
+        return;
+    }
+    for entry in entries {
+        info!("  {:<18} {:<14} {}", entry.kind.label(), entry.id, entry.name);
+    }
+}
+
+/// Print every tracked resource with who last changed it and when
+pub fn status(state: &SyncState) {
+    let entries = state.entries();
+    if entries.is_empty() {
+        info!("No resources tracked yet; run `rblxsync run` first.");
+        return;
+    }
+    info!("  {:<18} {:<24} {:<14} {:<22} {}", "TYPE", "NAME", "ID", "LAST SYNCED", "BY");
+    for entry in entries {
+        let (at, by) = match &entry.last_synced {
+            Some(stamp) => (template::format_timestamp(stamp.at), stamp.by.as_str()),
+            None => ("-".to_string(), "-"),
+        };
+        info!("  {:<18} {:<24} {:<14} {:<22} {}", entry.kind.label(), entry.name, entry.id, at, by);
+    }
+}
+
+/// Print the most recent `limit` audit log entries matching `filter`
+pub fn history(root: &Path, filter: &audit::HistoryFilter, limit: usize, report: &RunReport) -> Result<()> {
+    let entries: Vec<audit::AuditEntry> = audit::read(root)?.into_iter().filter(|e| filter.matches(e)).collect();
+    let shown = &entries[entries.len().saturating_sub(limit)..];
+    if report.output() == OutputMode::Json {
+        for entry in shown {
+            println!("{}", serde_json::to_string(entry)?);
+        }
+        return Ok(());
+    }
+    if shown.is_empty() {
+        info!("No matching changes in {}", audit::log_path(root).display());
+        return Ok(());
+    }
+    let value = |v: &serde_json::Value| {
+        let text = match v {
+            serde_json::Value::Null => "-".to_string(),
+            serde_json::Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+        if text.chars().count() > 30 { format!("{}...", text.chars().take(27).collect::<String>()) } else { text }
+    };
+    info!("  {:<22} {:<20} {:<18} {:<24} {:<14} {}", "WHEN", "BY", "TYPE", "NAME", "FIELD", "CHANGE");
+    for entry in shown {
+        let change = match &entry.field {
+            Some(_) => format!("{} -> {}", value(&entry.old), value(&entry.new)),
+            None => format!("{:?}", entry.action).to_lowercase(),
+        };
+        info!(
+            "  {:<22} {:<20} {:<18} {:<24} {:<14} {}",
+            template::format_timestamp(entry.timestamp),
+            entry.actor.as_deref().unwrap_or("-"),
+            entry.resource_type,
+            entry.name,
+            entry.field.as_deref().unwrap_or("-"),
+            change
+        );
+    }
+    if entries.len() > shown.len() {
+        info!("Showing the last {} of {} matching changes; use --limit to see more", shown.len(), entries.len());
+    }
+    Ok(())
+}
+
+/// Print a single lock file entry
+pub fn state_show(state: &SyncState, kind: EntryKind, key: &str) -> Result<()> {
+    for line in state.show(kind, key)?.lines() {
+        info!("{}", line);
+    }
+    Ok(())
+}
+
+/// Remove a lock file entry so the next sync matches it by name again (or recreates it)
+pub fn state_rm(root: &Path, mut state: SyncState, kind: EntryKind, key: &str, confirm: Confirmation) -> Result<()> {
+    let name = state.remove(kind, key)?;
+    confirm.require(&format!("Remove {} '{}' from state", kind.label(), name))?;
+    state.save(root)?;
+    info!("Removed {} '{}' from state", kind.label(), name);
+    Ok(())
+}
+
+/// Rename a lock file entry, e.g. after renaming a resource in config
+pub fn state_mv(root: &Path, mut state: SyncState, kind: Option<EntryKind>, old: &str, new: &str) -> Result<()> {
+    let kind = state.rename(kind, old, new)?;
+    state.save(root)?;
+    info!("Renamed {} '{}' to '{}' in state", kind.label(), old, new);
+    Ok(())
+}
+
+/// Restore the lock file from the backup kept by the last save
+pub fn state_rollback(root: &Path) -> Result<()> {
+    let state = SyncState::rollback(root)?;
+    info!(
+        "Restored {} from {} ({} entries); run `state rollback` again to undo",
+        SyncState::get_state_path(root).display(),
+        SyncState::backup_path(root).display(),
+        state.entries().len()
+    );
+    Ok(())
+}
+
+/// Rebuild the lock file from remote listings, matching config resources by
+/// pinned ID, name or alias. Universe, asset and icon upload state can't be derived from
+/// remote and is carried over when the existing lock file is readable.
+pub async fn state_refresh(config: RblxSyncConfig, root: &Path, client: RobloxClient, dry_run: bool, confirm: Confirmation, report: &mut RunReport) -> Result<()> {
+    report.dry_run = dry_run;
+    let previous = SyncState::load(root).unwrap_or_else(|e| {
+        warn!("Existing state is unreadable ({}); rebuilding from scratch", e);
+        SyncState::default()
+    });
+
+    let universe_id = config.universe.id;
+    info!("Rebuilding state from universe {}...", universe_id);
+    let mut fetch = ResumableFetch::open(root, "state-refresh", universe_id, false)?;
+    for kind in ResourceKind::ALL {
+        fetch.fetch(&client, kind).await?;
+    }
+
+    let mut state = SyncState {
+        universe_id: Some(universe_id),
+        universe: previous.universe.clone(),
+        assets: previous.assets.clone(),
+        icons: previous.icons.clone(),
+        badge_creations: previous.badge_creations.clone(),
+        timed_bans: previous.timed_bans.clone(),
+        icon_sources: previous.icon_sources.clone(),
+        pending: previous.pending.clone(),
+        test_publishes: previous.test_publishes.clone(),
+        ..Default::default()
+    };
+
+    for kind in ResourceKind::ALL {
+        let items = fetch.items(kind)?;
+        let wanted: Vec<(Option<u64>, &str, &[String])> = match kind {
+            ResourceKind::GamePasses => config.game_passes.iter().map(|p| (p.id, p.name.as_str(), p.aliases.as_slice())).collect(),
+            ResourceKind::DeveloperProducts => config.developer_products.iter().map(|p| (p.id, p.name.as_str(), p.aliases.as_slice())).collect(),
+            ResourceKind::Badges => config.badges.iter().map(|b| (b.id, b.name.as_str(), b.aliases.as_slice())).collect(),
+        };
+
+        for (pinned, name, aliases) in wanted {
+            let found = pull::find_item(&items, kind.key(), pinned, name, aliases);
+            let Some((id, item)) = found.and_then(|item| kind.item_id(item).map(|id| (id, item))) else {
+                warn!("  {} '{}' not found remotely; it will be created on the next run", kind.resource_type(), name);
+                continue;
+            };
+
+            // Keep icon tracking for the same resource so icons aren't re-uploaded
+            let old = previous.resources(kind.entry_kind()).and_then(|m| m.get(&id));
+            let description = item["description"].as_str().filter(|d| !d.is_empty()).map(|d| d.to_string());
+            let entry = ResourceState {
+                name: item["name"].as_str().unwrap_or(name).to_string(),
+                description,
+                price: if kind == ResourceKind::Badges { None } else { pull::price_of(item) },
+                is_for_sale: if kind == ResourceKind::GamePasses { pull::bool_of(item, &["isForSale"]) } else { None },
+                is_active: if kind == ResourceKind::DeveloperProducts { pull::bool_of(item, &["isForSale", "isActive"]) } else { None },
+                is_enabled: if kind == ResourceKind::Badges { pull::bool_of(item, &["enabled", "isEnabled"]) } else { None },
+                regional_pricing_enabled: if kind == ResourceKind::Badges { None } else { pull::bool_of(item, &["isRegionalPricingEnabled"]) },
+                icon_hash: old.and_then(|o| o.icon_hash.clone()),
+                icon_moderation_state: old.and_then(|o| o.icon_moderation_state.clone()),
+                icon_moderation_checked: old.and_then(|o| o.icon_moderation_checked),
+                last_synced: old.and_then(|o| o.last_synced.clone()),
+                remote_updated: pull::updated_of(item),
+                kept_remote: Default::default(),
+            };
+
+            if old != Some(&entry) {
+                let mut changes = ChangeSet::default();
+                changes.push("state", old, &entry);
+                info!("  {} '{}' -> {}", kind.resource_type(), entry.name, id);
+                report.record(kind.resource_type(), &entry.name, Some(id), ChangeAction::Update, changes);
+            }
+            if let Some(map) = state.resources_mut(kind.entry_kind()) {
+                map.insert(id, entry);
+            }
+        }
+    }
+    fetch.finish()?;
+
+    let dropped = previous.entries().len().saturating_sub(state.entries().len());
+    if dry_run {
+        info!("Dry Run: Would write {} entries to state ({} dropped)", state.entries().len(), dropped);
+        return Ok(());
+    }
+    if dropped > 0 {
+        confirm.require(&format!("Drop {} entries from state", dropped))?;
+    }
+    state.save(root)?;
+    report.artifact(SyncState::get_state_path(root));
+    info!("Rebuilt state with {} entries ({} dropped)", state.entries().len(), dropped);
+    Ok(())
+}
+
+pub async fn run(config: RblxSyncConfig, mut state: SyncState, client: RobloxClient, cookie_client: Option<RobloxCookieClient>, opts: &SyncOptions, report: &mut RunReport) -> Result<()> {
+    let dry_run = opts.dry_run;
+    report.dry_run = dry_run;
+    info!("{}", Msg::StartingSync { dry_run }.text());
+    if opts.no_create {
+        info!("No-create mode enabled: resources missing remotely will be skipped.");
+    }
+    if !opts.only.is_empty() || opts.filter.is_some() || !opts.targets.is_empty() {
+        info!("Selective sync: only matching sections and resources will be touched.");
+    }
+
+    // Resolve `{{variable}}` placeholders first so length limits see the rendered text
+    let config = template::render_config(config, &opts.root)?;
+    validate(&config)?;
+    check_targets(&config, &opts.targets)?;
+    let planned = planned_price_changes(&config, &state, opts);
+    check_protected(&planned, opts)?;
+    check_price_threshold(&config, &planned, opts)?;
+    confirm_price_drops(&planned, opts)?;
+
+    text_check::check(config.text_check.as_ref(), &planned_text_changes(&config, &state, opts))?;
+    
+    let universe_id = config.universe.id;
+    state.universe_id = Some(universe_id);
+    check_group_permissions(&config, &client).await?;
+    hooks::run(HookStage::PreSync, &config, &hooks::run_env(&config, report), dry_run)?;
+
+    // Finalize changes from earlier runs that were waiting on moderation
+    if dry_run {
+        if !state.pending.is_empty() {
+            info!("Dry Run: Would re-check {} change(s) awaiting moderation", state.pending.len());
+        }
+    } else {
+        let started = Instant::now();
+        verify_pending(universe_id, &opts.root, &mut state, &client, report).await?;
+        report.timing("verify_pending", started);
+    }
+
+    // Update Universe Settings (requires cookie client)
+    if config.universe.has_settings() && opts.syncs(SyncSection::Universe) {
+        if let Some(ref cookie_client) = cookie_client {
+            let started = Instant::now();
+            sync_universe_settings(universe_id, &config, &mut state, cookie_client, dry_run, report).await?;
+            report.timing("universe", started);
+        }
+    }
+
+    // 2. Sync Resources
+    if opts.syncs(SyncSection::GamePasses) {
+        let started = Instant::now();
+        sync_game_passes(universe_id, &config, &mut state, &client, opts, report).await?;
+        report.timing("game_passes", started);
+    }
+
+    if opts.syncs(SyncSection::DeveloperProducts) {
+        let started = Instant::now();
+        sync_developer_products(universe_id, &config, &mut state, &client, opts, report).await?;
+        report.timing("developer_products", started);
+    }
+
+    if opts.syncs(SyncSection::Badges) {
+        let started = Instant::now();
+        sync_badges(universe_id, &config, &mut state, &client, opts, report).await?;
+        report.timing("badges", started);
+    }
+
+    if opts.syncs(SyncSection::Assets) {
+        let started = Instant::now();
+        sync_assets(&config, &mut state, &client, opts, report).await?;
+        report.timing("assets", started);
+    }
+
+    if opts.syncs(SyncSection::Restrictions) && !config.restrictions.is_empty() {
+        let started = Instant::now();
+        sync_restrictions(universe_id, &config.restrictions, &mut state, &client, opts, report).await?;
+        report.timing("restrictions", started);
+    }
+
+    // Save state
+    if !dry_run {
+        state.save(&opts.root)?;
+        report.artifact(SyncState::get_state_path(&opts.root));
+    } else {
+        info!("Dry Run: Would save state.");
+    }
+
+    // Generate output config file if output_path is specified
+    if let Some(output_path) = &config.output_path {
+        if dry_run {
+            info!("Dry Run: Would generate config file at {}", output_path);
         } else {
-            if dry_run {
+            output::generate_config(&state, config.universe.id, output_path)?;
+            report.artifact(output_path);
+        }
+    }
+    if let Some(split) = &config.split_output {
+        if dry_run {
+            info!("Dry Run: Would generate {}.server.luau and {}.client.luau in {}", split.name, split.name, split.dir);
+        } else {
+            for path in output::generate_split(&state, config.universe.id, split)? {
+                report.artifact(path);
+            }
+        }
+    }
+
+    hooks::run(HookStage::PostSync, &config, &hooks::run_env(&config, report), dry_run)?;
+
+    info!("{}", Msg::SyncComplete.text());
+    Ok(())
+}
+
+/// Tries at publishing a place before giving up
+const PUBLISH_ATTEMPTS: u32 = 4;
+/// Wait before the first retry, doubled after each
+const PUBLISH_RETRY_DELAY: std::time::Duration = std::time::Duration::from_secs(2);
+/// Wait per attempt while Roblox is still processing an earlier version
+const PUBLISH_PROCESSING_DELAY: std::time::Duration = std::time::Duration::from_secs(15);
+
+/// Publish a place, retrying server errors, rate limits and "version
+/// already being processed" conflicts. Only the version number Roblox
+/// returns for our own upload counts as success: a newer version seen after
+/// a failed attempt may be another publisher's.
+async fn publish_with_retries(client: &RobloxClient, universe_id: u64, place_id: u64, path: &Path) -> Result<serde_json::Value> {
+    let before = match client.get_latest_place_version(place_id).await {
+        Ok(version) => version,
+        Err(e) => {
+            warn!("Could not read the current version of place {}: {}", place_id, e);
+            None
+        }
+    };
+    let mut attempt = 1;
+    loop {
+        let error = match client.publish_place(universe_id, place_id, path).await {
+            Ok(response) => {
+                let version = response.get("versionNumber").and_then(|v| v.as_u64())
+                    .ok_or_else(|| anyhow!("Publish of place {} returned no version number", place_id))?;
+                if before.is_some_and(|before| version <= before) {
+                    anyhow::bail!("Publish of place {} returned version {}, which is not newer than {}", place_id, version, before.unwrap_or_default());
+                }
+                return Ok(response);
+            }
+            Err(e) => e,
+        };
+        let (transient, processing) = match error.downcast_ref::<crate::api::PublishError>() {
+            Some(publish) => (publish.is_transient(), publish.is_processing()),
+            None => (error.downcast_ref::<reqwest::Error>().is_some_and(|e| e.is_timeout() || e.is_connect() || e.is_request()), false),
+        };
+        if !transient || attempt >= PUBLISH_ATTEMPTS {
+            return Err(error);
+        }
+        let delay = match processing {
+            true => PUBLISH_PROCESSING_DELAY * attempt,
+            false => PUBLISH_RETRY_DELAY * 2u32.pow(attempt - 1),
+        };
+        warn!("Publishing place {} failed (attempt {}/{}), retrying in {}s: {}", place_id, attempt, PUBLISH_ATTEMPTS, delay.as_secs(), error);
+        #[cfg(feature = "otel")]
+        crate::telemetry::count_retry("place_publish");
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
+
+/// Wait between checks of a place's current version
+const VERIFY_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+/// Default wait for a published version to become current
+pub const VERIFY_WAIT_SECS: u64 = 60;
+
+/// Check that `version` is the place's current version, waiting up to
+/// `wait_secs` for it to show up. A newer version means another publish
+/// replaced this one. An API key that can't read versions only gets a
+/// warning, as the publish itself went through.
+async fn verify_place_version(client: &RobloxClient, place_id: u64, version: u64, wait_secs: u64) -> Result<()> {
+    let deadline = Instant::now() + std::time::Duration::from_secs(wait_secs);
+    let progress = (wait_secs > 0).then(|| Progress::spinner(format!("Waiting for place {} version {}", place_id, version)));
+    loop {
+        let latest = match client.get_latest_place_version(place_id).await {
+            Ok(latest) => latest,
+            Err(e) if matches!(crate::api::ApiError::status_of(&e), Some(reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN)) => {
+                if let Some(progress) = &progress {
+                    progress.finish();
+                }
+                warn!("Could not verify the publish of place {} (needs the asset:read scope): {}", place_id, e);
+                return Ok(());
+            }
+            Err(e) => return Err(e.context(format!("Could not verify the publish of place {}", place_id))),
+        };
+        match latest {
+            Some(latest) if latest == version => {
+                if let Some(progress) = &progress {
+                    progress.finish();
+                }
+                return Ok(());
+            }
+            Some(latest) if latest > version => {
+                anyhow::bail!("Place {} is at version {}, so published version {} was replaced by another publish", place_id, latest, version)
+            }
+            _ if Instant::now() >= deadline => {
+                let latest = latest.map_or_else(|| "no version".to_string(), |v| format!("version {}", v));
+                anyhow::bail!("Place {} is still at {} after publishing version {}", place_id, latest, version)
+            }
+            _ => {}
+        }
+        if let Some(progress) = &progress {
+            progress.set_message(format!("at {}", latest.map_or_else(|| "no version".to_string(), |v| format!("version {}", v))));
+        }
+        tokio::time::sleep(VERIFY_INTERVAL.min(deadline.saturating_duration_since(Instant::now()))).await;
+    }
+}
+
+/// Publish a place and, with `verify` set, check that the new version is
+/// current, waiting up to that many seconds. Returns the version number.
+async fn publish_and_verify(client: &RobloxClient, universe_id: u64, place_id: u64, path: &Path, verify: Option<u64>) -> Result<u64> {
+    let response = publish_with_retries(client, universe_id, place_id, path).await?;
+    let version = response.get("versionNumber").and_then(|v| v.as_u64()).unwrap_or_default();
+    if let Some(wait_secs) = verify {
+        verify_place_version(client, place_id, version, wait_secs).await?;
+    }
+    Ok(version)
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PublishOptions {
+    /// Seconds to wait for each published version to become current, or
+    /// `None` not to check
+    pub verify: Option<u64>,
+    /// Publish places with a `test_place_id` to production, instead of to
+    /// their test place, once the same file was published there
+    pub promote: bool,
+    pub confirm: Confirmation,
+}
+
+/// Publish the configured places. Places with a test place are published
+/// there, and only with `promote` to production, which needs the file last
+/// published to the test place (as recorded in the lock file under `root`).
+pub async fn publish(config: RblxSyncConfig, root: &Path, client: RobloxClient, opts: PublishOptions, report: &mut RunReport) -> Result<()> {
+    let universe_id = config.universe.id;
+    let mut restart = false;
+    let mut state = SyncState::load(root)?;
+
+    for place in &config.places {
+        async {
+            if place.publish {
+                info!("Publishing place {} from {}", place.place_id, place.file_path);
+                let path = Path::new(&place.file_path);
+                if !path.exists() {
+                    error!("File not found: {}", place.file_path);
+                    report.fail("place", format!("File not found for place {}: {}", place.place_id, place.file_path));
+                    return Ok(());
+                }
+                if let Some(test_place_id) = place.test_place_id {
+                    let hash = assets::hash_file(path)?;
+                    let tested = state.test_publishes.get(&place.place_id).filter(|t| t.test_place_id == test_place_id);
+                    if opts.promote {
+                        let Some(tested) = tested.filter(|t| t.hash == hash) else {
+                            let problem = match tested {
+                                Some(t) => format!("{} changed since it was published to test place {} (version {})", place.file_path, test_place_id, t.version),
+                                None => format!("{} was not published to test place {}", place.file_path, test_place_id),
+                            };
+                            error!("Not promoting place {}: {}", place.place_id, problem);
+                            report.fail("place", format!("Not promoting place {}: {}; run `publish` without --promote first", place.place_id, problem));
+                            return Ok(());
+                        };
+                        opts.confirm.require(&format!("Promote {} (version {} of test place {}) to place {}", place.file_path, tested.version, test_place_id, place.place_id))?;
+                    } else {
+                        let started = Instant::now();
+                        let test_universe_id = place.test_universe_id.unwrap_or(universe_id);
+                        match publish_and_verify(&client, test_universe_id, test_place_id, path, opts.verify).await {
+                            Ok(version) => {
+                                info!("Published test place {} (version {})", test_place_id, version);
+                                report.record("place", &place.file_path, Some(test_place_id), ChangeAction::Publish, ChangeSet::default());
+                                state.test_publishes.insert(place.place_id, TestPublish { test_place_id, hash, version });
+                                state.save(root)?;
+                                info!("Not publishing place {}; run `publish --promote` once test place {} is checked", place.place_id, test_place_id);
+                            }
+                            Err(e) => {
+                                error!("Failed to publish test place {}: {:#}", test_place_id, e);
+                                report.fail("place", format!("Failed to publish test place {}: {:#}", test_place_id, e));
+                            }
+                        }
+                        report.timing(&format!("place:{}", test_place_id), started);
+                        return Ok(());
+                    }
+                }
+                let started = Instant::now();
+                match publish_and_verify(&client, universe_id, place.place_id, path, opts.verify).await {
+                    Ok(version) => {
+                        info!("Published place {} (version {})", place.place_id, version);
+                        report.record("place", &place.file_path, Some(place.place_id), ChangeAction::Publish, ChangeSet::default());
+                        let mut env = hooks::run_env(&config, report);
+                        env.push(("RBLXSYNC_PLACE_ID".to_string(), place.place_id.to_string()));
+                        env.push(("RBLXSYNC_PLACE_FILE".to_string(), place.file_path.clone()));
+                        env.push(("RBLXSYNC_PLACE_VERSION".to_string(), version.to_string()));
+                        hooks::run(HookStage::PostPublish, &config, &env, false)?;
+                        if let Some(hook) = config.hooks.as_ref().and_then(|h| h.publish_message.as_ref()) {
+                            let mut vars = template::variables(&config, root);
+                            vars.extend(env.iter().filter_map(|(key, value)| {
+                                let name = key.strip_prefix("RBLXSYNC_PLACE_")?;
+                                Some((format!("place.{}", name.to_lowercase()), value.clone()))
+                            }));
+                            send_message(&config, &client, &hook.topic, &template::render(&hook.message, &vars)?, report).await?;
+                        }
+                        restart |= place.restart_after_publish;
+                    }
+                    Err(e) => {
+                        error!("Failed to publish place {}: {:#}", place.place_id, e);
+                        report.fail("place", format!("Failed to publish place {}: {:#}", place.place_id, e));
+                    }
+                }
+                report.timing(&format!("place:{}", place.place_id), started);
+            }
+            Ok::<_, anyhow::Error>(())
+        }
+        .instrument(info_span!("place", place_id = place.place_id))
+        .await?;
+    }
+    if restart {
+        restart_servers(&config, &client, report).await?;
+    }
+    Ok(())
+}
+
+/// Publish `payload`, which must be JSON, to `topic` on every live server
+pub async fn message(config: &RblxSyncConfig, client: &RobloxClient, topic: &str, payload: &str, report: &mut RunReport) -> Result<()> {
+    let value: serde_json::Value = serde_json::from_str(payload).map_err(|e| anyhow!("Message must be valid JSON: {}", e))?;
+    send_message(config, client, topic, &value.to_string(), report).await
+}
+
+async fn send_message(config: &RblxSyncConfig, client: &RobloxClient, topic: &str, message: &str, report: &mut RunReport) -> Result<()> {
+    check_message(topic, message)?;
+    client.publish_message(config.universe.id, topic, message).await?;
+    info!("Sent message to topic '{}' ({} bytes)", topic, message.len());
+    let mut changes = ChangeSet::default();
+    changes.push("message", serde_json::Value::Null, message);
+    report.record("message", topic, Some(config.universe.id), ChangeAction::Publish, changes);
+    Ok(())
+}
+
+/// Longest MessagingService topic name
+const MAX_TOPIC_LENGTH: usize = 80;
+/// Largest MessagingService message
+const MAX_MESSAGE_BYTES: usize = 1024;
+
+fn check_message(topic: &str, message: &str) -> Result<()> {
+    if topic.is_empty() || topic.chars().count() > MAX_TOPIC_LENGTH {
+        return Err(anyhow!("Message topic must be 1 to {} characters, got '{}'", MAX_TOPIC_LENGTH, topic));
+    }
+    if message.len() > MAX_MESSAGE_BYTES {
+        return Err(anyhow!("Message is {} bytes; MessagingService accepts at most {}", message.len(), MAX_MESSAGE_BYTES));
+    }
+    Ok(())
+}
+
+/// Print a data store entry's value (the whole entry in JSON output mode)
+pub async fn datastore_get(config: &RblxSyncConfig, client: &RobloxClient, datastore: &str, scope: Option<&str>, key: &str, report: &RunReport) -> Result<()> {
+    let entry = client
+        .get_datastore_entry(config.universe.id, datastore, scope, key)
+        .await?
+        .ok_or_else(|| anyhow!("Key '{}' not found in data store '{}'", key, datastore))?;
+    match report.output() {
+        OutputMode::Text => println!("{}", serde_json::to_string_pretty(&entry["value"])?),
+        OutputMode::Json => println!("{}", entry),
+    }
+    Ok(())
+}
+
+/// Write `value` (JSON) to a data store entry, creating it if missing
+#[allow(clippy::too_many_arguments)]
+pub async fn datastore_set(config: &RblxSyncConfig, client: &RobloxClient, datastore: &str, scope: Option<&str>, key: &str, value: &str, dry_run: bool, report: &mut RunReport) -> Result<()> {
+    report.dry_run = dry_run;
+    let universe_id = config.universe.id;
+    let value: serde_json::Value = serde_json::from_str(value).map_err(|e| anyhow!("Value must be valid JSON: {}", e))?;
+    let old = client.get_datastore_entry(universe_id, datastore, scope, key).await?.map(|mut entry| entry["value"].take());
+    if old.as_ref() == Some(&value) {
+        info!("  [SKIP] {}/{} - no changes detected", datastore, key);
+        report.skip("datastore_entry");
+        return Ok(());
+    }
+
+    let action = if old.is_some() { ChangeAction::Update } else { ChangeAction::Create };
+    let mut changes = ChangeSet::default();
+    changes.push("value", &old, &value);
+    if dry_run {
+        info!("  Dry Run: Would set {}/{} to {}", datastore, key, value);
+    } else {
+        client.set_datastore_entry(universe_id, datastore, scope, key, &value, None).await?;
+        info!("  Set {}/{} to {}", datastore, key, value);
+    }
+    report.record("datastore_entry", &format!("{}/{}", datastore, key), None, action, changes);
+    Ok(())
+}
+
+/// Print the keys of a data store, one per line (whole entries in JSON output mode)
+pub async fn datastore_list(config: &RblxSyncConfig, client: &RobloxClient, datastore: &str, scope: Option<&str>, prefix: Option<&str>, report: &RunReport) -> Result<()> {
+    let mut page_token = None;
+    loop {
+        let page = client.list_datastore_entries(config.universe.id, datastore, scope, prefix, page_token).await?;
+        for entry in &page.data {
+            match report.output() {
+                OutputMode::Text => println!("{}", entry["id"].as_str().unwrap_or_default()),
+                OutputMode::Json => println!("{}", entry),
+            }
+        }
+        page_token = page.next_page_cursor.filter(|t| !t.is_empty());
+        if page_token.is_none() {
+            return Ok(());
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn datastore_delete(config: &RblxSyncConfig, client: &RobloxClient, datastore: &str, scope: Option<&str>, key: &str, dry_run: bool, confirm: Confirmation, report: &mut RunReport) -> Result<()> {
+    report.dry_run = dry_run;
+    let universe_id = config.universe.id;
+    let Some(mut entry) = client.get_datastore_entry(universe_id, datastore, scope, key).await? else {
+        info!("  [SKIP] {}/{} does not exist", datastore, key);
+        report.skip("datastore_entry");
+        return Ok(());
+    };
+    let mut changes = ChangeSet::default();
+    changes.push("value", entry["value"].take(), serde_json::Value::Null);
+    if dry_run {
+        info!("  Dry Run: Would delete {}/{}", datastore, key);
+    } else {
+        confirm.require(&format!("Delete {}/{} from universe {}", datastore, key, universe_id))?;
+        client.delete_datastore_entry(universe_id, datastore, scope, key).await?;
+        info!("  Deleted {}/{}", datastore, key);
+    }
+    report.record("datastore_entry", &format!("{}/{}", datastore, key), None, ChangeAction::Update, changes);
+    Ok(())
+}
+
+/// Dump every entry of a data store to a JSON Lines file
+pub async fn datastore_export(config: &RblxSyncConfig, client: &RobloxClient, datastore_name: &str, scope: Option<&str>, out: &Path, resume: bool, report: &mut RunReport) -> Result<()> {
+    info!("Exporting data store '{}' of universe {} to {}...", datastore_name, config.universe.id, out.display());
+    let count = datastore::export(client, config.universe.id, datastore_name, scope, out, resume).await?;
+    info!("Exported {} entries to {}", count, out.display());
+    report.artifact(out);
+    Ok(())
+}
+
+/// Write every entry of a dump from `datastore export` to a data store,
+/// overwriting existing keys
+#[allow(clippy::too_many_arguments)]
+pub async fn datastore_import(config: &RblxSyncConfig, client: &RobloxClient, datastore_name: &str, scope: Option<&str>, file: &Path, dry_run: bool, confirm: Confirmation, report: &mut RunReport) -> Result<()> {
+    report.dry_run = dry_run;
+    let universe_id = config.universe.id;
+    let entries = datastore::read_dump(file)?;
+    if dry_run {
+        info!("Dry Run: Would write {} entries from {} to data store '{}' in universe {}", entries.len(), file.display(), datastore_name, universe_id);
+        return Ok(());
+    }
+    confirm.require(&format!("Write {} entries to data store '{}' in universe {}", entries.len(), datastore_name, universe_id))?;
+
+    for (index, entry) in entries.iter().enumerate() {
+        client.set_datastore_entry(universe_id, datastore_name, scope, &entry.key, &entry.value, None).await?;
+        report.record("datastore_entry", &format!("{}/{}", datastore_name, entry.key), None, ChangeAction::Update, ChangeSet::default());
+        if (index + 1) % 100 == 0 {
+            info!("  {} / {} entries imported", index + 1, entries.len());
+        }
+    }
+    info!("Imported {} entries into data store '{}'", entries.len(), datastore_name);
+    Ok(())
+}
+
+/// Run a Luau script in a server of `place_id`, printing its logs and
+/// returned values. Fails unless the script completes.
+pub async fn run_script(config: &RblxSyncConfig, client: &RobloxClient, file: &Path, place_id: u64, version: Option<u64>, timeout_secs: u64, report: &mut RunReport) -> Result<()> {
+    let script = std::fs::read_to_string(file).map_err(|e| anyhow!("Failed to read {}: {}", file.display(), e))?;
+    info!("Running {} in place {}...", file.display(), place_id);
+    let started = Instant::now();
+    let task = client.run_luau(config.universe.id, place_id, version, &script, timeout_secs).await?;
+    report.timing("script", started);
+
+    match client.get_luau_task_logs(&task.path).await {
+        Ok(lines) => {
+            for line in lines {
+                info!("  [log] {}", line);
+            }
+        }
+        Err(e) => warn!("Failed to fetch script logs: {}", e),
+    }
+
+    if task.state != "COMPLETE" {
+        let reason = task.error.map(|e| format!("{}: {}", e.code, e.message)).unwrap_or_default();
+        return Err(anyhow!("Script {} ended {} {}", file.display(), task.state, reason));
+    }
+    let results = task.output.map(|o| o.results).unwrap_or_default();
+    match report.output() {
+        OutputMode::Text => println!("{}", serde_json::to_string_pretty(&results)?),
+        OutputMode::Json => println!("{}", serde_json::json!({ "type": "script_result", "results": results })),
+    }
+    info!("Script completed in {:.1}s", started.elapsed().as_secs_f64());
+    Ok(())
+}
+
+/// What `notify` sends
+pub struct Notification {
+    /// Notification string asset ID from the Creator Dashboard
+    pub message_id: String,
+    /// Values for the string's `{parameter}` placeholders
+    pub parameters: Vec<(String, String)>,
+    /// Passed to the experience when the user joins from the notification
+    pub launch_data: Option<String>,
+    /// Analytics category
+    pub category: Option<String>,
+}
+
+/// Send an experience notification to each of `user_ids`. Users who haven't
+/// opted in are reported as failures without stopping the others.
+pub async fn notify(config: &RblxSyncConfig, client: &RobloxClient, user_ids: &[u64], notification: &Notification, dry_run: bool, report: &mut RunReport) -> Result<()> {
+    report.dry_run = dry_run;
+    let mut payload = serde_json::json!({ "messageId": notification.message_id, "type": "MOMENT" });
+    if !notification.parameters.is_empty() {
+        let parameters: serde_json::Map<String, serde_json::Value> = notification
+            .parameters
+            .iter()
+            .map(|(key, value)| (key.clone(), serde_json::json!({ "stringValue": value })))
+            .collect();
+        payload["parameters"] = parameters.into();
+    }
+    if let Some(launch_data) = &notification.launch_data {
+        payload["joinExperience"] = serde_json::json!({ "launchData": launch_data });
+    }
+    if let Some(category) = &notification.category {
+        payload["analyticsData"] = serde_json::json!({ "category": category });
+    }
+
+    for &user_id in user_ids {
+        let mut changes = ChangeSet::default();
+        changes.push("message_id", serde_json::Value::Null, &notification.message_id);
+        if dry_run {
+            info!("  Dry Run: Would notify user {}", user_id);
+        } else if let Err(e) = client.send_notification(config.universe.id, user_id, &payload).await {
+            error!("  Failed to notify user {}: {}", user_id, e);
+            report.fail("notification", format!("Failed to notify user {}: {}", user_id, e));
+            continue;
+        } else {
+            info!("  Notified user {}", user_id);
+        }
+        report.record("notification", &user_id.to_string(), Some(user_id), ChangeAction::Publish, changes);
+    }
+    Ok(())
+}
+
+/// Output formats for `stats`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum StatsFormat {
+    Json,
+    Csv,
+}
+
+/// Columns of `stats` output, in order
+const STATS_FIELDS: [&str; 9] = ["captured_at", "universe_id", "name", "visits", "playing", "favorites", "up_votes", "down_votes", "updated"];
+
+/// Capture the universe's public engagement numbers (visits, concurrent
+/// players, favorites, votes). Printed to stdout, or with `out` written to a
+/// file; CSV rows are appended to an existing file so repeated runs build a
+/// time series.
+pub async fn stats(config: &RblxSyncConfig, client: &RobloxClient, format: StatsFormat, out: Option<&Path>, report: &mut RunReport) -> Result<()> {
+    let universe_id = config.universe.id;
+    let details = client
+        .get_universe_details(universe_id)
+        .await?
+        .ok_or_else(|| anyhow!("No public details for universe {}; stats are only available for public experiences", universe_id))?;
+    let votes = client.get_universe_votes(universe_id).await?.unwrap_or_default();
+
+    let row = serde_json::json!({
+        "captured_at": template::format_timestamp(actor::now()),
+        "universe_id": universe_id,
+        "name": details["name"],
+        "visits": details["visits"],
+        "playing": details["playing"],
+        "favorites": details["favoritedCount"],
+        "up_votes": votes["upVotes"],
+        "down_votes": votes["downVotes"],
+        "updated": details["updated"],
+    });
+
+    let (content, append) = match format {
+        StatsFormat::Json => (format!("{}\n", serde_json::to_string_pretty(&row)?), false),
+        StatsFormat::Csv => {
+            let values: Vec<String> = STATS_FIELDS.iter().map(|field| csv_field(&row[*field])).collect();
+            let append = out.is_some_and(|path| path.exists());
+            let header = if append { String::new() } else { format!("{}\n", STATS_FIELDS.join(",")) };
+            (format!("{}{}\n", header, values.join(",")), append)
+        }
+    };
+    match out {
+        Some(path) => {
+            let mut file = std::fs::OpenOptions::new().create(true).write(true).append(append).truncate(!append).open(path)?;
+            std::io::Write::write_all(&mut file, content.as_bytes())?;
+            info!("{} stats for universe {} to {}", if append { "Appended" } else { "Wrote" }, universe_id, path.display());
+            report.artifact(path);
+        }
+        None => print!("{}", content),
+    }
+    Ok(())
+}
+
+/// A JSON value as a CSV field, quoted when needed
+fn csv_field(value: &serde_json::Value) -> String {
+    let text = match value {
+        serde_json::Value::Null => String::new(),
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    if text.contains([',', '"', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text
+    }
+}
+
+/// Check an API key with Roblox and save it in the OS keychain under
+/// `profile`. The key is prompted for without echo, or read from stdin.
+pub async fn login(profile: &str, from_stdin: bool) -> Result<()> {
+    let api_key = if from_stdin {
+        let mut line = String::new();
+        std::io::stdin().read_line(&mut line)?;
+        line.trim().to_string()
+    } else {
+        prompt::ask_secret(&format!("API key for profile '{}':", profile))?
+    };
+    if api_key.is_empty() {
+        return Err(anyhow!("No API key given"));
+    }
+
+    let key = RobloxClient::new(api_key.clone())
+        .introspect_api_key()
+        .await
+        .map_err(|e| anyhow!("Roblox rejected the API key: {}", e))?;
+    credentials::store_api_key(profile, &api_key)?;
+    info!("Saved API key '{}' (owner: user {}) as profile '{}'", key["name"].as_str().unwrap_or("(unnamed)"), key["authorizedUserId"], profile);
+    if std::env::var_os("ROBLOX_API_KEY").is_some() {
+        warn!("ROBLOX_API_KEY is set and takes precedence over the saved key; remove it from your environment or .env");
+    }
+    Ok(())
+}
+
+/// Remove the API key saved under `profile`
+pub fn logout(profile: &str) -> Result<()> {
+    if credentials::delete_api_key(profile)? {
+        info!("Removed API key for profile '{}'", profile);
+    } else {
+        info!("No API key saved for profile '{}'", profile);
+    }
+    Ok(())
+}
+
+/// Print the API key's owner, scopes and expiry, then probe which of
+/// `universe_ids` its read scopes can list resources in
+pub async fn whoami(client: &RobloxClient, universe_ids: &[u64], report: &RunReport) -> Result<()> {
+    let key = client.introspect_api_key().await?;
+    let mut universes = Vec::new();
+    for &universe_id in universe_ids {
+        let name = client.get_universe_details(universe_id).await.ok().flatten().and_then(|d| d["name"].as_str().map(str::to_string));
+        let game_passes = probe_result(client.list_game_passes(universe_id, None).await);
+        let developer_products = probe_result(client.list_developer_products(universe_id, None).await);
+        universes.push(serde_json::json!({
+            "universe_id": universe_id,
+            "name": name,
+            "game_passes": game_passes,
+            "developer_products": developer_products,
+        }));
+    }
+
+    if report.output() == OutputMode::Json {
+        println!("{}", serde_json::json!({ "key": key, "universes": universes }));
+        return Ok(());
+    }
+    info!("API key:   {}", key["name"].as_str().unwrap_or("(unnamed)"));
+    info!("Owner:     user {}", key["authorizedUserId"]);
+    let status = match (key["enabled"].as_bool(), key["expired"].as_bool()) {
+        (_, Some(true)) => "expired",
+        (Some(false), _) => "disabled",
+        _ => "enabled",
+    };
+    info!("Status:    {}", status);
+    if let Some(expires) = key["expirationTimeUtc"].as_str().filter(|s| !s.is_empty()) {
+        info!("Expires:   {}", expires);
+    }
+    match key["allowedCidrs"].as_array() {
+        Some(cidrs) => info!("Allowed IPs: {}", cidrs.iter().filter_map(|c| c.as_str()).collect::<Vec<_>>().join(", ")),
+        None => info!("Allowed IPs: not reported (check the key on the Creator Dashboard)"),
+    }
+    info!("Scopes:");
+    for scope in key["scopes"].as_array().into_iter().flatten() {
+        let operations: Vec<&str> = scope["operations"].as_array().into_iter().flatten().filter_map(|o| o.as_str()).collect();
+        info!("  {:<40} {}", scope["name"].as_str().unwrap_or("?"), operations.join(", "));
+    }
+    if universes.is_empty() {
+        info!("No universe to check; pass universe IDs or run next to a config.");
+    }
+    for universe in &universes {
+        info!("Universe {} ({})", universe["universe_id"], universe["name"].as_str().unwrap_or("no public details"));
+        info!("  game-pass:read          {}", universe["game_passes"].as_str().unwrap_or_default());
+        info!("  developer-product:read  {}", universe["developer_products"].as_str().unwrap_or_default());
+    }
+    Ok(())
+}
+
+/// "ok", or the HTTP status of a failed access probe
+fn probe_result<T>(result: Result<T>) -> String {
+    match result {
+        Ok(_) => "ok".to_string(),
+        Err(e) => {
+            let message = e.to_string();
+            let message = message.strip_prefix("API request failed: ").unwrap_or(&message);
+            message.split(" - ").next().unwrap_or(message).to_string()
+        }
+    }
+}
+
+/// Restart every running server of the configured universe
+pub async fn restart_servers(config: &RblxSyncConfig, client: &RobloxClient, report: &mut RunReport) -> Result<()> {
+    let universe_id = config.universe.id;
+    client.restart_servers(universe_id).await?;
+    info!("Restarted servers of universe {}", universe_id);
+    let mut changes = ChangeSet::default();
+    changes.push("servers", "running", "restarted");
+    report.record("universe", config.universe.name.as_deref().unwrap_or_default(), Some(universe_id), ChangeAction::Update, changes);
+    Ok(())
+}
+
+/// Mirror the game passes, developer products and badges of universe `from`
+/// into universe `to`, creating missing ones and updating changed ones.
+/// Icons are not copied, and the universe name and description only with
+/// `cookie_client`. Which target resource mirrors each source is kept in a
+/// `PromotionState` file, so renames carry over.
+#[allow(clippy::too_many_arguments)]
+pub async fn promote(
+    config: &RblxSyncConfig,
+    root: &Path,
+    client: RobloxClient,
+    cookie_client: Option<RobloxCookieClient>,
+    from: u64,
+    to: u64,
+    opts: &SyncOptions,
+    report: &mut RunReport,
+) -> Result<()> {
+    if from == to {
+        return Err(anyhow!("Cannot promote universe {} to itself", from));
+    }
+    report.dry_run = opts.dry_run;
+    info!("Promoting universe {} to universe {}...", from, to);
+    let mut promotion = PromotionState::load(root, from, to)?;
+
+    // Describe the source universe as a config targeting `to`, like `init --from-remote`
+    let details = match cookie_client {
+        Some(_) => client.get_universe_details(from).await?,
+        None => None,
+    };
+    let mut doc = serde_yaml::Mapping::new();
+    doc.insert("universe".into(), serde_yaml::to_value(mirrored_universe(to, details.as_ref()))?);
+    if let Some(creator) = &config.creator {
+        doc.insert("creator".into(), serde_yaml::to_value(creator)?);
+    }
+    if let Some(source) = &config.badge_payment_source {
+        doc.insert("badge_payment_source".into(), source.clone().into());
+    }
+    let mut sources = Vec::new();
+    let mut fetch = ResumableFetch::open(root, "promote", from, false)?;
+    for kind in ResourceKind::ALL {
+        fetch.fetch(&client, kind).await?;
+        let mut entries = init::seed_entries(kind, &fetch.items(kind)?);
+        for entry in &mut entries {
+            let Some(map) = entry.as_mapping_mut() else { continue };
+            let source_id = map.remove("id").and_then(|id| id.as_u64());
+            let name = map.get("name").and_then(|n| n.as_str()).unwrap_or_default().to_string();
+            if let Some(source_id) = source_id {
+                // Pin to the mirror from an earlier promotion so renames update it
+                if let Some(target_id) = promotion.target_id(kind.entry_kind(), source_id) {
+                    map.insert("id".into(), target_id.into());
+                }
+                sources.push((kind, name, source_id));
+            }
+        }
+        doc.insert(kind.key().into(), serde_yaml::Value::Sequence(entries));
+    }
+    fetch.finish()?;
+    let target: RblxSyncConfig = serde_yaml::from_value(serde_yaml::Value::Mapping(doc))?;
+    validate(&target).map_err(|e| anyhow!("Universe {} can't be promoted as is:\n{}", from, e))?;
+
+    if !opts.dry_run {
+        opts.confirm.require(&format!("Promote {} resource(s) from universe {} to universe {}", sources.len(), from, to))?;
+    }
+
+    let state = &mut promotion.target;
+    if let Some(cookie_client) = &cookie_client {
+        if target.universe.has_settings() {
+            sync_universe_settings(to, &target, state, cookie_client, opts.dry_run, report).await?;
+        } else {
+            warn!("Universe {} details are not public; universe settings won't be promoted", from);
+        }
+    }
+    sync_game_passes(to, &target, state, &client, opts, report).await?;
+    sync_developer_products(to, &target, state, &client, opts, report).await?;
+    sync_badges(to, &target, state, &client, opts, report).await?;
+
+    let path = PromotionState::path(root, from, to);
+    if opts.dry_run {
+        info!("Dry Run: Would save {}", path.display());
+        return Ok(());
+    }
+    for (kind, name, source_id) in sources {
+        let mirror = promotion.target.resources(kind.entry_kind()).and_then(|resources| {
+            resources.iter().find(|(_, r)| r.name.eq_ignore_ascii_case(&name)).map(|(id, _)| *id)
+        });
+        if let Some(target_id) = mirror {
+            promotion.ids.entry(kind.resource_type().to_string()).or_default().insert(source_id, target_id);
+        }
+    }
+    report.artifact(promotion.save(root)?);
+    info!("Promotion complete; ID mapping saved to {}", path.display());
+    Ok(())
+}
+
+/// Universe config for `universe_id` that copies the name and description
+/// from another universe's public `details`, for `promote` and `restore`
+fn mirrored_universe(universe_id: u64, details: Option<&serde_json::Value>) -> serde_json::Value {
+    let mut universe = serde_json::json!({ "id": universe_id });
+    if let Some(details) = details {
+        for field in ["name", "description"] {
+            if let Some(value) = details[field].as_str() {
+                universe[field] = value.into();
+            }
+        }
+    }
+    universe
+}
+
+/// Write a snapshot of the configured universe to `out`: every remote game
+/// pass, developer product and badge, their rendered icons and the universe's
+/// public details. With `places`, the configured place files are included.
+pub async fn backup(config: &RblxSyncConfig, root: &Path, client: RobloxClient, out: &Path, places: bool, report: &mut RunReport) -> Result<()> {
+    let universe_id = config.universe.id;
+    info!("Backing up universe {} to {}...", universe_id, out.display());
+
+    let mut snapshot = Snapshot::new(universe_id, actor::now());
+    snapshot.universe = client.get_universe_details(universe_id).await?;
+    if snapshot.universe.is_none() {
+        warn!("  Universe {} details are not public; universe settings won't be in the snapshot", universe_id);
+    }
+
+    let mut fetch = ResumableFetch::open(root, "backup", universe_id, false)?;
+    for kind in ResourceKind::ALL {
+        fetch.fetch(&client, kind).await?;
+        *snapshot.items_mut(kind) = fetch.items(kind)?;
+    }
+    fetch.finish()?;
+
+    let mut writer = SnapshotWriter::create(out)?;
+    for kind in ResourceKind::ALL {
+        let ids: Vec<u64> = snapshot.items(kind).iter().filter_map(|item| kind.item_id(item)).collect();
+        let (path, id_param) = kind.icon_endpoint();
+        let mut icons = 0;
+        for chunk in ids.chunks(ICON_BATCH_SIZE) {
+            for thumb in client.get_icon_thumbnails(path, id_param, chunk).await? {
+                let Some(url) = thumb.image_url.as_deref().filter(|_| thumb.state == "Completed") else {
+                    warn!("  {} {} has no downloadable icon (state: {})", kind.resource_type(), thumb.target_id, thumb.state);
+                    continue;
+                };
+                writer.add_bytes(&Snapshot::icon_path(kind, thumb.target_id), &client.download_image(url).await?)?;
+                icons += 1;
+            }
+        }
+        info!("  {}: {} item(s), {} icon(s)", kind.key(), ids.len(), icons);
+    }
+
+    if places {
+        for place in &config.places {
+            let path = Path::new(&place.file_path);
+            if !path.is_file() {
+                warn!("  Place file {} not found, skipping place {}", place.file_path, place.place_id);
+                report.skip("place");
+                continue;
+            }
+            let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("rbxl");
+            let name = format!("places/{}.{}", place.place_id, extension);
+            writer.add_file(&name, path)?;
+            snapshot.places.insert(place.place_id, name);
+            info!("  place {} <- {}", place.place_id, place.file_path);
+        }
+    }
+
+    writer.finish(&snapshot)?;
+    report.artifact(out);
+    info!("Backup complete: {}", out.display());
+    Ok(())
+}
+
+/// Reapply a snapshot from `backup` to the configured universe. Resources
+/// are pinned to their backed up IDs when restoring into the same universe
+/// and matched by name otherwise; anything missing is recreated. Icons are
+/// only re-uploaded with `with_icons`, and place files only published with
+/// `places`. Universe name and description need `cookie_client`.
+#[allow(clippy::too_many_arguments)]
+pub async fn restore(
+    config: &RblxSyncConfig,
+    root: &Path,
+    client: RobloxClient,
+    cookie_client: Option<RobloxCookieClient>,
+    snapshot_path: &Path,
+    with_icons: bool,
+    places: bool,
+    opts: &SyncOptions,
+    report: &mut RunReport,
+) -> Result<()> {
+    report.dry_run = opts.dry_run;
+    let dir = root.join(".rblxsync").join("restore");
+    if dir.exists() {
+        std::fs::remove_dir_all(&dir)?;
+    }
+    let snapshot = snapshot::extract(snapshot_path, &dir)?;
+    let universe_id = config.universe.id;
+    let same_universe = snapshot.universe_id == universe_id;
+    info!(
+        "Restoring snapshot of universe {} taken {} into universe {}...",
+        snapshot.universe_id,
+        template::format_timestamp(snapshot.created_at),
+        universe_id
+    );
+    if !same_universe {
+        info!("  Snapshot is from another universe; resources are matched by name");
+    }
+
+    // Describe the snapshot as a config, like `promote` does for a live universe
+    let mut doc = serde_yaml::Mapping::new();
+    doc.insert("assets_dir".into(), dir.to_string_lossy().to_string().into());
+    doc.insert("universe".into(), serde_yaml::to_value(mirrored_universe(universe_id, snapshot.universe.as_ref()))?);
+    if let Some(creator) = &config.creator {
+        doc.insert("creator".into(), serde_yaml::to_value(creator)?);
+    }
+    if let Some(source) = &config.badge_payment_source {
+        doc.insert("badge_payment_source".into(), source.clone().into());
+    }
+    let mut count = 0;
+    let mut fetch = if same_universe { Some(ResumableFetch::open(root, "restore", universe_id, false)?) } else { None };
+    for kind in ResourceKind::ALL {
+        // Only resources that still exist are pinned; deleted ones are recreated by name
+        let live: Option<HashSet<u64>> = match fetch.as_mut() {
+            Some(fetch) => {
+                fetch.fetch(&client, kind).await?;
+                Some(fetch.items(kind)?.iter().filter_map(|item| kind.item_id(item)).collect())
+            }
+            None => None,
+        };
+        let mut entries = init::seed_entries(kind, snapshot.items(kind));
+        for entry in &mut entries {
+            let Some(map) = entry.as_mapping_mut() else { continue };
+            let source_id = map.get("id").and_then(|id| id.as_u64());
+            if !live.as_ref().is_some_and(|live| source_id.is_some_and(|id| live.contains(&id))) {
+                map.remove("id");
+            }
+            if let Some(source_id) = source_id.filter(|_| with_icons) {
+                let icon = Snapshot::icon_path(kind, source_id);
+                if dir.join(&icon).is_file() {
+                    map.insert("icon".into(), icon.into());
+                }
+            }
+        }
+        count += entries.len();
+        doc.insert(kind.key().into(), serde_yaml::Value::Sequence(entries));
+    }
+    if let Some(fetch) = fetch {
+        fetch.finish()?;
+    }
+    let target: RblxSyncConfig = serde_yaml::from_value(serde_yaml::Value::Mapping(doc))?;
+    validate(&target).map_err(|e| anyhow!("Snapshot {} can't be restored as is:\n{}", snapshot_path.display(), e))?;
+
+    if !opts.dry_run {
+        opts.confirm.require(&format!("Restore {} resource(s) from {} into universe {}", count, snapshot_path.display(), universe_id))?;
+    }
+
+    let mut state = SyncState::load(root)?;
+    if target.universe.has_settings() {
+        match &cookie_client {
+            Some(cookie_client) => sync_universe_settings(universe_id, &target, &mut state, cookie_client, opts.dry_run, report).await?,
+            None => warn!("ROBLOX_COOKIE is not set; skipping universe name and description"),
+        }
+    }
+    sync_game_passes(universe_id, &target, &mut state, &client, opts, report).await?;
+    sync_developer_products(universe_id, &target, &mut state, &client, opts, report).await?;
+    sync_badges(universe_id, &target, &mut state, &client, opts, report).await?;
+
+    if places {
+        for (place_id, file) in &snapshot.places {
+            if !same_universe {
+                warn!("  Place {} belongs to universe {}, skipping", place_id, snapshot.universe_id);
+                report.skip("place");
+                continue;
+            }
+            if opts.dry_run {
+                info!("  Dry Run: Would publish place {} from the snapshot", place_id);
+                continue;
+            }
+            publish_with_retries(&client, universe_id, *place_id, &dir.join(file)).await?;
+            info!("  Published place {}", place_id);
+            report.record("place", file, Some(*place_id), ChangeAction::Publish, ChangeSet::default());
+        }
+    }
+
+    if opts.dry_run {
+        info!("Dry Run: Would save state.");
+    } else {
+        state.save(root)?;
+        report.artifact(SyncState::get_state_path(root));
+        info!("Restore complete");
+    }
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+/// Make the configured universe public or private right away, recording it
+/// in the lock file so the next `run` only changes it again if
+/// `universe.active` says otherwise
+pub async fn universe_set_active(config: &RblxSyncConfig, root: &Path, cookie_client: RobloxCookieClient, active: bool, confirm: Confirmation, report: &mut RunReport) -> Result<()> {
+    let universe_id = config.universe.id;
+    if !active {
+        warn!("Deactivating makes universe {} private; players can't join until it is activated again", universe_id);
+        confirm.require(&format!("Deactivate universe {}", universe_id))?;
+    }
+    let mut state = SyncState::load(root)?;
+    let previous = state.universe.as_ref().and_then(|u| u.settings.get("active").cloned());
+
+    cookie_client.set_universe_active(universe_id, active).await?;
+    info!("Universe {} {}", universe_id, if active { "activated (public)" } else { "deactivated (private)" });
+
+    state.universe.get_or_insert_with(Default::default).settings.insert("active".to_string(), active.into());
+    state.save(root)?;
+    let mut changes = ChangeSet::default();
+    changes.push("active", previous, active);
+    report.record("universe", config.universe.name.as_deref().unwrap_or_default(), Some(universe_id), ChangeAction::Update, changes);
+    report.artifact(SyncState::get_state_path(root));
+    Ok(())
+}
+
+/// Enable or disable tracked badges (all of them, or those matching
+/// `filter`) without a full sync, e.g. when a seasonal event ends
+#[allow(clippy::too_many_arguments)]
+pub async fn badges_set_enabled(config: &RblxSyncConfig, root: &Path, client: &RobloxClient, filter: Option<&NameFilter>, enabled: bool, dry_run: bool, confirm: Confirmation, report: &mut RunReport) -> Result<()> {
+    report.dry_run = dry_run;
+    let mut state = SyncState::load(root)?;
+    let mut selected: Vec<(u64, String)> = state
+        .badges
+        .iter()
+        .filter(|(_, entry)| filter.is_none_or(|f| f.matches(&entry.name)))
+        .filter(|(_, entry)| entry.is_enabled != Some(enabled))
+        .map(|(id, entry)| (*id, entry.name.clone()))
+        .collect();
+    selected.sort_by(|a, b| a.1.cmp(&b.1));
+    let verb = if enabled { "enable" } else { "disable" };
+    if selected.is_empty() {
+        info!("No tracked badges left to {}", verb);
+        return Ok(());
+    }
+    if dry_run {
+        for (id, name) in &selected {
+            info!("  Dry Run: Would {} badge '{}' (ID: {})", verb, name, id);
+        }
+        return Ok(());
+    }
+    if !enabled {
+        confirm.require(&format!("Disable {} badge(s)", selected.len()))?;
+    }
+
+    let actor = actor::current();
+    for (id, name) in &selected {
+        // One badge failing doesn't stop the rest, and those already
+        // changed are still saved to the lock file below
+        if let Err(e) = client.update_badge(*id, &serde_json::json!({ "enabled": enabled })).await {
+            error!("  Failed to {} badge '{}' (ID: {}): {}", verb, name, id, e);
+            report.fail("badge", format!("Failed to {} badge '{}' (ID: {}): {}", verb, name, id, e));
+            continue;
+        }
+        info!("  Badge '{}' (ID: {}) {}d", name, id, verb);
+        let previous = state.badges.get(id).and_then(|entry| entry.is_enabled);
+        if let Some(entry) = state.badges.get_mut(id) {
+            entry.is_enabled = Some(enabled);
+            entry.last_synced = Some(SyncStamp::now(&actor));
+        }
+        let mut changes = ChangeSet::default();
+        changes.push("is_enabled", previous, enabled);
+        report.record("badge", name, Some(*id), ChangeAction::Update, changes);
+
+        // The next run would undo this for badges that pin is_enabled in config
+        let configured = config.badges.iter().find(|b| b.id == Some(*id) || (b.id.is_none() && b.name.eq_ignore_ascii_case(name)));
+        if let Some(badge) = configured.filter(|b| b.is_enabled == Some(!enabled)) {
+            warn!("    Config sets is_enabled: {} for '{}'; the next run will revert this unless the config is updated", !enabled, badge.name);
+        }
+    }
+    state.save(root)?;
+    report.artifact(SyncState::get_state_path(root));
+    Ok(())
+}
+
+async fn sync_universe_settings(universe_id: u64, config: &RblxSyncConfig, state: &mut SyncState, cookie_client: &RobloxCookieClient, dry_run: bool, report: &mut RunReport) -> Result<()> {
+    info!("{}", Msg::Syncing(Section::Universe).text());
+    
+    // Build the current desired state from config
+    // Convert private_server_cost to state string for comparison
+    let private_server_cost_state = config.universe.private_server_cost.as_ref().map(|c| match c {
+        PrivateServerCost::Disabled => "disabled".to_string(),
+        PrivateServerCost::Free => "0".to_string(),
+        PrivateServerCost::Paid(cost) => cost.to_string(),
+    });
+    
+    let desired_state = UniverseState {
+        name: config.universe.name.clone(),
+        description: config.universe.description.clone(),
+        genre: config.universe.genre.clone(),
+        playable_devices: config.universe.playable_devices.clone(),
+        max_players: config.universe.max_players,
+        private_server_cost: private_server_cost_state.clone(),
+        ..Default::default()
+    };
+    let extended = config.universe.extended_settings();
+    
+    // Check for diffs against stored state
+    let stored_state = state.universe.as_ref();
+    let mut changes = ChangeSet::default();
+    
+    if stored_state.map(|s| &s.name) != Some(&desired_state.name) && desired_state.name.is_some() {
+        changes.push("name", stored_state.and_then(|s| s.name.clone()), &desired_state.name);
+    }
+    if stored_state.map(|s| &s.description) != Some(&desired_state.description) && desired_state.description.is_some() {
+        changes.push("description", stored_state.and_then(|s| s.description.clone()), &desired_state.description);
+    }
+    if stored_state.map(|s| &s.playable_devices) != Some(&desired_state.playable_devices) && desired_state.playable_devices.is_some() {
+        changes.push("playable_devices", stored_state.and_then(|s| s.playable_devices.clone()), &desired_state.playable_devices);
+    }
+    if stored_state.map(|s| &s.private_server_cost) != Some(&desired_state.private_server_cost) && desired_state.private_server_cost.is_some() {
+        changes.push("private_server_cost", stored_state.and_then(|s| s.private_server_cost.clone()), &desired_state.private_server_cost);
+    }
+    if stored_state.map(|s| &s.max_players) != Some(&desired_state.max_players) && desired_state.max_players.is_some() {
+        changes.push("max_players", stored_state.and_then(|s| s.max_players), desired_state.max_players);
+    }
+    for setting in &extended {
+        let stored = stored_state.and_then(|s| s.settings.get(setting.field));
+        if stored != Some(&setting.value) {
+            changes.push(setting.field, stored, &setting.value);
+        }
+    }
+    
+    let has_changes = !changes.is_empty();
+    
+    if !has_changes {
+        info!("  [SKIP] Universe Settings - no changes detected");
+        report.skip("universe");
+        return Ok(());
+    }
+    
+    // Build the request body for develop.roblox.com/v2/universes/{id}/configuration
+    let mut body = serde_json::Map::new();
+    
+    // Add fields that are changing
+    if changes.contains("name") {
+        if let Some(name) = &desired_state.name {
+            body.insert("name".to_string(), name.clone().into());
+        }
+    }
+    if changes.contains("description") {
+        if let Some(desc) = &desired_state.description {
+            body.insert("description".to_string(), desc.clone().into());
+        }
+    }
+    
+    // Map playable devices to numeric array (1=Computer, 2=Phone, 3=Tablet, 4=Console, 5=VR)
+    if changes.contains("playable_devices") {
+        if let Some(devices) = &desired_state.playable_devices {
+            let device_ids: Vec<u8> = devices.iter().filter_map(|d| {
+                match d.to_lowercase().as_str() {
+                    "computer" => Some(1),
+                    "phone" => Some(2),
+                    "tablet" => Some(3),
+                    "console" => Some(4),
+                    "vr" => Some(5),
+                    _ => None,
+                }
+            }).collect();
+            body.insert("playableDevices".to_string(), serde_json::json!(device_ids));
+        }
+    }
+    
+    // Handle private server cost
+    if changes.contains("private_server_cost") {
+        if let Some(cost) = &config.universe.private_server_cost {
+            match cost {
+                PrivateServerCost::Disabled => {
+                    body.insert("allowPrivateServers".to_string(), serde_json::json!(false));
+                }
+                PrivateServerCost::Free => {
+                    body.insert("allowPrivateServers".to_string(), serde_json::json!(true));
+                    body.insert("privateServerPrice".to_string(), serde_json::json!(0));
+                }
+                PrivateServerCost::Paid(price) => {
+                    body.insert("allowPrivateServers".to_string(), serde_json::json!(true));
+                    body.insert("privateServerPrice".to_string(), serde_json::json!(price));
+                }
+            }
+        }
+    }
+    if changes.contains("max_players") {
+        body.insert("maxPlayerCount".to_string(), serde_json::json!(desired_state.max_players));
+    }
+    for setting in extended.iter().filter(|s| changes.contains(s.field)) {
+        body.extend(setting.body.clone());
+    }
+    let voice_chat = config.universe.voice_chat.filter(|_| changes.contains("voice_chat"));
+    let active = config.universe.active.filter(|_| changes.contains("active"));
+    
+    if dry_run {
+        info!("  [UPDATE] Universe Settings - would update: {}", changes.join(", "));
+        report.record("universe", desired_state.name.as_deref().unwrap_or_default(), Some(universe_id), ChangeAction::Update, changes.clone());
+        info!("  Dry Run: Would PATCH to https://develop.roblox.com/v2/universes/{}/configuration", universe_id);
+    } else {
+        if !body.is_empty() {
+            info!("  Request URL: https://develop.roblox.com/v2/universes/{}/configuration", universe_id);
+            info!("  Request Body: {}", serde_json::to_string_pretty(&serde_json::Value::Object(body.clone())).unwrap_or_default());
+            let response = cookie_client.update_universe_configuration(universe_id, &serde_json::Value::Object(body)).await?;
+
+            // Output raw response
+            info!("  Universe API Response: {}", serde_json::to_string_pretty(&response).unwrap_or_else(|_| response.to_string()));
+        }
+        if let Some(enabled) = voice_chat {
+            cookie_client.update_universe_voice(universe_id, enabled).await?;
+        }
+        if let Some(active) = active {
+            cookie_client.set_universe_active(universe_id, active).await?;
+        }
+        
+        // Update state after successful sync
+        state.update_universe(
+            desired_state.name.clone(),
+            desired_state.description.clone(),
+            desired_state.genre.clone(),
+            desired_state.playable_devices.clone(),
+            desired_state.max_players,
+            desired_state.private_server_cost.clone(),
+        );
+        if let Some(universe) = state.universe.as_mut() {
+            universe.settings.extend(extended.into_iter().map(|s| (s.field.to_string(), s.value)));
+        }
+        
+        info!("  [UPDATED] Universe Settings - updated: {}", changes.join(", "));
+        report.record("universe", desired_state.name.as_deref().unwrap_or_default(), Some(universe_id), ChangeAction::Update, changes.clone());
+    }
+    
+    Ok(())
+}
+
+async fn sync_game_passes(universe_id: u64, config: &RblxSyncConfig, state: &mut SyncState, client: &RobloxClient, opts: &SyncOptions, report: &mut RunReport) -> Result<()> {
+    let dry_run = opts.dry_run;
+    info!("{}", Msg::Syncing(Section::GamePasses).text());
+    
+    let mut created_count = 0;
+    let mut updated_count = 0;
+    let mut skipped_count = 0;
+    
+    // Fetch existing to handle initial discovery
+    let existing = list_remote(client.game_passes(universe_id), "game passes", dry_run).await?;
+
+    let mut remote_map = RemoteIndex::default();
+    for item in &existing {
+        tracing::debug!("Game pass item from API: {}", item);
+        let id = item["id"].as_u64()
+            .or_else(|| item["gamePassId"].as_u64())
+            .or_else(|| item["id"].as_str().and_then(|s| s.parse().ok()))
+            .or_else(|| item["gamePassId"].as_str().and_then(|s| s.parse().ok()));
+        
+        if let (Some(name), Some(id)) = (item["name"].as_str(), id) {
+            tracing::debug!("Found game pass: {} with ID: {}", name, id);
+            remote_map.insert(name, id, item);
+        }
+    }
+
+    let claimed: Vec<_> = config.game_passes.iter().map(|r| (r.id, r.name.as_str(), r.aliases.as_slice())).collect();
+    let renames = renamed_entries("Game Pass", &state.game_passes, &claimed, &remote_map, |name| opts.selects(SyncSection::GamePasses, name))?;
+
+    let icon_vars = template::variables(config, &opts.root);
+    for pass in &config.game_passes {
+        async {
+            if !opts.selects(SyncSection::GamePasses, &pass.name) {
+                return Ok(());
+            }
+            // State lookup by pinned ID, else case-insensitive by name or a config rename
+            let state_lookup = match pass.id {
+                Some(pinned) => state.game_passes.get(&pinned).map(|s| (pinned, s)),
+                None => state.find_game_pass_by_name(&pass.name)
+                    .or_else(|| pass.aliases.iter().find_map(|a| state.find_game_pass_by_name(a)))
+                    .or_else(|| renames.get(&pass.name).and_then(|id| state.game_passes.get(id).map(|s| (*id, s)))),
+            };
+            let state_entry = state_lookup.map(|(_, s)| s);
+            let last_synced = state_entry.and_then(|s| s.last_synced.clone());
+            let known_updated = state_entry.and_then(|s| s.remote_updated.clone());
+            let previous = state_entry.cloned();
+
+            if opts.no_create && state_lookup.is_none() && pass.id.is_none() && !remote_map.contains(&pass.name, &pass.aliases) {
+                warn!("  [SKIP] Game Pass '{}' - not found remotely, creation disabled by --no-create", pass.name);
+                skipped_count += 1;
+                report.skip("game_pass");
+                return Ok(());
+            }
+            remote_map.check_pinned(pass.id, universe_id, "Game Pass", &pass.name)?;
+            let mut asset_id = None;
+            let mut icon_hash = None;
+            let mut icon_source = None;
+            let mut icon_changed = false;
+            let mut changes = ChangeSet::default();
+
+            // Check for metadata changes (name, description, price, is_for_sale)
+            if let Some(entry) = state_entry {
+                if entry.name != pass.name {
+                    changes.push("name", &entry.name, &pass.name);
+                }
+                if entry.description.as_ref() != pass.description.as_ref() {
+                    changes.push("description", &entry.description, &pass.description);
+                }
+                if entry.price != pass.price.map(|p| p as u64) {
+                    changes.push("price", entry.price, pass.price);
+                }
+                if pass.is_for_sale.is_some() && entry.is_for_sale != pass.is_for_sale {
+                    changes.push("is_for_sale", entry.is_for_sale, pass.is_for_sale);
+                }
+                if pass.regional_pricing_enabled.is_some() && entry.regional_pricing_enabled != pass.regional_pricing_enabled {
+                    changes.push("regional_pricing_enabled", entry.regional_pricing_enabled, pass.regional_pricing_enabled);
+                }
+            }
+            skip_kept_remote(&mut changes, state_entry, opts);
+            // Fields as of the last sync, in config and remotely, to merge edits made
+            // outside rblxsync. Names always follow config, as they identify the resource.
+            let merge_fields = match state_lookup.and_then(|(sid, entry)| remote_map.item(sid).map(|item| (entry, item))) {
+                Some((entry, item)) => vec![
+                    MergeField::new("description", entry.merge_base("description"), &pass.description, item["description"].as_str()),
+                    MergeField::new("price", entry.merge_base("price"), pass.price, pull::price_of(item)),
+                    MergeField::new("is_for_sale", entry.merge_base("is_for_sale"), pass.is_for_sale, pull::bool_of(item, &["isForSale"])),
+                    MergeField::new("regional_pricing_enabled", entry.merge_base("regional_pricing_enabled"), pass.regional_pricing_enabled, pull::bool_of(item, &["isRegionalPricingEnabled"])),
+                ],
+                None => Vec::new(),
+            };
+
+            // Handle Icon - calculate hash and check for changes
+            let mut icon_file = None;
+            if let Some((icon, icon_path)) = resource_icon(&opts.root, config, &icon_vars, &pass.name, pass.price, pass.icon.as_ref(), pass.icon_template.as_ref(), IconKind::GamePass).await? {
+                let current_hash = icon.hash.clone();
+                let stored_hash = state_entry.and_then(|s| s.icon_hash.as_ref());
+                let known = state.reusable_icon(&current_hash, !opts.no_cache);
+                let unchanged = stored_hash == Some(&current_hash) && known.is_some();
+                let remote_id = state_lookup.map(|(id, _)| id).or(pass.id).or_else(|| remote_map.unique(&pass.name, &pass.aliases));
+                let live_match = match remote_id {
+                    Some(rid) if config.compare_remote_icons && !unchanged => remote_icon_matches(client, ResourceKind::GamePasses, rid, &icon.data).await.then_some(rid),
+                    _ => None,
+                };
+
+                if unchanged {
+                    asset_id = known;
+                    icon_hash = Some(current_hash);
+                    icon_changed = false;
+                } else if let Some(rid) = live_match {
+                    info!("  Game Pass '{}' icon already matches the live icon, skipping upload", pass.name);
+                    asset_id = remote_map.item(rid).and_then(pull::icon_asset_id_of);
+                    icon_hash = Some(current_hash);
+                    icon_source = Some(IconSource::Live);
+                    icon_changed = false;
+                } else if dry_run {
+                    asset_id = Some(0); 
+                    icon_hash = Some(current_hash);
+                    icon_changed = true;
+                    changes.push("icon", stored_hash, &icon.hash);
+                } else {
+                    let creator = config.creator.as_ref().ok_or_else(|| anyhow!("Creator configuration is required for asset uploads"))?;
+                    let (aid, hash, source) = ensure_icon(client, &icon, &icon_path, known, creator, !opts.no_cache).await?;
+                    asset_id = Some(aid);
+                    icon_hash = Some(hash);
+                    icon_source = source;
+                    icon_changed = true;
+                    changes.push("icon", stored_hash, &icon.hash);
+                }
+                icon_file = Some(icon);
+            }
+
+            // Determine ID (State -> Remote -> Create) - case-insensitive matching
+            let state_id = state_lookup.map(|(id, _)| id);
+            let remote_entry = if state_id.is_none() {
+                remote_map.find(pass.id, &pass.name, &pass.aliases, "Game Pass", opts)?
+            } else {
+                None
+            };
+            let is_new = state_id.is_none() && remote_entry.is_none() && pass.id.is_none();
+
+            // Adopting a remote resource found under another name (e.g. an alias) renames it
+            if let (None, Some((remote_name, _))) = (state_id, remote_entry) {
+                if remote_name != &pass.name {
+                    changes.push("name", remote_name, &pass.name);
+                }
+            }
+            // A known ID whose remote name differs from config is renamed in place
+            if let Some(sid) = state_id {
+                if let Some((remote_name, _)) = remote_map.by_id(sid) {
+                    if remote_name != &pass.name && !changes.contains("name") {
+                        changes.push("name", remote_name, &pass.name);
+                    }
+                }
+            }
+
+            let id = if let Some(sid) = state_id {
+                sid
+            } else if let Some((_, rid)) = remote_entry {
+                *rid
+            } else if let Some(pinned) = pass.id {
+                pinned
+            } else if dry_run {
+                info!("  [CREATE] Game Pass '{}' - would create with: name, description, price{}", 
+                    pass.name, 
+                    if pass.icon.is_some() || pass.icon_template.is_some() { ", icon" } else { "" });
+                created_count += 1;
+                report.record("game_pass", &pass.name, None, ChangeAction::Create, ChangeSet::created(pass));
+                0
+            } else {
+                // Without a price a pass can only be created off sale
+                let price = pass.price.filter(|p| *p > 0);
+                let mut body = serde_json::json!({
+                    "name": pass.name,
+                    "description": pass.description.clone().unwrap_or_default(),
+                    "isForSale": pass.is_for_sale.unwrap_or(price.is_some()),
+                });
+                if let Some(price) = price {
+                    body["price"] = price.into();
+                }
+                if let Some(aid) = asset_id {
+                    body["iconAssetId"] = aid.into();
+                }
+                if let Some(regional) = pass.regional_pricing_enabled {
+                    body["isRegionalPricingEnabled"] = regional.into();
+                }
+
+                let resp = client.create_game_pass(universe_id, &body).await?;
+                let new_id = resp["id"].as_u64().ok_or(anyhow!("Created game pass has no ID"))?;
+                info!("  [CREATED] Game Pass '{}' (ID: {}) - created with: name, description, price{}", 
+                    pass.name, new_id,
+                    if pass.icon.is_some() || pass.icon_template.is_some() { ", icon" } else { "" });
+                created_count += 1;
+                report.record("game_pass", &pass.name, Some(new_id), ChangeAction::Create, ChangeSet::created(pass));
+                new_id
+            };
+
+            let merged = merge_remote_edits("Game Pass", &pass.name, id, known_updated.as_deref(), &remote_map, &merge_fields, &mut changes, opts)?;
+            let mut remote_updated = merged.updated.clone();
+            let has_changes = !changes.is_empty();
+
+            // Update Remote (Idempotent PATCH) - only if newly created or has changes
+            if is_new {
+                // Already created above
+            } else if dry_run {
+                if has_changes {
+                    info!("  [UPDATE] Game Pass '{}' (ID: {}) - would update: {}", 
+                        pass.name, id, changes.join(", "));
+                    log_price_change(&changes);
+                    updated_count += 1;
+                    report.record("game_pass", &pass.name, Some(id), ChangeAction::Update, changes.clone());
+                } else {
+                    info!("  [SKIP] Game Pass '{}' (ID: {}) - no changes detected", pass.name, id);
+                    skipped_count += 1;
+                    report.skip("game_pass");
+                }
+            } else if has_changes {
+                // Only changed fields, so untouched ones can't clobber remote edits
+                let mut patch = serde_json::Map::new();
+                if changes.contains("name") { patch.insert("name".to_string(), pass.name.clone().into()); }
+                if let Some(d) = pass.description.as_ref().filter(|_| changes.contains("description")) { patch.insert("description".to_string(), d.clone().into()); }
+                if let Some(p) = pass.price.filter(|_| changes.contains("price")) { patch.insert("price".to_string(), p.into()); }
+                if let Some(s) = pass.is_for_sale.filter(|_| changes.contains("is_for_sale")) { patch.insert("isForSale".to_string(), s.into()); }
+                if let Some(r) = pass.regional_pricing_enabled.filter(|_| changes.contains("regional_pricing_enabled")) { patch.insert("isRegionalPricingEnabled".to_string(), r.into()); }
+
+                // Read image file if icon changed
+                let image_data = if icon_changed {
+                    icon_file.map(|icon| (icon.data, icon.filename))
+                } else {
+                    None
+                };
+
+                let response = client.update_game_pass_with_icon(universe_id, id, &serde_json::Value::Object(patch), image_data).await?;
+                remote_updated = updated_after_patch(&response, client.get_game_pass(universe_id, id)).await;
+                info!("  [UPDATED] Game Pass '{}' (ID: {}) - updated: {}", 
+                    pass.name, id, changes.join(", "));
+                log_price_change(&changes);
+                updated_count += 1;
+                report.record("game_pass", &pass.name, Some(id), ChangeAction::Update, changes.clone());
+            } else {
+                info!("  [SKIP] Game Pass '{}' (ID: {}) - no changes detected", pass.name, id);
+                skipped_count += 1;
+                report.skip("game_pass");
+            }
+
+            let (moderation, moderation_checked) = check_icon_moderation(client, "Game Pass", &pass.name, asset_id, icon_hash.as_deref(), state_entry, icon_changed, dry_run).await;
+
+            // Update State after successful sync
+            if !dry_run && id != 0 {
+                state.update_game_pass(
+                    id,
+                    pass.name.clone(), 
+                    pass.description.clone(),
+                    pass.price.map(|p| p as u64),
+                    pass.is_for_sale,
+                    icon_hash.clone(), 
+                    asset_id
+                );
+                if let (Some(hash), Some(source)) = (&icon_hash, icon_source.filter(|_| asset_id.is_some())) {
+                    state.set_icon_source(hash, source);
+                }
+                queue_verification(state, EntryKind::GamePass, id, &pass.name, &pass.description, is_new || changes.contains("name") || changes.contains("description"), asset_id.filter(|_| moderation.as_deref() == Some("Reviewing")));
+                if let Some(entry) = state.game_passes.get_mut(&id) {
+                    entry.icon_moderation_state = moderation;
+                    entry.icon_moderation_checked = moderation_checked;
+                    if pass.regional_pricing_enabled.is_some() {
+                        entry.regional_pricing_enabled = pass.regional_pricing_enabled;
+                    }
+                    entry.last_synced = if is_new || has_changes { Some(SyncStamp::now(&opts.actor)) } else { last_synced };
+                    entry.remote_updated = remote_updated;
+                    record_kept_remote(entry, previous.as_ref(), &merged.kept, &changes);
+                }
+            }
+            Ok::<_, anyhow::Error>(())
+        }
+        .instrument(info_span!("game_pass", resource = %pass.name))
+        .await?;
+    }
+    
+    info!("{}", Msg::Summary { section: Section::GamePasses, created: created_count, updated: updated_count, skipped: skipped_count }.text());
+    Ok(())
+}
+
+async fn sync_developer_products(universe_id: u64, config: &RblxSyncConfig, state: &mut SyncState, client: &RobloxClient, opts: &SyncOptions, report: &mut RunReport) -> Result<()> {
+    let dry_run = opts.dry_run;
+    info!("{}", Msg::Syncing(Section::DeveloperProducts).text());
+    
+    let mut created_count = 0;
+    let mut updated_count = 0;
+    let mut skipped_count = 0;
+    
+    let existing = list_remote(client.developer_products(universe_id), "developer products", dry_run).await?;
+
+    let mut remote_map = RemoteIndex::default();
+    for item in &existing {
+        tracing::debug!("Developer product item from API: {}", item);
+        let id = item["id"].as_u64()
+            .or_else(|| item["productId"].as_u64())
+            .or_else(|| item["developerProductId"].as_u64())
+            .or_else(|| item["id"].as_str().and_then(|s| s.parse().ok()))
+            .or_else(|| item["productId"].as_str().and_then(|s| s.parse().ok()));
+        
+        if let (Some(name), Some(id)) = (item["name"].as_str(), id) {
+            tracing::debug!("Found developer product: {} with ID: {}", name, id);
+            remote_map.insert(name, id, item);
+        }
+    }
+
+    let claimed: Vec<_> = config.developer_products.iter().map(|r| (r.id, r.name.as_str(), r.aliases.as_slice())).collect();
+    let renames = renamed_entries("Developer Product", &state.developer_products, &claimed, &remote_map, |name| opts.selects(SyncSection::DeveloperProducts, name))?;
+
+    let icon_vars = template::variables(config, &opts.root);
+    for prod in &config.developer_products {
+        async {
+            if !opts.selects(SyncSection::DeveloperProducts, &prod.name) {
+                return Ok(());
+            }
+            // State lookup by pinned ID, else case-insensitive by name or a config rename
+            let state_lookup = match prod.id {
+                Some(pinned) => state.developer_products.get(&pinned).map(|s| (pinned, s)),
+                None => state.find_developer_product_by_name(&prod.name)
+                    .or_else(|| prod.aliases.iter().find_map(|a| state.find_developer_product_by_name(a)))
+                    .or_else(|| renames.get(&prod.name).and_then(|id| state.developer_products.get(id).map(|s| (*id, s)))),
+            };
+            let state_entry = state_lookup.map(|(_, s)| s);
+            let last_synced = state_entry.and_then(|s| s.last_synced.clone());
+            let known_updated = state_entry.and_then(|s| s.remote_updated.clone());
+            let previous = state_entry.cloned();
+
+            if opts.no_create && state_lookup.is_none() && prod.id.is_none() && !remote_map.contains(&prod.name, &prod.aliases) {
+                warn!("  [SKIP] Developer Product '{}' - not found remotely, creation disabled by --no-create", prod.name);
+                skipped_count += 1;
+                report.skip("developer_product");
+                return Ok(());
+            }
+            remote_map.check_pinned(prod.id, universe_id, "Developer Product", &prod.name)?;
+            let mut asset_id = None;
+            let mut icon_hash = None;
+            let mut icon_source = None;
+            let mut icon_changed = false;
+            let mut changes = ChangeSet::default();
+
+            // Check for metadata changes (name, description, price, is_active)
+            if let Some(entry) = state_entry {
+                if entry.name != prod.name {
+                    changes.push("name", &entry.name, &prod.name);
+                }
+                if entry.description.as_ref() != prod.description.as_ref() {
+                    changes.push("description", &entry.description, &prod.description);
+                }
+                if entry.price != Some(prod.price as u64) {
+                    changes.push("price", entry.price, prod.price);
+                }
+                if prod.is_active.is_some() && entry.is_active != prod.is_active {
+                    changes.push("is_active", entry.is_active, prod.is_active);
+                }
+                if prod.regional_pricing_enabled.is_some() && entry.regional_pricing_enabled != prod.regional_pricing_enabled {
+                    changes.push("regional_pricing_enabled", entry.regional_pricing_enabled, prod.regional_pricing_enabled);
+                }
+            }
+            skip_kept_remote(&mut changes, state_entry, opts);
+            let merge_fields = match state_lookup.and_then(|(sid, entry)| remote_map.item(sid).map(|item| (entry, item))) {
+                Some((entry, item)) => vec![
+                    MergeField::new("description", entry.merge_base("description"), &prod.description, item["description"].as_str()),
+                    MergeField::new("price", entry.merge_base("price"), prod.price, pull::price_of(item)),
+                    MergeField::new("is_active", entry.merge_base("is_active"), prod.is_active, pull::bool_of(item, &["isForSale", "isActive"])),
+                    MergeField::new("regional_pricing_enabled", entry.merge_base("regional_pricing_enabled"), prod.regional_pricing_enabled, pull::bool_of(item, &["isRegionalPricingEnabled"])),
+                ],
+                None => Vec::new(),
+            };
+
+            let mut icon_file = None;
+            if let Some((icon, icon_path)) = resource_icon(&opts.root, config, &icon_vars, &prod.name, Some(prod.price), prod.icon.as_ref(), prod.icon_template.as_ref(), IconKind::DeveloperProduct).await? {
+                let current_hash = icon.hash.clone();
+                let stored_hash = state_entry.and_then(|s| s.icon_hash.as_ref());
+                let known = state.reusable_icon(&current_hash, !opts.no_cache);
+                let unchanged = stored_hash == Some(&current_hash) && known.is_some();
+                let remote_id = state_lookup.map(|(id, _)| id).or(prod.id).or_else(|| remote_map.unique(&prod.name, &prod.aliases));
+                let live_match = match remote_id {
+                    Some(rid) if config.compare_remote_icons && !unchanged => remote_icon_matches(client, ResourceKind::DeveloperProducts, rid, &icon.data).await.then_some(rid),
+                    _ => None,
+                };
+
+                if unchanged {
+                    asset_id = known;
+                    icon_hash = Some(current_hash);
+                    icon_changed = false;
+                } else if let Some(rid) = live_match {
+                    info!("  Developer Product '{}' icon already matches the live icon, skipping upload", prod.name);
+                    asset_id = remote_map.item(rid).and_then(pull::icon_asset_id_of);
+                    icon_hash = Some(current_hash);
+                    icon_source = Some(IconSource::Live);
+                    icon_changed = false;
+                } else if dry_run {
+                    asset_id = Some(0);
+                    icon_hash = Some(current_hash);
+                    icon_changed = true;
+                    changes.push("icon", stored_hash, &icon.hash);
+                } else {
+                    let creator = config.creator.as_ref().ok_or_else(|| anyhow!("Creator configuration is required for asset uploads"))?;
+                    let (aid, hash, source) = ensure_icon(client, &icon, &icon_path, known, creator, !opts.no_cache).await?;
+                    asset_id = Some(aid);
+                    icon_hash = Some(hash);
+                    icon_source = source;
+                    icon_changed = true;
+                    changes.push("icon", stored_hash, &icon.hash);
+                }
+                icon_file = Some(icon);
+            }
+
+            // Case-insensitive matching for ID lookup
+            let state_id = state_lookup.map(|(id, _)| id);
+            let remote_entry = if state_id.is_none() {
+                remote_map.find(prod.id, &prod.name, &prod.aliases, "Developer Product", opts)?
+            } else {
+                None
+            };
+            let is_new = state_id.is_none() && remote_entry.is_none() && prod.id.is_none();
+
+            // Adopting a remote resource found under another name (e.g. an alias) renames it
+            if let (None, Some((remote_name, _))) = (state_id, remote_entry) {
+                if remote_name != &prod.name {
+                    changes.push("name", remote_name, &prod.name);
+                }
+            }
+            // A known ID whose remote name differs from config is renamed in place
+            if let Some(sid) = state_id {
+                if let Some((remote_name, _)) = remote_map.by_id(sid) {
+                    if remote_name != &prod.name && !changes.contains("name") {
+                        changes.push("name", remote_name, &prod.name);
+                    }
+                }
+            }
+
+            let id = if let Some(sid) = state_id {
+                sid
+            } else if let Some((_, rid)) = remote_entry {
+                *rid
+            } else if let Some(pinned) = prod.id {
+                pinned
+            } else if dry_run {
                 info!("  [CREATE] Developer Product '{}' - would create with: name, price, description{}", 
                     prod.name,
-                    if prod.icon.is_some() { ", icon" } else { "" });
+                    if prod.icon.is_some() || prod.icon_template.is_some() { ", icon" } else { "" });
                 created_count += 1;
+                report.record("developer_product", &prod.name, None, ChangeAction::Create, ChangeSet::created(prod));
                 0
             } else {
                 let mut body = serde_json::json!({
@@ -499,351 +2634,1801 @@ async fn sync_developer_products(universe_id: u64, config: &RblxSyncConfig, stat
                     "description": prod.description.clone().unwrap_or_default(),
                 });
                 if let Some(aid) = asset_id { body["iconAssetId"] = aid.into(); }
+                if let Some(active) = prod.is_active { body["isForSale"] = active.into(); }
+                if let Some(regional) = prod.regional_pricing_enabled { body["isRegionalPricingEnabled"] = regional.into(); }
                 let resp = client.create_developer_product(universe_id, &body).await?;
                 let new_id = resp["id"].as_u64().ok_or(anyhow!("Created product has no ID"))?;
                 info!("  [CREATED] Developer Product '{}' (ID: {}) - created with: name, price, description{}", 
                     prod.name, new_id,
-                    if prod.icon.is_some() { ", icon" } else { "" });
+                    if prod.icon.is_some() || prod.icon_template.is_some() { ", icon" } else { "" });
                 created_count += 1;
+                report.record("developer_product", &prod.name, Some(new_id), ChangeAction::Create, ChangeSet::created(prod));
                 new_id
+            };
+
+            let merged = merge_remote_edits("Developer Product", &prod.name, id, known_updated.as_deref(), &remote_map, &merge_fields, &mut changes, opts)?;
+            let mut remote_updated = merged.updated.clone();
+            let has_changes = !changes.is_empty();
+
+            // Update Remote (Idempotent PATCH) - only if has changes
+            if is_new {
+                // Already created above
+            } else if dry_run {
+                if has_changes {
+                    info!("  [UPDATE] Developer Product '{}' (ID: {}) - would update: {}", 
+                        prod.name, id, changes.join(", "));
+                    log_price_change(&changes);
+                    updated_count += 1;
+                    report.record("developer_product", &prod.name, Some(id), ChangeAction::Update, changes.clone());
+                } else {
+                    info!("  [SKIP] Developer Product '{}' (ID: {}) - no changes detected", prod.name, id);
+                    skipped_count += 1;
+                    report.skip("developer_product");
+                }
+            } else if has_changes {
+                // Only changed fields, so untouched ones can't clobber remote edits
+                let mut patch = serde_json::Map::new();
+                if changes.contains("name") { patch.insert("name".to_string(), prod.name.clone().into()); }
+                if changes.contains("price") { patch.insert("price".to_string(), prod.price.into()); }
+                if let Some(d) = prod.description.as_ref().filter(|_| changes.contains("description")) { patch.insert("description".to_string(), d.clone().into()); }
+                if let Some(a) = prod.is_active.filter(|_| changes.contains("is_active")) { patch.insert("isForSale".to_string(), a.into()); }
+                if let Some(r) = prod.regional_pricing_enabled.filter(|_| changes.contains("regional_pricing_enabled")) { patch.insert("isRegionalPricingEnabled".to_string(), r.into()); }
+
+                // Read image file if icon changed
+                let image_data = if icon_changed {
+                    icon_file.map(|icon| (icon.data, icon.filename))
+                } else {
+                    None
+                };
+
+                let response = client.update_developer_product_with_icon(universe_id, id, &serde_json::Value::Object(patch), image_data).await?;
+                remote_updated = updated_after_patch(&response, client.get_developer_product(universe_id, id)).await;
+                info!("  [UPDATED] Developer Product '{}' (ID: {}) - updated: {}", 
+                    prod.name, id, changes.join(", "));
+                log_price_change(&changes);
+                updated_count += 1;
+                report.record("developer_product", &prod.name, Some(id), ChangeAction::Update, changes.clone());
+            } else {
+                info!("  [SKIP] Developer Product '{}' (ID: {}) - no changes detected", prod.name, id);
+                skipped_count += 1;
+                report.skip("developer_product");
             }
-        };
 
-        // Update Remote (Idempotent PATCH) - only if has changes
-        if is_new {
-            // Already created above
-        } else if dry_run {
-            if has_changes {
-                info!("  [UPDATE] Developer Product '{}' (ID: {}) - would update: {}", 
-                    prod.name, id, changes.join(", "));
-                updated_count += 1;
-            } else {
-                info!("  [SKIP] Developer Product '{}' (ID: {}) - no changes detected", prod.name, id);
-                skipped_count += 1;
+            let (moderation, moderation_checked) = check_icon_moderation(client, "Developer Product", &prod.name, asset_id, icon_hash.as_deref(), state_entry, icon_changed, dry_run).await;
+
+            // Update State after successful sync
+            if !dry_run && id != 0 {
+                state.update_developer_product(
+                    id,
+                    prod.name.clone(), 
+                    prod.description.clone(),
+                    Some(prod.price as u64),
+                    prod.is_active,
+                    icon_hash.clone(), 
+                    asset_id
+                );
+                if let (Some(hash), Some(source)) = (&icon_hash, icon_source.filter(|_| asset_id.is_some())) {
+                    state.set_icon_source(hash, source);
+                }
+                queue_verification(state, EntryKind::DeveloperProduct, id, &prod.name, &prod.description, is_new || changes.contains("name") || changes.contains("description"), asset_id.filter(|_| moderation.as_deref() == Some("Reviewing")));
+                if let Some(entry) = state.developer_products.get_mut(&id) {
+                    entry.icon_moderation_state = moderation;
+                    entry.icon_moderation_checked = moderation_checked;
+                    if prod.regional_pricing_enabled.is_some() {
+                        entry.regional_pricing_enabled = prod.regional_pricing_enabled;
+                    }
+                    entry.last_synced = if is_new || has_changes { Some(SyncStamp::now(&opts.actor)) } else { last_synced };
+                    entry.remote_updated = remote_updated;
+                    record_kept_remote(entry, previous.as_ref(), &merged.kept, &changes);
+                }
+            }
+            Ok::<_, anyhow::Error>(())
+        }
+        .instrument(info_span!("developer_product", resource = %prod.name))
+        .await?;
+    }
+    
+    info!("{}", Msg::Summary { section: Section::DeveloperProducts, created: created_count, updated: updated_count, skipped: skipped_count }.text());
+    Ok(())
+}
+
+async fn sync_badges(universe_id: u64, config: &RblxSyncConfig, state: &mut SyncState, client: &RobloxClient, opts: &SyncOptions, report: &mut RunReport) -> Result<()> {
+    let dry_run = opts.dry_run;
+    info!("{}", Msg::Syncing(Section::Badges).text());
+    
+    let mut created_count = 0;
+    let mut updated_count = 0;
+    let mut skipped_count = 0;
+    
+    let existing = list_remote(client.badges(universe_id), "badges", dry_run).await?;
+
+    let mut remote_map = RemoteIndex::default();
+    for item in existing {
+        if let (Some(name), Some(id)) = (item["name"].as_str(), item["id"].as_u64()) {
+            remote_map.insert(name, id, &item);
+        }
+    }
+
+    let claimed: Vec<_> = config.badges.iter().map(|r| (r.id, r.name.as_str(), r.aliases.as_slice())).collect();
+    let renames = renamed_entries("Badge", &state.badges, &claimed, &remote_map, |name| opts.selects(SyncSection::Badges, name))?;
+
+    confirm_badge_budget(config, state, &remote_map, &renames, opts)?;
+
+    let icon_vars = template::variables(config, &opts.root);
+    for badge in &config.badges {
+        async {
+            if !opts.selects(SyncSection::Badges, &badge.name) {
+                return Ok(());
+            }
+            // State lookup by pinned ID, else case-insensitive by name or a config rename
+            let state_lookup = match badge.id {
+                Some(pinned) => state.badges.get(&pinned).map(|s| (pinned, s)),
+                None => state.find_badge_by_name(&badge.name)
+                    .or_else(|| badge.aliases.iter().find_map(|a| state.find_badge_by_name(a)))
+                    .or_else(|| renames.get(&badge.name).and_then(|id| state.badges.get(id).map(|s| (*id, s)))),
+            };
+            let state_entry = state_lookup.map(|(_, s)| s);
+            let last_synced = state_entry.and_then(|s| s.last_synced.clone());
+            let known_updated = state_entry.and_then(|s| s.remote_updated.clone());
+            let previous = state_entry.cloned();
+
+            if opts.no_create && state_lookup.is_none() && badge.id.is_none() && !remote_map.contains(&badge.name, &badge.aliases) {
+                warn!("  [SKIP] Badge '{}' - not found remotely, creation disabled by --no-create", badge.name);
+                skipped_count += 1;
+                report.skip("badge");
+                return Ok(());
+            }
+            remote_map.check_pinned(badge.id, universe_id, "Badge", &badge.name)?;
+            let mut changes = ChangeSet::default();
+
+            // Check for metadata changes (name, description, is_enabled)
+            if let Some(entry) = state_entry {
+                if entry.name != badge.name {
+                    changes.push("name", &entry.name, &badge.name);
+                }
+                if entry.description.as_ref() != badge.description.as_ref() {
+                    changes.push("description", &entry.description, &badge.description);
+                }
+                if entry.is_enabled != badge.is_enabled {
+                    changes.push("is_enabled", entry.is_enabled, badge.is_enabled);
+                }
+            }
+            skip_kept_remote(&mut changes, state_entry, opts);
+            let merge_fields = match state_lookup.and_then(|(sid, entry)| remote_map.item(sid).map(|item| (entry, item))) {
+                Some((entry, item)) => vec![
+                    MergeField::new("description", entry.merge_base("description"), &badge.description, item["description"].as_str()),
+                    MergeField::new("is_enabled", entry.merge_base("is_enabled"), badge.is_enabled, pull::bool_of(item, &["enabled", "isEnabled"])),
+                ],
+                None => Vec::new(),
+            };
+
+            // Prepare icon data if provided
+            let icon_path = badge.icon.as_ref().map(|icon| Path::new(&config.assets_dir).join(icon)).filter(|_| badge.icon_template.is_none());
+            let icon_data = if let Some(icon_path) = icon_path.filter(|path| !path.exists()) {
+                warn!("Badge icon not found: {:?}", icon_path);
+                None
+            } else {
+                resource_icon(&opts.root, config, &icon_vars, &badge.name, None, badge.icon.as_ref(), badge.icon_template.as_ref(), IconKind::Badge)
+                    .await?
+                    .map(|(icon, _)| (icon.data, icon.filename, icon.hash))
+            };
+
+            // Check if icon has changed
+            let icon_changed = if let Some((data, _, new_hash)) = &icon_data {
+                let stored_hash = state_entry.and_then(|s| s.icon_hash.as_ref());
+                let remote_id = state_lookup.map(|(id, _)| id).or(badge.id).or_else(|| remote_map.unique(&badge.name, &badge.aliases));
+                let live_match = match remote_id {
+                    Some(rid) if config.compare_remote_icons && stored_hash != Some(new_hash) => remote_icon_matches(client, ResourceKind::Badges, rid, data).await,
+                    _ => false,
+                };
+                if stored_hash == Some(new_hash) {
+                    false
+                } else if live_match {
+                    info!("  Badge '{}' icon already matches the live icon, skipping upload", badge.name);
+                    false
+                } else {
+                    changes.push("icon", stored_hash, new_hash);
+                    true
+                }
+            } else {
+                false
+            };
+
+            // Case-insensitive matching for ID lookup
+            let state_id = state_lookup.map(|(id, _)| id);
+            let remote_entry = if state_id.is_none() {
+                remote_map.find(badge.id, &badge.name, &badge.aliases, "Badge", opts)?
+            } else {
+                None
+            };
+            let is_new = state_id.is_none() && remote_entry.is_none() && badge.id.is_none();
+
+            // Adopting a remote resource found under another name (e.g. an alias) renames it
+            if let (None, Some((remote_name, _))) = (state_id, remote_entry) {
+                if remote_name != &badge.name {
+                    changes.push("name", remote_name, &badge.name);
+                }
+            }
+            // A known ID whose remote name differs from config is renamed in place
+            if let Some(sid) = state_id {
+                if let Some((remote_name, _)) = remote_map.by_id(sid) {
+                    if remote_name != &badge.name && !changes.contains("name") {
+                        changes.push("name", remote_name, &badge.name);
+                    }
+                }
+            }
+
+            let id = if let Some(sid) = state_id {
+                sid
+            } else if let Some((_, rid)) = remote_entry {
+                *rid
+            } else if let Some(pinned) = badge.id {
+                pinned
+            } else if dry_run {
+                info!("  [CREATE] Badge '{}' - would create with: name, description{}", 
+                    badge.name,
+                    if badge.icon.is_some() || badge.icon_template.is_some() { ", icon" } else { "" });
+                created_count += 1;
+                report.record("badge", &badge.name, None, ChangeAction::Create, ChangeSet::created(badge));
+                0
+            } else {
+                let image_for_create = icon_data.as_ref().map(|(data, filename, _)| (data.clone(), filename.clone()));
+
+                let result = client.create_badge(
+                    universe_id,
+                    &badge.name,
+                    badge.description.as_deref().unwrap_or(""),
+                    image_for_create,
+                    config.badge_payment_source.as_deref()
+                ).await;
+
+                let resp = match result {
+                    Ok(r) => r,
+                    Err(e) => {
+                        let err_str = e.to_string();
+                        if err_str.contains("Payment source is invalid") || err_str.contains("code\":16") {
+                            error!("Badge creation failed: Payment source is required.");
+                            error!("");
+                            error!("Creating badges costs {}. Please add the following to your rblxsync.yml:", i18n::robux(100));
+                            error!("");
+                            error!("  badge_payment_source: \"user\"   # Pay from your user account");
+                            error!("  # OR");
+                            error!("  badge_payment_source: \"group\"  # Pay from group funds");
+                            error!("");
+                            return Err(anyhow!("Badge creation requires badge_payment_source configuration"));
+                        }
+                        return Err(e);
+                    }
+                };
+
+                let new_id = resp["id"].as_u64().ok_or(anyhow!("Created badge has no ID"))?;
+                state.badge_creations.push(actor::now());
+                info!("  [CREATED] Badge '{}' (ID: {}) - created with: name, description{}", 
+                    badge.name, new_id,
+                    if badge.icon.is_some() || badge.icon_template.is_some() { ", icon" } else { "" });
+                created_count += 1;
+                report.record("badge", &badge.name, Some(new_id), ChangeAction::Create, ChangeSet::created(badge));
+                new_id
+            };
+
+            // Update state with icon hash
+            let icon_hash = icon_data.as_ref().map(|(_, _, hash)| hash.clone());
+
+            let merged = merge_remote_edits("Badge", &badge.name, id, known_updated.as_deref(), &remote_map, &merge_fields, &mut changes, opts)?;
+            let mut remote_updated = merged.updated.clone();
+            let has_changes = !changes.is_empty();
+
+            // Update Remote (Idempotent PATCH) - only if has changes
+            if is_new {
+                // Already created above
+            } else if dry_run {
+                if has_changes {
+                    info!("  [UPDATE] Badge '{}' (ID: {}) - would update: {}", 
+                        badge.name, id, changes.join(", "));
+                    updated_count += 1;
+                    report.record("badge", &badge.name, Some(id), ChangeAction::Update, changes.clone());
+                } else {
+                    info!("  [SKIP] Badge '{}' (ID: {}) - no changes detected", badge.name, id);
+                    skipped_count += 1;
+                    report.skip("badge");
+                }
+            } else if has_changes {
+                // Only changed fields, so untouched ones can't clobber remote edits
+                let mut patch = serde_json::Map::new();
+                if changes.contains("name") { patch.insert("name".to_string(), badge.name.clone().into()); }
+                if let Some(d) = badge.description.as_ref().filter(|_| changes.contains("description")) { patch.insert("description".to_string(), d.clone().into()); }
+                if let Some(e) = badge.is_enabled.filter(|_| changes.contains("is_enabled")) { patch.insert("enabled".to_string(), e.into()); }
+                // An icon-only change has no fields to PATCH
+                if !patch.is_empty() {
+                    client.update_badge(id, &serde_json::Value::Object(patch)).await?;
+                }
+
+                // Update icon if it changed
+                if icon_changed {
+                    if let Some((data, filename, _)) = &icon_data {
+                        client.update_badge_icon(id, data.clone(), filename).await?;
+                    }
+                }
+                remote_updated = updated_after_patch(&serde_json::Value::Null, client.get_badge(id)).await;
+                info!("  [UPDATED] Badge '{}' (ID: {}) - updated: {}", 
+                    badge.name, id, changes.join(", "));
+                updated_count += 1;
+                report.record("badge", &badge.name, Some(id), ChangeAction::Update, changes.clone());
+            } else {
+                info!("  [SKIP] Badge '{}' (ID: {}) - no changes detected", badge.name, id);
+                skipped_count += 1;
+                report.skip("badge");
+            }
+
+            // Update State after successful sync
+            if !dry_run && id != 0 {
+                state.update_badge(
+                    id,
+                    badge.name.clone(), 
+                    badge.description.clone(),
+                    badge.is_enabled,
+                    icon_hash.clone()
+                );
+                queue_verification(state, EntryKind::Badge, id, &badge.name, &badge.description, is_new || changes.contains("name") || changes.contains("description"), None);
+                if let Some(entry) = state.badges.get_mut(&id) {
+                    entry.last_synced = if is_new || has_changes { Some(SyncStamp::now(&opts.actor)) } else { last_synced };
+                    entry.remote_updated = remote_updated;
+                    record_kept_remote(entry, previous.as_ref(), &merged.kept, &changes);
+                }
+            }
+            Ok::<_, anyhow::Error>(())
+        }
+        .instrument(info_span!("badge", resource = %badge.name))
+        .await?;
+    }
+    
+    info!("{}", Msg::Summary { section: Section::Badges, created: created_count, updated: updated_count, skipped: skipped_count }.text());
+    Ok(())
+}
+
+async fn sync_assets(config: &RblxSyncConfig, state: &mut SyncState, client: &RobloxClient, opts: &SyncOptions, report: &mut RunReport) -> Result<()> {
+    if config.assets.is_empty() {
+        return Ok(());
+    }
+    let dry_run = opts.dry_run;
+    info!("{}", Msg::Syncing(Section::Assets).text());
+
+    let mut uploaded_count = 0;
+    let mut skipped_count = 0;
+
+    for asset in &config.assets {
+        async {
+            if !opts.selects(SyncSection::Assets, &asset.name) {
+                return Ok(());
+            }
+            let path = Path::new(&config.assets_dir).join(&asset.path);
+            if !path.exists() {
+                return Err(anyhow!("Asset file not found for '{}': {:?}", asset.name, path));
+            }
+            let asset_type = asset_type_for(asset, &path)?;
+            let data = tokio::fs::read(&path).await?;
+            let hash = assets::hash_bytes(&data);
+
+            if let Some(existing) = state.assets.get(&asset.name) {
+                if existing.hash == hash {
+                    info!("  [SKIP] Asset '{}' (ID: {}) - no changes detected", asset.name, existing.asset_id);
+                    skipped_count += 1;
+                    report.skip("asset");
+                    return Ok(());
+                }
+            }
+
+            // Replace in place only while the tracked asset still has the same type
+            let replace_id = state.assets.get(&asset.name)
+                .filter(|existing| asset.replace && existing.asset_type == asset_type.api_name())
+                .map(|existing| existing.asset_id);
+
+            if dry_run {
+                match replace_id {
+                    Some(asset_id) => info!("  [UPDATE] Asset '{}' (ID: {}) - would update in place from {:?}", asset.name, asset_id, path),
+                    None => info!("  [UPLOAD] Asset '{}' - would upload {:?}", asset.name, path),
+                }
+                uploaded_count += 1;
+                report.record("asset", &asset.name, replace_id, ChangeAction::Upload, asset_changes(state, &asset.name, &hash));
+                return Ok(());
+            }
+
+            if let Some(asset_id) = replace_id {
+                let filename = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+                client
+                    .update_asset_bytes(asset_id, data, &filename, asset_type, asset.description.as_deref())
+                    .await
+                    .with_context(|| format!("Failed to update asset '{}' (ID: {}) in place; remove `replace` to upload it as a new asset", asset.name, asset_id))?;
+                info!("  [UPDATED] Asset '{}' (ID: {})", asset.name, asset_id);
+                uploaded_count += 1;
+                report.record("asset", &asset.name, Some(asset_id), ChangeAction::Upload, asset_changes(state, &asset.name, &hash));
+                if let Some(existing) = state.assets.get_mut(&asset.name) {
+                    existing.hash = hash;
+                    existing.last_synced = Some(SyncStamp::now(&opts.actor));
+                }
+                return Ok(());
+            }
+
+            let creator = config.creator.as_ref().ok_or_else(|| anyhow!("Creator configuration is required for asset uploads"))?;
+            let filename = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+            let asset_id = client
+                .upload_typed_asset(data, &filename, &asset.name, asset.description.as_deref(), asset_type, creator)
+                .await?
+                .parse::<u64>()?;
+            info!("  [UPLOADED] Asset '{}' (ID: {})", asset.name, asset_id);
+            uploaded_count += 1;
+            report.record("asset", &asset.name, Some(asset_id), ChangeAction::Upload, asset_changes(state, &asset.name, &hash));
+
+            state.assets.insert(asset.name.clone(), AssetState {
+                asset_id,
+                hash,
+                asset_type: asset_type.api_name().to_string(),
+                last_synced: Some(SyncStamp::now(&opts.actor)),
+            });
+            Ok::<_, anyhow::Error>(())
+        }
+        .instrument(info_span!("asset", resource = %asset.name))
+        .await?;
+    }
+
+    info!("{}", Msg::AssetSummary { uploaded: uploaded_count, skipped: skipped_count }.text());
+    Ok(())
+}
+
+/// Ban or unban the configured users. Restrictions are compared with the
+/// live ones rather than the lock file, since moderators also ban in game.
+async fn sync_restrictions(universe_id: u64, restrictions: &[RestrictionConfig], state: &mut SyncState, client: &RobloxClient, opts: &SyncOptions, report: &mut RunReport) -> Result<()> {
+    info!("{}", Msg::Syncing(Section::Restrictions).text());
+    for restriction in restrictions {
+        async {
+            let name = restriction_name(restriction);
+            if !opts.selects(SyncSection::Restrictions, &restriction.user_id.to_string()) {
+                return Ok(());
+            }
+            let key = restriction_key(restriction);
+            let current = client.get_user_restriction(universe_id, restriction.place_id, restriction.user_id).await?;
+            let live = &current["gameJoinRestriction"];
+            // A time-limited ban that has run out shows as inactive; applying it
+            // again would make it permanent in effect
+            let timed = state.timed_bans.get(&key).filter(|ban| restriction.active && Some(ban.duration) == restriction.duration);
+            if let Some(ban) = timed.filter(|ban| ban.expired(actor::now()) && !live["active"].as_bool().unwrap_or(false)) {
+                info!("  [SKIP] Restriction {} - {}s ban has run out", name, ban.duration);
+                report.skip("restriction");
+                return Ok(());
+            }
+            let changes = restriction_changes(live, restriction);
+            if changes.is_empty() {
+                info!("  [SKIP] Restriction {} - no changes detected", name);
+                report.skip("restriction");
+                return Ok(());
+            }
+            if opts.dry_run {
+                info!("  [UPDATE] Restriction {} - would {}", name, if restriction.active { "ban" } else { "unban" });
+            } else {
+                client.update_user_restriction(universe_id, restriction.place_id, restriction.user_id, &restriction_body(restriction)).await?;
+                info!("  [UPDATED] Restriction {} - {}", name, if restriction.active { "banned" } else { "unbanned" });
+                match restriction.duration.filter(|_| restriction.active) {
+                    Some(duration) if changes.contains("active") => {
+                        state.timed_bans.insert(key, TimedBan { started: actor::now(), duration });
+                    }
+                    Some(_) => {}
+                    None => {
+                        state.timed_bans.remove(&key);
+                    }
+                }
+            }
+            report.record("restriction", &name, Some(restriction.user_id), ChangeAction::Update, changes);
+            Ok::<_, anyhow::Error>(())
+        }
+        .instrument(info_span!("restriction", user_id = restriction.user_id))
+        .await?;
+    }
+    Ok(())
+}
+
+/// `timed_bans` key of a restriction: `123`, or `123@456` for a place
+fn restriction_key(restriction: &RestrictionConfig) -> String {
+    match restriction.place_id {
+        Some(place_id) => format!("{}@{}", restriction.user_id, place_id),
+        None => restriction.user_id.to_string(),
+    }
+}
+
+/// `user 123` or `user 123 in place 456`
+fn restriction_name(restriction: &RestrictionConfig) -> String {
+    match restriction.place_id {
+        Some(place_id) => format!("user {} in place {}", restriction.user_id, place_id),
+        None => format!("user {}", restriction.user_id),
+    }
+}
+
+/// `gameJoinRestriction` request body for a configured restriction
+fn restriction_body(restriction: &RestrictionConfig) -> serde_json::Value {
+    let mut body = serde_json::json!({ "active": restriction.active });
+    if restriction.active {
+        body["excludeAltAccounts"] = (!restriction.ban_alt_accounts).into();
+        if let Some(duration) = restriction.duration {
+            body["duration"] = format!("{}s", duration).into();
+        }
+        body["displayReason"] = restriction.display_reason.clone().unwrap_or_default().into();
+        body["privateReason"] = restriction.private_reason.clone().unwrap_or_default().into();
+    }
+    body
+}
+
+/// Fields that differ between a live `gameJoinRestriction` and the config.
+/// The duration of an active ban is not compared, since reapplying it would
+/// restart the ban.
+fn restriction_changes(current: &serde_json::Value, restriction: &RestrictionConfig) -> ChangeSet {
+    let mut changes = ChangeSet::default();
+    let active = current["active"].as_bool().unwrap_or(false);
+    if active != restriction.active {
+        changes.push("active", active, restriction.active);
+    }
+    if restriction.active {
+        let fields = [
+            ("display_reason", "displayReason", restriction.display_reason.clone().unwrap_or_default()),
+            ("private_reason", "privateReason", restriction.private_reason.clone().unwrap_or_default()),
+        ];
+        for (field, key, desired) in fields {
+            let live = current[key].as_str().unwrap_or_default();
+            if live != desired {
+                changes.push(field, live, desired);
+            }
+        }
+        let ban_alts = !current["excludeAltAccounts"].as_bool().unwrap_or(false);
+        if ban_alts != restriction.ban_alt_accounts {
+            changes.push("ban_alt_accounts", ban_alts, restriction.ban_alt_accounts);
+        }
+    }
+    changes
+}
+
+/// Print the universe's (or a place's) active restrictions
+pub async fn restrictions_list(config: &RblxSyncConfig, client: &RobloxClient, place_id: Option<u64>, report: &RunReport) -> Result<()> {
+    let mut count = 0;
+    let mut restrictions = client.user_restrictions(config.universe.id, place_id).stream::<serde_json::Value>();
+    while let Some(item) = restrictions.next().await {
+        let item = item?;
+        let restriction = &item["gameJoinRestriction"];
+        if !restriction["active"].as_bool().unwrap_or(false) {
+            continue;
+        }
+        count += 1;
+        match report.output() {
+            OutputMode::Text => {
+                let user = item["user"].as_str().unwrap_or_default().trim_start_matches("users/");
+                let duration = restriction["duration"].as_str().unwrap_or("permanent");
+                info!("  {:<14} {:<12} {}", user, duration, restriction["displayReason"].as_str().unwrap_or_default());
+            }
+            OutputMode::Json => println!("{}", item),
+        }
+    }
+    info!("{} active restriction(s)", count);
+    Ok(())
+}
+
+/// Ban or unban one user right away
+pub async fn restrictions_apply(config: &RblxSyncConfig, client: &RobloxClient, restriction: &RestrictionConfig, dry_run: bool, confirm: Confirmation, report: &mut RunReport) -> Result<()> {
+    report.dry_run = dry_run;
+    let name = restriction_name(restriction);
+    let action = if restriction.active { "Ban" } else { "Unban" };
+    let mut changes = ChangeSet::default();
+    changes.push("active", !restriction.active, restriction.active);
+    if dry_run {
+        info!("  Dry Run: Would {} {}", action.to_lowercase(), name);
+    } else {
+        if restriction.active {
+            confirm.require(&format!("{} {} from universe {}", action, name, config.universe.id))?;
+        }
+        client.update_user_restriction(config.universe.id, restriction.place_id, restriction.user_id, &restriction_body(restriction)).await?;
+        info!("  {}ned {}", action, name);
+    }
+    report.record("restriction", &name, Some(restriction.user_id), ChangeAction::Update, changes);
+    Ok(())
+}
+
+/// Badges each universe can create per 24 hours without paying
+const FREE_BADGES_PER_DAY: usize = 5;
+
+/// Robux charged per badge beyond the free daily quota
+const BADGE_COST: u64 = 100;
+
+/// Show how many badges `run` will create and what that may cost, and require
+/// confirmation (or `--yes`) when the free daily quota won't cover them. The
+/// quota is estimated from creations recorded in the lock file, so badges made
+/// elsewhere (e.g. in the Creator Dashboard) aren't counted.
+fn confirm_badge_budget(config: &RblxSyncConfig, state: &mut SyncState, remote_map: &RemoteIndex, renames: &HashMap<String, u64>, opts: &SyncOptions) -> Result<()> {
+    if opts.no_create {
+        return Ok(());
+    }
+    let to_create = config
+        .badges
+        .iter()
+        .filter(|b| opts.selects(SyncSection::Badges, &b.name) && b.id.is_none())
+        .filter(|b| {
+            state.find_badge_by_name(&b.name).is_none()
+                && !b.aliases.iter().any(|a| state.find_badge_by_name(a).is_some())
+                && !remote_map.contains(&b.name, &b.aliases)
+                && !renames.contains_key(&b.name)
+        })
+        .count();
+    if to_create == 0 {
+        return Ok(());
+    }
+
+    let used = state.recent_badge_creations(actor::now());
+    let free = FREE_BADGES_PER_DAY.saturating_sub(used).min(to_create);
+    let cost = (to_create - free) as u64 * BADGE_COST;
+    info!(
+        "  {} badge(s) will be created (payment source: {}); {} of {} free daily badges used in the last 24h; estimated cost: {}",
+        to_create,
+        config.badge_payment_source.as_deref().unwrap_or("none"),
+        used,
+        FREE_BADGES_PER_DAY,
+        i18n::robux(cost)
+    );
+    if cost == 0 || opts.dry_run {
+        return Ok(());
+    }
+    opts.confirm.require(&format!("Create {} badge(s) for an estimated {}", to_create, i18n::robux(cost)))
+}
+
+/// Log a changed price as a localized Robux amount
+fn log_price_change(changes: &ChangeSet) {
+    if let Some(field) = changes.fields.iter().find(|f| f.field == "price") {
+        if let Some(new) = field.new.as_u64() {
+            info!("    {}", Msg::PriceChange { old: field.old.as_u64(), new }.text());
+        }
+    }
+    if let Some(field) = changes.fields.iter().find(|f| f.field == "is_for_sale" || f.field == "is_active") {
+        if let Some(on_sale) = field.new.as_bool() {
+            info!("    {}", Msg::SaleChange { on_sale }.text());
+        }
+    }
+}
+
+/// Resolve the configured asset type, falling back to the file extension
+fn asset_type_for(asset: &AssetConfig, path: &Path) -> Result<AssetType> {
+    if let Some(t) = asset.asset_type {
+        return Ok(t);
+    }
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or_default();
+    AssetType::from_extension(extension)
+        .ok_or_else(|| anyhow!("Cannot infer asset type for '{}' from {:?}; set `type` explicitly", asset.name, path))
+}
+
+/// Describe the hash change for an asset upload
+fn asset_changes(state: &SyncState, name: &str, hash: &str) -> ChangeSet {
+    let mut changes = ChangeSet::default();
+    changes.push("hash", state.assets.get(name).map(|a| &a.hash), hash);
+    changes
+}
+
+/// State entries that no config entry refers to by pinned ID, name or alias
+fn orphaned_entries(entries: &HashMap<u64, ResourceState>, claimed: &[(Option<u64>, &str, &[String])]) -> Vec<(u64, String)> {
+    let mut orphans: Vec<(u64, String)> = entries
+        .iter()
+        .filter(|(id, entry)| {
+            let name = entry.name.to_lowercase();
+            !claimed.iter().any(|(pinned, n, aliases)| {
+                *pinned == Some(**id) || n.to_lowercase() == name || aliases.iter().any(|a| a.to_lowercase() == name)
+            })
+        })
+        .map(|(id, entry)| (*id, entry.name.clone()))
+        .collect();
+    orphans.sort();
+    orphans
+}
+
+/// Config entries renamed in config since the last sync, mapped to the ID of
+/// the state entry they were renamed from. A selected entry that matches
+/// nothing in state or remotely is a rename when it and one orphaned state
+/// entry are the only ones left over; with more leftovers on either side the
+/// pairing is ambiguous, so it's an error instead of a duplicate.
+fn renamed_entries(
+    resource_type: &str,
+    entries: &HashMap<u64, ResourceState>,
+    claimed: &[(Option<u64>, &str, &[String])],
+    remote_map: &RemoteIndex,
+    selected: impl Fn(&str) -> bool,
+) -> Result<HashMap<String, u64>> {
+    let tracked = |name: &str| entries.values().any(|e| e.name.to_lowercase() == name.to_lowercase());
+    let unmatched: Vec<&str> = claimed
+        .iter()
+        .filter(|(pinned, name, aliases)| {
+            pinned.is_none()
+                && selected(name)
+                && !tracked(name)
+                && !aliases.iter().any(|a| tracked(a))
+                && !remote_map.contains(name, aliases)
+        })
+        .map(|(_, name, _)| *name)
+        .collect();
+    let orphans = orphaned_entries(entries, claimed);
+    match (unmatched.as_slice(), orphans.as_slice()) {
+        ([], _) | (_, []) => Ok(HashMap::new()),
+        ([name], [(id, old)]) => {
+            info!("  {} '{}' (ID: {}) was renamed to '{}' in config", resource_type, old, id, name);
+            Ok(HashMap::from([(name.to_string(), *id)]))
+        }
+        _ => {
+            let names: Vec<String> = unmatched.iter().map(|n| format!("'{}'", n)).collect();
+            let list: Vec<String> = orphans.iter().map(|(id, n)| format!("'{}' (ID: {})", n, id)).collect();
+            Err(anyhow!(
+                "{} {} match nothing in state or remotely, while state still tracks {} which no longer appear in config. \
+                 Set `id:` or `aliases:` on renamed entries so they are renamed instead of duplicated, or drop stale entries with `rblxsync state rm`.",
+                resource_type,
+                names.join(", "),
+                list.join(", ")
+            ))
+        }
+    }
+}
+
+/// Check a resource for edits made outside rblxsync (e.g. on the Creator
+/// Dashboard) since its last sync, by comparing the remote `updated`
+/// timestamp with `known`. When config changes the resource too, the edits
+/// are merged field by field (see `merge`): fields only edited remotely are
+/// dropped from `changes`, and fields changed differently on both sides
+/// need a yes in a terminal. `--overwrite-remote-changes` replaces every
+/// edit with config.
+#[allow(clippy::too_many_arguments)]
+fn merge_remote_edits(
+    resource_type: &str,
+    name: &str,
+    id: u64,
+    known: Option<&str>,
+    remote: &RemoteIndex,
+    fields: &[MergeField],
+    changes: &mut ChangeSet,
+    opts: &SyncOptions,
+) -> Result<RemoteMerge> {
+    let current = remote.updated(id);
+    let edited = match (known, current.as_deref()) {
+        (Some(known), Some(current)) if known != current => current.to_string(),
+        _ => return Ok(RemoteMerge { updated: current, kept: Vec::new() }),
+    };
+    if changes.is_empty() {
+        warn!("  {} '{}' (ID: {}) was edited outside rblxsync at {}; see `rblxsync check`, or `rblxsync pull` the edit into config", resource_type, name, id, edited);
+        // Keep the old timestamp so the edit is reported again
+        return Ok(RemoteMerge { updated: known.map(|t| t.to_string()), kept: Vec::new() });
+    }
+    if opts.overwrite_remote_changes {
+        warn!("  Overwriting edits made to {} '{}' outside rblxsync at {}", resource_type, name, edited);
+        return Ok(RemoteMerge { updated: current, kept: Vec::new() });
+    }
+
+    let merge = merge::three_way(fields);
+    if !merge.keep_remote.is_empty() {
+        let kept: Vec<&str> = merge.keep_remote.iter().map(|f| f.field).collect();
+        info!("  Keeping {} of {} '{}' as edited outside rblxsync at {}", kept.join(", "), resource_type, name, edited);
+        changes.fields.retain(|c| !kept.contains(&c.field.as_str()));
+    }
+    if !merge.conflicts.is_empty() {
+        let conflicts: Vec<String> = merge.conflicts.iter().map(|f| format!("{} (config {}, remote {})", f.field, f.config, f.remote)).collect();
+        if opts.dry_run {
+            warn!("  [CONFLICT] {} '{}' (ID: {}) was edited outside rblxsync at {}; syncing would overwrite {}", resource_type, name, id, edited, conflicts.join(", "));
+        } else {
+            let question = format!("{} '{}' was edited outside rblxsync at {}: {}. Overwrite with config?", resource_type, name, edited, conflicts.join(", "));
+            if !(opts.confirm.interactive && prompt::confirm(&question)?) {
+                return Err(anyhow!(
+                    "{} '{}' (ID: {}) was edited outside rblxsync at {} and config changes the same fields: {}; `rblxsync pull` the edit into config, or pass --overwrite-remote-changes to replace it",
+                    resource_type,
+                    name,
+                    id,
+                    edited,
+                    conflicts.join(", ")
+                ));
             }
-        } else if has_changes {
-            let mut patch = serde_json::Map::new();
-            patch.insert("name".to_string(), prod.name.clone().into());
-            patch.insert("price".to_string(), prod.price.into());
-            if let Some(d) = &prod.description { patch.insert("description".to_string(), d.clone().into()); }
-            
-            // Read image file if icon changed
-            let image_data = if icon_changed {
-                if let Some(icon_path_str) = &prod.icon {
-                    let icon_path = Path::new(&config.assets_dir).join(icon_path_str);
-                    if icon_path.exists() {
-                        let data = tokio::fs::read(&icon_path).await?;
-                        let filename = icon_path.file_name().unwrap_or_default().to_string_lossy().to_string();
-                        Some((data, filename))
-                    } else {
-                        warn!("Developer product icon not found: {:?}", icon_path);
-                        None
-                    }
-                } else {
-                    None
-                }
-            } else {
-                None
-            };
-            
-            client.update_developer_product_with_icon(universe_id, id, &serde_json::Value::Object(patch), image_data).await?;
-            info!("  [UPDATED] Developer Product '{}' (ID: {}) - updated: {}", 
-                prod.name, id, changes.join(", "));
-            updated_count += 1;
+        }
+    }
+    Ok(RemoteMerge { updated: current, kept: merge.keep_remote.into_iter().cloned().collect() })
+}
+
+/// Outcome of `merge_remote_edits`
+struct RemoteMerge {
+    /// Remote `updated` timestamp to store. A PATCH changes it, so it's
+    /// replaced with `updated_after_patch` then.
+    updated: Option<String>,
+    /// Fields left as edited remotely
+    kept: Vec<MergeField>,
+}
+
+/// Remote `updated` timestamp after a PATCH: from its response when it has
+/// one, else read back, so the next run still notices later edits
+async fn updated_after_patch(response: &serde_json::Value, read_back: impl std::future::Future<Output = Result<serde_json::Value>>) -> Option<String> {
+    if let Some(updated) = pull::updated_of(response) {
+        return Some(updated);
+    }
+    match read_back.await {
+        Ok(item) => pull::updated_of(&item),
+        Err(e) => {
+            warn!("  Could not read back the updated resource, so edits made before the next run can't be told apart: {}", e);
+            None
+        }
+    }
+}
+
+/// Drop changes to fields kept as edited remotely while config still has
+/// the value they were kept over, unless those edits are to be overwritten
+fn skip_kept_remote(changes: &mut ChangeSet, entry: Option<&ResourceState>, opts: &SyncOptions) {
+    let Some(entry) = entry.filter(|_| !opts.overwrite_remote_changes) else { return };
+    changes.fields.retain(|c| entry.kept_remote.get(&c.field) != Some(&merge::value(&c.new)));
+}
+
+/// In the freshly written state `entry`, hold the remote value of each field
+/// kept as edited remotely (see `ResourceState::kept_remote`): fields `kept`
+/// now, and fields kept before that config hasn't changed since
+fn record_kept_remote(entry: &mut ResourceState, previous: Option<&ResourceState>, kept: &[MergeField], changes: &ChangeSet) {
+    let mut kept_remote = std::collections::BTreeMap::new();
+    for (field, config) in previous.map(|p| &p.kept_remote).into_iter().flatten() {
+        if !changes.contains(field) {
+            entry.set_field(field, &previous.map(|p| p.field(field)).unwrap_or_default());
+            kept_remote.insert(field.clone(), config.clone());
+        }
+    }
+    for field in kept {
+        entry.set_field(field.field, &field.remote);
+        if !field.config.is_null() && field.config != field.remote {
+            kept_remote.insert(field.field.to_string(), field.config.clone());
         } else {
-            info!("  [SKIP] Developer Product '{}' (ID: {}) - no changes detected", prod.name, id);
-            skipped_count += 1;
+            kept_remote.remove(field.field);
         }
+    }
+    entry.kept_remote = kept_remote;
+}
 
-        // Update State after successful sync
-        if !dry_run && id != 0 {
-            state.update_developer_product(
-                id,
-                prod.name.clone(), 
-                prod.description.clone(),
-                Some(prod.price as u64),
-                icon_hash, 
-                asset_id
-            );
+/// Every remote item of a list endpoint, across all pages. Dry runs treat a
+/// failed listing (e.g. invalid credentials or universe) as empty.
+async fn list_remote(pages: Pages<'_>, label: &str, dry_run: bool) -> Result<Vec<serde_json::Value>> {
+    let mut stream = pages.stream();
+    let mut items = Vec::new();
+    let progress = Progress::items(format!("Listing {}", label), None);
+    while let Some(item) = stream.next().await {
+        match item {
+            Ok(item) => {
+                items.push(item);
+                progress.inc(1);
+            }
+            Err(e) if dry_run => {
+                warn!("Dry Run: Failed to list {} (likely due to invalid credentials/universe): {}", label, e);
+                return Ok(Vec::new());
+            }
+            Err(e) => return Err(e),
         }
     }
-    
-    info!("Developer Products Summary: {} created, {} updated, {} skipped (unchanged)", 
-        created_count, updated_count, skipped_count);
+    Ok(items)
+}
+
+/// Remote resources of one type, indexed by lowercase name. Names shared by
+/// several remote resources are kept so lookups can detect the collision.
+#[derive(Default)]
+struct RemoteIndex {
+    by_name: HashMap<String, Vec<(String, u64)>>,
+    /// Listed items by ID
+    items: HashMap<u64, serde_json::Value>,
+}
+
+impl RemoteIndex {
+    fn insert(&mut self, name: &str, id: u64, item: &serde_json::Value) {
+        self.by_name.entry(name.to_lowercase()).or_default().push((name.to_string(), id));
+        self.items.insert(id, item.clone());
+    }
+
+    fn item(&self, id: u64) -> Option<&serde_json::Value> {
+        self.items.get(&id)
+    }
+
+    /// When the remote resource was last modified
+    fn updated(&self, id: u64) -> Option<String> {
+        self.item(id).and_then(pull::updated_of)
+    }
+
+    /// Fail for a pinned ID this universe doesn't list, e.g. a typo or an ID
+    /// from another universe, rather than writing to it blindly
+    fn check_pinned(&self, pinned: Option<u64>, universe_id: u64, resource_type: &str, name: &str) -> Result<()> {
+        match pinned {
+            Some(id) if !self.items.contains_key(&id) => Err(anyhow!("{} '{}': pinned id {} not found in universe {}", resource_type, name, id, universe_id)),
+            _ => Ok(()),
+        }
+    }
+
+    fn by_id(&self, id: u64) -> Option<&(String, u64)> {
+        self.by_name.values().flatten().find(|(_, rid)| *rid == id)
+    }
+
+    /// ID of the only remote resource with `name` or else one of `aliases`
+    fn unique(&self, name: &str, aliases: &[String]) -> Option<u64> {
+        match std::iter::once(name).chain(aliases.iter().map(|a| a.as_str())).find_map(|n| self.by_name.get(&n.to_lowercase()))?.as_slice() {
+            [(_, id)] => Some(*id),
+            _ => None,
+        }
+    }
+
+    /// Whether any remote resource has `name` or one of `aliases`
+    fn contains(&self, name: &str, aliases: &[String]) -> bool {
+        std::iter::once(name).chain(aliases.iter().map(|a| a.as_str())).any(|n| self.by_name.contains_key(&n.to_lowercase()))
+    }
+
+    /// Find a remote resource by pinned ID, or else by name falling back to
+    /// its aliases (case-insensitive). A name shared by several remote
+    /// resources is resolved with `--use-id` or an interactive prompt, and is
+    /// an error otherwise.
+    fn find(&self, pinned: Option<u64>, name: &str, aliases: &[String], resource_type: &str, opts: &SyncOptions) -> Result<Option<&(String, u64)>> {
+        if let Some(pinned) = pinned {
+            return Ok(self.by_id(pinned));
+        }
+        let Some(matches) = std::iter::once(name)
+            .chain(aliases.iter().map(|a| a.as_str()))
+            .find_map(|n| self.by_name.get(&n.to_lowercase()))
+        else {
+            return Ok(None);
+        };
+        if matches.len() == 1 {
+            return Ok(matches.first());
+        }
+
+        if let Some(chosen) = matches.iter().find(|(_, id)| opts.use_ids.contains(id)) {
+            info!("  {} '{}' matches several remote resources; using ID {} from --use-id", resource_type, name, chosen.1);
+            return Ok(Some(chosen));
+        }
+        let ids: Vec<String> = matches.iter().map(|(n, id)| format!("{} ('{}')", id, n)).collect();
+        if opts.confirm.interactive {
+            let question = format!("Several remote {}s are named '{}'. Which one should rblxsync manage?", resource_type.to_lowercase(), name);
+            let index = prompt::choose(&question, &ids)?;
+            return Ok(matches.get(index));
+        }
+        Err(anyhow!(
+            "Several remote {}s are named '{}': IDs {}. Pin one with `id:` in config or pass --use-id <ID>",
+            resource_type.to_lowercase(),
+            name,
+            ids.join(", ")
+        ))
+    }
+}
+
+/// Check that no pinned ID is used twice
+fn check_for_duplicate_ids(ids: &[u64], resource_type: &str) -> Result<()> {
+    let mut seen = HashSet::new();
+    let duplicates: Vec<u64> = ids.iter().copied().filter(|id| !seen.insert(*id)).collect();
+    if !duplicates.is_empty() {
+        return Err(anyhow!("Duplicate {} IDs found (each pinned id must be unique): {:?}", resource_type, duplicates));
+    }
     Ok(())
 }
 
-async fn sync_badges(universe_id: u64, config: &RblxSyncConfig, state: &mut SyncState, client: &RobloxClient, dry_run: bool) -> Result<()> {
-    info!("Syncing Badges...");
+/// Check for duplicate names (case-insensitive) in a list
+fn check_for_duplicates(names: &[&str], resource_type: &str) -> Result<()> {
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut duplicates: Vec<String> = Vec::new();
     
-    let mut created_count = 0;
-    let mut updated_count = 0;
-    let mut skipped_count = 0;
+    for name in names {
+        let lower = name.to_lowercase();
+        if seen.contains(&lower) {
+            duplicates.push((*name).to_string());
+        } else {
+            seen.insert(lower);
+        }
+    }
     
-    let existing = if !dry_run {
-        client.list_badges(universe_id, None).await?
-    } else {
-        match client.list_badges(universe_id, None).await {
-            Ok(r) => r,
-            Err(e) => {
-                warn!("Dry Run: Failed to list badges: {}", e);
-                crate::api::ListResponse { data: vec![], next_page_cursor: None }
-            }
+    if !duplicates.is_empty() {
+        return Err(anyhow!(
+            "Duplicate {} names found (names must be unique, case-insensitive): {:?}",
+            resource_type,
+            duplicates
+        ));
+    }
+    
+    Ok(())
+}
+
+/// Load a resource's `icon` file, or render its `icon_template`. A rendered
+/// icon is named after the resource, as there is no file behind it.
+#[allow(clippy::too_many_arguments)]
+async fn resource_icon(root: &Path, config: &RblxSyncConfig, vars: &HashMap<String, String>, name: &str, price: Option<u32>, icon: Option<&String>, template: Option<&String>, kind: IconKind) -> Result<Option<(IconFile, PathBuf)>> {
+    let assets_dir = Path::new(&config.assets_dir);
+    if let Some(template_name) = template {
+        let template = config.icon_templates.get(template_name).ok_or_else(|| anyhow!("Unknown icon template '{}'", template_name))?;
+        let png = icon_template::render(template, assets_dir, &icon_template::resource_vars(vars, name, price))
+            .with_context(|| format!("Failed to render icon template '{}' for '{}'", template_name, name))?;
+        let path = PathBuf::from(format!("{}.png", icon_file_stem(name)));
+        let icon = assets::prepare_icon(root, png, &path, config.image_processing.as_ref(), kind).await?;
+        return Ok(Some((icon, path)));
+    }
+    let Some(icon) = icon else { return Ok(None) };
+    let path = assets_dir.join(icon);
+    Ok(Some((assets::load_icon(root, &path, config.image_processing.as_ref(), kind).await?, path)))
+}
+
+/// Asset for `icon`: the one the lock file already knows, one from the asset
+/// cache, or a new upload. Returns where a newly recorded asset came from;
+/// `None` when it was already known.
+async fn ensure_icon(client: &RobloxClient, icon: &IconFile, path: &Path, known_asset_id: Option<u64>, creator: &crate::config::CreatorConfig, use_cache: bool) -> Result<(u64, String, Option<IconSource>)> {
+    // Reuse an identical icon already uploaded, e.g. for another resource
+    if let Some(aid) = known_asset_id {
+        info!("Reusing uploaded icon {:?} (asset {})", path, aid);
+        return Ok((aid, icon.hash.clone(), None));
+    }
+    // ... or by another project of the same creator
+    if let Some(aid) = asset_cache::lookup(creator, &icon.hash).filter(|_| use_cache) {
+        info!("Reusing icon {:?} from the asset cache (asset {})", path, aid);
+        return Ok((aid, icon.hash.clone(), Some(IconSource::Cache)));
+    }
+
+    // Upload
+    info!("Uploading icon: {:?}", path);
+    let name = path.file_stem().unwrap_or_default().to_string_lossy();
+    let progress = Progress::spinner(format!("Uploading icon {}", name));
+    let asset_id_str = client.upload_asset_bytes(icon.data.clone(), &icon.filename, &name, creator).await?;
+    progress.finish();
+    let asset_id = asset_id_str.parse::<u64>()?;
+    // Also after --no-cache, replacing an entry that may be unusable
+    asset_cache::record(creator, &icon.hash, asset_id);
+
+    Ok((asset_id, icon.hash.clone(), Some(IconSource::Uploaded)))
+}
+
+/// Whether the live icon of remote resource `id` shows the same picture as
+/// the icon file `data`, so an upload the lock file can't rule out (e.g.
+/// after it was lost) can be skipped. Any failure counts as a mismatch.
+async fn remote_icon_matches(client: &RobloxClient, kind: ResourceKind, id: u64, data: &[u8]) -> bool {
+    let (path, id_param) = kind.icon_endpoint();
+    let compare = async {
+        let thumbnails = client.get_icon_thumbnails(path, id_param, &[id]).await?;
+        let Some(url) = thumbnails.iter().find(|t| t.target_id == id && t.state == "Completed").and_then(|t| t.image_url.as_deref()) else {
+            return Ok(false);
+        };
+        let live = client.download_image(url).await?;
+        assets::looks_identical(data, &live)
+    };
+    match compare.await {
+        Ok(matches) => matches,
+        Err(e) => {
+            tracing::debug!("Failed to compare with the live icon of {} {}: {}", kind.resource_type(), id, e);
+            false
         }
+    }
+}
+
+/// Moderation state of a resource's icon and when it was read, warning if
+/// it was rejected. A result stored for the same icon is reused unless the
+/// icon was just uploaded: approval is final, icons under review are
+/// re-checked by `verify_pending`, and other results are read again at most
+/// once per `MODERATION_RECHECK_SECS`. Dry runs never call the API.
+#[allow(clippy::too_many_arguments)]
+async fn check_icon_moderation(client: &RobloxClient, resource_type: &str, name: &str, asset_id: Option<u64>, icon_hash: Option<&str>, stored: Option<&ResourceState>, uploaded: bool, dry_run: bool) -> (Option<String>, Option<u64>) {
+    let Some(asset_id) = asset_id.filter(|id| *id != 0) else {
+        return (None, None);
     };
+    let stored = stored.filter(|s| s.icon_hash.is_some() && s.icon_hash.as_deref() == icon_hash);
+    let stored_state = stored.and_then(|s| s.icon_moderation_state.clone());
+    let checked = stored.and_then(|s| s.icon_moderation_checked);
 
-    let mut remote_map: HashMap<String, (String, u64)> = HashMap::new();
-    for item in existing.data {
-        if let (Some(name), Some(id)) = (item["name"].as_str(), item["id"].as_u64()) {
-            remote_map.insert(name.to_lowercase(), (name.to_string(), id));
+    let now = actor::now();
+    let fresh = match stored_state.as_deref() {
+        Some("Approved" | "Reviewing") => true,
+        Some(_) => checked.is_some_and(|at| now.saturating_sub(at) < MODERATION_RECHECK_SECS),
+        None => false,
+    };
+    if dry_run || (!uploaded && fresh) {
+        return (stored_state, checked);
+    }
+
+    let current = match client.get_asset_moderation_state(asset_id).await {
+        Ok(state) => state,
+        Err(e) => {
+            tracing::debug!("Failed to fetch moderation state for asset {}: {}", asset_id, e);
+            return (stored_state, checked);
         }
+    };
+
+    match current.as_deref() {
+        Some("Rejected") => warn!("  [MODERATED] {} '{}' icon (asset {}) was rejected by moderation and will not display", resource_type, name, asset_id),
+        Some("Reviewing") => info!("  {} '{}' icon (asset {}) is awaiting moderation", resource_type, name, asset_id),
+        _ => {}
     }
 
-    for badge in &config.badges {
-        // Case-insensitive state lookup by name
-        let state_lookup = state.find_badge_by_name(&badge.name);
-        let state_entry = state_lookup.map(|(_, s)| s);
-        let mut changes: Vec<&str> = Vec::new();
+    match current {
+        Some(current) => (Some(current), Some(now)),
+        None => (stored_state, checked),
+    }
+}
 
-        // Check for metadata changes (name, description, is_enabled)
-        if let Some(entry) = state_entry {
-            if entry.name != badge.name {
-                changes.push("name");
-            }
-            if entry.description.as_ref() != badge.description.as_ref() {
-                changes.push("description");
+/// How long a stored moderation result other than approval is trusted
+const MODERATION_RECHECK_SECS: u64 = 24 * 60 * 60;
+
+/// Text checks that still haven't read back after this many runs are dropped
+const MAX_TEXT_VERIFY_ATTEMPTS: u32 = 10;
+
+/// Queue moderation checks for a resource that was just created or changed
+fn queue_verification(state: &mut SyncState, kind: EntryKind, id: u64, name: &str, description: &Option<String>, text_changed: bool, reviewing_icon: Option<u64>) {
+    if text_changed {
+        let check = PendingCheck::Text { expected_name: name.to_string(), expected_description: description.clone() };
+        state.queue_pending(kind, id, name, check);
+    }
+    if let Some(asset_id) = reviewing_icon {
+        state.queue_pending(kind, id, name, PendingCheck::Icon { asset_id });
+    }
+}
+
+/// Re-check changes awaiting moderation. Approved icons and text that reads
+/// back as configured are finalized; rejected or filtered ones are reported
+/// as errors; anything else stays queued for the next run.
+pub async fn verify_pending(universe_id: u64, root: &Path, state: &mut SyncState, client: &RobloxClient, report: &mut RunReport) -> Result<()> {
+    if state.pending.is_empty() {
+        return Ok(());
+    }
+    info!("Verifying {} change(s) awaiting moderation...", state.pending.len());
+
+    // Text checks compare against the remote listings
+    let mut remote: HashMap<EntryKind, Vec<serde_json::Value>> = HashMap::new();
+    let text_kinds: Vec<ResourceKind> = ResourceKind::ALL
+        .into_iter()
+        .filter(|k| state.pending.iter().any(|p| p.kind == k.entry_kind() && matches!(p.check, PendingCheck::Text { .. })))
+        .collect();
+    if !text_kinds.is_empty() {
+        let mut fetch = ResumableFetch::open(root, "verify-pending", universe_id, false)?;
+        for kind in text_kinds {
+            fetch.fetch(client, kind).await?;
+            remote.insert(kind.entry_kind(), fetch.items(kind)?);
+        }
+        fetch.finish()?;
+    }
+
+    let mut still_pending = Vec::new();
+    for mut item in std::mem::take(&mut state.pending) {
+        item.attempts += 1;
+        let label = item.kind.label();
+        match &item.check {
+            PendingCheck::Icon { asset_id } => {
+                let moderation = match client.get_asset_moderation_state(*asset_id).await {
+                    Ok(moderation) => moderation,
+                    Err(e) => {
+                        warn!("  [PENDING] {} '{}' icon - could not check moderation: {}", label, item.name, e);
+                        still_pending.push(item);
+                        continue;
+                    }
+                };
+                match moderation.as_deref() {
+                    Some("Approved") => info!("  [VERIFIED] {} '{}' icon (asset {}) approved", label, item.name, asset_id),
+                    Some("Rejected") => {
+                        warn!("  [MODERATED] {} '{}' icon (asset {}) was rejected by moderation", label, item.name, asset_id);
+                        report.fail(item.kind.resource_type(), format!("{} '{}' icon (asset {}) was rejected by moderation", label, item.name, asset_id));
+                    }
+                    _ => {
+                        info!("  [PENDING] {} '{}' icon (asset {}) is still awaiting moderation", label, item.name, asset_id);
+                        still_pending.push(item);
+                        continue;
+                    }
+                }
+                // Unless the resource has moved on to another icon since
+                let current = state.resources(item.kind).and_then(|m| m.get(&item.id)).and_then(|e| state.icon_asset_id(e));
+                let entry = state.resources_mut(item.kind).and_then(|m| m.get_mut(&item.id));
+                if let Some(entry) = entry.filter(|_| current == Some(*asset_id)) {
+                    entry.icon_moderation_state = moderation;
+                    entry.icon_moderation_checked = Some(actor::now());
+                }
             }
-            if entry.is_enabled != badge.is_enabled {
-                changes.push("is_enabled");
+            PendingCheck::Text { expected_name, expected_description } => {
+                let kind = ResourceKind::ALL.into_iter().find(|k| k.entry_kind() == item.kind);
+                let found = kind.and_then(|k| remote.get(&item.kind)?.iter().find(|i| k.item_id(i) == Some(item.id)));
+                let Some(found) = found else {
+                    warn!("  {} '{}' (ID: {}) no longer exists remotely; dropping its pending check", label, item.name, item.id);
+                    continue;
+                };
+                let name = found["name"].as_str().unwrap_or_default();
+                let description = found["description"].as_str().filter(|d| !d.is_empty());
+                let expected_description = expected_description.as_deref().filter(|d| !d.is_empty());
+
+                if name == expected_name && description == expected_description {
+                    info!("  [VERIFIED] {} '{}' text is live", label, item.name);
+                } else if is_filtered(name, expected_name) || description.zip(expected_description).is_some_and(|(r, e)| is_filtered(r, e)) {
+                    warn!("  [MODERATED] {} '{}' (ID: {}) text was filtered by moderation; rewrite it in config", label, item.name, item.id);
+                    report.fail(item.kind.resource_type(), format!("{} '{}' text was filtered by moderation", label, item.name));
+                } else if item.attempts >= MAX_TEXT_VERIFY_ATTEMPTS {
+                    warn!("  {} '{}' (ID: {}) text still differs after {} checks; giving up (was it edited remotely?)", label, item.name, item.id, item.attempts);
+                } else {
+                    info!("  [PENDING] {} '{}' text has not propagated yet", label, item.name);
+                    still_pending.push(item);
+                }
             }
         }
-        
-        // Prepare icon data if provided
-        let icon_data = if let Some(icon_path_str) = &badge.icon {
-            let icon_path = Path::new(&config.assets_dir).join(icon_path_str);
-            if icon_path.exists() {
-                let data = tokio::fs::read(&icon_path).await?;
-                let filename = icon_path.file_name().unwrap_or_default().to_string_lossy().to_string();
-                
-                let mut hasher = Sha256::new();
-                hasher.update(&data);
-                let hash = format!("{:x}", hasher.finalize());
-                
-                Some((data, filename, hash))
-            } else {
-                warn!("Badge icon not found: {:?}", icon_path);
+    }
+
+    if !still_pending.is_empty() {
+        info!("{} change(s) still awaiting moderation; they will be re-checked on the next run", still_pending.len());
+    }
+    state.pending = still_pending;
+    Ok(())
+}
+
+/// Roblox replaces filtered words with `#`
+fn is_filtered(remote: &str, expected: &str) -> bool {
+    remote != expected && remote.matches('#').count() > expected.matches('#').count()
+}
+
+/// Options for `init`; missing values are asked for when `interactive`
+pub struct InitOptions {
+    pub universe_id: Option<u64>,
+    pub creator: Option<CreatorConfig>,
+    pub from_remote: bool,
+    pub force: bool,
+    pub interactive: bool,
+}
+
+/// Scaffold a new project: the config file, `.rblxsync/`, `.env.example` and
+/// `.gitignore` entries. `client` is needed to seed from the remote universe.
+pub async fn init(config_path: &Path, opts: InitOptions, client: Option<RobloxClient>, report: &mut RunReport) -> Result<()> {
+    if config_path.exists() && !opts.force {
+        return Err(anyhow!("{} already exists; pass --force to overwrite it", config_path.display()));
+    }
+    let root = match config_path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => std::path::PathBuf::from("."),
+    };
+
+    let universe_id = match opts.universe_id {
+        Some(id) => id,
+        None if opts.interactive => {
+            let answer = prompt::ask("Universe ID:")?;
+            answer.parse().map_err(|_| anyhow!("Invalid universe ID '{}'", answer))?
+        }
+        None => return Err(anyhow!("init needs a universe; pass --universe-id")),
+    };
+
+    let creator = match opts.creator {
+        Some(creator) => Some(creator),
+        None if opts.interactive => {
+            let id = prompt::ask("Creator user or group ID for icon uploads (blank to skip):")?;
+            if id.is_empty() {
                 None
+            } else {
+                let creator_type = if prompt::confirm("Is this a group?")? { "group" } else { "user" };
+                Some(CreatorConfig { id, creator_type: creator_type.to_string() })
             }
-        } else {
+        }
+        None => None,
+    };
+
+    let from_remote = opts.from_remote
+        || (opts.interactive && client.is_some() && prompt::confirm("Seed the config from the universe's existing resources?")?);
+    let mut seeded = Vec::new();
+    if from_remote {
+        let client = client.ok_or_else(|| anyhow!("--from-remote needs ROBLOX_API_KEY"))?;
+        info!("Fetching existing resources for universe {}...", universe_id);
+        let mut fetch = ResumableFetch::open(&root, "init", universe_id, false)?;
+        for kind in ResourceKind::ALL {
+            fetch.fetch(&client, kind).await?;
+            let items = fetch.items(kind)?;
+            info!("Seeding {} {}", items.len(), kind.key());
+            seeded.push((kind, items));
+        }
+        fetch.finish()?;
+    }
+
+    // The template is YAML with comments; other formats are converted without them
+    let rendered = init::render_config(universe_id, creator.as_ref(), &seeded)?;
+    let content = match ConfigFormat::from_path(config_path) {
+        ConfigFormat::Yaml => rendered,
+        format => format.to_string(&ConfigFormat::Yaml.parse_document(&rendered)?)?,
+    };
+    std::fs::write(config_path, content)?;
+    report.artifact(config_path);
+    info!("Wrote {}", config_path.display());
+
+    std::fs::create_dir_all(root.join(".rblxsync"))?;
+
+    let env_example = root.join(".env.example");
+    if env_example.exists() {
+        info!("{} already exists; leaving it unchanged", env_example.display());
+    } else {
+        std::fs::write(&env_example, init::ENV_EXAMPLE)?;
+        report.artifact(&env_example);
+        info!("Wrote {}", env_example.display());
+    }
+
+    if init::ensure_gitignore(&root)? {
+        report.artifact(root.join(".gitignore"));
+        info!("Added {} to .gitignore", init::GITIGNORE_ENTRIES.join(" and "));
+    }
+
+    info!("Next: copy .env.example to .env, add your API key, then run `rblxsync run --dry-run`");
+    Ok(())
+}
+
+/// Write a config translated from the Mantle project at `mantle_path`, and
+/// seed the lock file with what Mantle deployed when its state file is found
+pub async fn migrate_from_mantle(config_path: &Path, mantle_path: &Path, state_path: Option<&Path>, environment: Option<&str>, universe_id: Option<u64>, force: bool, report: &mut RunReport) -> Result<()> {
+    if config_path.exists() && !force {
+        return Err(anyhow!("{} already exists; pass --force to overwrite it", config_path.display()));
+    }
+    let root = match config_path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    let mut state = SyncState::load(&root)?;
+    if !state.entries().is_empty() && !force {
+        return Err(anyhow!("{} already tracks resources; pass --force to add Mantle's to it", SyncState::get_state_path(&root).display()));
+    }
+
+    let content = std::fs::read_to_string(mantle_path).with_context(|| format!("Failed to read {}", mantle_path.display()))?;
+    let doc: serde_yaml::Value = serde_yaml::from_str(&content).with_context(|| format!("Failed to parse {}", mantle_path.display()))?;
+    let mantle_dir = mantle_path.parent().filter(|dir| !dir.as_os_str().is_empty());
+    let state_path = match state_path {
+        Some(path) => Some(path.to_path_buf()),
+        None => Some(mantle_dir.unwrap_or(Path::new(".")).join(".mantle-state.yml")).filter(|path| path.exists()),
+    };
+    let mantle_state = match &state_path {
+        Some(path) => Some(mantle::MantleState::load(path, environment)?),
+        None => {
+            warn!("No Mantle state file found; resources won't be pinned to their IDs (pass --state to use one)");
             None
-        };
+        }
+    };
+
+    let base = mantle_dir.map(|dir| dir.to_string_lossy().into_owned()).unwrap_or_else(|| ".".to_string());
+    let migration = mantle::convert(&doc, &base, mantle_state.as_ref(), universe_id)?;
+    let config: RblxSyncConfig = serde_yaml::from_value(migration.config.clone()).context("The migrated config is invalid")?;
+    for skipped in &migration.skipped {
+        warn!("Not migrated: {}", skipped);
+    }
+    for problem in config_problems(&config) {
+        warn!("Fix before running: {}", problem);
+    }
+
+    let rendered = format!(
+        "# Migrated from {} by `rblxsync migrate`; see the README's Configuration Reference for every option\n{}",
+        mantle_path.display(),
+        serde_yaml::to_string(&migration.config)?
+    );
+    let content = match ConfigFormat::from_path(config_path) {
+        ConfigFormat::Yaml => rendered,
+        format => format.to_string(&ConfigFormat::Yaml.parse_document(&rendered)?)?,
+    };
+    std::fs::write(config_path, content)?;
+    report.artifact(config_path);
+    info!("Wrote {}", config_path.display());
+
+    let Some(mantle_state) = mantle_state else { return Ok(()) };
+    // Icons are assumed unchanged since Mantle's last deploy, so they aren't uploaded again
+    let assets_dir = Path::new(&config.assets_dir);
+    let project_root = root.as_path();
+    let icon_hash = |icon: &Option<String>, kind| {
+        let path = icon.as_ref().map(|icon| assets_dir.join(icon));
+        async move {
+            match path {
+                Some(path) => assets::load_icon(project_root, &path, None, kind).await.ok().map(|icon| icon.hash),
+                None => None,
+            }
+        }
+    };
+    let mut seeded = 0;
+    for pass in &config.game_passes {
+        let Some(id) = pass.id else { continue };
+        let asset_id = migration.icon_asset_ids.get(&("game_passes", pass.name.clone())).copied();
+        let hash = icon_hash(&pass.icon, IconKind::GamePass).await.filter(|_| asset_id.is_some());
+        if let Some(hash) = &hash {
+            state.set_icon_source(hash, IconSource::Imported);
+        }
+        state.update_game_pass(id, pass.name.clone(), pass.description.clone(), pass.price.map(u64::from), None, hash, asset_id);
+        seeded += 1;
+    }
+    for prod in &config.developer_products {
+        let Some(id) = prod.id else { continue };
+        let asset_id = migration.icon_asset_ids.get(&("developer_products", prod.name.clone())).copied();
+        let hash = icon_hash(&prod.icon, IconKind::DeveloperProduct).await.filter(|_| asset_id.is_some());
+        if let Some(hash) = &hash {
+            state.set_icon_source(hash, IconSource::Imported);
+        }
+        state.update_developer_product(id, prod.name.clone(), prod.description.clone(), Some(u64::from(prod.price)), None, hash, asset_id);
+        seeded += 1;
+    }
+    for badge in &config.badges {
+        let Some(id) = badge.id else { continue };
+        let hash = icon_hash(&badge.icon, IconKind::Badge).await;
+        state.update_badge(id, badge.name.clone(), badge.description.clone(), badge.is_enabled, hash);
+        seeded += 1;
+    }
+    state.save(&root)?;
+    report.artifact(SyncState::get_state_path(&root));
+    info!("Seeded the lock file with {} resource(s) from Mantle environment '{}'", seeded, mantle_state.environment);
+    info!("Next: review {}, then run `rblxsync run --dry-run`", config_path.display());
+    Ok(())
+}
+
+/// Rewrite the config file so prices, descriptions and enabled flags match remote values
+pub async fn pull(config_path: &Path, config: RblxSyncConfig, client: RobloxClient, dry_run: bool, report: &mut RunReport) -> Result<()> {
+    let universe_id = config.universe.id;
+    info!("Pulling remote values for universe {}...", universe_id);
+
+    let root = std::env::current_dir()?;
+    let mut fetch = ResumableFetch::open(&root, "pull", universe_id, false)?;
+    for kind in ResourceKind::ALL {
+        fetch.fetch(&client, kind).await?;
+    }
+
+    let format = ConfigFormat::from_path(config_path);
+    let content = std::fs::read_to_string(config_path)?;
+    let mut doc = format.parse_document(&content)?;
+
+    let mut changes = Vec::new();
+    for kind in ResourceKind::ALL {
+        let items = fetch.items(kind)?;
+        for change in pull::apply_remote(&mut doc, kind.key(), &items) {
+            changes.push((kind, change));
+        }
+    }
+    fetch.finish()?;
+
+    // Templates and file-backed fields would lose their source if replaced
+    // with the rendered remote text
+    let (conflicts, changes): (Vec<_>, Vec<_>) = changes.into_iter().partition(|(_, change)| change.conflict);
+    for (kind, change) in &conflicts {
+        warn!("  ! {} (not pulled: the config value is a template or file; update it by hand)", change);
+        let mut set = ChangeSet::default();
+        set.push(&change.field, &change.old, &change.new);
+        report.record(kind.resource_type(), &change.name, None, ChangeAction::Drift, set);
+    }
+
+    if changes.is_empty() {
+        info!("Config already matches remote; nothing to pull.");
+        return Ok(());
+    }
+
+    info!("Changes to {}:", config_path.display());
+    for (kind, change) in &changes {
+        info!("  ~ {}", change);
+        let mut set = ChangeSet::default();
+        set.push(&change.field, &change.old, &change.new);
+        report.record(kind.resource_type(), &change.name, None, ChangeAction::Update, set);
+    }
+
+    if dry_run {
+        info!("Dry Run: Would update {} field(s) in {}", changes.len(), config_path.display());
+        return Ok(());
+    }
+
+    std::fs::write(config_path, format.to_string(&doc)?)?;
+    report.artifact(config_path);
+    info!("Updated {} field(s) in {} (comments are not preserved)", changes.len(), config_path.display());
+    Ok(())
+}
+
+/// Exit code used by `check` when the remote universe has drifted from the config
+pub const DRIFT_EXIT_CODE: i32 = 2;
+
+/// Compare remote resources against the config without changing anything.
+///
+/// Prints a JSON drift report to stdout and returns whether any drift was found.
+pub async fn check(config: RblxSyncConfig, root: &Path, client: RobloxClient, report: &mut RunReport) -> Result<bool> {
+    let config = template::render_config(config, root)?;
+    validate(&config)?;
+    let universe_id = config.universe.id;
+    info!("Checking universe {} for drift...", universe_id);
+
+    let mut fetch = ResumableFetch::open(root, "check", universe_id, false)?;
+    for kind in ResourceKind::ALL {
+        fetch.fetch(&client, kind).await?;
+    }
+
+    // Compare against the rendered config so template variables don't show as drift
+    let mut doc = serde_yaml::to_value(&config)?;
+    let mut drifted = Vec::new();
+    let mut missing = Vec::new();
+    for kind in ResourceKind::ALL {
+        let items = fetch.items(kind)?;
+        for name in pull::missing_remote(&doc, kind.key(), &items) {
+            warn!("  ! {}[\"{}\"] does not exist remotely", kind.key(), name);
+            report.record(kind.resource_type(), &name, None, ChangeAction::Drift, ChangeSet::default());
+            missing.push(serde_json::json!({ "resource_type": kind.resource_type(), "name": name }));
+        }
+        for change in pull::apply_remote(&mut doc, kind.key(), &items) {
+            // Fields left unset in config are not managed by rblxsync
+            let Some(old) = change.old.clone().filter(|v| !v.is_null()) else { continue };
+            warn!("  ~ {}", change);
+            let mut set = ChangeSet::default();
+            set.push(&change.field, &old, &change.new);
+            report.record(kind.resource_type(), &change.name, None, ChangeAction::Drift, set);
+            drifted.push(serde_json::json!({
+                "resource_type": kind.resource_type(),
+                "name": change.name,
+                "field": change.field,
+                "config": old,
+                "remote": change.new,
+            }));
+        }
+    }
+    fetch.finish()?;
+
+    let has_drift = !drifted.is_empty() || !missing.is_empty();
+    let output = serde_json::json!({
+        "universe_id": universe_id,
+        "drift": has_drift,
+        "drifted": drifted,
+        "missing": missing,
+    });
+    // In JSON output mode the drift is already streamed as change events
+    if report.output() == OutputMode::Text {
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    }
+
+    if has_drift {
+        warn!("Drift detected: {} field(s) differ, {} resource(s) missing remotely", drifted.len(), missing.len());
+    } else {
+        info!("No drift: remote matches config.");
+    }
+    Ok(has_drift)
+}
 
-        // Check if icon has changed
-        let icon_changed = if let Some((_, _, new_hash)) = &icon_data {
-            let stored_hash = state_entry.and_then(|s| s.icon_hash.as_ref());
-            if stored_hash != Some(new_hash) {
-                changes.push("icon");
-                true
-            } else {
-                false
-            }
-        } else {
-            false
+/// Download the live icon of every tracked game pass, developer product and
+/// badge into `assets_dir`. Icons go to the resource's configured `icon` path,
+/// or to `icons/<section>/<name>.png` when none is set. Existing files are kept
+/// unless `force` is set, so a forced download shows imagery drift as a diff.
+pub async fn icons_download(config: RblxSyncConfig, state: &SyncState, client: RobloxClient, force: bool, dry_run: bool, report: &mut RunReport) -> Result<()> {
+    report.dry_run = dry_run;
+    let assets_dir = Path::new(&config.assets_dir);
+    let mut written = 0;
+
+    for kind in ResourceKind::ALL {
+        let Some(tracked) = state.resources(kind.entry_kind()) else { continue };
+        if tracked.is_empty() {
+            continue;
+        }
+        let configured: Vec<ConfiguredIcon> = match kind {
+            ResourceKind::GamePasses => config.game_passes.iter().map(|p| (p.id, p.name.as_str(), p.aliases.as_slice(), p.icon.as_ref())).collect(),
+            ResourceKind::DeveloperProducts => config.developer_products.iter().map(|p| (p.id, p.name.as_str(), p.aliases.as_slice(), p.icon.as_ref())).collect(),
+            ResourceKind::Badges => config.badges.iter().map(|b| (b.id, b.name.as_str(), b.aliases.as_slice(), b.icon.as_ref())).collect(),
         };
 
-        // Case-insensitive matching for ID lookup
-        let state_id = state_lookup.map(|(id, _)| id);
-        let remote_entry = remote_map.get(&badge.name.to_lowercase());
-        let is_new = state_id.is_none() && remote_entry.is_none();
-        let has_changes = !changes.is_empty();
+        let ids: Vec<u64> = tracked.keys().copied().collect();
+        let (path, id_param) = kind.icon_endpoint();
+        let mut thumbnails = Vec::new();
+        for chunk in ids.chunks(ICON_BATCH_SIZE) {
+            thumbnails.extend(client.get_icon_thumbnails(path, id_param, chunk).await?);
+        }
 
-        let id = if let Some(sid) = state_id {
-            sid
-        } else if let Some((_, rid)) = remote_entry {
-            *rid
-        } else {
+        for thumb in thumbnails {
+            let Some(entry) = tracked.get(&thumb.target_id) else { continue };
+            let name = &entry.name;
+            let Some(url) = thumb.image_url.as_deref().filter(|_| thumb.state == "Completed") else {
+                warn!("  {} '{}' has no downloadable icon (state: {})", kind.resource_type(), name, thumb.state);
+                report.skip(kind.resource_type());
+                continue;
+            };
+
+            let icon = configured
+                .iter()
+                .find(|(pinned, config_name, aliases, _)| {
+                    *pinned == Some(thumb.target_id)
+                        || (pinned.is_none() && std::iter::once(*config_name).chain(aliases.iter().map(|a| a.as_str())).any(|n| n.eq_ignore_ascii_case(name)))
+                })
+                .and_then(|(_, _, _, icon)| icon.cloned());
+            let relative = icon_download_path(kind, name, icon.is_some());
+            let dest = assets_dir.join(&relative);
+
+            if dest.exists() && !force {
+                info!("  {} '{}': {} exists, skipping (use --force to overwrite)", kind.resource_type(), name, dest.display());
+                report.skip(kind.resource_type());
+                continue;
+            }
             if dry_run {
-                info!("  [CREATE] Badge '{}' - would create with: name, description{}", 
-                    badge.name,
-                    if badge.icon.is_some() { ", icon" } else { "" });
-                created_count += 1;
-                0
-            } else {
-                let image_for_create = icon_data.as_ref().map(|(data, filename, _)| (data.clone(), filename.clone()));
-                
-                let result = client.create_badge(
-                    universe_id,
-                    &badge.name,
-                    badge.description.as_deref().unwrap_or(""),
-                    image_for_create,
-                    config.badge_payment_source.as_deref()
-                ).await;
-                
-                let resp = match result {
-                    Ok(r) => r,
-                    Err(e) => {
-                        let err_str = e.to_string();
-                        if err_str.contains("Payment source is invalid") || err_str.contains("code\":16") {
-                            error!("Badge creation failed: Payment source is required.");
-                            error!("");
-                            error!("Creating badges costs 100 Robux. Please add the following to your rblxsync.yml:");
-                            error!("");
-                            error!("  badge_payment_source: \"user\"   # Pay from your user account");
-                            error!("  # OR");
-                            error!("  badge_payment_source: \"group\"  # Pay from group funds");
-                            error!("");
-                            return Err(anyhow!("Badge creation requires badge_payment_source configuration"));
-                        }
-                        return Err(e);
-                    }
-                };
-                
-                let new_id = resp["id"].as_u64().ok_or(anyhow!("Created badge has no ID"))?;
-                info!("  [CREATED] Badge '{}' (ID: {}) - created with: name, description{}", 
-                    badge.name, new_id,
-                    if badge.icon.is_some() { ", icon" } else { "" });
-                created_count += 1;
-                new_id
+                info!("  Dry Run: Would download {} '{}' icon to {}", kind.resource_type(), name, dest.display());
+                continue;
             }
-        };
-
-        // Update state with icon hash
-        let icon_hash = icon_data.as_ref().map(|(_, _, hash)| hash.clone());
 
-        // Update Remote (Idempotent PATCH) - only if has changes
-        if is_new {
-            // Already created above
-        } else if dry_run {
-            if has_changes {
-                info!("  [UPDATE] Badge '{}' (ID: {}) - would update: {}", 
-                    badge.name, id, changes.join(", "));
-                updated_count += 1;
-            } else {
-                info!("  [SKIP] Badge '{}' (ID: {}) - no changes detected", badge.name, id);
-                skipped_count += 1;
+            let bytes = client.download_image(url).await?;
+            if let Some(parent) = dest.parent() {
+                std::fs::create_dir_all(parent)?;
             }
-        } else if has_changes {
-            let mut patch = serde_json::Map::new();
-            patch.insert("name".to_string(), badge.name.clone().into());
-            if let Some(d) = &badge.description { patch.insert("description".to_string(), d.clone().into()); }
-            if let Some(e) = badge.is_enabled { patch.insert("enabled".to_string(), e.into()); }
-            
-            client.update_badge(id, &serde_json::Value::Object(patch)).await?;
-            
-            // Update icon if it changed
-            if icon_changed {
-                if let Some((data, filename, _)) = &icon_data {
-                    client.update_badge_icon(id, data.clone(), filename).await?;
-                }
-            }
-            info!("  [UPDATED] Badge '{}' (ID: {}) - updated: {}", 
-                badge.name, id, changes.join(", "));
-            updated_count += 1;
-        } else {
-            info!("  [SKIP] Badge '{}' (ID: {}) - no changes detected", badge.name, id);
-            skipped_count += 1;
-        }
-
-        // Update State after successful sync
-        if !dry_run && id != 0 {
-            state.update_badge(
-                id,
-                badge.name.clone(), 
-                badge.description.clone(),
-                badge.is_enabled,
-                icon_hash.clone(), 
-                None
-            );
+            std::fs::write(&dest, bytes)?;
+            info!("  {} '{}' -> {}", kind.resource_type(), name, dest.display());
+            match &icon {
+                Some(configured) => info!("    {} keeps the configured icon {}", relative, configured),
+                None => info!("    Add `icon: {}` to its config entry to sync this file", relative),
+            }
+            let mut changes = ChangeSet::default();
+            changes.push("icon", serde_json::Value::Null, &relative);
+            report.record(kind.resource_type(), name, Some(thumb.target_id), ChangeAction::Download, changes);
+            report.artifact(&dest);
+            written += 1;
         }
     }
-    
-    info!("Badges Summary: {} created, {} updated, {} skipped (unchanged)", 
-        created_count, updated_count, skipped_count);
+
+    if !dry_run {
+        info!("Downloaded {} icon(s) into {}", written, assets_dir.display());
+    }
     Ok(())
 }
 
-/// Check for duplicate names (case-insensitive) in a list
-fn check_for_duplicates(names: &[&str], resource_type: &str) -> Result<()> {
-    let mut seen: HashSet<String> = HashSet::new();
-    let mut duplicates: Vec<String> = Vec::new();
-    
-    for name in names {
-        let lower = name.to_lowercase();
-        if seen.contains(&lower) {
-            duplicates.push((*name).to_string());
+/// Record icons already uploaded by Tarmac or Asphalt, as listed in their
+/// `lockfile`, so `run` uses those assets instead of uploading the files again
+pub async fn icons_import(config: &RblxSyncConfig, root: &Path, lockfile: &Path, dry_run: bool, report: &mut RunReport) -> Result<()> {
+    report.dry_run = dry_run;
+    let imported = asset_import::Lockfile::load(lockfile)?;
+    if imported.unlinked > 0 {
+        warn!("{} entries in {} have no file path (e.g. an Asphalt 1.x lockfile, keyed by content hash) and can't be imported", imported.unlinked, lockfile.display());
+    }
+    let by_path: HashMap<PathBuf, u64> = imported.assets.iter().filter_map(|a| Some((a.path.canonicalize().ok()?, a.asset_id))).collect();
+
+    let mut state = SyncState::load(root)?;
+    let assets_dir = Path::new(&config.assets_dir);
+    let icons = config.game_passes.iter().map(|p| ("game pass", &p.name, &p.icon, IconKind::GamePass))
+        .chain(config.developer_products.iter().map(|p| ("developer product", &p.name, &p.icon, IconKind::DeveloperProduct)))
+        .chain(config.badges.iter().map(|b| ("badge", &b.name, &b.icon, IconKind::Badge)));
+    let mut recorded = 0;
+    for (resource_type, name, icon, kind) in icons {
+        let Some(icon) = icon else { continue };
+        let path = assets_dir.join(icon);
+        let Some(asset_id) = path.canonicalize().ok().and_then(|p| by_path.get(&p).copied()) else { continue };
+        let file = assets::load_icon(root, &path, config.image_processing.as_ref(), kind).await?;
+        match state.icons.get(&file.hash) {
+            Some(known) if *known == asset_id => continue,
+            Some(known) => {
+                info!("  {} '{}': {} was already uploaded as asset {}, keeping it", resource_type, name, path.display(), known);
+                continue;
+            }
+            None => {}
+        }
+        if dry_run {
+            info!("  Dry Run: Would record {} '{}' icon {} as asset {}", resource_type, name, path.display(), asset_id);
         } else {
-            seen.insert(lower);
+            info!("  {} '{}': icon {} is asset {}", resource_type, name, path.display(), asset_id);
         }
+        let mut changes = ChangeSet::default();
+        changes.push("icon_asset_id", serde_json::Value::Null, asset_id);
+        report.record(resource_type, name, None, ChangeAction::Update, changes);
+        state.set_icon_source(&file.hash, IconSource::Imported);
+        state.icons.insert(file.hash, asset_id);
+        recorded += 1;
     }
-    
-    if !duplicates.is_empty() {
-        return Err(anyhow!(
-            "Duplicate {} names found (names must be unique, case-insensitive): {:?}",
-            resource_type,
-            duplicates
-        ));
+
+    if recorded == 0 {
+        info!("No new configured icons found in {}", lockfile.display());
+        return Ok(());
+    }
+    if !dry_run {
+        state.save(root)?;
+        report.artifact(SyncState::get_state_path(root));
+        info!("Imported {} icon(s) from {}", recorded, lockfile.display());
     }
-    
     Ok(())
 }
 
-/// Calculate SHA-256 hash of a file
-async fn calculate_file_hash(path: &Path) -> Result<String> {
-    if !path.exists() {
-        return Err(anyhow!("File not found: {:?}", path));
-    }
-    let content = tokio::fs::read(path).await?;
-    let mut hasher = Sha256::new();
-    hasher.update(&content);
-    Ok(format!("{:x}", hasher.finalize()))
-}
+/// Archive icons recorded in the lock file that no resource uses anymore,
+/// such as earlier versions of a changed icon
+pub async fn assets_gc(config: &RblxSyncConfig, root: &Path, client: &RobloxClient, dry_run: bool, confirm: Confirmation, report: &mut RunReport) -> Result<()> {
+    report.dry_run = dry_run;
+    let mut state = SyncState::load(root)?;
 
-async fn ensure_icon(client: &RobloxClient, path: &Path, state: Option<&ResourceState>, creator: &crate::config::CreatorConfig) -> Result<(u64, String)> {
-    if !path.exists() {
-        return Err(anyhow!("Icon file not found: {:?}", path));
+    // Icons of tracked resources, plus configured ones not synced yet
+    let mut in_use: HashSet<String> = [&state.game_passes, &state.developer_products, &state.badges]
+        .into_iter()
+        .flat_map(|resources| resources.values())
+        .filter_map(|entry| entry.icon_hash.clone())
+        .collect();
+    let icon_vars = template::variables(config, root);
+    for pass in &config.game_passes {
+        if let Some((icon, _)) = resource_icon(root, config, &icon_vars, &pass.name, pass.price, pass.icon.as_ref(), pass.icon_template.as_ref(), IconKind::GamePass).await? {
+            in_use.insert(icon.hash);
+        }
+    }
+    for prod in &config.developer_products {
+        if let Some((icon, _)) = resource_icon(root, config, &icon_vars, &prod.name, Some(prod.price), prod.icon.as_ref(), prod.icon_template.as_ref(), IconKind::DeveloperProduct).await? {
+            in_use.insert(icon.hash);
+        }
+    }
+    for badge in &config.badges {
+        if let Some((icon, _)) = resource_icon(root, config, &icon_vars, &badge.name, None, badge.icon.as_ref(), badge.icon_template.as_ref(), IconKind::Badge).await? {
+            in_use.insert(icon.hash);
+        }
     }
 
-    // Calculate Hash
-    let content = tokio::fs::read(path).await?;
-    let mut hasher = Sha256::new();
-    hasher.update(&content);
-    let hash = format!("{:x}", hasher.finalize());
+    let (unused, foreign) = unused_icons(&state, &in_use);
+    if foreign > 0 {
+        info!("Keeping {} unused icon(s) this project didn't upload (from the asset cache, an import or a live icon)", foreign);
+    }
+    if unused.is_empty() {
+        info!("No unused icons in the lock file");
+        return Ok(());
+    }
+    if dry_run {
+        for (_, id) in &unused {
+            info!("  Dry Run: Would archive icon asset {}", id);
+            report.record("icon", &id.to_string(), Some(*id), ChangeAction::Archive, ChangeSet::default());
+        }
+        return Ok(());
+    }
+    confirm.require(&format!("Archive {} unused icon asset(s)", unused.len()))?;
 
-    // Check State
-    if let Some(s) = state {
-        if let (Some(sh), Some(sid)) = (&s.icon_hash, s.icon_asset_id) {
-            if sh == &hash {
-                return Ok((sid, hash));
-            }
+    let mut archived = 0;
+    for (hash, id) in &unused {
+        if let Err(e) = client.archive_asset(*id).await {
+            error!("  Failed to archive icon asset {}: {}", id, e);
+            report.fail("icon", format!("Failed to archive icon asset {}: {}", id, e));
+            continue;
         }
+        info!("  Archived icon asset {}", id);
+        state.forget_icon(hash);
+        asset_cache::forget(hash, *id);
+        report.record("icon", &id.to_string(), Some(*id), ChangeAction::Archive, ChangeSet::default());
+        archived += 1;
     }
+    if archived > 0 {
+        state.save(root)?;
+        report.artifact(SyncState::get_state_path(root));
+    }
+    info!("Archived {} of {} unused icon(s)", archived, unused.len());
+    Ok(())
+}
 
-    // Upload
-    info!("Uploading icon: {:?}", path);
-    let name = path.file_stem().unwrap_or_default().to_string_lossy();
-    let asset_id_str = client.upload_asset(path, &name, creator).await?;
-    let asset_id = asset_id_str.parse::<u64>()?;
-    
-    Ok((asset_id, hash))
+/// Icons `assets gc` may archive, by hash and asset ID: those no resource
+/// uses that this project uploaded itself. An asset recorded under several
+/// hashes stays while any of them is in use or came from elsewhere. Also
+/// returns how many unused icons were kept for not being this project's.
+fn unused_icons(state: &SyncState, in_use: &HashSet<String>) -> (Vec<(String, u64)>, usize) {
+    let uploaded = |hash: &str| state.icon_source(hash) == Some(IconSource::Uploaded);
+    let kept: HashSet<u64> = state.icons.iter()
+        .filter(|(hash, _)| in_use.contains(*hash) || !uploaded(hash))
+        .map(|(_, id)| *id)
+        .collect();
+    let mut unused: Vec<(String, u64)> = state.icons.iter()
+        .filter(|(hash, id)| !in_use.contains(*hash) && !kept.contains(id))
+        .map(|(hash, id)| (hash.clone(), *id))
+        .collect();
+    unused.sort_by_key(|(_, id)| *id);
+    let foreign = state.icons.keys().filter(|hash| !in_use.contains(*hash) && !uploaded(hash)).count();
+    (unused, foreign)
+}
+
+/// Pinned ID, name, aliases and icon path of a configured resource
+type ConfiguredIcon<'a> = (Option<u64>, &'a str, &'a [String], Option<&'a String>);
+
+/// Maximum IDs per thumbnails API request
+const ICON_BATCH_SIZE: usize = 100;
+
+/// File name for a downloaded icon, keeping letters, digits, spaces, `-` and `_`
+/// Where `icons download` writes the live icon of resource `name`, relative
+/// to `assets_dir`. Configured icons are source art that the downscaled
+/// thumbnail must not replace (the next `run` would upload it), so those
+/// go to a directory of their own.
+fn icon_download_path(kind: ResourceKind, name: &str, configured: bool) -> String {
+    if configured {
+        format!("icons/downloaded/{}/{}.png", kind.key(), icon_file_stem(name))
+    } else {
+        format!("icons/{}/{}.png", kind.key(), icon_file_stem(name))
+    }
+}
+
+/// File name for a downloaded icon, keeping letters, digits, spaces, `-` and `_`
+fn icon_file_stem(name: &str) -> String {
+    name.chars().map(|c| if c.is_alphanumeric() || matches!(c, ' ' | '-' | '_') { c } else { '_' }).collect::<String>().trim().to_string()
 }
 
-pub async fn export(config: RblxSyncConfig, client: RobloxClient, output: Option<String>, format_lua: bool) -> Result<()> {
+pub async fn export(config: RblxSyncConfig, state: &SyncState, client: RobloxClient, output: Option<String>, format_lua: bool, resume: bool, report: &mut RunReport) -> Result<()> {
     let universe_id = config.universe.id;
 
     info!("Exporting universe {}...", universe_id);
-    // Fetch all data
-    let passes = client.list_game_passes(universe_id, None).await?;
-    let products = client.list_developer_products(universe_id, None).await?;
-    let badges = client.list_badges(universe_id, None).await?;
+    // Fetch all pages, checkpointing to disk so large universes can resume
+    let root = std::env::current_dir()?;
+    let mut fetch = ResumableFetch::open(&root, "export", universe_id, resume)?;
+    for kind in ResourceKind::ALL {
+        let started = Instant::now();
+        fetch.fetch(&client, kind).await?;
+        report.timing(kind.key(), started);
+    }
+    let passes = fetch.items(ResourceKind::GamePasses)?;
+    let products = fetch.items(ResourceKind::DeveloperProducts)?;
+    let badges = fetch.items(ResourceKind::Badges)?;
 
     // Generate output
     // Simple Luau table generation
     let mut lua = String::from("return {\n");
     
     lua.push_str("  game_passes = {\n");
-    for item in passes.data {
+    for item in passes {
         lua.push_str("    {\n");
-        if let Some(n) = item["name"].as_str() { lua.push_str(&format!("      name = \"{}\",\n", n)); }
+        if let Some(n) = item["name"].as_str() { lua.push_str(&format!("      name = \"{}\",\n", output::escape_luau_string(n))); }
         if let Some(id) = item["id"].as_u64() { lua.push_str(&format!("      id = {},\n", id)); }
         if let Some(p) = item["price"].as_u64() { lua.push_str(&format!("      price = {},\n", p)); }
         lua.push_str("    },\n");
@@ -851,9 +4436,9 @@ pub async fn export(config: RblxSyncConfig, client: RobloxClient, output: Option
     lua.push_str("  },\n");
 
     lua.push_str("  developer_products = {\n");
-    for item in products.data {
+    for item in products {
         lua.push_str("    {\n");
-        if let Some(n) = item["name"].as_str() { lua.push_str(&format!("      name = \"{}\",\n", n)); }
+        if let Some(n) = item["name"].as_str() { lua.push_str(&format!("      name = \"{}\",\n", output::escape_luau_string(n))); }
         if let Some(id) = item["id"].as_u64() { lua.push_str(&format!("      id = {},\n", id)); }
         if let Some(p) = item["price"].as_u64() { lua.push_str(&format!("      price = {},\n", p)); }
         lua.push_str("    },\n");
@@ -861,20 +4446,580 @@ pub async fn export(config: RblxSyncConfig, client: RobloxClient, output: Option
     lua.push_str("  },\n");
 
     lua.push_str("  badges = {\n");
-    for item in badges.data {
+    for item in badges {
         lua.push_str("    {\n");
-        if let Some(n) = item["name"].as_str() { lua.push_str(&format!("      name = \"{}\",\n", n)); }
+        if let Some(n) = item["name"].as_str() { lua.push_str(&format!("      name = \"{}\",\n", output::escape_luau_string(n))); }
         if let Some(id) = item["id"].as_u64() { lua.push_str(&format!("      id = {},\n", id)); }
         lua.push_str("    },\n");
     }
     lua.push_str("  },\n");
 
+    // Uploaded assets are only known locally, so they come from the lock file
+    lua.push_str("  assets = {\n");
+    let mut uploaded: Vec<_> = state.assets.iter().collect();
+    uploaded.sort_by_key(|(name, _)| name.as_str());
+    for (name, asset) in uploaded {
+        lua.push_str("    {\n");
+        lua.push_str(&format!("      name = \"{}\",\n", output::escape_luau_string(name)));
+        lua.push_str(&format!("      id = {},\n", asset.asset_id));
+        lua.push_str(&format!("      type = \"{}\",\n", asset.asset_type));
+        lua.push_str("    },\n");
+    }
+    lua.push_str("  },\n");
+
     lua.push_str("}\n");
 
     let out_path = output.unwrap_or_else(|| if format_lua { "config.lua".to_string() } else { "config.luau".to_string() });
     std::fs::write(&out_path, lua)?;
     info!("Exported to {}", out_path);
+    report.artifact(&out_path);
+
+    if let Some(split) = &config.split_output {
+        for path in output::generate_split(state, universe_id, split)? {
+            report.artifact(path);
+        }
+    }
+    fetch.finish()?;
 
     Ok(())
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{ApiResponse, ClientOptions, Middleware};
+    use reqwest::{Method, StatusCode};
+    use serde_json::{json, Value};
+    use std::fs;
+    use std::sync::{Arc, Mutex};
+
+    /// Roblox as far as a sync of game passes sees it, kept in memory
+    #[derive(Clone, Default)]
+    struct FakeRoblox(Arc<Mutex<Fake>>);
+
+    #[derive(Default)]
+    struct Fake {
+        game_passes: Vec<Value>,
+        /// Source of `updatedTimestamp`s
+        clock: u64,
+        /// Fields the next PATCH sets, as its multipart body can't be read back
+        next_patch: serde_json::Map<String, Value>,
+        patches: usize,
+    }
+
+    impl FakeRoblox {
+        fn client(&self) -> RobloxClient {
+            RobloxClient::with_options("fake-api-key".to_string(), ClientOptions::new().with_middleware(self.clone()))
+        }
+
+        /// Change a game pass as if on the Creator Dashboard
+        fn edit(&self, id: u64, fields: Value) {
+            let mut fake = self.0.lock().unwrap();
+            fake.clock += 1;
+            let updated = format!("t{}", fake.clock);
+            let pass = fake.game_passes.iter_mut().find(|p| p["id"] == id).unwrap();
+            for (key, value) in fields.as_object().unwrap() {
+                pass[key] = value.clone();
+            }
+            pass["updatedTimestamp"] = updated.into();
+        }
+    }
+
+    impl Middleware for FakeRoblox {
+        fn on_request(&self, request: &mut reqwest::Request) -> Option<ApiResponse> {
+            let path = request.url().path().to_string();
+            let id = path.trim_end_matches("/creator").rsplit('/').next().and_then(|id| id.parse::<u64>().ok());
+            let (status, body) = match (request.method().clone(), id) {
+                (Method::GET, None) => (StatusCode::OK, json!({ "gamePasses": self.0.lock().unwrap().game_passes })),
+                (Method::GET, Some(id)) => {
+                    let fake = self.0.lock().unwrap();
+                    (StatusCode::OK, fake.game_passes.iter().find(|p| p["id"] == id).cloned().unwrap_or_default())
+                }
+                (Method::PATCH, Some(id)) => {
+                    let fields = {
+                        let mut fake = self.0.lock().unwrap();
+                        fake.patches += 1;
+                        Value::Object(std::mem::take(&mut fake.next_patch))
+                    };
+                    self.edit(id, fields);
+                    return Some(ApiResponse::new(request, StatusCode::NO_CONTENT, Vec::new()));
+                }
+                _ => (StatusCode::NOT_FOUND, json!({})),
+            };
+            Some(ApiResponse::new(request, status, body.to_string().into_bytes()))
+        }
+    }
+
+    /// Answers requests in turn with canned responses, repeating the last
+    #[derive(Clone, Default)]
+    struct Canned(Arc<Mutex<CannedState>>);
+
+    #[derive(Default)]
+    struct CannedState {
+        responses: std::collections::VecDeque<(u16, Value)>,
+        /// Method and path of each request answered
+        requests: Vec<String>,
+    }
+
+    impl Canned {
+        fn new(responses: impl IntoIterator<Item = (u16, Value)>) -> Self {
+            Self(Arc::new(Mutex::new(CannedState { responses: responses.into_iter().collect(), requests: Vec::new() })))
+        }
+
+        fn client(&self) -> RobloxClient {
+            RobloxClient::with_options("fake-api-key".to_string(), ClientOptions::new().with_middleware(self.clone()))
+        }
+
+        fn requests(&self) -> Vec<String> {
+            self.0.lock().unwrap().requests.clone()
+        }
+    }
+
+    impl Middleware for Canned {
+        fn on_request(&self, request: &mut reqwest::Request) -> Option<ApiResponse> {
+            let mut canned = self.0.lock().unwrap();
+            canned.requests.push(format!("{} {}", request.method(), request.url().path()));
+            let (status, body) = match canned.responses.len() {
+                0 | 1 => canned.responses.front().cloned().unwrap_or((404, Value::Null)),
+                _ => canned.responses.pop_front().unwrap(),
+            };
+            Some(ApiResponse::new(request, StatusCode::from_u16(status).unwrap(), body.to_string().into_bytes()))
+        }
+    }
+
+    fn versions(number: u64) -> (u16, Value) {
+        (200, json!({ "assetVersions": [{ "path": format!("assets/5/versions/{}", number) }] }))
+    }
+
+    #[test]
+    fn test_publish_error_is_transient() {
+        let error = |status: u16, body: &str| crate::api::PublishError { status: StatusCode::from_u16(status).unwrap(), body: body.to_string() };
+        assert!(error(500, "").is_transient());
+        assert!(error(503, "").is_transient());
+        assert!(error(429, "").is_transient());
+        assert!(error(409, "{\"message\":\"A version is being processed\"}").is_processing());
+        assert!(error(409, "Publish already in progress").is_transient());
+        assert!(!error(409, "{\"message\":\"Place is archived\"}").is_transient());
+        assert!(!error(400, "being processed").is_transient());
+        assert!(!error(403, "").is_transient());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_publish_with_retries() {
+        let path = std::env::temp_dir().join(format!("rblxsync-retry-{}.rbxl", std::process::id()));
+        fs::write(&path, b"place").unwrap();
+
+        let canned = Canned::new([versions(3), (409, json!({ "message": "Version is being processed" })), (500, json!({})), (200, json!({ "versionNumber": 4 }))]);
+        let response = publish_with_retries(&canned.client(), 1, 5, &path).await.unwrap();
+        assert_eq!(response["versionNumber"], 4);
+        let publishes = canned.requests().iter().filter(|r| r.starts_with("POST")).count();
+        assert_eq!(publishes, 3);
+
+        let canned = Canned::new([versions(3), (409, json!({ "message": "Place is archived" }))]);
+        assert!(publish_with_retries(&canned.client(), 1, 5, &path).await.unwrap_err().to_string().contains("archived"));
+        assert_eq!(canned.requests().iter().filter(|r| r.starts_with("POST")).count(), 1);
+
+        // Versions aren't re-read after a failure, as a bump may be another publisher's
+        let canned = Canned::new([versions(3), (500, json!({}))]);
+        assert!(publish_with_retries(&canned.client(), 1, 5, &path).await.is_err());
+        let methods: Vec<String> = canned.requests().iter().map(|r| r.split(' ').next().unwrap().to_string()).collect();
+        assert_eq!(methods, ["GET", "POST", "POST", "POST", "POST"]);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_check_lock_universe() {
+        let mut state = SyncState::default();
+        check_lock_universe(&state, 1, 2).unwrap();
+
+        state.update_game_pass(10, "VIP".into(), None, Some(100), Some(true), None, None);
+        check_lock_universe(&state, 1, 1).unwrap();
+        assert!(check_lock_universe(&state, 1, 2).unwrap_err().to_string().contains("holds IDs from universe 1, not 2"));
+
+        state.universe_id = Some(2);
+        check_lock_universe(&state, 1, 2).unwrap();
+        assert!(check_lock_universe(&state, 1, 1).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_verify_place_version() {
+        let client = Canned::new([versions(3)]).client();
+        verify_place_version(&client, 5, 3, 0).await.unwrap();
+
+        let replaced = verify_place_version(&client, 5, 2, 0).await.unwrap_err();
+        assert!(replaced.to_string().contains("replaced by another publish"), "{}", replaced);
+
+        let stale = verify_place_version(&client, 5, 4, 0).await.unwrap_err();
+        assert!(stale.to_string().contains("still at version 3"), "{}", stale);
+
+        // Polled until the version shows up
+        let canned = Canned::new([versions(3), versions(4)]);
+        verify_place_version(&canned.client(), 5, 4, 10).await.unwrap();
+        assert_eq!(canned.requests().len(), 2);
+
+        // A key without asset:read only warns
+        let client = Canned::new([(403, json!({ "message": "Forbidden" }))]).client();
+        verify_place_version(&client, 5, 4, 10).await.unwrap();
+        let client = Canned::new([(500, json!({}))]).client();
+        assert!(verify_place_version(&client, 5, 4, 0).await.is_err());
+    }
+
+    fn restriction(yaml: &str) -> RestrictionConfig {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn test_restriction_body() {
+        let ban = restriction("user_id: 1\nduration: 60\ndisplay_reason: Exploiting\n");
+        assert_eq!(
+            restriction_body(&ban),
+            json!({ "active": true, "excludeAltAccounts": true, "duration": "60s", "displayReason": "Exploiting", "privateReason": "" })
+        );
+        let with_alts = restriction("user_id: 1\nban_alt_accounts: true\n");
+        assert_eq!(restriction_body(&with_alts)["excludeAltAccounts"], false);
+        assert_eq!(restriction_body(&restriction("user_id: 1\nactive: false\n")), json!({ "active": false }));
+    }
+
+    #[test]
+    fn test_restriction_changes() {
+        let ban = restriction("user_id: 1\nban_alt_accounts: true\ndisplay_reason: Exploiting\n");
+        let live = json!({ "active": true, "excludeAltAccounts": false, "displayReason": "Exploiting", "privateReason": "", "duration": "60s" });
+        assert!(restriction_changes(&live, &ban).is_empty(), "duration isn't compared");
+
+        let live_without_alts = json!({ "active": true, "excludeAltAccounts": true, "displayReason": "Cheating" });
+        let changes = restriction_changes(&live_without_alts, &ban);
+        assert!(changes.contains("ban_alt_accounts") && changes.contains("display_reason"));
+
+        let lifted = restriction("user_id: 1\nactive: false\n");
+        assert!(restriction_changes(&json!({ "active": false, "displayReason": "x" }), &lifted).is_empty());
+        assert!(restriction_changes(&live, &lifted).contains("active"));
+    }
+
+    #[tokio::test]
+    async fn test_expired_ban_is_not_reapplied() {
+        let bans = [restriction("user_id: 1\nduration: 86400\n")];
+        let inactive = (200, json!({ "gameJoinRestriction": { "active": false } }));
+        let opts = SyncOptions::default();
+        let mut report = RunReport::new("run");
+        let mut state = SyncState::default();
+
+        // First run bans and records when
+        let roblox = Canned::new([inactive.clone(), (200, json!({}))]);
+        sync_restrictions(1, &bans, &mut state, &roblox.client(), &opts, &mut report).await.unwrap();
+        assert_eq!(roblox.requests().len(), 2);
+        assert_eq!(state.timed_bans["1"].duration, 86400);
+
+        // Once the ban has run out, it reads as inactive but is left alone
+        state.timed_bans.get_mut("1").unwrap().started -= 86400;
+        let roblox = Canned::new([inactive.clone()]);
+        sync_restrictions(1, &bans, &mut state, &roblox.client(), &opts, &mut report).await.unwrap();
+        assert_eq!(roblox.requests(), ["GET /cloud/v2/universes/1/user-restrictions/1"]);
+
+        // A new duration is a new ban
+        let longer = [restriction("user_id: 1\nduration: 172800\n")];
+        let roblox = Canned::new([inactive, (200, json!({}))]);
+        sync_restrictions(1, &longer, &mut state, &roblox.client(), &opts, &mut report).await.unwrap();
+        assert_eq!(roblox.requests().len(), 2);
+        assert_eq!(state.timed_bans["1"].duration, 172800);
+    }
+
+    #[tokio::test]
+    async fn test_promote_requires_tested_file() {
+        let root = std::env::temp_dir().join(format!("rblxsync-promote-{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        let file = root.join("game.rbxl");
+        fs::write(&file, "build 1").unwrap();
+        let yaml = format!("universe: {{ id: 1 }}\nplaces:\n  - {{ place_id: 10, test_place_id: 20, publish: true, file_path: '{}' }}\n", file.display());
+        let config: RblxSyncConfig = serde_yaml::from_str(&yaml).unwrap();
+        let publish_opts = |promote| PublishOptions { verify: None, promote, confirm: Confirmation { yes: true, interactive: false } };
+
+        // Without a test publish there's nothing to promote
+        let canned = Canned::new([versions(4), (200, json!({ "versionNumber": 5 }))]);
+        let mut report = RunReport::new("publish");
+        publish(config.clone(), &root, canned.client(), publish_opts(true), &mut report).await.unwrap();
+        assert_eq!(report.errors.len(), 1);
+        assert!(canned.requests().is_empty());
+
+        publish(config.clone(), &root, canned.client(), publish_opts(false), &mut report).await.unwrap();
+        assert_eq!(canned.requests(), ["GET /assets/v1/assets/20/versions", "POST /v1/universes/1/places/20/versions"]);
+        let tested = SyncState::load(&root).unwrap().test_publishes[&10].clone();
+        assert_eq!((tested.test_place_id, tested.version), (20, 5));
+
+        // A different file isn't promoted
+        fs::write(&file, "build 2").unwrap();
+        let canned = Canned::new([versions(7), (200, json!({ "versionNumber": 8 }))]);
+        let mut report = RunReport::new("publish");
+        publish(config.clone(), &root, canned.client(), publish_opts(true), &mut report).await.unwrap();
+        assert!(report.errors[0].contains("changed since it was published to test place 20"), "{:?}", report.errors);
+        assert!(canned.requests().is_empty());
+
+        // The tested file goes to production only
+        fs::write(&file, "build 1").unwrap();
+        let mut report = RunReport::new("publish");
+        publish(config, &root, canned.client(), publish_opts(true), &mut report).await.unwrap();
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+        assert_eq!(canned.requests(), ["GET /assets/v1/assets/10/versions", "POST /v1/universes/1/places/10/versions"]);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_badges_set_enabled_saves_partial_progress() {
+        let root = std::env::temp_dir().join(format!("rblxsync-badges-enabled-{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        let mut state = SyncState::default();
+        for (id, name) in [(1, "Alpha"), (2, "Beta"), (3, "Gamma")] {
+            state.update_badge(id, name.to_string(), None, Some(true), None);
+        }
+        state.save(&root).unwrap();
+        let config: RblxSyncConfig = serde_yaml::from_str("universe: { id: 1 }\n").unwrap();
+
+        // Beta fails; Alpha and Gamma are still disabled and saved
+        let canned = Canned::new([(200, json!({})), (500, json!({})), (200, json!({}))]);
+        let mut report = RunReport::new("badges");
+        let confirm = Confirmation { yes: true, interactive: false };
+        badges_set_enabled(&config, &root, &canned.client(), None, false, false, confirm, &mut report).await.unwrap();
+        assert_eq!(canned.requests(), ["PATCH /legacy-badges/v1/badges/1", "PATCH /legacy-badges/v1/badges/2", "PATCH /legacy-badges/v1/badges/3"]);
+        assert_eq!((report.counts.updated, report.counts.failed), (2, 1));
+        let saved = SyncState::load(&root).unwrap();
+        let enabled: Vec<Option<bool>> = [1, 2, 3].iter().map(|id| saved.badges[id].is_enabled).collect();
+        assert_eq!(enabled, [Some(false), Some(true), Some(false)]);
+
+        // A second run only retries the one that failed
+        let canned = Canned::new([(200, json!({}))]);
+        let mut report = RunReport::new("badges");
+        badges_set_enabled(&config, &root, &canned.client(), None, false, false, confirm, &mut report).await.unwrap();
+        assert_eq!(canned.requests(), ["PATCH /legacy-badges/v1/badges/2"]);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_state_refresh_keeps_local_records() {
+        let root = std::env::temp_dir().join(format!("rblxsync-state-refresh-{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        let mut state = SyncState::default();
+        state.timed_bans.insert("1:42".to_string(), crate::state::TimedBan { started: 100, duration: 60 });
+        state.icon_sources.insert("abc".to_string(), crate::state::IconSource::Uploaded);
+        state.test_publishes.insert(5, crate::state::TestPublish { test_place_id: 6, hash: "abc".to_string(), version: 3 });
+        state.pending.push(crate::state::PendingVerification {
+            kind: EntryKind::Badge,
+            id: 9,
+            name: "First Win".to_string(),
+            check: crate::state::PendingCheck::Icon { asset_id: 11 },
+            since: 100,
+            attempts: 0,
+        });
+        state.save(&root).unwrap();
+
+        let canned = Canned::new([(200, json!({}))]);
+        let mut report = RunReport::new("state");
+        state_refresh(serde_yaml::from_str("universe: { id: 1 }").unwrap(), &root, canned.client(), false, Confirmation::default(), &mut report).await.unwrap();
+
+        let refreshed = SyncState::load(&root).unwrap();
+        assert_eq!(refreshed.timed_bans.get("1:42").map(|ban| (ban.started, ban.duration)), Some((100, 60)));
+        assert_eq!(refreshed.icon_sources.get("abc"), Some(&crate::state::IconSource::Uploaded));
+        assert_eq!(refreshed.test_publishes.get(&5).map(|publish| publish.version), Some(3));
+        assert_eq!(refreshed.pending.len(), 1);
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_image_processing_limits() {
+        let config: RblxSyncConfig = serde_yaml::from_str("universe: { id: 1 }\nimage_processing: { size: 4096 }\n").unwrap();
+        let problems = config_problems(&config);
+        assert!(problems.iter().any(|p| p.starts_with("image_processing.size must be between 1 and 1024")), "{:?}", problems);
+
+        let config: RblxSyncConfig = serde_yaml::from_str("universe: { id: 1 }\nimage_processing: { size: 256, sizes: { badge: 0, game_pass: 2048 } }\n").unwrap();
+        let problems = config_problems(&config);
+        assert!(problems.iter().any(|p| p.starts_with("image_processing.sizes.badge")), "{:?}", problems);
+        assert!(problems.iter().any(|p| p.starts_with("image_processing.sizes.game_pass")), "{:?}", problems);
+        assert!(!problems.iter().any(|p| p.starts_with("image_processing.size ")), "{:?}", problems);
+
+        let config: RblxSyncConfig = serde_yaml::from_str("universe: { id: 1 }\nimage_processing: { size: 256, sizes: { badge: 150 } }\n").unwrap();
+        assert!(config_problems(&config).is_empty());
+    }
+
+    #[test]
+    fn test_mirrored_universe() {
+        let details = serde_json::json!({ "name": "Dev", "description": "Test build", "maxPlayers": 10 });
+        assert_eq!(mirrored_universe(2, Some(&details)), serde_json::json!({ "id": 2, "name": "Dev", "description": "Test build" }));
+        assert_eq!(mirrored_universe(2, None), serde_json::json!({ "id": 2 }));
+    }
+
+    #[test]
+    fn test_icon_download_path() {
+        assert_eq!(icon_download_path(ResourceKind::Badges, "First Win!", false), "icons/badges/First Win_.png");
+        assert_eq!(icon_download_path(ResourceKind::GamePasses, "VIP", true), "icons/downloaded/game_passes/VIP.png");
+    }
+
+    #[tokio::test]
+    async fn test_icons_download_keeps_configured_art() {
+        let root = std::env::temp_dir().join(format!("rblxsync-icons-download-{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join("champion.png"), "source art").unwrap();
+        let yaml = format!("universe: {{ id: 1 }}\nassets_dir: '{}'\nbadges:\n  - {{ name: Champion, icon: champion.png }}\n", root.display());
+        let config: RblxSyncConfig = serde_yaml::from_str(&yaml).unwrap();
+        let mut state = SyncState::default();
+        state.update_badge(5, "Champion".to_string(), None, Some(true), None);
+        let thumbnails = json!({ "data": [{ "targetId": 5, "state": "Completed", "imageUrl": "https://tr.rbxcdn.com/5.png" }] });
+        let canned = Canned::new([(200, thumbnails), (200, json!("thumbnail"))]);
+
+        let mut report = RunReport::new("icons");
+        icons_download(config, &state, canned.client(), true, false, &mut report).await.unwrap();
+        assert_eq!(fs::read_to_string(root.join("champion.png")).unwrap(), "source art");
+        assert_eq!(fs::read_to_string(root.join("icons/downloaded/badges/Champion.png")).unwrap(), "\"thumbnail\"");
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    fn pass_config(yaml: &str) -> RblxSyncConfig {
+        serde_yaml::from_str(&format!("universe: {{ id: 1 }}\ngame_passes:\n{}", yaml)).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_remote_edits_between_runs() {
+        let fake = FakeRoblox::default();
+        fake.0.lock().unwrap().game_passes.push(json!({ "id": 7, "name": "VIP", "description": "A", "price": 100, "isForSale": true }));
+        fake.edit(7, json!({}));
+        let client = fake.client();
+        let opts = SyncOptions::default();
+        let mut report = RunReport::new("run");
+        let mut state = SyncState::default();
+
+        // First sync adopts the pass and remembers its timestamp
+        let config = pass_config("  - { name: VIP, description: A, price: 100 }\n");
+        sync_game_passes(1, &config, &mut state, &client, &opts, &mut report).await.unwrap();
+        assert_eq!(state.game_passes[&7].remote_updated.as_deref(), Some("t1"));
+
+        // A dashboard edit, then a config change to another field
+        fake.edit(7, json!({ "description": "Dashboard" }));
+        fake.0.lock().unwrap().next_patch = json!({ "price": 150 }).as_object().unwrap().clone();
+        let config = pass_config("  - { name: VIP, description: A, price: 150 }\n");
+        sync_game_passes(1, &config, &mut state, &client, &opts, &mut report).await.unwrap();
+        let entry = &state.game_passes[&7];
+        assert_eq!(fake.0.lock().unwrap().patches, 1);
+        assert_eq!(entry.remote_updated.as_deref(), Some("t3"), "timestamp read back after the PATCH");
+        assert_eq!((entry.description.as_deref(), entry.price), (Some("Dashboard"), Some(150)));
+        assert_eq!(entry.kept_remote.get("description"), Some(&json!("A")));
+
+        // Another dashboard edit is still noticed and kept, not overwritten
+        fake.edit(7, json!({ "description": "Dashboard 2" }));
+        fake.0.lock().unwrap().next_patch = json!({ "price": 200 }).as_object().unwrap().clone();
+        let config = pass_config("  - { name: VIP, description: A, price: 200 }\n");
+        sync_game_passes(1, &config, &mut state, &client, &opts, &mut report).await.unwrap();
+        let entry = &state.game_passes[&7];
+        assert_eq!(fake.0.lock().unwrap().patches, 2);
+        assert_eq!(entry.remote_updated.as_deref(), Some("t5"));
+        assert_eq!((entry.description.as_deref(), entry.price), (Some("Dashboard 2"), Some(200)));
+
+        // Nothing changed on either side: no PATCH
+        sync_game_passes(1, &config, &mut state, &client, &opts, &mut report).await.unwrap();
+        assert_eq!(fake.0.lock().unwrap().patches, 2);
+
+        // Changing the kept field in config applies it again
+        fake.0.lock().unwrap().next_patch = json!({ "description": "B" }).as_object().unwrap().clone();
+        let config = pass_config("  - { name: VIP, description: B, price: 200 }\n");
+        sync_game_passes(1, &config, &mut state, &client, &opts, &mut report).await.unwrap();
+        let entry = &state.game_passes[&7];
+        assert_eq!(fake.0.lock().unwrap().patches, 3);
+        assert_eq!(entry.description.as_deref(), Some("B"));
+        assert!(entry.kept_remote.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_rename_in_config() {
+        let fake = FakeRoblox::default();
+        fake.0.lock().unwrap().game_passes.push(json!({ "id": 7, "name": "VIP", "description": "A", "price": 100, "isForSale": true }));
+        fake.0.lock().unwrap().game_passes.push(json!({ "id": 8, "name": "Gold", "description": "A", "price": 50, "isForSale": true }));
+        let client = fake.client();
+        let opts = SyncOptions::default();
+        let mut report = RunReport::new("run");
+        let mut state = SyncState::default();
+
+        let config = pass_config("  - { name: VIP, description: A, price: 100 }\n  - { name: Gold, description: A, price: 50 }\n");
+        sync_game_passes(1, &config, &mut state, &client, &opts, &mut report).await.unwrap();
+
+        // Renaming one entry renames the tracked pass instead of creating another
+        fake.0.lock().unwrap().next_patch = json!({ "name": "VIP Plus" }).as_object().unwrap().clone();
+        let config = pass_config("  - { name: VIP Plus, description: A, price: 100 }\n  - { name: Gold, description: A, price: 50 }\n");
+        sync_game_passes(1, &config, &mut state, &client, &opts, &mut report).await.unwrap();
+        assert_eq!(fake.0.lock().unwrap().patches, 1);
+        assert_eq!(fake.0.lock().unwrap().game_passes.len(), 2);
+        assert_eq!(state.game_passes[&7].name, "VIP Plus");
+        assert_eq!(state.game_passes.len(), 2);
+
+        // Renaming both at once can't be paired up and fails before any request
+        let config = pass_config("  - { name: VIP Max, description: A, price: 100 }\n  - { name: Platinum, description: A, price: 50 }\n");
+        let err = sync_game_passes(1, &config, &mut state, &client, &opts, &mut report).await.unwrap_err();
+        assert!(err.to_string().contains("'VIP Plus' (ID: 7), 'Gold' (ID: 8)"), "{}", err);
+        assert!(err.to_string().contains("`id:`"));
+        assert_eq!(fake.0.lock().unwrap().patches, 1);
+        assert_eq!(state.game_passes[&8].name, "Gold");
+    }
+
+    #[tokio::test]
+    async fn test_pinned_id_must_exist() {
+        let fake = FakeRoblox::default();
+        fake.0.lock().unwrap().game_passes.push(json!({ "id": 7, "name": "VIP", "description": "A", "price": 100, "isForSale": true }));
+        let mut state = SyncState::default();
+        let config = pass_config("  - { id: 70, name: VIP, description: B, price: 100 }\n");
+        let err = sync_game_passes(1, &config, &mut state, &fake.client(), &SyncOptions::default(), &mut RunReport::new("run")).await.unwrap_err();
+        assert_eq!(err.to_string(), "Game Pass 'VIP': pinned id 70 not found in universe 1");
+        assert_eq!(fake.0.lock().unwrap().patches, 0);
+        assert!(state.game_passes.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_icon_moderation_is_cached() {
+        let stored = |state: &str, checked: u64| -> ResourceState {
+            serde_yaml::from_str(&format!("{{ name: VIP, icon_hash: abc, icon_moderation_state: {}, icon_moderation_checked: {} }}", state, checked)).unwrap()
+        };
+        let roblox = Canned::new([(200, json!({ "moderationResult": { "moderationState": "Approved" } }))]);
+        let client = roblox.client();
+
+        // Approved and recent results are reused, and dry runs never ask
+        check_icon_moderation(&client, "Game Pass", "VIP", Some(5), Some("abc"), Some(&stored("Approved", 0)), false, false).await;
+        check_icon_moderation(&client, "Game Pass", "VIP", Some(5), Some("abc"), Some(&stored("Rejected", actor::now())), false, false).await;
+        check_icon_moderation(&client, "Game Pass", "VIP", Some(5), Some("abc"), Some(&stored("Rejected", 0)), false, true).await;
+        assert!(roblox.requests().is_empty());
+
+        // A stale result or a new upload is read again
+        let (state, checked) = check_icon_moderation(&client, "Game Pass", "VIP", Some(5), Some("abc"), Some(&stored("Rejected", 0)), false, false).await;
+        assert_eq!(state.as_deref(), Some("Approved"));
+        assert!(checked.is_some_and(|at| at > 0));
+        check_icon_moderation(&client, "Game Pass", "VIP", Some(5), Some("abc"), Some(&stored("Approved", 0)), true, false).await;
+        assert_eq!(roblox.requests().len(), 2);
+    }
+
+    #[test]
+    fn test_unused_icons() {
+        let mut state = SyncState::default();
+        for (hash, id, source) in [
+            ("used", 1, Some(IconSource::Uploaded)),
+            ("old", 2, Some(IconSource::Uploaded)),
+            ("cached", 3, Some(IconSource::Cache)),
+            ("imported", 4, Some(IconSource::Imported)),
+            ("live", 5, Some(IconSource::Live)),
+            ("legacy", 6, None),
+            // Same asset as an icon still in use
+            ("reencoded", 1, Some(IconSource::Uploaded)),
+            // Same asset as one from the cache
+            ("cached-copy", 3, Some(IconSource::Uploaded)),
+        ] {
+            state.icons.insert(hash.to_string(), id);
+            if let Some(source) = source {
+                state.set_icon_source(hash, source);
+            }
+        }
+        let in_use = HashSet::from(["used".to_string()]);
+        let (unused, foreign) = unused_icons(&state, &in_use);
+        assert_eq!(unused, vec![("old".to_string(), 2)]);
+        assert_eq!(foreign, 4);
+
+        state.forget_icon("old");
+        assert!(state.icon_source("old").is_none());
+        assert!(unused_icons(&state, &in_use).0.is_empty());
+    }
+}
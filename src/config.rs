@@ -1,8 +1,13 @@
-use anyhow::{Context, Result};
+use crate::credentials;
+use anyhow::{anyhow, bail, Context, Result};
+use regex::Regex;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashMap;
 use std::env;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 
 // --- Private Server Cost ---
 
@@ -99,11 +104,20 @@ pub struct Config {
 }
 
 impl Config {
-    pub fn from_env() -> Result<Self> {
+    /// Load credentials. The API key comes from `ROBLOX_API_KEY`, then the
+    /// `api_key_command` of the config at `config_path`, then the selected
+    /// profile (credentials.toml, then the keychain).
+    pub fn from_env(config_path: &Path) -> Result<Self> {
         let _ = dotenvy::dotenv();
 
-        let api_key = env::var("ROBLOX_API_KEY")
-            .context("ROBLOX_API_KEY environment variable not set")?;
+        let profile = credentials::selected_profile();
+        let api_key = match (env::var("ROBLOX_API_KEY"), api_key_command(config_path)) {
+            (Ok(key), _) => key,
+            (Err(_), Some(command)) => credentials::command_api_key(&command)?,
+            (Err(_), None) => credentials::profile_api_key(profile)?.with_context(|| {
+                format!("ROBLOX_API_KEY environment variable not set and no key saved for profile '{}' (run `rblxsync login`)", profile)
+            })?,
+        };
 
         let roblox_cookie = env::var("ROBLOX_COOKIE").ok();
 
@@ -114,6 +128,15 @@ impl Config {
     }
 }
 
+/// The top-level `api_key_command` of a config file, or else of the user
+/// config, read without loading the rest (problems there are reported once
+/// the config is loaded)
+fn api_key_command(path: &Path) -> Option<String> {
+    let project = fs::read_to_string(path).ok().and_then(|content| ConfigFormat::from_path(path).parse_document(&content).ok());
+    let user = load_user_config().ok().flatten();
+    [project, user].into_iter().flatten().find_map(|doc| doc.get("api_key_command")?.as_str().map(str::to_string))
+}
+
 // --- YAML Configuration ---
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -130,18 +153,324 @@ pub struct RblxSyncConfig {
     pub badges: Vec<BadgeConfig>,
     #[serde(default)]
     pub places: Vec<PlaceConfig>,
+    /// Standalone assets (images, audio, models) uploaded from `assets_dir`
+    #[serde(default)]
+    pub assets: Vec<AssetConfig>,
+    /// User bans applied by `run`
+    #[serde(default)]
+    pub restrictions: Vec<RestrictionConfig>,
     /// Payment source type for badge creation (costs 100 Robux per badge)
     /// Valid values: "user" (pay from user funds) or "group" (pay from group funds)
     pub badge_payment_source: Option<String>,
     /// Output path for generating Luau config from the lock file after sync
     /// e.g. "Config.luau" or "src/shared/Config.luau"
     pub output_path: Option<String>,
+    /// Emit separate server and client Luau modules with per-field visibility
+    pub split_output: Option<SplitOutputConfig>,
+    /// Validate and normalise icons before upload (disabled when omitted)
+    pub image_processing: Option<ImageProcessingConfig>,
+    /// Before uploading an icon the lock file doesn't know, compare it with
+    /// the resource's live icon and skip the upload if they look the same
+    #[serde(default)]
+    pub compare_remote_icons: bool,
+    /// Icons generated at sync time, by name (see `icon_template` on resources)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub icon_templates: HashMap<String, IconTemplateConfig>,
+    /// Naming conventions enforced by `validate`
+    pub naming: Option<NamingConfig>,
+    /// Abort `run` when a pass or product price changes by more than this
+    /// percentage, unless `--confirm-price-changes` is passed
+    pub max_price_change_percent: Option<f64>,
+    /// Pre-check of names and descriptions for text Roblox would filter
+    pub text_check: Option<TextCheckConfig>,
+    /// Shell commands run before and after `run` and `publish`
+    pub hooks: Option<HooksConfig>,
+    /// Custom template variables usable as `{{name}}` in descriptions
+    #[serde(default)]
+    pub variables: HashMap<String, String>,
+    /// Shell command printing the API key, used when `ROBLOX_API_KEY` isn't
+    /// set (e.g. `op read op://vault/roblox/key`)
+    pub api_key_command: Option<String>,
+    /// Shared copy of the lock file for stateless CI runners
+    pub state_backend: Option<StateBackendConfig>,
 }
 
 fn default_assets_dir() -> String {
     "assets".to_string()
 }
 
+/// Where the shared copy of the lock file is kept (see `remote_state`).
+/// Every backend stores it under `prefix` followed by the lock file name.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum StateBackendConfig {
+    /// An entry in a standard data store of the synced universe
+    Datastore {
+        #[serde(default = "default_state_datastore")]
+        datastore: String,
+        #[serde(default)]
+        prefix: String,
+    },
+    /// An object in an S3-compatible bucket; credentials come from
+    /// `AWS_ACCESS_KEY_ID` and `AWS_SECRET_ACCESS_KEY`
+    S3 {
+        bucket: String,
+        #[serde(default = "default_s3_region")]
+        region: String,
+        /// e.g. `https://<account>.r2.cloudflarestorage.com` (default: AWS for `region`)
+        endpoint: Option<String>,
+        #[serde(default)]
+        prefix: String,
+    },
+    /// A file on a branch of the project's git repository
+    Git {
+        #[serde(default = "default_state_branch")]
+        branch: String,
+        #[serde(default = "default_git_remote")]
+        remote: String,
+        #[serde(default)]
+        prefix: String,
+    },
+}
+
+fn default_state_datastore() -> String {
+    "rblxsync-state".to_string()
+}
+
+fn default_s3_region() -> String {
+    "us-east-1".to_string()
+}
+
+fn default_state_branch() -> String {
+    "rblxsync-state".to_string()
+}
+
+fn default_git_remote() -> String {
+    "origin".to_string()
+}
+
+/// Shell commands run around syncs and publishes (see `hooks`)
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct HooksConfig {
+    /// Run before `run` changes anything; a failure aborts the sync
+    #[serde(default)]
+    pub pre_sync: Vec<String>,
+    /// Run after a successful `run`
+    #[serde(default)]
+    pub post_sync: Vec<String>,
+    /// Run after each place is published
+    #[serde(default)]
+    pub post_publish: Vec<String>,
+    /// Sent to live servers through MessagingService after each place is published
+    pub publish_message: Option<MessageHook>,
+}
+
+/// A MessagingService message; `message` may use `{{variable}}` placeholders
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct MessageHook {
+    pub topic: String,
+    pub message: String,
+}
+
+/// Naming rules per resource type
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct NamingConfig {
+    pub game_passes: Option<NamingRule>,
+    pub developer_products: Option<NamingRule>,
+    pub badges: Option<NamingRule>,
+    pub assets: Option<NamingRule>,
+}
+
+/// A regex and/or case style that names must follow
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct NamingRule {
+    /// Regular expression names must match
+    pub pattern: Option<NamePattern>,
+    /// Case style names must follow
+    pub case: Option<CaseStyle>,
+}
+
+/// A naming regex, compiled when the config loads so an invalid pattern is
+/// a config error rather than a failure on each check
+#[derive(Debug, Clone)]
+pub struct NamePattern(pub Regex);
+
+impl<'de> Deserialize<'de> for NamePattern {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let pattern = String::deserialize(deserializer)?;
+        Regex::new(&pattern)
+            .map(NamePattern)
+            .map_err(|e| serde::de::Error::custom(format!("invalid naming pattern '{}': {}", pattern, e)))
+    }
+}
+
+impl Serialize for NamePattern {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.0.as_str())
+    }
+}
+
+/// How `run` treats names and descriptions likely to be filtered
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct TextCheckConfig {
+    #[serde(default)]
+    pub level: TextCheckLevel,
+    /// Extra words to reject (case-insensitive, whole words)
+    #[serde(default)]
+    pub blocked_words: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TextCheckLevel {
+    /// Log a warning and sync anyway
+    #[default]
+    Warn,
+    /// Fail before syncing
+    Error,
+    Off,
+}
+
+/// Supported name case styles
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum CaseStyle {
+    Title,
+    Sentence,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+}
+
+/// Server/client split of the generated Luau module
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct SplitOutputConfig {
+    /// Directory `{name}.server.luau` and `{name}.client.luau` are written to
+    pub dir: String,
+    /// Module base name (default: "Monetization")
+    #[serde(default = "default_split_output_name")]
+    pub name: String,
+    /// Per-field visibility; `price` is server-only unless overridden
+    #[serde(default)]
+    pub visibility: HashMap<String, FieldVisibility>,
+}
+
+/// Which generated modules a field appears in
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldVisibility {
+    /// Server and client modules
+    Shared,
+    /// Server module only
+    Server,
+}
+
+impl SplitOutputConfig {
+    /// Resource fields that visibility can be set for
+    pub const FIELDS: [&'static str; 6] = ["id", "name", "description", "price", "is_for_sale", "is_enabled"];
+
+    pub fn is_client_visible(&self, field: &str) -> bool {
+        let default = if field == "price" { FieldVisibility::Server } else { FieldVisibility::Shared };
+        self.visibility.get(field).copied().unwrap_or(default) == FieldVisibility::Shared
+    }
+}
+
+fn default_split_output_name() -> String {
+    "Monetization".to_string()
+}
+
+/// Icon preprocessing applied before upload
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ImageProcessingConfig {
+    /// Resize and pad icons to a `size`x`size` square (default: true)
+    #[serde(default = "default_true")]
+    pub resize: bool,
+    /// Target square size in pixels (default: 512)
+    #[serde(default = "default_icon_size")]
+    pub size: u32,
+    /// Per-resource-type size overrides
+    #[serde(default)]
+    pub sizes: IconSizesConfig,
+    /// Maximum source file size in kilobytes (default: 20480)
+    #[serde(default = "default_max_file_size_kb")]
+    pub max_file_size_kb: u64,
+}
+
+/// Icon sizes derived from a single source image for each resource type
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct IconSizesConfig {
+    /// Game pass icon size (default: `size`)
+    pub game_pass: Option<u32>,
+    /// Developer product icon size (default: `size`)
+    pub developer_product: Option<u32>,
+    /// Badge icon size (default: 150)
+    pub badge: Option<u32>,
+}
+
+/// Icon generated at sync time for each resource that names it in
+/// `icon_template`, from an SVG or a base image with text drawn on it
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct IconTemplateConfig {
+    /// SVG file (relative to `assets_dir`) whose `{{name}}`-style
+    /// placeholders are filled in before it's rasterized
+    pub svg: Option<String>,
+    /// Image (relative to `assets_dir`) to draw `text` on
+    pub base: Option<String>,
+    /// Lines of text drawn on the image
+    #[serde(default)]
+    pub text: Vec<TextOverlayConfig>,
+    /// Side of the square output in pixels (default: 512)
+    #[serde(default = "default_icon_size")]
+    pub size: u32,
+    /// Command rasterizing `svg`, with `{input}`, `{output}` and `{size}`
+    /// placeholders (default: resvg)
+    pub svg_renderer: Option<String>,
+}
+
+/// A line of text drawn on a templated icon
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct TextOverlayConfig {
+    /// Text with `{{name}}`-style placeholders, e.g. "{{price}} R$"
+    pub text: String,
+    /// Center of the line in pixels from the top left (default: the middle)
+    pub x: Option<u32>,
+    pub y: Option<u32>,
+    /// Height of the text in pixels (default: 48)
+    #[serde(default = "default_text_size")]
+    pub size: u32,
+    /// `#rrggbb` or `#rrggbbaa` (default: white)
+    #[serde(default = "default_text_color")]
+    pub color: String,
+}
+
+fn default_text_size() -> u32 {
+    48
+}
+
+fn default_text_color() -> String {
+    "#ffffff".to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_icon_size() -> u32 {
+    512
+}
+
+fn default_max_file_size_kb() -> u64 {
+    20480
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct CreatorConfig {
     pub id: String,
@@ -160,6 +489,21 @@ pub struct UniverseConfig {
     pub max_players: Option<u32>,
     /// Private server cost: "disabled", 0 (free), or a positive number (Robux cost)
     pub private_server_cost: Option<PrivateServerCost>,
+    /// Paid access price in Robux; 0 turns paid access off
+    pub paid_access: Option<u32>,
+    pub avatar_type: Option<AvatarType>,
+    pub avatar_animation: Option<AvatarAnimation>,
+    pub avatar_collision: Option<AvatarCollision>,
+    /// Allowed avatar scale ranges
+    pub avatar_scales: Option<AvatarScales>,
+    /// Gear types players may bring in; an empty list allows none
+    pub allowed_gear: Option<Vec<GearType>>,
+    /// Let Studio access API services (DataStores, etc.)
+    pub studio_api_access: Option<bool>,
+    /// Enable spatial voice chat
+    pub voice_chat: Option<bool>,
+    /// Whether the experience is public; `false` takes it private
+    pub active: Option<bool>,
 }
 
 impl UniverseConfig {
@@ -171,49 +515,828 @@ impl UniverseConfig {
             || self.playable_devices.is_some() 
             || self.max_players.is_some()
             || self.private_server_cost.is_some()
+            || !self.extended_settings().is_empty()
+    }
+
+    /// Settings beyond the ones `UniverseState` tracks by name, each with its
+    /// value as stored in the lock file and the develop API request body
+    /// fields it maps to. `voice_chat` and `active` have no body fields;
+    /// they are set through their own endpoints.
+    pub fn extended_settings(&self) -> Vec<UniverseSetting> {
+        let mut settings = Vec::new();
+        let mut push = |field: &'static str, value: serde_json::Value, body: serde_json::Value| {
+            let body = match body {
+                serde_json::Value::Object(map) => map,
+                _ => serde_json::Map::new(),
+            };
+            settings.push(UniverseSetting { field, value, body });
+        };
+
+        if let Some(price) = self.paid_access {
+            push("paid_access", price.into(), serde_json::json!({ "isForSale": price > 0, "price": price }));
+        }
+        if let Some(avatar_type) = self.avatar_type {
+            push("avatar_type", serde_json::to_value(avatar_type).unwrap_or_default(), serde_json::json!({ "universeAvatarType": avatar_type.api_value() }));
+        }
+        if let Some(animation) = self.avatar_animation {
+            push("avatar_animation", serde_json::to_value(animation).unwrap_or_default(), serde_json::json!({ "universeAnimationType": animation.api_value() }));
+        }
+        if let Some(collision) = self.avatar_collision {
+            push("avatar_collision", serde_json::to_value(collision).unwrap_or_default(), serde_json::json!({ "universeCollisionType": collision.api_value() }));
+        }
+        if let Some(scales) = &self.avatar_scales {
+            let (mut min, mut max) = (serde_json::Map::new(), serde_json::Map::new());
+            for (_, key, range) in scales.ranges() {
+                if let Some(range) = range {
+                    min.insert(key.to_string(), range.min.into());
+                    max.insert(key.to_string(), range.max.into());
+                }
+            }
+            push(
+                "avatar_scales",
+                serde_json::to_value(scales).unwrap_or_default(),
+                serde_json::json!({ "universeScaleType": "AllScales", "universeAvatarMinScales": min, "universeAvatarMaxScales": max }),
+            );
+        }
+        if let Some(gear) = &self.allowed_gear {
+            let types: Vec<&str> = gear.iter().map(|g| g.api_value()).collect();
+            push("allowed_gear", serde_json::to_value(gear).unwrap_or_default(), serde_json::json!({ "allowedGearTypes": types }));
+        }
+        if let Some(allowed) = self.studio_api_access {
+            push("studio_api_access", allowed.into(), serde_json::json!({ "studioAccessToApisAllowed": allowed }));
+        }
+        if let Some(enabled) = self.voice_chat {
+            push("voice_chat", enabled.into(), serde_json::Value::Null);
+        }
+        if let Some(active) = self.active {
+            push("active", active.into(), serde_json::Value::Null);
+        }
+        settings
+    }
+}
+
+/// One universe setting as it is tracked and sent
+#[derive(Debug, Clone, PartialEq)]
+pub struct UniverseSetting {
+    /// Config field name
+    pub field: &'static str,
+    /// Value recorded in the lock file
+    pub value: serde_json::Value,
+    /// develop.roblox.com configuration request fields
+    pub body: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Avatar type players spawn as
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AvatarType {
+    R6,
+    R15,
+    PlayerChoice,
+}
+
+impl AvatarType {
+    pub fn api_value(&self) -> &'static str {
+        match self {
+            AvatarType::R6 => "MorphToR6",
+            AvatarType::R15 => "MorphToR15",
+            AvatarType::PlayerChoice => "PlayerChoice",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AvatarAnimation {
+    Standard,
+    PlayerChoice,
+}
+
+impl AvatarAnimation {
+    pub fn api_value(&self) -> &'static str {
+        match self {
+            AvatarAnimation::Standard => "Standard",
+            AvatarAnimation::PlayerChoice => "PlayerChoice",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AvatarCollision {
+    InnerBox,
+    OuterBox,
+}
+
+impl AvatarCollision {
+    pub fn api_value(&self) -> &'static str {
+        match self {
+            AvatarCollision::InnerBox => "InnerBox",
+            AvatarCollision::OuterBox => "OuterBox",
+        }
+    }
+}
+
+/// Allowed avatar scale ranges; unset scales keep their current range
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+pub struct AvatarScales {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub height: Option<ScaleRange>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width: Option<ScaleRange>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub head: Option<ScaleRange>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_type: Option<ScaleRange>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proportion: Option<ScaleRange>,
+}
+
+impl AvatarScales {
+    /// Each scale's config field, API key and range
+    pub fn ranges(&self) -> [(&'static str, &'static str, Option<ScaleRange>); 5] {
+        [
+            ("height", "height", self.height),
+            ("width", "width", self.width),
+            ("head", "head", self.head),
+            ("body_type", "bodyType", self.body_type),
+            ("proportion", "proportion", self.proportion),
+        ]
+    }
+
+    /// Bounds Roblox accepts for each scale, by config field
+    pub fn limits(field: &str) -> (f64, f64) {
+        match field {
+            "height" => (0.9, 1.05),
+            "width" => (0.7, 1.0),
+            "head" => (0.95, 1.0),
+            _ => (0.0, 1.0),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+pub struct ScaleRange {
+    pub min: f64,
+    pub max: f64,
+}
+
+/// Gear categories that can be allowed in a universe
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GearType {
+    Melee,
+    Ranged,
+    Explosive,
+    PowerUp,
+    Navigation,
+    Music,
+    Social,
+    Building,
+    Transport,
+}
+
+impl GearType {
+    pub fn api_value(&self) -> &'static str {
+        match self {
+            GearType::Melee => "MeleeWeapons",
+            GearType::Ranged => "RangedWeapons",
+            GearType::Explosive => "Explosives",
+            GearType::PowerUp => "PowerUps",
+            GearType::Navigation => "NavigationEnhancers",
+            GearType::Music => "MusicalInstruments",
+            GearType::Social => "SocialItems",
+            GearType::Building => "BuildingTools",
+            GearType::Transport => "Transport",
+        }
     }
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct GamePassConfig {
     pub name: String,
+    /// Existing Roblox ID to match by instead of name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<u64>,
+    /// Previous names used to match resources created under a different name
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
     pub description: Option<String>,
     pub price: Option<u32>,
     pub icon: Option<String>,
+    /// Name of an entry in `icon_templates` to generate the icon from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon_template: Option<String>,
     pub is_for_sale: Option<bool>,
+    /// Let Roblox adjust the price for each region
+    pub regional_pricing_enabled: Option<bool>,
+    /// Refuse price changes and taking it off sale unless `--allow-protected` is passed
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub protected: bool,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct DeveloperProductConfig {
     pub name: String,
+    /// Existing Roblox ID to match by instead of name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<u64>,
+    /// Previous names used to match resources created under a different name
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
     pub description: Option<String>,
     pub price: u32,
     pub icon: Option<String>,
+    /// Name of an entry in `icon_templates` to generate the icon from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon_template: Option<String>,
     pub is_active: Option<bool>,
+    /// Let Roblox adjust the price for each region
+    pub regional_pricing_enabled: Option<bool>,
+    /// Refuse price changes unless `--allow-protected` is passed
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub protected: bool,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct BadgeConfig {
     pub name: String,
+    /// Existing Roblox ID to match by instead of name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<u64>,
+    /// Previous names used to match resources created under a different name
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
     pub description: Option<String>,
     pub icon: Option<String>,
+    /// Name of an entry in `icon_templates` to generate the icon from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon_template: Option<String>,
     pub is_enabled: Option<bool>,
 }
 
+/// A user restriction (ban) from the universe or one of its places
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct RestrictionConfig {
+    pub user_id: u64,
+    /// Restrict only this place instead of the whole universe
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub place_id: Option<u64>,
+    /// `false` lifts the ban
+    #[serde(default = "default_true")]
+    pub active: bool,
+    /// Ban length in seconds; permanent when omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration: Option<u64>,
+    /// Reason shown to the user
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_reason: Option<String>,
+    /// Reason only visible to the experience's moderators
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub private_reason: Option<String>,
+    /// Also ban the user's alternate accounts (the API's
+    /// `excludeAltAccounts: false`)
+    #[serde(default)]
+    pub ban_alt_accounts: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct AssetConfig {
+    /// Unique name used to track the asset in state
+    pub name: String,
+    /// File path relative to `assets_dir`
+    pub path: String,
+    /// Asset type; inferred from the file extension when omitted
+    #[serde(rename = "type")]
+    pub asset_type: Option<AssetType>,
+    pub description: Option<String>,
+    /// Update the existing asset's content when the file changes instead of
+    /// uploading a new one, so its ID stays the same. Roblox only allows this
+    /// for some asset types, e.g. models.
+    #[serde(default)]
+    pub replace: bool,
+}
+
+/// Asset types supported by the Open Cloud Assets API
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AssetType {
+    Image,
+    Decal,
+    Audio,
+    Model,
+}
+
+impl AssetType {
+    /// Infer the asset type from a file extension
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_lowercase().as_str() {
+            "png" | "jpg" | "jpeg" | "bmp" | "tga" => Some(AssetType::Image),
+            "mp3" | "ogg" | "wav" | "flac" => Some(AssetType::Audio),
+            "fbx" | "obj" | "gltf" | "glb" | "rbxm" | "rbxmx" => Some(AssetType::Model),
+            _ => None,
+        }
+    }
+
+    /// Name used by the Assets API `assetType` field
+    pub fn api_name(&self) -> &'static str {
+        match self {
+            AssetType::Image => "Image",
+            AssetType::Decal => "Decal",
+            AssetType::Audio => "Audio",
+            AssetType::Model => "Model",
+        }
+    }
+
+    /// MIME type for a file of this asset type, if the extension is supported
+    pub fn content_type(&self, extension: &str) -> Option<&'static str> {
+        let ext = extension.to_lowercase();
+        match (self, ext.as_str()) {
+            (AssetType::Image | AssetType::Decal, "png") => Some("image/png"),
+            (AssetType::Image | AssetType::Decal, "jpg" | "jpeg") => Some("image/jpeg"),
+            (AssetType::Image | AssetType::Decal, "bmp") => Some("image/bmp"),
+            (AssetType::Image | AssetType::Decal, "tga") => Some("image/tga"),
+            (AssetType::Audio, "mp3") => Some("audio/mpeg"),
+            (AssetType::Audio, "ogg") => Some("audio/ogg"),
+            (AssetType::Audio, "wav") => Some("audio/wav"),
+            (AssetType::Audio, "flac") => Some("audio/flac"),
+            (AssetType::Model, "fbx") => Some("model/fbx"),
+            (AssetType::Model, "obj") => Some("model/obj"),
+            (AssetType::Model, "gltf") => Some("model/gltf+json"),
+            (AssetType::Model, "glb") => Some("model/gltf-binary"),
+            (AssetType::Model, "rbxm") => Some("model/x-rbxm"),
+            (AssetType::Model, "rbxmx") => Some("model/x-rbxm"),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct PlaceConfig {
     pub place_id: u64,
     pub file_path: String,
     #[serde(default)]
     pub publish: bool,
+    /// Restart the universe's servers once this place has been published
+    #[serde(default)]
+    pub restart_after_publish: bool,
+    /// QA place the file is published to first. `place_id` is then only
+    /// published by `publish --promote`, after confirmation, with the same file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub test_place_id: Option<u64>,
+    /// Universe of `test_place_id` (default: the configured universe)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub test_universe_id: Option<u64>,
+}
+
+/// File formats the config can be written in, picked by extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    Yaml,
+    Toml,
+    Json,
+}
+
+impl ConfigFormat {
+    /// `.toml` and `.json` files use those formats; anything else is YAML
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()).map(|e| e.to_ascii_lowercase()).as_deref() {
+            Some("toml") => ConfigFormat::Toml,
+            Some("json") => ConfigFormat::Json,
+            _ => ConfigFormat::Yaml,
+        }
+    }
+
+    /// Deserialize `content`, passing the dotted path of every key the target
+    /// type doesn't know to `on_unknown`
+    fn deserialize<T: DeserializeOwned>(&self, content: &str, mut on_unknown: impl FnMut(String)) -> Result<T> {
+        let mut callback = |path: serde_ignored::Path| on_unknown(path.to_string());
+        let value = match self {
+            ConfigFormat::Yaml => serde_ignored::deserialize(serde_yaml::Deserializer::from_str(content), &mut callback)?,
+            ConfigFormat::Toml => serde_ignored::deserialize(toml::Deserializer::new(content), &mut callback)?,
+            ConfigFormat::Json => serde_ignored::deserialize(&mut serde_json::Deserializer::from_str(content), &mut callback)?,
+        };
+        Ok(value)
+    }
+
+    /// Parse into a generic document, e.g. for `pull` to edit in place
+    pub fn parse_document(&self, content: &str) -> Result<serde_yaml::Value> {
+        self.deserialize(content, |_| {})
+    }
+
+    /// Serialize in this format
+    pub fn to_string<T: Serialize>(&self, value: &T) -> Result<String> {
+        Ok(match self {
+            ConfigFormat::Yaml => serde_yaml::to_string(value)?,
+            ConfigFormat::Toml => toml::to_string_pretty(value)?,
+            ConfigFormat::Json => serde_json::to_string_pretty(value)? + "\n",
+        })
+    }
 }
 
 impl RblxSyncConfig {
+    /// Load a YAML, TOML or JSON config, picking the format by extension
     pub fn load(path: &Path) -> Result<Self> {
+        Self::load_with(path, selected_universe(), |_| {})
+    }
+
+    /// Load the config as `--universe <name>` would, whatever was selected
+    pub fn load_universe(path: &Path, name: &str) -> Result<Self> {
+        Self::load_with(path, Some(name), |_| {})
+    }
+
+    /// Load like `load`, also returning the dotted paths of keys the config
+    /// schema doesn't know (usually typos), which `load` silently ignores
+    pub fn load_strict(path: &Path) -> Result<(Self, Vec<String>)> {
+        let mut unknown = Vec::new();
+        let config = Self::load_with(path, selected_universe(), |key| unknown.push(key))?;
+        Ok((config, unknown))
+    }
+
+    fn load_with(path: &Path, selected: Option<&str>, mut on_unknown: impl FnMut(String)) -> Result<Self> {
         let content = fs::read_to_string(path)
             .with_context(|| format!("Failed to read config file at {:?}", path))?;
-        let config: RblxSyncConfig = serde_yaml::from_str(&content)
-            .context("Failed to parse config file")?;
-        Ok(config)
+        let format = ConfigFormat::from_path(path);
+        let doc = format.parse_document(&content).context("Failed to parse config file")?;
+        let user = load_user_config()?;
+        if doc.get("include").is_none() && doc.get("universes").is_none() && selected.is_none() && user.is_none() {
+            // Parse the text directly so errors keep their line numbers
+            return format.deserialize(&content, on_unknown).context("Failed to parse config file");
+        }
+
+        let root = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        let merged = resolve_includes(path, doc, &mut vec![root])?;
+        let merged = apply_universe(merged, selected)?;
+        let merged = with_user_defaults(merged, user);
+        let mut callback = |key: serde_ignored::Path| on_unknown(key.to_string());
+        serde_ignored::deserialize(merged, &mut callback).context("Failed to parse config file")
+    }
+}
+
+// --- Discovery ---
+
+/// Config file names looked for, in order of preference
+pub const CONFIG_FILE_NAMES: [&str; 4] = ["rblxsync.yml", "rblxsync.yaml", "rblxsync.toml", "rblxsync.json"];
+
+/// Find the project config in `start` or the nearest parent directory that
+/// has one, as Cargo does for `Cargo.toml`. A config in `start` itself is
+/// returned as a bare file name.
+pub fn discover_config(start: &Path) -> Option<PathBuf> {
+    for dir in start.ancestors() {
+        if let Some(name) = CONFIG_FILE_NAMES.iter().find(|name| dir.join(name).is_file()) {
+            return Some(if dir == start { PathBuf::from(name) } else { dir.join(name) });
+        }
+    }
+    None
+}
+
+// --- User Config ---
+
+/// Top-level keys a user config may set: personal defaults and credential
+/// settings. Resources, hooks and the like stay in the project config, so
+/// the user config can't add them to every project.
+const USER_CONFIG_KEYS: [&str; 8] = [
+    "creator",
+    "badge_payment_source",
+    "image_processing",
+    "compare_remote_icons",
+    "naming",
+    "max_price_change_percent",
+    "text_check",
+    "api_key_command",
+];
+
+/// The user's config directory: `$XDG_CONFIG_HOME`, `~/.config`, or
+/// `%APPDATA%` on Windows
+pub fn user_config_dir() -> Option<PathBuf> {
+    env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .or_else(|| env::var_os("APPDATA").map(PathBuf::from))
+}
+
+/// The user's cache directory: `$XDG_CACHE_HOME`, `~/.cache`, or
+/// `%LOCALAPPDATA%` on Windows
+pub fn user_cache_dir() -> Option<PathBuf> {
+    env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+        .or_else(|| env::var_os("LOCALAPPDATA").map(PathBuf::from))
+}
+
+/// Path of the user-level config: `RBLXSYNC_USER_CONFIG`, or
+/// `rblxsync/config.toml` in the user's config directory
+pub fn user_config_path() -> Option<PathBuf> {
+    match env::var_os("RBLXSYNC_USER_CONFIG") {
+        Some(path) => Some(PathBuf::from(path)),
+        None => Some(user_config_dir()?.join("rblxsync").join("config.toml")),
+    }
+}
+
+/// The user-level config, whose settings are defaults every project config
+/// overrides. None when the file doesn't exist.
+pub fn load_user_config() -> Result<Option<serde_yaml::Value>> {
+    let Some(path) = user_config_path().filter(|p| p.exists()) else { return Ok(None) };
+    read_user_config(&path).map(Some)
+}
+
+fn read_user_config(path: &Path) -> Result<serde_yaml::Value> {
+    let content = fs::read_to_string(path).with_context(|| format!("Failed to read user config {}", path.display()))?;
+    let doc = ConfigFormat::from_path(path)
+        .parse_document(&content)
+        .with_context(|| format!("Failed to parse user config {}", path.display()))?;
+    check_user_config(&doc).with_context(|| format!("Invalid user config {}", path.display()))?;
+    Ok(doc)
+}
+
+fn check_user_config(doc: &serde_yaml::Value) -> Result<()> {
+    let Some(mapping) = doc.as_mapping() else { bail!("expected a table of settings") };
+    for key in mapping.keys() {
+        let key = key.as_str().unwrap_or_default();
+        if !USER_CONFIG_KEYS.contains(&key) {
+            bail!("`{}` can only be set in a project config; the user config takes {}", key, USER_CONFIG_KEYS.join(", "));
+        }
+    }
+    Ok(())
+}
+
+/// Layer a project config over the user config's defaults
+fn with_user_defaults(project: serde_yaml::Value, user: Option<serde_yaml::Value>) -> serde_yaml::Value {
+    match user {
+        Some(mut merged) => {
+            merge_value(&mut merged, project, None);
+            merged
+        }
+        None => project,
+    }
+}
+
+// --- Named Universes ---
+
+static SELECTED_UNIVERSE: OnceLock<Option<String>> = OnceLock::new();
+
+/// Pick the entry of `universes` that configs and lock files are loaded for
+/// (`--universe`). Call once at startup, before anything is loaded.
+pub fn select_universe(name: Option<String>) {
+    let _ = SELECTED_UNIVERSE.set(name);
+}
+
+/// Name of the universe picked with `--universe`, if any
+pub fn selected_universe() -> Option<&'static str> {
+    SELECTED_UNIVERSE.get().and_then(|name| name.as_deref())
+}
+
+/// Merge the `universes.<selected>` overlay onto the rest of the config and
+/// drop the `universes` key. Without a selection the top-level `universe` is
+/// used, which must then exist.
+fn apply_universe(mut doc: serde_yaml::Value, selected: Option<&str>) -> Result<serde_yaml::Value> {
+    let universes = doc.as_mapping_mut().and_then(|m| m.remove("universes"));
+    let Some(serde_yaml::Value::Mapping(mut universes)) = universes else {
+        if universes.is_some() {
+            bail!("universes must map names to universe configs");
+        }
+        if let Some(name) = selected {
+            bail!("--universe {} was given but the config declares no universes", name);
+        }
+        return Ok(doc);
+    };
+    let names: Vec<String> = universes.keys().filter_map(|k| k.as_str().map(str::to_string)).collect();
+    if let Some(bad) = names.iter().find(|n| n.is_empty() || !n.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')) {
+        bail!("Invalid universe name '{}': use letters, digits, '-' and '_'", bad);
+    }
+
+    match selected {
+        Some(name) => {
+            let overlay = universes
+                .remove(name)
+                .ok_or_else(|| anyhow!("Unknown universe '{}'; the config declares: {}", name, names.join(", ")))?;
+            merge_value(&mut doc, overlay, None);
+        }
+        None if doc.get("universe").is_none() => {
+            bail!("The config declares universes {}; pick one with --universe", names.join(", "));
+        }
+        None => {}
+    }
+    Ok(doc)
+}
+
+// --- Includes ---
+
+/// Merge the files listed under `doc`'s `include` key (relative to `path`),
+/// in order, then `doc` itself on top. `stack` holds the files being
+/// included, to detect cycles.
+///
+/// Mappings merge key by key. Resource lists merge by `name`
+/// (case-insensitive), or `place_id` for `places`, so an overlay can change
+/// one field of a shared game pass; entries only in the overlay are
+/// appended. Any other value in the overlay replaces the base value.
+fn resolve_includes(path: &Path, mut doc: serde_yaml::Value, stack: &mut Vec<PathBuf>) -> Result<serde_yaml::Value> {
+    let includes = match doc.as_mapping_mut().and_then(|m| m.remove("include")) {
+        None => return Ok(doc),
+        Some(serde_yaml::Value::String(file)) => vec![file],
+        Some(serde_yaml::Value::Sequence(files)) => files
+            .into_iter()
+            .map(|f| f.as_str().map(str::to_string).ok_or_else(|| anyhow!("{}: include entries must be file paths", path.display())))
+            .collect::<Result<_>>()?,
+        Some(_) => bail!("{}: include must be a file path or a list of file paths", path.display()),
+    };
+
+    let dir = path.parent().unwrap_or(Path::new(""));
+    let mut merged = serde_yaml::Value::Mapping(serde_yaml::Mapping::new());
+    for file in includes {
+        let included_path = dir.join(&file);
+        let canonical = included_path
+            .canonicalize()
+            .with_context(|| format!("Failed to read config {} included from {}", included_path.display(), path.display()))?;
+        if stack.contains(&canonical) {
+            let chain: Vec<String> = stack.iter().chain(std::iter::once(&canonical)).map(|p| p.display().to_string()).collect();
+            bail!("Config include cycle: {}", chain.join(" -> "));
+        }
+        let content = fs::read_to_string(&included_path)?;
+        let included = ConfigFormat::from_path(&included_path)
+            .parse_document(&content)
+            .with_context(|| format!("Failed to parse included config {}", included_path.display()))?;
+        stack.push(canonical);
+        let included = resolve_includes(&included_path, included, stack)?;
+        stack.pop();
+        merge_value(&mut merged, included, None);
+    }
+    merge_value(&mut merged, doc, None);
+    Ok(merged)
+}
+
+/// Field that identifies entries of a mergeable resource list
+fn list_identity(key: &str) -> Option<&'static str> {
+    match key {
+        "game_passes" | "developer_products" | "badges" | "assets" => Some("name"),
+        "places" => Some("place_id"),
+        _ => None,
+    }
+}
+
+fn merge_value(base: &mut serde_yaml::Value, overlay: serde_yaml::Value, key: Option<&str>) {
+    use serde_yaml::Value;
+    match (base, overlay, key.and_then(list_identity)) {
+        (Value::Mapping(base), Value::Mapping(overlay), _) => {
+            for (k, v) in overlay {
+                match base.get_mut(&k) {
+                    Some(existing) => merge_value(existing, v, k.as_str()),
+                    None => {
+                        base.insert(k, v);
+                    }
+                }
+            }
+        }
+        (Value::Sequence(base), Value::Sequence(overlay), Some(id_field)) => {
+            let identity = |entry: &Value| match &entry[id_field] {
+                Value::String(s) => Some(s.to_lowercase()),
+                Value::Number(n) => Some(n.to_string()),
+                _ => None,
+            };
+            for entry in overlay {
+                let id = identity(&entry);
+                match base.iter_mut().find(|existing| id.is_some() && identity(existing) == id) {
+                    Some(existing) => merge_value(existing, entry, None),
+                    None => base.push(entry),
+                }
+            }
+        }
+        (base, overlay, _) => *base = overlay,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_formats() {
+        assert_eq!(ConfigFormat::from_path(Path::new("rblxsync.TOML")), ConfigFormat::Toml);
+        assert_eq!(ConfigFormat::from_path(Path::new("config/rblxsync.json")), ConfigFormat::Json);
+        assert_eq!(ConfigFormat::from_path(Path::new("rblxsync.yml")), ConfigFormat::Yaml);
+
+        let toml = "[universe]\nid = 1\n\n[[game_passes]]\nname = \"VIP\"\nprice = 100\ncolour = \"red\"\n";
+        let mut unknown = Vec::new();
+        let config: RblxSyncConfig = ConfigFormat::Toml.deserialize(toml, |key| unknown.push(key)).unwrap();
+        assert_eq!(config.game_passes[0].price, Some(100));
+        assert_eq!(unknown, vec!["game_passes.0.colour".to_string()]);
+
+        let json = ConfigFormat::Json.to_string(&ConfigFormat::Toml.parse_document(toml).unwrap()).unwrap();
+        let config: RblxSyncConfig = ConfigFormat::Json.deserialize(&json, |_| {}).unwrap();
+        assert_eq!((config.universe.id, config.game_passes[0].name.as_str()), (1, "VIP"));
+    }
+
+    #[test]
+    fn test_universe_extended_settings() {
+        let yaml = "id: 1\npaid_access: 0\navatar_type: r15\navatar_scales:\n  body_type: { min: 0, max: 0.5 }\nallowed_gear: [melee, power_up]\nvoice_chat: true\nactive: false\n";
+        let universe: UniverseConfig = serde_yaml::from_str(yaml).unwrap();
+        assert!(universe.has_settings());
+
+        let settings = universe.extended_settings();
+        let fields: Vec<&str> = settings.iter().map(|s| s.field).collect();
+        assert_eq!(fields, vec!["paid_access", "avatar_type", "avatar_scales", "allowed_gear", "voice_chat", "active"]);
+        assert_eq!(settings[0].body["isForSale"], false);
+        assert_eq!(settings[1].body["universeAvatarType"], "MorphToR15");
+        assert_eq!(settings[2].body["universeAvatarMaxScales"], serde_json::json!({ "bodyType": 0.5 }));
+        assert_eq!(settings[3].body["allowedGearTypes"], serde_json::json!(["MeleeWeapons", "PowerUps"]));
+        assert!(settings[4].body.is_empty());
+    }
+
+    /// Point HOME and the config/cache overrides at an empty temp directory,
+    /// so tests never read the developer's own user config or credentials
+    fn temp_home() {
+        static HOME: std::sync::Once = std::sync::Once::new();
+        HOME.call_once(|| {
+            let home = std::env::temp_dir().join(format!("rblxsync-home-{}", std::process::id()));
+            fs::create_dir_all(&home).unwrap();
+            std::env::set_var("HOME", &home);
+            for var in ["XDG_CONFIG_HOME", "XDG_CACHE_HOME", "APPDATA", "LOCALAPPDATA", "RBLXSYNC_USER_CONFIG", "RBLXSYNC_CREDENTIALS"] {
+                std::env::remove_var(var);
+            }
+        });
+    }
+
+    #[test]
+    fn test_includes() {
+        temp_home();
+        let dir = std::env::temp_dir().join(format!("rblxsync-include-{}", std::process::id()));
+        fs::create_dir_all(dir.join("shared")).unwrap();
+        fs::write(dir.join("shared/base.yml"), "universe: { id: 1, max_players: 10 }\ngame_passes:\n  - { name: VIP, price: 100, icon: vip.png }\n  - { name: Radio, price: 50 }\n").unwrap();
+        fs::write(dir.join("shared/prices.toml"), "[[game_passes]]\nname = \"vip\"\nprice = 150\n").unwrap();
+        fs::write(dir.join("game.yml"), "include: [shared/base.yml, shared/prices.toml]\nuniverse: { id: 2 }\ngame_passes:\n  - { name: Sword, price: 25 }\n").unwrap();
+
+        let config = RblxSyncConfig::load(&dir.join("game.yml")).unwrap();
+        assert_eq!((config.universe.id, config.universe.max_players), (2, Some(10)));
+        let passes: Vec<(&str, Option<u32>, Option<&str>)> = config.game_passes.iter().map(|p| (p.name.as_str(), p.price, p.icon.as_deref())).collect();
+        assert_eq!(passes, vec![("vip", Some(150), Some("vip.png")), ("Radio", Some(50), None), ("Sword", Some(25), None)]);
+
+        fs::write(dir.join("shared/base.yml"), "include: ../game.yml\n").unwrap();
+        let err = RblxSyncConfig::load(&dir.join("game.yml")).unwrap_err();
+        assert!(format!("{:#}", err).contains("Config include cycle"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_apply_universe() {
+        let doc: serde_yaml::Value = serde_yaml::from_str(
+            "game_passes: [{ name: VIP, price: 100 }]\nuniverses:\n  main: { universe: { id: 1 } }\n  test:\n    universe: { id: 2 }\n    game_passes: [{ name: VIP, price: 1 }]\n",
+        )
+        .unwrap();
+
+        let config: RblxSyncConfig = serde_yaml::from_value(apply_universe(doc.clone(), Some("test")).unwrap()).unwrap();
+        assert_eq!((config.universe.id, config.game_passes[0].price), (2, Some(1)));
+        let config: RblxSyncConfig = serde_yaml::from_value(apply_universe(doc.clone(), Some("main")).unwrap()).unwrap();
+        assert_eq!((config.universe.id, config.game_passes[0].price), (1, Some(100)));
+
+        assert!(apply_universe(doc.clone(), Some("staging")).unwrap_err().to_string().contains("main, test"));
+        assert!(apply_universe(doc, None).unwrap_err().to_string().contains("--universe"));
+    }
+
+    #[test]
+    fn test_user_defaults() {
+        let user: serde_yaml::Value =
+            toml::from_str("badge_payment_source = \"group\"\n\n[creator]\nid = \"5\"\ntype = \"group\"\n").unwrap();
+        check_user_config(&user).unwrap();
+        let project: serde_yaml::Value = serde_yaml::from_str("universe: { id: 1 }\ncreator: { id: \"9\", type: user }\n").unwrap();
+
+        let config: RblxSyncConfig = serde_yaml::from_value(with_user_defaults(project, Some(user))).unwrap();
+        assert_eq!(config.badge_payment_source.as_deref(), Some("group"));
+        let creator = config.creator.unwrap();
+        assert_eq!((creator.id.as_str(), creator.creator_type.as_str()), ("9", "user"));
+
+        for bad in ["universe: { id: 1 }\n", "hooks: { pre_sync: [\"curl x\"] }\n", "game_passes: [{ name: VIP }]\n"] {
+            let bad: serde_yaml::Value = serde_yaml::from_str(bad).unwrap();
+            assert!(check_user_config(&bad).unwrap_err().to_string().contains("can only be set in a project config"));
+        }
+    }
+
+    #[test]
+    fn test_load_user_config() {
+        temp_home();
+        assert!(load_user_config().unwrap().is_none());
+
+        let path = std::env::var_os("HOME").map(PathBuf::from).unwrap().join("user-config.toml");
+        fs::write(&path, "badge_payment_source = \"group\"\n").unwrap();
+        assert_eq!(read_user_config(&path).unwrap()["badge_payment_source"].as_str(), Some("group"));
+        fs::write(&path, "[[restrictions]]\nname = \"x\"\n").unwrap();
+        assert!(format!("{:#}", read_user_config(&path).unwrap_err()).contains("`restrictions` can only be set"));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_discover_config() {
+        let root = std::env::temp_dir().join(format!("rblxsync-discover-{}", std::process::id()));
+        let nested = root.join("src").join("shared");
+        fs::create_dir_all(&nested).unwrap();
+        assert_eq!(discover_config(&nested).filter(|p| p.starts_with(&root)), None);
+
+        fs::write(root.join("rblxsync.toml"), "").unwrap();
+        assert_eq!(discover_config(&nested), Some(root.join("rblxsync.toml")));
+        assert_eq!(discover_config(&root), Some(PathBuf::from("rblxsync.toml")));
+        fs::write(root.join("rblxsync.yml"), "").unwrap();
+        assert_eq!(discover_config(&nested), Some(root.join("rblxsync.yml")));
+
+        fs::remove_dir_all(&root).unwrap();
     }
 }
@@ -0,0 +1,180 @@
+//! API keys stored outside the project, by named profile.
+//!
+//! `rblxsync login` saves a key under a profile in the OS keychain (macOS
+//! Keychain, Windows Credential Manager or the Secret Service on Linux).
+//! Profiles can also be declared in a global `credentials.toml`, each with
+//! an optional key and default universe, for people working across several
+//! groups. `Config::from_env` falls back to the selected profile when
+//! `ROBLOX_API_KEY` isn't set, so keys don't have to sit in `.env` files.
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+
+/// Keychain service name entries are stored under
+const SERVICE: &str = "rblxsync";
+
+/// Profile used when `--profile` isn't given
+pub const DEFAULT_PROFILE: &str = "default";
+
+static SELECTED_PROFILE: OnceLock<Option<String>> = OnceLock::new();
+
+/// Pick the credential profile (`--profile`). Call once at startup, before
+/// credentials are loaded.
+pub fn select_profile(name: Option<String>) {
+    let _ = SELECTED_PROFILE.set(name);
+}
+
+/// Name of the selected profile, or `default`
+pub fn selected_profile() -> &'static str {
+    SELECTED_PROFILE.get().and_then(|name| name.as_deref()).unwrap_or(DEFAULT_PROFILE)
+}
+
+/// A profile declared in `credentials.toml`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    /// API key; when absent, the key saved with `login` is used
+    pub api_key: Option<String>,
+    /// Universe used when neither `--universe-id` nor a config gives one
+    pub universe_id: Option<u64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CredentialsFile {
+    #[serde(default)]
+    profiles: BTreeMap<String, Profile>,
+}
+
+/// Path of the global credentials file: `RBLXSYNC_CREDENTIALS`, or
+/// `rblxsync/credentials.toml` in the user's config directory
+/// (`$XDG_CONFIG_HOME`, `~/.config`, or `%APPDATA%` on Windows)
+pub fn credentials_path() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os("RBLXSYNC_CREDENTIALS") {
+        return Some(PathBuf::from(path));
+    }
+    Some(crate::config::user_config_dir()?.join("rblxsync").join("credentials.toml"))
+}
+
+fn parse_credentials(content: &str) -> Result<CredentialsFile> {
+    Ok(toml::from_str(content)?)
+}
+
+/// `profile` from the credentials file, or None when the file doesn't
+/// exist or doesn't declare it
+pub fn load_profile(profile: &str) -> Result<Option<Profile>> {
+    let Some(path) = credentials_path().filter(|p| p.exists()) else { return Ok(None) };
+    let content = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let mut file = parse_credentials(&content).with_context(|| format!("Failed to parse {}", path.display()))?;
+    Ok(file.profiles.remove(profile))
+}
+
+/// API key for `profile`: from the credentials file, else the keychain
+pub fn profile_api_key(profile: &str) -> Result<Option<String>> {
+    if let Some(key) = load_profile(profile)?.and_then(|p| p.api_key) {
+        return Ok(Some(key));
+    }
+    Ok(load_api_key(profile))
+}
+
+/// Run `command` through the shell and return its trimmed stdout as the API
+/// key. Its stderr goes to the terminal so secret managers can prompt. The
+/// key is reused for the rest of the process, so a prompting secret manager
+/// asks once.
+pub fn command_api_key(command: &str) -> Result<String> {
+    static KEYS: Mutex<BTreeMap<String, String>> = Mutex::new(BTreeMap::new());
+    if let Some(key) = KEYS.lock().unwrap().get(command) {
+        return Ok(key.clone());
+    }
+    let output = crate::hooks::shell(command)
+        .stdin(std::process::Stdio::inherit())
+        .stderr(std::process::Stdio::inherit())
+        .output()
+        .with_context(|| format!("Failed to run api_key_command `{}`", command))?;
+    if !output.status.success() {
+        bail!("api_key_command `{}` failed ({})", command, output.status);
+    }
+    let key = String::from_utf8(output.stdout).context("api_key_command printed invalid UTF-8")?.trim().to_string();
+    if key.is_empty() {
+        bail!("api_key_command `{}` printed nothing", command);
+    }
+    KEYS.lock().unwrap().insert(command.to_string(), key.clone());
+    Ok(key)
+}
+
+/// Short identifier of `api_key` for logs and the audit log: the first 12
+/// hex digits of its SHA-256, which don't reveal the key
+pub fn fingerprint(api_key: &str) -> String {
+    let mut hash = crate::assets::hash_bytes(api_key.as_bytes());
+    hash.truncate(12);
+    hash
+}
+
+fn entry(profile: &str) -> Result<keyring::Entry> {
+    keyring::Entry::new(SERVICE, profile).context("OS keychain is unavailable")
+}
+
+/// Run a keychain call on its own thread. The Linux backend blocks on its
+/// own async runtime, which panics inside the tokio runtime rblxsync runs in.
+fn off_runtime<T: Send>(f: impl FnOnce() -> T + Send) -> T {
+    std::thread::scope(|scope| scope.spawn(f).join().expect("keychain thread panicked"))
+}
+
+/// Save `api_key` in the keychain under `profile`, replacing any earlier key
+pub fn store_api_key(profile: &str, api_key: &str) -> Result<()> {
+    off_runtime(|| entry(profile)?.set_password(api_key).map_err(anyhow::Error::from))
+        .with_context(|| format!("Failed to save API key for profile '{}' in the OS keychain", profile))
+}
+
+/// The key saved under `profile`, or None when there is none (or no
+/// keychain to ask)
+pub fn load_api_key(profile: &str) -> Option<String> {
+    match off_runtime(|| entry(profile).ok().map(|e| e.get_password()))? {
+        Ok(key) => Some(key),
+        Err(keyring::Error::NoEntry) => None,
+        Err(e) => {
+            tracing::debug!("Could not read API key for profile '{}' from the OS keychain: {}", profile, e);
+            None
+        }
+    }
+}
+
+/// Remove the key saved under `profile`, returning whether there was one
+pub fn delete_api_key(profile: &str) -> Result<bool> {
+    match off_runtime(|| entry(profile).map(|e| e.delete_credential()))? {
+        Ok(()) => Ok(true),
+        Err(keyring::Error::NoEntry) => Ok(false),
+        Err(e) => Err(e).with_context(|| format!("Failed to remove API key for profile '{}' from the OS keychain", profile)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_credentials() {
+        let file = parse_credentials(
+            "[profiles.studio-a]\napi_key = \"key-a\"\nuniverse_id = 42\n\n[profiles.studio-b]\nuniverse_id = 7\n",
+        )
+        .unwrap();
+        assert_eq!(file.profiles["studio-a"].api_key.as_deref(), Some("key-a"));
+        assert_eq!(file.profiles["studio-a"].universe_id, Some(42));
+        assert!(file.profiles["studio-b"].api_key.is_none());
+
+        assert!(parse_credentials("[profiles.a]\napi_kye = \"typo\"\n").is_err());
+        assert!(parse_credentials("").unwrap().profiles.is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_command_api_key() {
+        assert_eq!(command_api_key("echo '  secret-key  '").unwrap(), "secret-key");
+        assert!(command_api_key("exit 3").unwrap_err().to_string().contains("failed"));
+        assert!(command_api_key("true").unwrap_err().to_string().contains("printed nothing"));
+    }
+}
@@ -0,0 +1,135 @@
+//! Whole-data-store dumps for `datastore export` and `datastore import`.
+//!
+//! A dump is a JSON Lines file with one `{"key": ..., "value": ...}` object
+//! per entry. Exports page through every key and append entries as they are
+//! read, checkpointing the next page token to `<out>.progress.json`, so an
+//! interrupted export of a large data store continues with `--resume`.
+
+use crate::api::RobloxClient;
+use anyhow::{anyhow, Context, Result};
+use tracing::info;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+/// One line of a dump
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DumpEntry {
+    pub key: String,
+    pub value: serde_json::Value,
+}
+
+/// Checkpoint of an export in progress
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ExportProgress {
+    universe_id: u64,
+    datastore: String,
+    scope: Option<String>,
+    /// Token of the next page to read
+    page_token: Option<String>,
+    /// Entries written so far
+    count: u64,
+    /// Length of the dump at the last checkpoint
+    bytes: u64,
+}
+
+fn progress_path(out: &Path) -> PathBuf {
+    let mut name = out.as_os_str().to_owned();
+    name.push(".progress.json");
+    PathBuf::from(name)
+}
+
+/// Write every entry of `datastore` to `out`, returning how many were
+/// written. With `resume`, a checkpoint for the same data store continues
+/// where it stopped; otherwise `out` is started over.
+pub async fn export(client: &RobloxClient, universe_id: u64, datastore: &str, scope: Option<&str>, out: &Path, resume: bool) -> Result<u64> {
+    let checkpoint = progress_path(out);
+    let mut progress = if resume && checkpoint.exists() {
+        let progress: ExportProgress = serde_json::from_str(&fs::read_to_string(&checkpoint)?).context("Failed to read export checkpoint")?;
+        if progress.universe_id != universe_id || progress.datastore != datastore || progress.scope.as_deref() != scope {
+            return Err(anyhow!(
+                "{} is for data store '{}' in universe {}; rerun without --resume",
+                checkpoint.display(),
+                progress.datastore,
+                progress.universe_id
+            ));
+        }
+        info!("Resuming export after {} entries", progress.count);
+        progress
+    } else {
+        ExportProgress { universe_id, datastore: datastore.to_string(), scope: scope.map(|s| s.to_string()), ..Default::default() }
+    };
+
+    if let Some(parent) = out.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    // Drop anything written after the last checkpoint
+    let mut file = OpenOptions::new().create(true).append(true).open(out)?;
+    file.set_len(progress.bytes)?;
+
+    loop {
+        let page = client
+            .list_datastore_entries(universe_id, datastore, scope, None, progress.page_token.clone())
+            .await
+            .context("Failed to list entries; rerun with --resume to continue")?;
+        for listed in &page.data {
+            let Some(key) = listed["id"].as_str() else { continue };
+            // Keys deleted since the page was listed are skipped
+            let Some(mut entry) = client.get_datastore_entry(universe_id, datastore, scope, key).await? else { continue };
+            let line = DumpEntry { key: key.to_string(), value: entry["value"].take() };
+            writeln!(file, "{}", serde_json::to_string(&line)?)?;
+            progress.count += 1;
+        }
+        file.flush()?;
+
+        progress.bytes = file.metadata()?.len();
+        progress.page_token = page.next_page_cursor.filter(|t| !t.is_empty());
+        if progress.page_token.is_none() {
+            if checkpoint.exists() {
+                fs::remove_file(&checkpoint)?;
+            }
+            return Ok(progress.count);
+        }
+        let tmp = checkpoint.with_extension("tmp");
+        fs::write(&tmp, serde_json::to_string_pretty(&progress)?)?;
+        fs::rename(tmp, &checkpoint)?;
+        info!("  {} entries exported", progress.count);
+    }
+}
+
+/// Read every entry of a dump, skipping blank lines
+pub fn read_dump(path: &Path) -> Result<Vec<DumpEntry>> {
+    let reader = BufReader::new(fs::File::open(path).with_context(|| format!("Failed to open {}", path.display()))?);
+    let mut entries = Vec::new();
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry = serde_json::from_str(&line).with_context(|| format!("{} line {} is not a dump entry", path.display(), index + 1))?;
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_dump() {
+        let path = std::env::temp_dir().join(format!("rblxsync-dump-{}.jsonl", std::process::id()));
+        fs::write(&path, "{\"key\":\"a\",\"value\":{\"coins\":5}}\n\n{\"key\":\"b\",\"value\":\"x\"}\n").unwrap();
+        let entries = read_dump(&path).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0], DumpEntry { key: "a".to_string(), value: serde_json::json!({ "coins": 5 }) });
+
+        fs::write(&path, "{\"key\":\"a\",\"value\":1}\nnot json\n").unwrap();
+        let err = read_dump(&path).unwrap_err();
+        assert!(err.to_string().contains("line 2"));
+
+        fs::remove_file(&path).unwrap();
+        assert_eq!(progress_path(Path::new("out/dump.jsonl")), PathBuf::from("out/dump.jsonl.progress.json"));
+    }
+}
@@ -0,0 +1,264 @@
+//! Resumable paginated fetches of every remote resource in a universe.
+//!
+//! Large universes can have thousands of passes, products and badges.
+//! Instead of holding every page in memory, each page is appended to a
+//! JSON Lines file under `.rblxsync/fetch/<command>-<universe>/` and the
+//! next cursor is checkpointed, so an interrupted fetch (Ctrl-C, network
+//! failure) can pick up where it left off with `--resume`. Each command
+//! keeps its own checkpoint, so one command never discards another's.
+
+use crate::api::RobloxClient;
+use crate::state::EntryKind;
+use anyhow::{anyhow, Context, Result};
+use tracing::info;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+
+/// Remote resource collections that can be fetched
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResourceKind {
+    GamePasses,
+    DeveloperProducts,
+    Badges,
+}
+
+impl ResourceKind {
+    pub const ALL: [ResourceKind; 3] = [ResourceKind::GamePasses, ResourceKind::DeveloperProducts, ResourceKind::Badges];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            ResourceKind::GamePasses => "game_passes",
+            ResourceKind::DeveloperProducts => "developer_products",
+            ResourceKind::Badges => "badges",
+        }
+    }
+
+    /// Resource type as recorded in the run manifest
+    pub fn resource_type(&self) -> &'static str {
+        match self {
+            ResourceKind::GamePasses => "game_pass",
+            ResourceKind::DeveloperProducts => "developer_product",
+            ResourceKind::Badges => "badge",
+        }
+    }
+
+    /// Matching lock file entry kind
+    pub fn entry_kind(&self) -> EntryKind {
+        match self {
+            ResourceKind::GamePasses => EntryKind::GamePass,
+            ResourceKind::DeveloperProducts => EntryKind::DeveloperProduct,
+            ResourceKind::Badges => EntryKind::Badge,
+        }
+    }
+
+    /// ID of a listed item; the APIs disagree on the field name and type
+    pub fn item_id(&self, item: &serde_json::Value) -> Option<u64> {
+        let keys: &[&str] = match self {
+            ResourceKind::GamePasses => &["id", "gamePassId"],
+            ResourceKind::DeveloperProducts => &["id", "productId", "developerProductId"],
+            ResourceKind::Badges => &["id"],
+        };
+        keys.iter().find_map(|k| item[*k].as_u64().or_else(|| item[*k].as_str().and_then(|s| s.parse().ok())))
+    }
+
+    /// Thumbnails API path and ID query parameter for this kind's icons
+    pub fn icon_endpoint(&self) -> (&'static str, &'static str) {
+        match self {
+            ResourceKind::GamePasses => ("v1/game-passes", "gamePassIds"),
+            ResourceKind::DeveloperProducts => ("v1/developer-products/icons", "developerProductIds"),
+            ResourceKind::Badges => ("v1/badges/icons", "badgeIds"),
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            ResourceKind::GamePasses => "game passes",
+            ResourceKind::DeveloperProducts => "developer products",
+            ResourceKind::Badges => "badges",
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct Progress {
+    universe_id: u64,
+    #[serde(default)]
+    collections: BTreeMap<String, CollectionProgress>,
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+#[serde(default)]
+struct CollectionProgress {
+    cursor: Option<String>,
+    done: bool,
+    count: u64,
+    pages: u64,
+    /// Length of the data file at the last checkpoint
+    bytes: u64,
+}
+
+/// Fetches whole collections page by page with on-disk checkpoints
+pub struct ResumableFetch {
+    dir: PathBuf,
+    progress: Progress,
+    abort: Arc<AtomicBool>,
+    /// Ctrl-C listener setting `abort`, stopped when the fetch is dropped
+    interrupt: JoinHandle<()>,
+}
+
+impl ResumableFetch {
+    /// Start a fetch of `universe_id` for `command`. With `resume`, an
+    /// existing checkpoint of the same command and universe is continued;
+    /// otherwise it is discarded.
+    pub fn open(project_root: &Path, command: &str, universe_id: u64, resume: bool) -> Result<Self> {
+        let dir = project_root.join(".rblxsync").join("fetch").join(format!("{}-{}", command, universe_id));
+        let progress_path = dir.join("progress.json");
+
+        let existing = if resume && progress_path.exists() {
+            let content = fs::read_to_string(&progress_path)?;
+            Some(serde_json::from_str::<Progress>(&content).context("Failed to read fetch checkpoint")?)
+        } else {
+            None
+        };
+
+        let progress = match existing {
+            Some(p) => {
+                info!("Resuming fetch from checkpoint in {:?}", dir);
+                p
+            }
+            None => {
+                if dir.exists() {
+                    fs::remove_dir_all(&dir)?;
+                }
+                fs::create_dir_all(&dir)?;
+                Progress { universe_id, ..Default::default() }
+            }
+        };
+
+        let abort = Arc::new(AtomicBool::new(false));
+        let flag = abort.clone();
+        let interrupt = tokio::spawn(async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                tracing::warn!("Interrupt received; stopping after the current page...");
+                flag.store(true, Ordering::SeqCst);
+            }
+        });
+
+        Ok(Self { dir, progress, abort, interrupt })
+    }
+
+    /// Fetch every page of `kind`, appending items to disk as they arrive
+    pub async fn fetch(&mut self, client: &RobloxClient, kind: ResourceKind) -> Result<()> {
+        let universe_id = self.progress.universe_id;
+        let mut state = self.progress.collections.get(kind.key()).cloned().unwrap_or_default();
+        if state.done {
+            info!("  {}: {} already fetched", kind.label(), state.count);
+            return Ok(());
+        }
+
+        // Drop any partial page written after the last checkpoint
+        let data_path = self.data_path(kind);
+        let mut file = OpenOptions::new().create(true).append(true).open(&data_path)?;
+        file.set_len(state.bytes)?;
+
+        loop {
+            if self.abort.load(Ordering::SeqCst) {
+                return Err(anyhow!("Fetch interrupted; rerun with --resume to continue"));
+            }
+
+            let cursor = state.cursor.clone();
+            let page = match kind {
+                ResourceKind::GamePasses => client.list_game_passes(universe_id, cursor).await,
+                ResourceKind::DeveloperProducts => client.list_developer_products(universe_id, cursor).await,
+                ResourceKind::Badges => client.list_badges(universe_id, cursor).await,
+            }
+            .with_context(|| format!("Failed to fetch {}; rerun with --resume to continue", kind.label()))?;
+
+            for item in &page.data {
+                writeln!(file, "{}", item)?;
+            }
+            file.flush()?;
+
+            state.bytes = file.metadata()?.len();
+            state.count += page.data.len() as u64;
+            state.pages += 1;
+            state.cursor = page.next_page_cursor.filter(|c| !c.is_empty());
+            state.done = state.cursor.is_none();
+            info!("  {}: {} fetched (page {})", kind.label(), state.count, state.pages);
+
+            self.progress.collections.insert(kind.key().to_string(), state.clone());
+            self.save_progress()?;
+
+            if state.done {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Read back every fetched item of `kind`
+    pub fn items(&self, kind: ResourceKind) -> Result<Vec<serde_json::Value>> {
+        let path = self.data_path(kind);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let reader = BufReader::new(fs::File::open(path)?);
+        reader
+            .lines()
+            .filter(|l| l.as_ref().map(|l| !l.trim().is_empty()).unwrap_or(true))
+            .map(|l| Ok(serde_json::from_str(&l?)?))
+            .collect()
+    }
+
+    /// Remove the checkpoint once the fetched data has been consumed
+    pub fn finish(self) -> Result<()> {
+        if self.dir.exists() {
+            fs::remove_dir_all(&self.dir)?;
+        }
+        Ok(())
+    }
+
+    fn data_path(&self, kind: ResourceKind) -> PathBuf {
+        self.dir.join(format!("{}.jsonl", kind.key()))
+    }
+
+    fn save_progress(&self) -> Result<()> {
+        let path = self.dir.join("progress.json");
+        let tmp = self.dir.join("progress.json.tmp");
+        fs::write(&tmp, serde_json::to_string_pretty(&self.progress)?)?;
+        fs::rename(tmp, path)?;
+        Ok(())
+    }
+}
+
+impl Drop for ResumableFetch {
+    fn drop(&mut self) {
+        self.interrupt.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_checkpoints_are_per_command() {
+        let root = std::env::temp_dir().join(format!("rblxsync-fetch-{}", std::process::id()));
+        let export = ResumableFetch::open(&root, "export", 1, false).unwrap();
+        fs::write(export.data_path(ResourceKind::Badges), "{}\n").unwrap();
+        export.save_progress().unwrap();
+        drop(export);
+
+        // Another command starting over leaves the export checkpoint alone
+        ResumableFetch::open(&root, "pull", 1, false).unwrap().finish().unwrap();
+        let resumed = ResumableFetch::open(&root, "export", 1, true).unwrap();
+        assert_eq!(resumed.items(ResourceKind::Badges).unwrap().len(), 1);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,149 @@
+//! Selective sync: restrict `run` to some config sections and/or resource names.
+//!
+//! `--only` picks sections; `--filter` matches resource names with a
+//! case-insensitive glob (`*`, `?`), or a regex when written as `/pattern/`;
+//! `--target` picks single resources by address, e.g. `game_pass.VIP`.
+
+use regex::Regex;
+use std::str::FromStr;
+
+/// Config sections that `run --only` can select
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+#[value(rename_all = "snake_case")]
+pub enum SyncSection {
+    Universe,
+    GamePasses,
+    DeveloperProducts,
+    Badges,
+    Assets,
+    Restrictions,
+}
+
+/// A single resource picked with `run --target`: `<type>.<name>`, or just
+/// `universe` for universe settings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResourceAddress {
+    pub section: SyncSection,
+    /// Config name, matched case-insensitively (None for `universe`)
+    pub name: Option<String>,
+}
+
+impl ResourceAddress {
+    pub fn matches(&self, section: SyncSection, name: &str) -> bool {
+        self.section == section && self.name.as_deref().is_some_and(|n| n.eq_ignore_ascii_case(name))
+    }
+}
+
+impl std::fmt::Display for ResourceAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = match self.section {
+            SyncSection::Universe => "universe",
+            SyncSection::GamePasses => "game_pass",
+            SyncSection::DeveloperProducts => "developer_product",
+            SyncSection::Badges => "badge",
+            SyncSection::Assets => "asset",
+            SyncSection::Restrictions => "restriction",
+        };
+        match &self.name {
+            Some(name) => write!(f, "{}.{}", kind, name),
+            None => write!(f, "{}", kind),
+        }
+    }
+}
+
+impl FromStr for ResourceAddress {
+    type Err = String;
+
+    fn from_str(address: &str) -> Result<Self, Self::Err> {
+        if address == "universe" {
+            return Ok(Self { section: SyncSection::Universe, name: None });
+        }
+        let (kind, name) = address
+            .split_once('.')
+            .filter(|(_, name)| !name.trim().is_empty())
+            .ok_or_else(|| format!("Invalid target '{}': expected <type>.<name>, e.g. game_pass.VIP", address))?;
+        let section = match kind {
+            "game_pass" => SyncSection::GamePasses,
+            "developer_product" => SyncSection::DeveloperProducts,
+            "badge" => SyncSection::Badges,
+            "asset" => SyncSection::Assets,
+            "restriction" => SyncSection::Restrictions,
+            _ => return Err(format!("Invalid target type '{}': expected game_pass, developer_product, badge, asset, restriction or universe", kind)),
+        };
+        Ok(Self { section, name: Some(name.trim().to_string()) })
+    }
+}
+
+/// Resource name filter for `run --filter`
+#[derive(Debug, Clone)]
+pub struct NameFilter {
+    regex: Regex,
+}
+
+impl NameFilter {
+    pub fn matches(&self, name: &str) -> bool {
+        self.regex.is_match(name)
+    }
+}
+
+impl FromStr for NameFilter {
+    type Err = String;
+
+    fn from_str(pattern: &str) -> Result<Self, Self::Err> {
+        let source = match pattern.strip_prefix('/').and_then(|p| p.strip_suffix('/')) {
+            Some(regex) => regex.to_string(),
+            None => {
+                let mut glob = String::from("(?i)^");
+                for c in pattern.chars() {
+                    match c {
+                        '*' => glob.push_str(".*"),
+                        '?' => glob.push('.'),
+                        c => glob.push_str(&regex::escape(&c.to_string())),
+                    }
+                }
+                glob.push('$');
+                glob
+            }
+        };
+        Regex::new(&source).map(|regex| Self { regex }).map_err(|e| format!("Invalid filter '{}': {}", pattern, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_name_filter() {
+        let glob: NameFilter = "VIP*".parse().unwrap();
+        assert!(glob.matches("VIP Pass"));
+        assert!(glob.matches("vip"));
+        assert!(!glob.matches("Super VIP"));
+
+        let single: NameFilter = "Gem?".parse().unwrap();
+        assert!(single.matches("Gems"));
+        assert!(!single.matches("Gems Pack"));
+
+        let regex: NameFilter = "/^\\d+ Coins$/".parse().unwrap();
+        assert!(regex.matches("100 Coins"));
+        assert!(!regex.matches("Coins"));
+
+        assert!("/(/".parse::<NameFilter>().is_err());
+    }
+
+    #[test]
+    fn test_resource_address() {
+        let address: ResourceAddress = "game_pass.VIP Pass".parse().unwrap();
+        assert!(address.matches(SyncSection::GamePasses, "vip pass"));
+        assert!(!address.matches(SyncSection::Badges, "VIP Pass"));
+        assert_eq!(address.to_string(), "game_pass.VIP Pass");
+
+        let dotted: ResourceAddress = "developer_product.1.5x Boost".parse().unwrap();
+        assert_eq!(dotted.name.as_deref(), Some("1.5x Boost"));
+
+        assert_eq!("universe".parse::<ResourceAddress>().unwrap().section, SyncSection::Universe);
+        assert!("restriction.123".parse::<ResourceAddress>().unwrap().matches(SyncSection::Restrictions, "123"));
+        assert!("game_pass".parse::<ResourceAddress>().is_err());
+        assert!("gamepass.VIP".parse::<ResourceAddress>().is_err());
+    }
+}
@@ -0,0 +1,78 @@
+//! Group permission preflight for group-owned experiences.
+//!
+//! When `creator.type` is `group`, every upload and monetization change is
+//! made on the group's behalf, so the API key's owner needs the matching
+//! group role permissions. Checking them before `run` changes anything turns
+//! a mid-sync 403 into a clear error.
+
+use crate::config::RblxSyncConfig;
+
+/// A group role permission the config needs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PermissionRequirement {
+    /// Permission name as returned by the Groups API
+    pub permission: &'static str,
+    /// Why rblxsync needs it
+    pub reason: &'static str,
+}
+
+/// Role permissions needed to sync `config` into a group-owned universe
+pub fn required_permissions(config: &RblxSyncConfig) -> Vec<PermissionRequirement> {
+    let mut required = Vec::new();
+    let manages_universe = !config.game_passes.is_empty()
+        || !config.developer_products.is_empty()
+        || !config.badges.is_empty()
+        || !config.assets.is_empty()
+        || config.places.iter().any(|p| p.publish);
+    if manages_universe {
+        required.push(PermissionRequirement {
+            permission: "manageGroupUniverses",
+            reason: "Create and update the group experience's passes, products, badges, assets and places",
+        });
+    }
+    if !config.badges.is_empty() && config.badge_payment_source.as_deref() == Some("group") {
+        required.push(PermissionRequirement {
+            permission: "spendGroupFunds",
+            reason: "Pay for badge creation from group funds (badge_payment_source: group)",
+        });
+    }
+    required
+}
+
+/// Requirements whose permission is not granted in a role's `permissions`
+pub fn missing_permissions<'a>(permissions: &serde_json::Value, required: &'a [PermissionRequirement]) -> Vec<&'a PermissionRequirement> {
+    required
+        .iter()
+        .filter(|req| !permissions[req.permission].as_bool().unwrap_or(false))
+        .collect()
+}
+
+/// User ID from a resource path such as `users/123`
+pub fn user_id_from_path(path: &str) -> Option<u64> {
+    path.strip_prefix("users/")?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group_permissions() {
+        let config: RblxSyncConfig = serde_yaml::from_str(
+            "universe: { id: 1 }\ncreator: { id: \"5\", type: group }\nbadge_payment_source: group\nbadges: [{ name: Welcome }]\n",
+        )
+        .unwrap();
+        let required = required_permissions(&config);
+        assert_eq!(required.iter().map(|r| r.permission).collect::<Vec<_>>(), vec!["manageGroupUniverses", "spendGroupFunds"]);
+
+        let permissions = serde_json::json!({ "manageGroupUniverses": true, "spendGroupFunds": false });
+        let missing = missing_permissions(&permissions, &required);
+        assert_eq!(missing.len(), 1);
+        assert_eq!(missing[0].permission, "spendGroupFunds");
+
+        let bare: RblxSyncConfig = serde_yaml::from_str("universe: { id: 1 }\n").unwrap();
+        assert!(required_permissions(&bare).is_empty());
+        assert_eq!(user_id_from_path("users/42"), Some(42));
+        assert_eq!(user_id_from_path("groups/42"), None);
+    }
+}
@@ -0,0 +1,116 @@
+//! User-defined shell commands run around `run` and `publish`.
+//!
+//! Each hook runs through the platform shell (`sh -c`, or `cmd /C` on
+//! Windows) from the current directory, with `RBLXSYNC_*` environment
+//! variables describing the run. A hook that exits non-zero fails the
+//! command; `pre_sync` hooks run before anything is changed, so a failing one
+//! aborts the sync.
+
+use crate::config::RblxSyncConfig;
+use crate::report::RunReport;
+use anyhow::{bail, Context, Result};
+use tracing::info;
+use std::process::Command;
+
+/// When a hook runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookStage {
+    PreSync,
+    PostSync,
+    PostPublish,
+}
+
+impl HookStage {
+    pub fn name(&self) -> &'static str {
+        match self {
+            HookStage::PreSync => "pre_sync",
+            HookStage::PostSync => "post_sync",
+            HookStage::PostPublish => "post_publish",
+        }
+    }
+
+    fn commands<'a>(&self, config: &'a RblxSyncConfig) -> &'a [String] {
+        let Some(hooks) = &config.hooks else { return &[] };
+        match self {
+            HookStage::PreSync => &hooks.pre_sync,
+            HookStage::PostSync => &hooks.post_sync,
+            HookStage::PostPublish => &hooks.post_publish,
+        }
+    }
+}
+
+/// Environment variables describing the run so far
+pub fn run_env(config: &RblxSyncConfig, report: &RunReport) -> Vec<(String, String)> {
+    let counts = &report.counts;
+    [
+        ("RBLXSYNC_UNIVERSE_ID", config.universe.id.to_string()),
+        ("RBLXSYNC_DRY_RUN", report.dry_run.to_string()),
+        ("RBLXSYNC_CREATED", counts.created.to_string()),
+        ("RBLXSYNC_UPDATED", counts.updated.to_string()),
+        ("RBLXSYNC_UPLOADED", counts.uploaded.to_string()),
+        ("RBLXSYNC_PUBLISHED", counts.published.to_string()),
+        ("RBLXSYNC_SKIPPED", counts.skipped.to_string()),
+        ("RBLXSYNC_FAILED", counts.failed.to_string()),
+        ("RBLXSYNC_CHANGED", (counts.created + counts.updated + counts.uploaded + counts.published).to_string()),
+    ]
+    .into_iter()
+    .map(|(k, v)| (k.to_string(), v))
+    .collect()
+}
+
+/// Run the configured hooks for `stage` in order, stopping at the first failure
+pub fn run(stage: HookStage, config: &RblxSyncConfig, env: &[(String, String)], dry_run: bool) -> Result<()> {
+    let commands = stage.commands(config);
+    if commands.is_empty() {
+        return Ok(());
+    }
+    if dry_run {
+        info!("Dry Run: Would run {} {} hook(s)", commands.len(), stage.name());
+        return Ok(());
+    }
+    for command in commands {
+        info!("Running {} hook: {}", stage.name(), command);
+        let status = shell(command)
+            .env("RBLXSYNC_HOOK", stage.name())
+            .envs(env.iter().map(|(k, v)| (k.as_str(), v.as_str())))
+            .status()
+            .with_context(|| format!("Failed to start {} hook '{}'", stage.name(), command))?;
+        if !status.success() {
+            bail!("{} hook '{}' failed ({})", stage.name(), command, status);
+        }
+    }
+    Ok(())
+}
+
+/// `command` run through the platform shell
+pub(crate) fn shell(command: &str) -> Command {
+    if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", command]);
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", command]);
+        cmd
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hooks_env_and_failures() {
+        let config: RblxSyncConfig = serde_yaml::from_str(
+            "universe: { id: 1 }\nhooks:\n  post_sync: ['test \"$RBLXSYNC_HOOK\" = post_sync', 'test \"$RBLXSYNC_CREATED\" = 2']\n  post_publish: ['exit 3']\n",
+        )
+        .unwrap();
+        let env = vec![("RBLXSYNC_CREATED".to_string(), "2".to_string())];
+
+        assert!(run(HookStage::PostSync, &config, &env, false).is_ok());
+        assert!(run(HookStage::PreSync, &config, &env, false).is_ok());
+        assert!(run(HookStage::PostPublish, &config, &env, true).is_ok());
+        let err = run(HookStage::PostPublish, &config, &env, false).unwrap_err();
+        assert!(err.to_string().contains("post_publish hook 'exit 3' failed"));
+    }
+}
@@ -0,0 +1,181 @@
+//! Translations for user-facing CLI messages.
+//!
+//! The language comes from `RBLXSYNC_LANG` (e.g. `es`, `pt-BR`, `pt_BR.UTF-8`)
+//! and defaults to English. Only sync progress and summaries are translated so
+//! far; other messages stay in English. Robux amounts use the language's digit
+//! grouping.
+
+use std::sync::OnceLock;
+
+/// Supported CLI languages
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lang {
+    En,
+    Es,
+    Pt,
+}
+
+impl Lang {
+    /// Language selected by `RBLXSYNC_LANG`, resolved once per process
+    pub fn current() -> Lang {
+        static LANG: OnceLock<Lang> = OnceLock::new();
+        *LANG.get_or_init(|| std::env::var("RBLXSYNC_LANG").map(|v| Lang::parse(&v)).unwrap_or(Lang::En))
+    }
+
+    /// Parse a locale string, falling back to English for unknown languages
+    pub fn parse(locale: &str) -> Lang {
+        let code = locale.split(['-', '_', '.']).next().unwrap_or("").to_lowercase();
+        match code.as_str() {
+            "es" => Lang::Es,
+            "pt" => Lang::Pt,
+            _ => Lang::En,
+        }
+    }
+
+    fn thousands_separator(&self) -> char {
+        match self {
+            Lang::En => ',',
+            Lang::Es | Lang::Pt => '.',
+        }
+    }
+}
+
+/// Resource sections named in progress and summary messages
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Section {
+    Universe,
+    GamePasses,
+    DeveloperProducts,
+    Badges,
+    Assets,
+    Restrictions,
+}
+
+impl Section {
+    fn name(&self, lang: Lang) -> &'static str {
+        match (self, lang) {
+            (Section::Universe, Lang::En) => "Universe Settings",
+            (Section::Universe, Lang::Es) => "Configuración del universo",
+            (Section::Universe, Lang::Pt) => "Configurações do universo",
+            (Section::GamePasses, Lang::En) => "Game Passes",
+            (Section::GamePasses, Lang::Es) => "Pases de juego",
+            (Section::GamePasses, Lang::Pt) => "Passes de jogo",
+            (Section::DeveloperProducts, Lang::En) => "Developer Products",
+            (Section::DeveloperProducts, Lang::Es) => "Productos para desarrolladores",
+            (Section::DeveloperProducts, Lang::Pt) => "Produtos de desenvolvedor",
+            (Section::Badges, Lang::En) => "Badges",
+            (Section::Badges, Lang::Es) => "Insignias",
+            (Section::Badges, Lang::Pt) => "Emblemas",
+            (Section::Assets, Lang::En) => "Assets",
+            (Section::Assets, Lang::Es) | (Section::Assets, Lang::Pt) => "Recursos",
+            (Section::Restrictions, Lang::En) => "User Restrictions",
+            (Section::Restrictions, Lang::Es) => "Restricciones de usuarios",
+            (Section::Restrictions, Lang::Pt) => "Restrições de usuários",
+        }
+    }
+}
+
+/// Translatable messages
+#[derive(Debug, Clone, Copy)]
+pub enum Msg {
+    StartingSync { dry_run: bool },
+    DryRunEnabled,
+    SyncComplete,
+    Syncing(Section),
+    Summary { section: Section, created: u32, updated: u32, skipped: u32 },
+    AssetSummary { uploaded: u32, skipped: u32 },
+    PriceChange { old: Option<u64>, new: u64 },
+    SaleChange { on_sale: bool },
+}
+
+impl Msg {
+    /// Message text in the current language
+    pub fn text(&self) -> String {
+        self.text_in(Lang::current())
+    }
+
+    pub fn text_in(&self, lang: Lang) -> String {
+        match (*self, lang) {
+            (Msg::StartingSync { dry_run }, Lang::En) => format!("Starting sync... (dry_run: {})", dry_run),
+            (Msg::StartingSync { dry_run }, Lang::Es) => format!("Iniciando sincronización... (dry_run: {})", dry_run),
+            (Msg::StartingSync { dry_run }, Lang::Pt) => format!("Iniciando sincronização... (dry_run: {})", dry_run),
+            (Msg::DryRunEnabled, Lang::En) => "Dry-run mode enabled.".to_string(),
+            (Msg::DryRunEnabled, Lang::Es) => "Modo de prueba (dry-run) activado.".to_string(),
+            (Msg::DryRunEnabled, Lang::Pt) => "Modo de simulação (dry-run) ativado.".to_string(),
+            (Msg::SyncComplete, Lang::En) => "Sync complete!".to_string(),
+            (Msg::SyncComplete, Lang::Es) => "¡Sincronización completada!".to_string(),
+            (Msg::SyncComplete, Lang::Pt) => "Sincronização concluída!".to_string(),
+            (Msg::Syncing(section), Lang::En) => format!("Syncing {}...", section.name(lang)),
+            (Msg::Syncing(section), Lang::Es) => format!("Sincronizando {}...", section.name(lang)),
+            (Msg::Syncing(section), Lang::Pt) => format!("Sincronizando {}...", section.name(lang)),
+            (Msg::Summary { section, created, updated, skipped }, Lang::En) => {
+                format!("{} Summary: {} created, {} updated, {} skipped (unchanged)", section.name(lang), created, updated, skipped)
+            }
+            (Msg::Summary { section, created, updated, skipped }, Lang::Es) => {
+                format!("Resumen de {}: {} creados, {} actualizados, {} omitidos (sin cambios)", section.name(lang), created, updated, skipped)
+            }
+            (Msg::Summary { section, created, updated, skipped }, Lang::Pt) => {
+                format!("Resumo de {}: {} criados, {} atualizados, {} ignorados (sem alterações)", section.name(lang), created, updated, skipped)
+            }
+            (Msg::AssetSummary { uploaded, skipped }, Lang::En) => format!("Assets Summary: {} uploaded, {} skipped (unchanged)", uploaded, skipped),
+            (Msg::AssetSummary { uploaded, skipped }, Lang::Es) => format!("Resumen de recursos: {} subidos, {} omitidos (sin cambios)", uploaded, skipped),
+            (Msg::AssetSummary { uploaded, skipped }, Lang::Pt) => format!("Resumo de recursos: {} enviados, {} ignorados (sem alterações)", uploaded, skipped),
+            (Msg::PriceChange { old, new }, _) => {
+                let label = match lang {
+                    Lang::En => "price",
+                    Lang::Es => "precio",
+                    Lang::Pt => "preço",
+                };
+                match old {
+                    Some(old) => format!("{}: {} -> {}", label, robux_in(old, lang), robux_in(new, lang)),
+                    None => format!("{}: {}", label, robux_in(new, lang)),
+                }
+            }
+            (Msg::SaleChange { on_sale: true }, Lang::En) => "put on sale".to_string(),
+            (Msg::SaleChange { on_sale: true }, Lang::Es) => "puesto a la venta".to_string(),
+            (Msg::SaleChange { on_sale: true }, Lang::Pt) => "colocado à venda".to_string(),
+            (Msg::SaleChange { on_sale: false }, Lang::En) => "taken off sale".to_string(),
+            (Msg::SaleChange { on_sale: false }, Lang::Es) => "retirado de la venta".to_string(),
+            (Msg::SaleChange { on_sale: false }, Lang::Pt) => "retirado da venda".to_string(),
+        }
+    }
+}
+
+/// Format a Robux amount with the current language's digit grouping, e.g. "1,500 Robux"
+pub fn robux(amount: u64) -> String {
+    robux_in(amount, Lang::current())
+}
+
+fn robux_in(amount: u64, lang: Lang) -> String {
+    let digits = amount.to_string();
+    let mut grouped = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(lang.thousands_separator());
+        }
+        grouped.push(c);
+    }
+    format!("{} Robux", grouped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lang_and_robux_formatting() {
+        assert_eq!(Lang::parse("pt_BR.UTF-8"), Lang::Pt);
+        assert_eq!(Lang::parse("es-MX"), Lang::Es);
+        assert_eq!(Lang::parse("fr"), Lang::En);
+
+        assert_eq!(robux_in(999, Lang::En), "999 Robux");
+        assert_eq!(robux_in(1500, Lang::En), "1,500 Robux");
+        assert_eq!(robux_in(1234567, Lang::Es), "1.234.567 Robux");
+
+        let msg = Msg::Summary { section: Section::Badges, created: 1, updated: 2, skipped: 3 };
+        assert_eq!(msg.text_in(Lang::En), "Badges Summary: 1 created, 2 updated, 3 skipped (unchanged)");
+        assert_eq!(msg.text_in(Lang::Pt), "Resumo de Emblemas: 1 criados, 2 atualizados, 3 ignorados (sem alterações)");
+        assert_eq!(Msg::PriceChange { old: Some(100), new: 1000 }.text_in(Lang::Es), "precio: 100 Robux -> 1.000 Robux");
+        assert_eq!(Msg::SaleChange { on_sale: false }.text_in(Lang::Pt), "retirado da venda");
+    }
+}
@@ -0,0 +1,276 @@
+//! Icons generated at sync time from `icon_templates`, so hundreds of
+//! similar icons (e.g. a coin pack per price) don't need to be exported by
+//! hand.
+//!
+//! A template is either an SVG, whose `{{name}}`-style placeholders are
+//! filled in before an external renderer (resvg by default) rasterizes it,
+//! or a base image. Either can then get lines of text drawn on it in a
+//! built-in pixel font. Placeholders are the resource's `name` and `price`
+//! plus the usual template variables. The result goes through
+//! `image_processing` and hashing like any icon file, so an icon is only
+//! uploaded again when its rendered pixels change.
+
+use crate::assets::{self, hash_bytes};
+use crate::config::{IconTemplateConfig, TextOverlayConfig};
+use crate::template;
+use anyhow::{anyhow, bail, Context, Result};
+use image::{DynamicImage, ImageFormat, Rgba, RgbaImage};
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::path::Path;
+
+/// Renders SVGs when a template doesn't set `svg_renderer`
+const DEFAULT_SVG_RENDERER: &str = "resvg -w {size} -h {size} {input} {output}";
+
+/// Directory (relative to the project root) holding rasterized SVGs
+const SVG_CACHE_DIR: &str = ".rblxsync/cache/templates";
+
+/// Glyph size in font pixels; glyphs are one pixel apart
+const GLYPH_WIDTH: u32 = 5;
+const GLYPH_HEIGHT: u32 = 7;
+
+/// Rows of each glyph, most significant of the 5 bits on the left.
+/// Lowercase letters are drawn as capitals.
+const FONT: [(char, [u8; 7]); 61] = [
+    (' ', [0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000]),
+    ('!', [0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00000, 0b00100]),
+    ('"', [0b01010, 0b01010, 0b01010, 0b00000, 0b00000, 0b00000, 0b00000]),
+    ('#', [0b01010, 0b01010, 0b11111, 0b01010, 0b11111, 0b01010, 0b01010]),
+    ('$', [0b00100, 0b01111, 0b10100, 0b01110, 0b00101, 0b11110, 0b00100]),
+    ('%', [0b11000, 0b11001, 0b00010, 0b00100, 0b01000, 0b10011, 0b00011]),
+    ('&', [0b01100, 0b10010, 0b10100, 0b01000, 0b10101, 0b10010, 0b01101]),
+    ('\'', [0b00100, 0b00100, 0b01000, 0b00000, 0b00000, 0b00000, 0b00000]),
+    ('(', [0b00010, 0b00100, 0b01000, 0b01000, 0b01000, 0b00100, 0b00010]),
+    (')', [0b01000, 0b00100, 0b00010, 0b00010, 0b00010, 0b00100, 0b01000]),
+    ('*', [0b00000, 0b00100, 0b10101, 0b01110, 0b10101, 0b00100, 0b00000]),
+    ('+', [0b00000, 0b00100, 0b00100, 0b11111, 0b00100, 0b00100, 0b00000]),
+    (',', [0b00000, 0b00000, 0b00000, 0b00000, 0b01100, 0b00100, 0b01000]),
+    ('-', [0b00000, 0b00000, 0b00000, 0b11111, 0b00000, 0b00000, 0b00000]),
+    ('.', [0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b01100, 0b01100]),
+    ('/', [0b00000, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b00000]),
+    ('0', [0b01110, 0b10001, 0b10011, 0b10101, 0b11001, 0b10001, 0b01110]),
+    ('1', [0b00100, 0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110]),
+    ('2', [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b01000, 0b11111]),
+    ('3', [0b11111, 0b00010, 0b00100, 0b00010, 0b00001, 0b10001, 0b01110]),
+    ('4', [0b00010, 0b00110, 0b01010, 0b10010, 0b11111, 0b00010, 0b00010]),
+    ('5', [0b11111, 0b10000, 0b11110, 0b00001, 0b00001, 0b10001, 0b01110]),
+    ('6', [0b00110, 0b01000, 0b10000, 0b11110, 0b10001, 0b10001, 0b01110]),
+    ('7', [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b01000, 0b01000]),
+    ('8', [0b01110, 0b10001, 0b10001, 0b01110, 0b10001, 0b10001, 0b01110]),
+    ('9', [0b01110, 0b10001, 0b10001, 0b01111, 0b00001, 0b00010, 0b01100]),
+    (':', [0b00000, 0b01100, 0b01100, 0b00000, 0b01100, 0b01100, 0b00000]),
+    ('=', [0b00000, 0b00000, 0b11111, 0b00000, 0b11111, 0b00000, 0b00000]),
+    ('?', [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b00000, 0b00100]),
+    ('@', [0b01110, 0b10001, 0b00001, 0b01101, 0b10101, 0b10101, 0b01110]),
+    ('A', [0b01110, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001]),
+    ('B', [0b11110, 0b10001, 0b10001, 0b11110, 0b10001, 0b10001, 0b11110]),
+    ('C', [0b01110, 0b10001, 0b10000, 0b10000, 0b10000, 0b10001, 0b01110]),
+    ('D', [0b11100, 0b10010, 0b10001, 0b10001, 0b10001, 0b10010, 0b11100]),
+    ('E', [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b11111]),
+    ('F', [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b10000]),
+    ('G', [0b01110, 0b10001, 0b10000, 0b10111, 0b10001, 0b10001, 0b01111]),
+    ('H', [0b10001, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001]),
+    ('I', [0b01110, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110]),
+    ('J', [0b00111, 0b00010, 0b00010, 0b00010, 0b00010, 0b10010, 0b01100]),
+    ('K', [0b10001, 0b10010, 0b10100, 0b11000, 0b10100, 0b10010, 0b10001]),
+    ('L', [0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b11111]),
+    ('M', [0b10001, 0b11011, 0b10101, 0b10101, 0b10001, 0b10001, 0b10001]),
+    ('N', [0b10001, 0b10001, 0b11001, 0b10101, 0b10011, 0b10001, 0b10001]),
+    ('O', [0b01110, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110]),
+    ('P', [0b11110, 0b10001, 0b10001, 0b11110, 0b10000, 0b10000, 0b10000]),
+    ('Q', [0b01110, 0b10001, 0b10001, 0b10001, 0b10101, 0b10010, 0b01101]),
+    ('R', [0b11110, 0b10001, 0b10001, 0b11110, 0b10100, 0b10010, 0b10001]),
+    ('S', [0b01111, 0b10000, 0b10000, 0b01110, 0b00001, 0b00001, 0b11110]),
+    ('T', [0b11111, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100]),
+    ('U', [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110]),
+    ('V', [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01010, 0b00100]),
+    ('W', [0b10001, 0b10001, 0b10001, 0b10101, 0b10101, 0b10101, 0b01010]),
+    ('X', [0b10001, 0b10001, 0b01010, 0b00100, 0b01010, 0b10001, 0b10001]),
+    ('Y', [0b10001, 0b10001, 0b10001, 0b01010, 0b00100, 0b00100, 0b00100]),
+    ('Z', [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b11111]),
+    ('[', [0b01110, 0b01000, 0b01000, 0b01000, 0b01000, 0b01000, 0b01110]),
+    (']', [0b01110, 0b00010, 0b00010, 0b00010, 0b00010, 0b00010, 0b01110]),
+    ('_', [0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b11111]),
+    ('<', [0b00010, 0b00100, 0b01000, 0b10000, 0b01000, 0b00100, 0b00010]),
+    ('>', [0b01000, 0b00100, 0b00010, 0b00001, 0b00010, 0b00100, 0b01000]),
+];
+
+fn glyph(c: char) -> [u8; 7] {
+    let c = c.to_ascii_uppercase();
+    FONT.iter().find(|(g, _)| *g == c).or_else(|| FONT.iter().find(|(g, _)| *g == '?')).map(|(_, rows)| *rows).unwrap_or_default()
+}
+
+/// Placeholder values for one resource's icon, on top of `vars`
+pub fn resource_vars(vars: &HashMap<String, String>, name: &str, price: Option<u32>) -> HashMap<String, String> {
+    let mut vars = vars.clone();
+    vars.insert("name".to_string(), name.to_string());
+    if let Some(price) = price {
+        vars.insert("price".to_string(), price.to_string());
+    }
+    vars
+}
+
+/// Render `template` as a PNG for one resource
+pub fn render(template: &IconTemplateConfig, assets_dir: &Path, vars: &HashMap<String, String>) -> Result<Vec<u8>> {
+    let mut image = match (&template.svg, &template.base) {
+        (Some(svg), None) => {
+            let png = rasterize_svg(&assets_dir.join(svg), template, vars)?;
+            image::load_from_memory(&png).context("Failed to decode the rasterized SVG")?
+        }
+        (None, Some(base)) => {
+            let path = assets_dir.join(base);
+            let raw = std::fs::read(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+            image::load_from_memory(&raw).with_context(|| format!("Failed to decode {}", path.display()))?
+        }
+        _ => bail!("an icon template needs exactly one of `svg` and `base`"),
+    };
+    if image.width() != template.size || image.height() != template.size {
+        image = assets::fit_to_square(&image, template.size);
+    }
+
+    let mut canvas = image.to_rgba8();
+    for line in &template.text {
+        draw_text(&mut canvas, line, &template::render(&line.text, vars)?)?;
+    }
+    let mut buf = Cursor::new(Vec::new());
+    DynamicImage::ImageRgba8(canvas).write_to(&mut buf, ImageFormat::Png).context("Failed to encode PNG")?;
+    Ok(buf.into_inner())
+}
+
+/// Fill in the SVG's placeholders and rasterize it, reusing an earlier
+/// result for the same SVG, size and renderer
+fn rasterize_svg(path: &Path, template: &IconTemplateConfig, vars: &HashMap<String, String>) -> Result<Vec<u8>> {
+    let source = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let escaped: HashMap<String, String> = vars.iter().map(|(k, v)| (k.clone(), xml_escape(v))).collect();
+    let svg = template::render(&source, &escaped).with_context(|| format!("Failed to fill in {}", path.display()))?;
+    let renderer = template.svg_renderer.as_deref().unwrap_or(DEFAULT_SVG_RENDERER);
+
+    let key = hash_bytes(format!("{}\n{}\n{}", renderer, template.size, svg).as_bytes());
+    let cache_dir = Path::new(SVG_CACHE_DIR);
+    let output = cache_dir.join(format!("{}.png", key));
+    if let Ok(png) = std::fs::read(&output) {
+        return Ok(png);
+    }
+
+    std::fs::create_dir_all(cache_dir)?;
+    let input = cache_dir.join(format!("{}.svg", key));
+    std::fs::write(&input, svg)?;
+    let command = renderer
+        .replace("{input}", &shell_quote(&input.to_string_lossy()))
+        .replace("{output}", &shell_quote(&output.to_string_lossy()))
+        .replace("{size}", &template.size.to_string());
+    let result = crate::hooks::shell(&command).output();
+    let _ = std::fs::remove_file(&input);
+    let result = result.with_context(|| format!("Failed to start the SVG renderer `{}`", command))?;
+    if !result.status.success() {
+        bail!(
+            "SVG renderer `{}` failed ({}): {}. Install resvg or set `svg_renderer`",
+            command,
+            result.status,
+            String::from_utf8_lossy(&result.stderr).trim()
+        );
+    }
+    std::fs::read(&output).with_context(|| format!("SVG renderer `{}` wrote no {}", command, output.display()))
+}
+
+fn xml_escape(value: &str) -> String {
+    value.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;").replace('\'', "&apos;")
+}
+
+fn shell_quote(value: &str) -> String {
+    if cfg!(windows) {
+        format!("\"{}\"", value)
+    } else {
+        format!("'{}'", value.replace('\'', "'\\''"))
+    }
+}
+
+/// Parse `#rrggbb` or `#rrggbbaa`
+fn parse_color(color: &str) -> Result<Rgba<u8>> {
+    let hex = color.strip_prefix('#').filter(|h| (h.len() == 6 || h.len() == 8) && h.is_ascii()).ok_or_else(|| anyhow!("invalid color '{}': expected #rrggbb or #rrggbbaa", color))?;
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| anyhow!("invalid color '{}'", color));
+    Ok(Rgba([channel(0)?, channel(2)?, channel(4)?, if hex.len() == 8 { channel(6)? } else { 255 }]))
+}
+
+/// Draw `text` centered on the line's position, scaled to its size
+fn draw_text(canvas: &mut RgbaImage, line: &TextOverlayConfig, text: &str) -> Result<()> {
+    let color = parse_color(&line.color)?;
+    let scale = (line.size / GLYPH_HEIGHT).max(1);
+    let chars = text.chars().count() as u32;
+    let width = (chars * (GLYPH_WIDTH + 1)).saturating_sub(1) * scale;
+    let height = GLYPH_HEIGHT * scale;
+    let center_x = line.x.unwrap_or(canvas.width() / 2) as i64;
+    let center_y = line.y.unwrap_or(canvas.height() / 2) as i64;
+    let left = center_x - i64::from(width / 2);
+    let top = center_y - i64::from(height / 2);
+
+    for (i, c) in text.chars().enumerate() {
+        let glyph_left = left + i64::from(i as u32 * (GLYPH_WIDTH + 1) * scale);
+        for (row, bits) in glyph(c).iter().enumerate() {
+            for col in 0..GLYPH_WIDTH {
+                if bits & (1 << (GLYPH_WIDTH - 1 - col)) == 0 {
+                    continue;
+                }
+                for dy in 0..scale {
+                    for dx in 0..scale {
+                        let x = glyph_left + i64::from(col * scale + dx);
+                        let y = top + i64::from(row as u32 * scale + dy);
+                        if let (Ok(x), Ok(y)) = (u32::try_from(x), u32::try_from(y)) {
+                            if x < canvas.width() && y < canvas.height() {
+                                blend(canvas.get_pixel_mut(x, y), color);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Paint `color` over `pixel` according to its alpha
+fn blend(pixel: &mut Rgba<u8>, color: Rgba<u8>) {
+    let alpha = u32::from(color[3]);
+    for i in 0..3 {
+        pixel[i] = ((u32::from(color[i]) * alpha + u32::from(pixel[i]) * (255 - alpha)) / 255) as u8;
+    }
+    pixel[3] = pixel[3].max(color[3]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_base_with_text() {
+        let dir = std::env::temp_dir().join(format!("rblxsync-icon-template-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let base = RgbaImage::from_pixel(64, 64, Rgba([0, 0, 0, 255]));
+        DynamicImage::ImageRgba8(base).save(dir.join("coin.png")).unwrap();
+
+        let template = IconTemplateConfig {
+            svg: None,
+            base: Some("coin.png".to_string()),
+            text: vec![TextOverlayConfig { text: "{{price}}".to_string(), x: None, y: None, size: 14, color: "#ff0000".to_string() }],
+            size: 128,
+            svg_renderer: None,
+        };
+        let vars = resource_vars(&HashMap::new(), "Coins", Some(10));
+        let png = render(&template, &dir, &vars).unwrap();
+        let image = image::load_from_memory(&png).unwrap().to_rgba8();
+        assert_eq!(image.dimensions(), (128, 128));
+        // "10" at scale 2 spans x 53..75: the 1's stem is left of the center, the 0's left side right of it
+        assert_eq!(image.get_pixel(57, 60), &Rgba([255, 0, 0, 255]));
+        assert_eq!(image.get_pixel(65, 60), &Rgba([255, 0, 0, 255]));
+        assert_eq!(image.get_pixel(63, 60), &Rgba([0, 0, 0, 255]));
+        assert_eq!(image.get_pixel(0, 0), &Rgba([0, 0, 0, 255]));
+
+        // The price is part of the picture, so a new price is a new icon
+        let other = render(&template, &dir, &resource_vars(&HashMap::new(), "Coins", Some(20))).unwrap();
+        assert_ne!(hash_bytes(&png), hash_bytes(&other));
+
+        assert!(parse_color("red").is_err());
+        assert_eq!(parse_color("#00ff0080").unwrap(), Rgba([0, 255, 0, 128]));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
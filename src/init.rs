@@ -0,0 +1,153 @@
+//! Project scaffolding for `rblxsync init`.
+//!
+//! Renders a starter `rblxsync.yml`, optionally seeded with the universe's
+//! existing game passes, developer products and badges (pinned by ID so the
+//! first `run` adopts them instead of creating duplicates), plus the
+//! `.env.example` and `.gitignore` entries a new project needs.
+
+use crate::config::CreatorConfig;
+use crate::fetch::ResourceKind;
+use crate::pull;
+use anyhow::Result;
+use serde_yaml::{Mapping, Value};
+use std::fs;
+use std::path::Path;
+use tracing::warn;
+
+/// Contents of the generated `.env.example`
+pub const ENV_EXAMPLE: &str = "\
+# Copy to .env and fill in. Never commit .env.
+# Open Cloud API key (run `rblxsync scopes` for the scopes your config needs)
+ROBLOX_API_KEY=
+# .ROBLOSECURITY cookie, only needed to update universe settings
+ROBLOX_COOKIE=
+";
+
+/// Entries `init` makes sure are in `.gitignore`
+pub const GITIGNORE_ENTRIES: [&str; 2] = [".env", ".rblxsync/"];
+
+/// Render the starter config. `seeded` holds remote items per kind; kinds
+/// without items get an empty list.
+pub fn render_config(universe_id: u64, creator: Option<&CreatorConfig>, seeded: &[(ResourceKind, Vec<serde_json::Value>)]) -> Result<String> {
+    let mut out = String::from("# rblxsync configuration; see the README's Configuration Reference for every option\n");
+    out.push_str("assets_dir: assets\n\n");
+    match creator {
+        Some(creator) => out.push_str(&serde_yaml::to_string(&Mapping::from_iter([(
+            Value::from("creator"),
+            serde_yaml::to_value(creator)?,
+        )]))?),
+        None => out.push_str("# Required to upload icons and assets\n# creator:\n#   id: \"12345678\"\n#   type: \"user\" # or \"group\"\n"),
+    }
+    out.push_str(&format!("\nuniverse:\n  id: {}\n", universe_id));
+
+    for kind in ResourceKind::ALL {
+        let entries = seeded
+            .iter()
+            .find(|(k, _)| *k == kind)
+            .map(|(_, items)| seed_entries(kind, items))
+            .unwrap_or_default();
+        out.push('\n');
+        if entries.is_empty() {
+            out.push_str(&format!("{}: []\n", kind.key()));
+        } else {
+            out.push_str(&serde_yaml::to_string(&Mapping::from_iter([(Value::from(kind.key()), Value::Sequence(entries))]))?);
+        }
+    }
+    Ok(out)
+}
+
+/// Config entries for remote items, pinned by ID, with the fields `pull` tracks
+pub fn seed_entries(kind: ResourceKind, items: &[serde_json::Value]) -> Vec<Value> {
+    let fields = pull::mappings(kind.key());
+    items
+        .iter()
+        .filter_map(|item| {
+            let name = item["name"].as_str()?;
+            let mut map = Mapping::new();
+            map.insert("name".into(), name.into());
+            if let Some(id) = kind.item_id(item) {
+                map.insert("id".into(), id.into());
+            }
+            for mapping in &fields {
+                if let Some(value) = (mapping.extract)(item) {
+                    map.insert(mapping.field.into(), value);
+                }
+            }
+            // A price of 0 means none was set, which config only accepts off sale
+            if map.get("price").and_then(|p| p.as_u64()) == Some(0) {
+                map.remove("price");
+            }
+            match kind {
+                ResourceKind::GamePasses if !map.contains_key("price") && !map.contains_key("is_for_sale") => {
+                    map.insert("is_for_sale".into(), false.into());
+                }
+                ResourceKind::DeveloperProducts if !map.contains_key("price") => {
+                    warn!("Developer product '{}' has no price; set `price` in its config entry", name);
+                }
+                _ => {}
+            }
+            Some(Value::Mapping(map))
+        })
+        .collect()
+}
+
+/// Append missing `GITIGNORE_ENTRIES` to `<root>/.gitignore`, creating it if
+/// needed. Returns whether the file changed.
+pub fn ensure_gitignore(root: &Path) -> Result<bool> {
+    let path = root.join(".gitignore");
+    let existing = fs::read_to_string(&path).unwrap_or_default();
+    let missing: Vec<&str> = GITIGNORE_ENTRIES
+        .iter()
+        .copied()
+        .filter(|entry| !existing.lines().any(|line| line.trim() == *entry || line.trim() == entry.trim_end_matches('/')))
+        .collect();
+    if missing.is_empty() {
+        return Ok(false);
+    }
+    let mut content = existing;
+    if !content.is_empty() && !content.ends_with('\n') {
+        content.push('\n');
+    }
+    for entry in missing {
+        content.push_str(entry);
+        content.push('\n');
+    }
+    fs::write(&path, content)?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RblxSyncConfig;
+
+    #[test]
+    fn test_render_config_round_trips() {
+        let creator = CreatorConfig { id: "42".to_string(), creator_type: "group".to_string() };
+        let passes = vec![
+            serde_json::json!({ "gamePassId": 7, "name": "VIP", "price": 100, "isForSale": true, "description": "" }),
+            serde_json::json!({ "id": 8 }),
+        ];
+        let products = vec![serde_json::json!({ "productId": "9", "name": "Coins", "priceInformation": { "defaultPriceInRobux": 25 }, "isForSale": false })];
+        let yaml = render_config(123, Some(&creator), &[(ResourceKind::GamePasses, passes), (ResourceKind::DeveloperProducts, products)]).unwrap();
+
+        let config: RblxSyncConfig = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(config.universe.id, 123);
+        assert_eq!(config.creator.as_ref().unwrap().creator_type, "group");
+        assert_eq!(config.game_passes.len(), 1);
+        assert_eq!((config.game_passes[0].id, config.game_passes[0].price, config.game_passes[0].description.as_deref()), (Some(7), Some(100), None));
+        assert_eq!((config.developer_products[0].id, config.developer_products[0].price, config.developer_products[0].is_active), (Some(9), 25, Some(false)));
+        assert!(config.badges.is_empty());
+        assert!(crate::commands::config_problems(&config).is_empty());
+
+        // Passes without a price are seeded off sale; products without one get no made-up price
+        let passes = vec![serde_json::json!({ "gamePassId": 7, "name": "VIP", "price": 0 })];
+        let products = vec![serde_json::json!({ "productId": "9", "name": "Coins" })];
+        let pass = &seed_entries(ResourceKind::GamePasses, &passes)[0];
+        assert_eq!((pass.get("price"), pass["is_for_sale"].as_bool()), (None, Some(false)));
+        assert!(seed_entries(ResourceKind::DeveloperProducts, &products)[0].get("price").is_none());
+
+        let bare: RblxSyncConfig = serde_yaml::from_str(&render_config(5, None, &[]).unwrap()).unwrap();
+        assert!(bare.creator.is_none());
+    }
+}
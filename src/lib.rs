@@ -1,5 +1,40 @@
+pub mod actor;
 pub mod api;
+pub mod asset_cache;
+pub mod asset_import;
+pub mod assets;
+pub mod audit;
 pub mod config;
+pub mod credentials;
+pub mod datastore;
+pub mod fetch;
+pub mod filter;
+pub mod groups;
+pub mod hooks;
+pub mod icon_template;
+pub mod i18n;
+pub mod init;
+pub mod logging;
+pub mod mantle;
 pub mod state;
 pub mod commands;
+pub mod merge;
+pub mod naming;
 pub mod output;
+pub mod progress;
+pub mod prompt;
+pub mod pull;
+pub mod redact;
+pub mod remote_state;
+pub mod report;
+pub mod runlock;
+pub mod scopes;
+pub mod security;
+pub mod serve;
+pub mod snapshot;
+#[cfg(feature = "otel")]
+pub mod telemetry;
+pub mod template;
+pub mod text_check;
+pub mod watch;
+pub mod workspace;
@@ -0,0 +1,163 @@
+//! Log output. Commands log through `tracing`, inside spans for each
+//! resource (`game_pass{resource=VIP}`) and each HTTP call; `tracing-subscriber`
+//! writes every event with its spans to stderr, as text or as JSON lines
+//! (`--log-format json`), and optionally to a log file rolled over daily by
+//! `tracing-appender` (`--log-file`). Records from dependencies that use the
+//! `log` crate go through the same output.
+//!
+//! Filtering follows `RUST_LOG` ([`EnvFilter`] directives). Every line is
+//! passed through [`redact`](crate::redact::redact) before it's written.
+
+use crate::redact::redact;
+use anyhow::{anyhow, Context, Result};
+use std::io::{self, IsTerminal, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer, Registry};
+
+/// Daily log files kept next to the current one (`<file>.YYYY-MM-DD`)
+const KEPT_LOG_FILES: usize = 7;
+
+/// How log lines are written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum LogFormat {
+    /// `time LEVEL target: spans: message`
+    #[default]
+    Text,
+    /// One JSON object per line, for CI log processors
+    Json,
+}
+
+type BoxedLayer = Box<dyn Layer<Registry> + Send + Sync>;
+
+/// Install the global logger. `filter` is used unless `RUST_LOG` is set.
+pub fn init(filter: &str, format: LogFormat, file: Option<&Path>) -> Result<()> {
+    let env_filter = || EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(filter));
+    let color = std::io::stderr().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+    let mut layers = vec![output(format, Redacted(|| AboveBars), color, env_filter())];
+    if let Some(path) = file {
+        layers.push(output(format, Redacted(rolling_file(path)?), false, env_filter()));
+    }
+    #[cfg(feature = "otel")]
+    if crate::telemetry::active() {
+        layers.push(crate::telemetry::layer());
+    }
+    tracing_subscriber::registry().with(layers).try_init().context("A tracing subscriber is already installed")
+}
+
+/// A fmt layer writing `format` lines allowed by `filter` to `writer`
+fn output<W>(format: LogFormat, writer: W, ansi: bool, filter: EnvFilter) -> BoxedLayer
+where
+    W: for<'a> MakeWriter<'a> + Send + Sync + 'static,
+{
+    let layer = tracing_subscriber::fmt::layer().with_writer(writer).with_ansi(ansi);
+    match format {
+        LogFormat::Text => layer.with_filter(filter).boxed(),
+        LogFormat::Json => layer.json().with_current_span(false).with_span_list(true).with_filter(filter).boxed(),
+    }
+}
+
+/// Appender for `path`, started afresh each day
+fn rolling_file(path: &Path) -> Result<RollingFileAppender> {
+    let name = path.file_name().ok_or_else(|| anyhow!("Log file {} has no file name", path.display()))?;
+    let dir = path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(name.to_string_lossy())
+        .max_log_files(KEPT_LOG_FILES)
+        .build(dir)
+        .with_context(|| format!("Failed to open log file {}", path.display()))
+}
+
+/// Stderr, with progress bars cleared while a line is written
+struct AboveBars;
+
+impl Write for AboveBars {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        crate::progress::suspend(|| io::stderr().lock().write_all(buf))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        io::stderr().flush()
+    }
+}
+
+/// Writers whose lines are redacted before they reach `W`
+struct Redacted<W>(W);
+
+impl<'a, W: MakeWriter<'a>> MakeWriter<'a> for Redacted<W> {
+    type Writer = RedactedWriter<W::Writer>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        RedactedWriter(self.0.make_writer())
+    }
+}
+
+/// Writes each line given to it (the fmt layer writes a line at a time)
+/// with secrets replaced
+struct RedactedWriter<W>(W);
+
+impl<W: Write> Write for RedactedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.write_all(redact(&String::from_utf8_lossy(buf)).as_bytes())?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
+
+/// ISO 8601 time in UTC with milliseconds, e.g. `2024-05-01T12:00:00.000Z`
+pub(crate) fn timestamp(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = since_epoch.as_secs();
+    let (y, m, d) = crate::template::civil_from_days((secs / 86_400) as i64);
+    let day_secs = secs % 86_400;
+    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z", y, m, d, day_secs / 3600, day_secs / 60 % 60, day_secs % 60, since_epoch.subsec_millis())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_output() {
+        crate::redact::register("logging-secret-key");
+        let buffer = Buffer::default();
+        let writer = buffer.clone();
+        let subscriber = tracing_subscriber::registry().with(vec![output(LogFormat::Json, Redacted(move || writer.clone()), false, EnvFilter::new("info"))]);
+        tracing::subscriber::with_default(subscriber, || {
+            let _span = tracing::info_span!("game_pass", resource = "VIP").entered();
+            tracing::info!(status = 200, "Sent logging-secret-key");
+            tracing::debug!("Filtered out");
+        });
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<serde_json::Value> = output.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0]["fields"]["message"], "Sent <redacted>");
+        assert_eq!(lines[0]["fields"]["status"], 200);
+        assert_eq!((lines[0]["spans"][0]["name"].as_str(), lines[0]["spans"][0]["resource"].as_str()), (Some("game_pass"), Some("VIP")));
+    }
+}
@@ -1,115 +1,1334 @@
-use clap::{Parser, Subcommand};
-use rblxsync::config::{Config, RblxSyncConfig};
-use rblxsync::api::{RobloxClient, RobloxCookieClient};
-use rblxsync::state::SyncState;
+use clap::{Args, CommandFactory, Parser, Subcommand};
+use rblxsync::config::{Config, CreatorConfig, RblxSyncConfig, RestrictionConfig};
+use rblxsync::api::fixtures::{Recorder, Replayer};
+use rblxsync::api::har::HarLog;
+use rblxsync::api::{ClientOptions, RobloxClient, RobloxCookieClient};
+use rblxsync::state::{EntryKind, SyncState};
+use rblxsync::actor;
 use rblxsync::commands;
-use log::{info, error};
+use rblxsync::i18n::Msg;
+use rblxsync::filter::{NameFilter, ResourceAddress, SyncSection};
+use rblxsync::prompt::Confirmation;
+use rblxsync::remote_state::RemoteState;
+use rblxsync::logging::LogFormat;
+use rblxsync::report::{OutputMode, RunReport};
+use rblxsync::runlock::RunLock;
+use rblxsync::security;
+use rblxsync::serve::{self, Action};
+use rblxsync::watch::{ChangeFilter, ChangeWatcher};
+use rblxsync::workspace::{Workspace, WORKSPACE_FILE};
+use anyhow::{anyhow, Context};
+use tracing::{debug, info, warn, error, Instrument};
+use std::io::IsTerminal;
 use std::path::Path;
+use std::sync::OnceLock;
 
 #[derive(Parser)]
-#[command(name = "rblxsync")]
+#[command(name = "rblxsync", version)]
 #[command(about = "Manage Roblox experience metadata via Open Cloud", long_about = None)]
 struct Cli {
     #[command(subcommand)]
     command: Option<Commands>,
 
-    /// Path to config file
-    #[arg(short, long, default_value = "rblxsync.yml")]
-    config: String,
+    /// Path to config file [default: rblxsync.yml (or .yaml, .toml, .json) in
+    /// this or the nearest parent directory]
+    #[arg(short, long, global = true)]
+    config: Option<String>,
+
+    /// Load environment variables from this file instead of .env
+    #[arg(long, global = true, value_name = "FILE")]
+    env: Option<String>,
+
+    /// Print newline-delimited JSON events on stdout instead of log lines
+    #[arg(long, global = true, value_enum, default_value = "text")]
+    output: OutputMode,
+
+    /// Log more detail: -v for debug logs, -vv for trace (RUST_LOG overrides)
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    verbose: u8,
+
+    /// Format of log lines on stderr and in --log-file
+    #[arg(long, global = true, value_enum, default_value = "text")]
+    log_format: LogFormat,
+
+    /// Also append log lines to this file, rolled over daily
+    #[arg(long, global = true, value_name = "FILE")]
+    log_file: Option<String>,
+
+    /// Fail the command when the end-of-run security audit finds issues
+    #[arg(long, global = true)]
+    strict_security: bool,
+
+    /// Use this entry of the config's `universes` (e.g. main or test)
+    #[arg(long, global = true, value_name = "NAME", env = "RBLXSYNC_UNIVERSE")]
+    universe: Option<String>,
+
+    /// Credential profile: from credentials.toml, or saved with `rblxsync login`
+    #[arg(long, global = true, value_name = "NAME", env = "RBLXSYNC_PROFILE")]
+    profile: Option<String>,
+
+    /// Target this universe instead of the one in the config file
+    #[arg(long, global = true, value_name = "ID", env = "RBLXSYNC_UNIVERSE_ID")]
+    universe_id: Option<u64>,
+
+    /// Repeat the --universe-id value to allow changes to an overridden universe
+    #[arg(long, global = true, value_name = "ID", env = "RBLXSYNC_CONFIRM_UNIVERSE")]
+    confirm_universe: Option<u64>,
+
+    /// Approve costly or destructive operations without prompting
+    #[arg(long, short = 'y', global = true)]
+    yes: bool,
+
+    /// Never prompt; operations that need confirmation fail unless --yes is set
+    #[arg(long, global = true)]
+    non_interactive: bool,
+
+    /// Seconds to wait for another rblxsync process changing this project to finish
+    #[arg(long, global = true, value_name = "SECONDS", default_value_t = 0, env = "RBLXSYNC_LOCK_TIMEOUT")]
+    lock_timeout: u64,
+
+    /// Run the command for every project in rblxsync-workspace.yml
+    #[arg(long, global = true)]
+    all: bool,
+
+    /// Run the command for this workspace project (repeatable)
+    #[arg(long = "project", global = true, value_name = "NAME")]
+    project: Vec<String>,
+
+    /// Save every Open Cloud request and response to this directory, for --replay
+    #[arg(long, global = true, value_name = "DIR", conflicts_with = "replay")]
+    record: Option<String>,
+
+    /// Answer Open Cloud requests from a directory written by --record instead of calling Roblox
+    #[arg(long, global = true, value_name = "DIR")]
+    replay: Option<String>,
+
+    /// Write all Open Cloud traffic to this HAR file, with credentials redacted
+    #[arg(long, global = true, value_name = "FILE")]
+    debug_http: Option<String>,
+}
+
+/// How API clients of this process are set up, from --record, --replay and --debug-http
+#[derive(Default)]
+struct ApiSetup {
+    options: ClientOptions,
+    /// Requests are answered from fixtures, so no API key is needed
+    replay: bool,
+}
+
+static API_SETUP: OnceLock<ApiSetup> = OnceLock::new();
+
+impl ApiSetup {
+    fn from_args(args: &Cli) -> anyhow::Result<Self> {
+        let mut options = ClientOptions::new();
+        // Before a replay, which answers requests without calling later middleware
+        if let Some(path) = &args.debug_http {
+            options = options.with_middleware(HarLog::new(Path::new(path))?);
+        }
+        Ok(match (&args.record, &args.replay) {
+            (Some(dir), _) => Self { options: options.with_middleware(Recorder::new(Path::new(dir))?), replay: false },
+            (_, Some(dir)) => Self { options: options.with_middleware(Replayer::load(Path::new(dir))?), replay: true },
+            _ => Self { options, replay: false },
+        })
+    }
+
+    fn get() -> &'static Self {
+        API_SETUP.get_or_init(Self::default)
+    }
+}
+
+/// Open Cloud client for `api_key`, recording or replaying when requested
+fn api_client(api_key: String) -> RobloxClient {
+    RobloxClient::with_options(api_key, ApiSetup::get().options.clone())
 }
 
-#[derive(Subcommand)]
+/// Universe override from the command line
+struct UniverseTarget {
+    id: Option<u64>,
+    confirm: Option<u64>,
+    /// Whether the override may be confirmed at a prompt instead
+    interactive: bool,
+    /// `universe_id` of the credential profile, for commands that don't
+    /// read a config
+    profile_default: Option<u64>,
+}
+
+#[derive(Clone, Subcommand)]
 enum Commands {
     /// Sync universe settings and assets (default)
     Run {
         /// Preview changes without applying them
         #[arg(long)]
         dry_run: bool,
+        /// Only update or adopt existing resources; skip anything that would be created
+        #[arg(long)]
+        no_create: bool,
+        /// Remote ID to use when several remote resources share a configured name (repeatable)
+        #[arg(long = "use-id", value_name = "ID")]
+        use_ids: Vec<u64>,
+        /// Only sync these sections (comma-separated)
+        #[arg(long, value_enum, value_delimiter = ',')]
+        only: Vec<SyncSection>,
+        /// Only sync resources whose name matches a glob ("VIP*") or /regex/
+        #[arg(long, value_name = "PATTERN")]
+        filter: Option<NameFilter>,
+        /// Only sync this resource, e.g. game_pass.VIP or universe (repeatable)
+        #[arg(long = "target", value_name = "ADDRESS")]
+        targets: Vec<ResourceAddress>,
+        /// Allow price changes and taking resources off sale for `protected` resources
+        #[arg(long)]
+        allow_protected: bool,
+        /// Allow price changes larger than max_price_change_percent
+        #[arg(long)]
+        confirm_price_changes: bool,
+        /// Replace edits made outside rblxsync (e.g. on the Creator Dashboard) since the last sync
+        #[arg(long)]
+        overwrite_remote_changes: bool,
+        /// Don't reuse icons uploaded by other projects (see the asset cache)
+        #[arg(long)]
+        no_cache: bool,
+        /// Also write the end-of-run summary to a file in this format
+        #[arg(long, value_enum, value_name = "FORMAT")]
+        report: Option<ReportFormat>,
+        /// Where to write the --report file
+        #[arg(long, default_value = "rblxsync-report.json", requires = "report")]
+        report_file: String,
+        /// Write the changes as a markdown table to this file, e.g. for a PR comment
+        #[arg(long, value_name = "FILE")]
+        markdown: Option<String>,
+    },
+    /// Create a starter config, .rblxsync/, .env.example and .gitignore entries
+    Init {
+        /// Creator user or group ID for icon uploads
+        #[arg(long)]
+        creator_id: Option<String>,
+        /// Whether --creator-id is a user or a group
+        #[arg(long, default_value = "user", value_parser = ["user", "group"], requires = "creator_id")]
+        creator_type: String,
+        /// Seed the config with the universe's existing passes, products and badges (needs ROBLOX_API_KEY)
+        #[arg(long)]
+        from_remote: bool,
+        /// Overwrite an existing config file
+        #[arg(long)]
+        force: bool,
+    },
+    /// Convert another tool's project into an rblxsync config and lock file
+    Migrate {
+        /// Tool the project comes from
+        #[arg(long, value_parser = ["mantle"])]
+        from: String,
+        /// Its config file, e.g. mantle.yml
+        source: String,
+        /// Its state file (default: .mantle-state.yml next to the config)
+        #[arg(long)]
+        state: Option<String>,
+        /// Environment to take resource IDs from, when the state file has several
+        #[arg(long)]
+        environment: Option<String>,
+        /// Overwrite an existing config file and add to an existing lock file
+        #[arg(long)]
+        force: bool,
+    },
+    /// Mirror game passes, developer products and badges from one universe to another
+    Promote {
+        /// Source universe: a name from the config's `universes` or a universe ID
+        #[arg(long)]
+        from: String,
+        /// Target universe: a name from the config's `universes` or a universe ID
+        #[arg(long)]
+        to: String,
+        /// Also copy the universe name and description (needs ROBLOX_COOKIE)
+        #[arg(long)]
+        with_settings: bool,
+        /// Preview changes without applying them
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Save remote resources, icons and universe settings to a snapshot archive
+    Backup {
+        /// Snapshot file to write
+        #[arg(long, default_value = "snapshot.tar.zst")]
+        out: String,
+        /// Also include the configured place files
+        #[arg(long)]
+        places: bool,
+    },
+    /// Reapply a snapshot written by `backup` to the configured universe
+    Restore {
+        /// Snapshot file to read
+        snapshot: String,
+        /// Re-upload the icons stored in the snapshot
+        #[arg(long)]
+        with_icons: bool,
+        /// Publish the place files stored in the snapshot
+        #[arg(long)]
+        places: bool,
+        /// Preview changes without applying them
+        #[arg(long)]
+        dry_run: bool,
     },
     /// Publish place files
-    Publish,
+    Publish {
+        /// Seconds to wait for each published version to become the
+        /// place's current version
+        #[arg(long, value_name = "SECS", default_value_t = commands::VERIFY_WAIT_SECS)]
+        wait_live: u64,
+        /// Don't check that the published versions are current
+        #[arg(long)]
+        no_verify: bool,
+        /// Publish places that have a test place to production, after a
+        /// confirmation, if their file is the one last published to the test place
+        #[arg(long)]
+        promote: bool,
+    },
+    /// Restart every running server of the universe
+    RestartServers,
+    /// Run a Luau script inside a place through Open Cloud Luau Execution
+    RunScript {
+        /// Luau file to run
+        file: String,
+        /// Place to run the script in
+        #[arg(long)]
+        place: u64,
+        /// Place version (default: latest)
+        #[arg(long)]
+        version: Option<u64>,
+        /// Seconds the script may run
+        #[arg(long, default_value_t = 60)]
+        timeout: u64,
+    },
+    /// Send an experience notification to opted-in users
+    Notify {
+        /// User to notify; repeat for several users
+        #[arg(long = "user", required = true)]
+        users: Vec<u64>,
+        /// Notification string asset ID from the Creator Dashboard
+        #[arg(long)]
+        message_id: String,
+        /// Value for a `{parameter}` in the notification string, as key=value; repeatable
+        #[arg(long = "param", value_parser = parse_key_value)]
+        params: Vec<(String, String)>,
+        /// Launch data passed to the experience when joining from the notification
+        #[arg(long)]
+        launch_data: Option<String>,
+        /// Analytics category
+        #[arg(long)]
+        category: Option<String>,
+        /// List the users without notifying them
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Print the universe's visits, players, favorites and votes
+    Stats {
+        /// Output format
+        #[arg(long, value_enum, default_value = "json")]
+        format: commands::StatsFormat,
+        /// Write to this file instead of stdout (CSV rows are appended)
+        #[arg(long)]
+        out: Option<String>,
+    },
+    /// Send a JSON message to live servers through MessagingService
+    Message {
+        /// MessagingService topic
+        topic: String,
+        /// JSON payload
+        message: String,
+    },
     /// Validate configuration file
     Validate,
     /// Export existing resources to Luau/Lua
     Export {
         /// Output file path
         #[arg(short, long)]
-        output: Option<String>,
+        out: Option<String>,
         /// Export as Lua instead of Luau
         #[arg(long)]
         lua: bool,
+        /// Continue an interrupted export from its last checkpoint
+        #[arg(long)]
+        resume: bool,
+    },
+    /// Update the config file from remote values (prices, descriptions, enabled flags)
+    Pull {
+        /// Show the diff without writing the config file
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Report drift between remote resources and the config; exits with code 2 when drift is found
+    Check,
+    /// Re-check changes awaiting moderation and finalize them
+    VerifyPending,
+    /// Show tracked resources and who last synced each one
+    Status,
+    /// Show applied changes from the audit log, newest last
+    History {
+        /// Only changes to this resource (name or ID)
+        name: Option<String>,
+        /// Only this resource type, e.g. game_pass
+        #[arg(long = "type", value_name = "TYPE")]
+        resource_type: Option<String>,
+        /// Only changes to this field, e.g. price
+        #[arg(long)]
+        field: Option<String>,
+        /// Only changes by actors containing this text
+        #[arg(long)]
+        actor: Option<String>,
+        /// Show at most this many changes
+        #[arg(long, default_value_t = 50)]
+        limit: usize,
+    },
+    /// Inspect and repair the lock file
+    State {
+        #[command(subcommand)]
+        action: StateCommand,
+    },
+    /// Work with the live icons of tracked resources
+    Icons {
+        #[command(subcommand)]
+        action: IconsCommand,
+    },
+    /// Clean up assets uploaded by earlier runs
+    Assets {
+        #[command(subcommand)]
+        action: AssetsCommand,
+    },
+    /// Enable or disable tracked badges without a full sync
+    Badges {
+        #[command(subcommand)]
+        action: BadgesCommand,
+    },
+    /// Make the universe public or private (requires ROBLOX_COOKIE)
+    Universe {
+        #[command(subcommand)]
+        action: UniverseCommand,
+    },
+    /// List, ban and unban users of the universe
+    Restrictions {
+        #[command(subcommand)]
+        action: RestrictionsCommand,
+    },
+    /// Read and write standard data store entries
+    Datastore {
+        #[command(subcommand)]
+        action: DatastoreCommand,
+    },
+    /// Save an API key in the OS keychain (under --profile, default "default")
+    Login {
+        /// Read the key from stdin instead of prompting
+        #[arg(long)]
+        stdin: bool,
+    },
+    /// Remove the API key saved with `login`
+    Logout,
+    /// Show who owns the API key, its scopes and which universes it can reach
+    Whoami {
+        /// Universe IDs to check access to (default: the configured universe)
+        universe_ids: Vec<u64>,
     },
+    /// Sync, then sync again whenever the config or assets_dir changes
+    Watch {
+        /// Preview changes without applying them
+        #[arg(long)]
+        dry_run: bool,
+        /// Milliseconds without further changes to wait before syncing
+        #[arg(long, default_value_t = 500)]
+        debounce: u64,
+    },
+    /// Serve an HTTP API that triggers plan, sync and publish and streams their progress
+    Serve {
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1:8090")]
+        listen: std::net::SocketAddr,
+        /// Token every request must send as `Authorization: Bearer <TOKEN>`
+        /// (default: a random one, printed at startup)
+        #[arg(long, value_name = "TOKEN", env = "RBLXSYNC_SERVE_TOKEN", hide_env_values = true)]
+        token: Option<String>,
+    },
+    /// Print the effective config, with user-level defaults applied
+    Config,
+    /// Print a shell completion script, e.g. `rblxsync completions zsh > _rblxsync`
+    Completions {
+        /// Shell to generate completions for
+        #[arg(value_enum)]
+        shell: clap_complete::Shell,
+    },
+    /// Show which API key scopes this config needs
+    Scopes {
+        /// Only list scopes needed for read-only commands (export, run --dry-run)
+        #[arg(long)]
+        read_only: bool,
+    },
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum ReportFormat {
+    Json,
+}
+
+#[derive(Clone, Subcommand)]
+enum StateCommand {
+    /// List every entry in the lock file
+    List,
+    /// Show a single entry
+    Show {
+        /// Entry kind
+        #[arg(value_enum)]
+        kind: EntryKind,
+        /// Entry name (case-insensitive) or ID
+        name: String,
+    },
+    /// Remove an entry so the next sync matches it by name again
+    Rm {
+        /// Entry kind
+        #[arg(value_enum)]
+        kind: EntryKind,
+        /// Entry name (case-insensitive) or ID
+        name: String,
+    },
+    /// Rename an entry
+    Mv {
+        /// Current entry name (case-insensitive) or ID
+        old: String,
+        /// New name
+        new: String,
+        /// Entry kind, required when the name exists in several kinds
+        #[arg(long = "type", value_enum)]
+        kind: Option<EntryKind>,
+    },
+    /// Restore the lock file as it was before its last change
+    Rollback,
+    /// Rebuild the lock file from remote resources, matching by pinned ID, name or alias
+    Refresh {
+        /// Preview the rebuilt state without writing it
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Clone, Subcommand)]
+enum IconsCommand {
+    /// Download the current icon of every tracked resource into assets_dir
+    Download {
+        /// Overwrite icon files that already exist
+        #[arg(long)]
+        force: bool,
+        /// List the files that would be written without downloading
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Record icons already uploaded by Tarmac or Asphalt in the lock file
+    Import {
+        /// Their lockfile, e.g. tarmac-manifest.toml or asphalt.lock.toml
+        lockfile: String,
+        /// List the icons that would be recorded without changing the lock file
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Clone, Subcommand)]
+enum AssetsCommand {
+    /// Archive icons in the lock file that no resource uses anymore
+    Gc {
+        /// List the icons that would be archived without archiving them
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Clone, Subcommand)]
+enum BadgesCommand {
+    /// Enable badges, e.g. when an event starts
+    Enable(BadgeSelection),
+    /// Disable badges, e.g. when a seasonal event ends
+    Disable(BadgeSelection),
+}
+
+#[derive(Clone, Args)]
+#[group(skip)]
+#[command(group(clap::ArgGroup::new("badges").required(true).args(["all", "filter"])))]
+struct BadgeSelection {
+    /// Every badge in the lock file
+    #[arg(long)]
+    all: bool,
+    /// Badges whose name matches a glob (`Event*`) or `/regex/`
+    #[arg(long)]
+    filter: Option<NameFilter>,
+    /// List the badges that would change without changing them
+    #[arg(long)]
+    dry_run: bool,
+}
+
+#[derive(Clone, Subcommand)]
+enum RestrictionsCommand {
+    /// List active bans
+    List {
+        /// Only list bans from this place
+        #[arg(long)]
+        place: Option<u64>,
+    },
+    /// Ban a user
+    Ban {
+        /// User to ban
+        user_id: u64,
+        /// Ban from this place only instead of the whole universe
+        #[arg(long)]
+        place: Option<u64>,
+        /// Ban length in seconds (default: permanent)
+        #[arg(long)]
+        duration: Option<u64>,
+        /// Reason shown to the user
+        #[arg(long)]
+        reason: Option<String>,
+        /// Reason only visible to moderators
+        #[arg(long)]
+        private_reason: Option<String>,
+        /// Also ban the user's alternate accounts
+        #[arg(long)]
+        ban_alt_accounts: bool,
+        /// Show the change without applying it
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Lift a user's ban
+    Unban {
+        /// User to unban
+        user_id: u64,
+        /// Lift the ban from this place only
+        #[arg(long)]
+        place: Option<u64>,
+        /// Show the change without applying it
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Clone, Subcommand)]
+enum DatastoreCommand {
+    /// Print an entry's value
+    Get {
+        /// Data store name
+        datastore: String,
+        /// Entry key
+        key: String,
+        /// Data store scope (default: global)
+        #[arg(long)]
+        scope: Option<String>,
+    },
+    /// Create or overwrite an entry with a JSON value
+    Set {
+        /// Data store name
+        datastore: String,
+        /// Entry key
+        key: String,
+        /// JSON value, e.g. '{"enabled": true}' or '42'
+        value: String,
+        /// Data store scope (default: global)
+        #[arg(long)]
+        scope: Option<String>,
+        /// Show the change without writing it
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Print every key in a data store
+    ListEntries {
+        /// Data store name
+        datastore: String,
+        /// Only list keys starting with this prefix
+        #[arg(long)]
+        prefix: Option<String>,
+        /// Data store scope (default: global)
+        #[arg(long)]
+        scope: Option<String>,
+    },
+    /// Dump every entry to a JSON Lines file
+    Export {
+        /// Data store name
+        datastore: String,
+        /// Dump file to write
+        #[arg(long)]
+        out: String,
+        /// Data store scope (default: global)
+        #[arg(long)]
+        scope: Option<String>,
+        /// Continue an interrupted export from its last checkpoint
+        #[arg(long)]
+        resume: bool,
+    },
+    /// Write every entry of a dump from `export`, overwriting existing keys
+    Import {
+        /// Data store name
+        datastore: String,
+        /// Dump file to read
+        file: String,
+        /// Data store scope (default: global)
+        #[arg(long)]
+        scope: Option<String>,
+        /// Count the entries without writing them
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Delete an entry
+    Delete {
+        /// Data store name
+        datastore: String,
+        /// Entry key
+        key: String,
+        /// Data store scope (default: global)
+        #[arg(long)]
+        scope: Option<String>,
+        /// Show the change without deleting anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Clone, Subcommand)]
+enum UniverseCommand {
+    /// Make the universe public
+    Activate,
+    /// Make the universe private, e.g. for maintenance
+    Deactivate,
+}
+
+impl Commands {
+    /// Command name as recorded in the run manifest
+    fn name(&self) -> &'static str {
+        match self {
+            Commands::Run { .. } => "run",
+            Commands::Init { .. } => "init",
+            Commands::Migrate { .. } => "migrate",
+            Commands::Promote { .. } => "promote",
+            Commands::Backup { .. } => "backup",
+            Commands::Restore { .. } => "restore",
+            Commands::Publish { .. } => "publish",
+            Commands::RestartServers => "restart-servers",
+            Commands::RunScript { .. } => "run-script",
+            Commands::Message { .. } => "message",
+            Commands::Notify { .. } => "notify",
+            Commands::Stats { .. } => "stats",
+            Commands::Validate => "validate",
+            Commands::Export { .. } => "export",
+            Commands::Pull { .. } => "pull",
+            Commands::Check => "check",
+            Commands::State { .. } => "state",
+            Commands::Status => "status",
+            Commands::History { .. } => "history",
+            Commands::VerifyPending => "verify-pending",
+            Commands::Icons { .. } => "icons",
+            Commands::Assets { .. } => "assets",
+            Commands::Badges { .. } => "badges",
+            Commands::Universe { .. } => "universe",
+            Commands::Restrictions { .. } => "restrictions",
+            Commands::Datastore { .. } => "datastore",
+            Commands::Login { .. } => "login",
+            Commands::Logout => "logout",
+            Commands::Whoami { .. } => "whoami",
+            Commands::Config => "config",
+            Commands::Watch { .. } => "watch",
+            Commands::Serve { .. } => "serve",
+            Commands::Completions { .. } => "completions",
+            Commands::Scopes { .. } => "scopes",
+        }
+    }
+
+    /// Whether the command reads or writes the lock file, and so needs the
+    /// remote copy when a `state_backend` is configured
+    fn uses_state(&self) -> bool {
+        matches!(
+            self,
+            Commands::Run { .. }
+                | Commands::Publish { .. }
+                | Commands::Promote { .. }
+                | Commands::Backup { .. }
+                | Commands::Restore { .. }
+                | Commands::Export { .. }
+                | Commands::Status
+                | Commands::State { .. }
+                | Commands::Icons { .. }
+                | Commands::Assets { .. }
+                | Commands::Badges { .. }
+                | Commands::Universe { .. }
+                | Commands::VerifyPending
+        )
+    }
+
+    /// Whether the command changes the universe or the lock file, and so
+    /// must not run alongside another such command
+    fn needs_lock(&self) -> bool {
+        match self {
+            Commands::Run { dry_run, .. } | Commands::Promote { dry_run, .. } | Commands::Restore { dry_run, .. } | Commands::Pull { dry_run } => !dry_run,
+            Commands::State { action } => matches!(action, StateCommand::Rm { .. } | StateCommand::Mv { .. } | StateCommand::Rollback | StateCommand::Refresh { dry_run: false }),
+            Commands::Badges { action: BadgesCommand::Enable(selection) | BadgesCommand::Disable(selection) } => !selection.dry_run,
+            Commands::Icons { action: IconsCommand::Import { dry_run, .. } } => !dry_run,
+            Commands::Assets { action: AssetsCommand::Gc { dry_run } } => !dry_run,
+            Commands::Publish { .. } | Commands::VerifyPending | Commands::Universe { .. } | Commands::Migrate { .. } => true,
+            _ => false,
+        }
+    }
+}
+
+/// Load the `--env` file or `.env`. `RBLXSYNC_CONFIRM_UNIVERSE` is dropped
+/// from it: a confirmation committed with the project would disable the
+/// universe guard for everyone, so it only counts from the real environment
+/// or `--confirm-universe`. Returns whether the file tried to set it.
+fn load_env_file() -> (anyhow::Result<()>, bool) {
+    const CONFIRM: &str = "RBLXSYNC_CONFIRM_UNIVERSE";
+    let from_process = std::env::var_os(CONFIRM).is_some();
+    let loaded = match env_file_arg() {
+        Some(path) => dotenvy::from_path(&path).map_err(|e| anyhow!("Failed to load --env file {}: {}", path, e)),
+        None => {
+            let _ = dotenvy::dotenv();
+            Ok(())
+        }
+    };
+    let from_file = !from_process && std::env::var_os(CONFIRM).is_some();
+    if from_file {
+        std::env::remove_var(CONFIRM);
+    }
+    (loaded, from_file)
+}
+
+/// Turn `export --output <file>`, the spelling from before `--output` became
+/// the global output mode, into `export --out <file>`. Values `text` and
+/// `json` are left to the global flag. Returns whether anything was rewritten.
+fn rewrite_legacy_export_output(mut argv: Vec<std::ffi::OsString>) -> (Vec<std::ffi::OsString>, bool) {
+    let is_mode = |value: &str| matches!(value, "text" | "json");
+    // `datastore export` never had `--output`
+    let Some(start) = argv.iter().position(|arg| arg == "export" || arg == "datastore").filter(|&i| argv[i] == "export") else {
+        return (argv, false);
+    };
+    let mut rewritten = false;
+    for i in start + 1..argv.len() {
+        let Some(arg) = argv[i].to_str().map(str::to_string) else { continue };
+        if arg == "--output" && argv.get(i + 1).and_then(|v| v.to_str()).is_some_and(|v| !is_mode(v)) {
+            argv[i] = "--out".into();
+            rewritten = true;
+        } else if let Some(value) = arg.strip_prefix("--output=").filter(|v| !is_mode(v)) {
+            argv[i] = format!("--out={}", value).into();
+            rewritten = true;
+        }
+    }
+    (argv, rewritten)
 }
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+    // Load .env first so it can set flag defaults such as RBLXSYNC_UNIVERSE_ID
+    let (env_loaded, confirm_in_env_file) = load_env_file();
+    let (argv, legacy_export_output) = rewrite_legacy_export_output(std::env::args_os().collect());
+    let mut args = Cli::parse_from(argv);
+    rblxsync::config::select_universe(args.universe.clone());
+    rblxsync::credentials::select_profile(args.profile.clone());
+    // JSON mode keeps stdout for events; only warnings and errors are logged (to stderr)
+    let default_level = if args.output == OutputMode::Json { "warn" } else { "info" };
+    let default_filter = match args.verbose {
+        0 => default_level.to_string(),
+        1 => format!("{},rblxsync=debug", default_level),
+        _ => "debug,rblxsync=trace".to_string(),
+    };
+    if let Err(e) = rblxsync::logging::init(&default_filter, args.log_format, args.log_file.as_deref().map(Path::new)) {
+        eprintln!("Error: {:#}", e);
+        std::process::exit(1);
+    }
+    // Bars only where someone watches them; CI logs get plain lines instead
+    rblxsync::progress::enable(std::io::stderr().is_terminal() && args.log_format == LogFormat::Text && args.output == OutputMode::Text);
+    if let Err(e) = env_loaded {
+        error!("{:#}", e);
+        std::process::exit(1);
+    }
+    if confirm_in_env_file {
+        warn!("Ignoring RBLXSYNC_CONFIRM_UNIVERSE from the env file; set it in the environment or pass --confirm-universe");
+    }
+    if legacy_export_output {
+        warn!("`export --output <file>` is deprecated; use `export --out <file>` (`--output` now sets the output mode)");
+    }
+    match ApiSetup::from_args(&args) {
+        Ok(setup) => {
+            let _ = API_SETUP.set(setup);
+        }
+        Err(e) => {
+            error!("{:#}", e);
+            std::process::exit(1);
+        }
+    }
 
-    let args = Cli::parse();
-    
-    // Check for "Validate" command early to avoid needing API key if possible, 
-    // but for now we'll load env for all.
-    let env_config = Config::from_env(); 
+    if let Some(Commands::Completions { shell }) = args.command {
+        clap_complete::generate(shell, &mut Cli::command(), "rblxsync", &mut std::io::stdout());
+        return Ok(());
+    }
 
-    let command = args.command.unwrap_or(Commands::Run { dry_run: false });
+    let command = args.command.take().unwrap_or_else(|| plain_run(false));
 
-    match command {
-        Commands::Validate => {
-            let path = Path::new(&args.config);
-            if !path.exists() {
-                error!("Config file not found: {}", args.config);
+    let confirm = Confirmation::from_flags(args.yes, args.non_interactive);
+    let profile_default = match rblxsync::credentials::load_profile(rblxsync::credentials::selected_profile()) {
+        Ok(profile) => profile.and_then(|p| p.universe_id),
+        Err(e) => {
+            warn!("{:#}", e);
+            None
+        }
+    };
+    let target = UniverseTarget { id: args.universe_id, confirm: args.confirm_universe, interactive: confirm.interactive, profile_default };
+
+    if args.all || !args.project.is_empty() {
+        let code = match run_workspace(&args, command, &target, confirm).await {
+            Ok(code) => code,
+            Err(e) => {
+                error!("{:#}", e);
+                1
+            }
+        };
+        if code != 0 {
+            std::process::exit(code);
+        }
+        return Ok(());
+    }
+
+    // Without --config, look upward so commands work from any subdirectory.
+    // `init` creates the config, so it always uses the current directory.
+    let discovered = match (&args.config, &command) {
+        (None, Commands::Init { .. }) | (Some(_), _) => None,
+        (None, _) => std::env::current_dir().ok().and_then(|cwd| rblxsync::config::discover_config(&cwd)),
+    };
+    let config_arg = match (&args.config, &discovered) {
+        (Some(path), _) => path.clone(),
+        (None, Some(path)) => path.to_string_lossy().into_owned(),
+        (None, None) => "rblxsync.yml".to_string(),
+    };
+    // A config found in a parent directory is run from there, so paths in it
+    // (assets_dir, place files, output_path) resolve as they do at the root
+    let config_arg = match discovered.as_deref().and_then(|path| Some((path.parent()?, path.file_name()?))) {
+        Some((root, file)) if !root.as_os_str().is_empty() => {
+            info!("Using config {}", config_arg);
+            if let Err(e) = std::env::set_current_dir(root) {
+                error!("Failed to enter {}: {}", root.display(), e);
                 std::process::exit(1);
             }
-            match RblxSyncConfig::load(path) {
-                Ok(config) => {
-                    // Run additional validation checks
-                    if let Err(e) = commands::validate(&config) {
-                        error!("Config validation failed: {}", e);
-                        std::process::exit(1);
-                    }
-                    info!("Config file is valid.");
+            file.to_string_lossy().into_owned()
+        }
+        _ => config_arg,
+    };
+    let code = match command {
+        Commands::Watch { dry_run, debounce } => watch(&args, &config_arg, &target, confirm, dry_run, debounce).await,
+        Commands::Serve { listen, token } => serve(&args, &config_arg, &target, listen, token).await,
+        command => invoke(&args, command, &config_arg, &target, confirm, None).await,
+    };
+    if code != 0 {
+        std::process::exit(code);
+    }
+    Ok(())
+}
+
+/// Value of `--env` in the raw arguments. The file can set flag defaults,
+/// so it has to be loaded before the arguments are parsed.
+fn env_file_arg() -> Option<String> {
+    let mut raw = std::env::args().skip(1);
+    while let Some(arg) = raw.next() {
+        match arg.as_str() {
+            "--" => break,
+            "--env" => return raw.next(),
+            _ => {
+                if let Some(path) = arg.strip_prefix("--env=") {
+                    return Some(path.to_string());
                 }
-                Err(e) => {
-                    error!("Config validation failed: {}", e);
-                    std::process::exit(1);
+            }
+        }
+    }
+    None
+}
+
+/// `run` with default options
+fn plain_run(dry_run: bool) -> Commands {
+    Commands::Run { dry_run, no_create: false, use_ids: Vec::new(), only: Vec::new(), filter: None, targets: Vec::new(), allow_protected: false, confirm_price_changes: false, overwrite_remote_changes: false, no_cache: false, report: None, report_file: String::new(), markdown: None }
+}
+
+/// Sync, then sync again after every change to the config or assets_dir
+/// until interrupted. Failed syncs are logged and watching continues.
+async fn watch(args: &Cli, config_arg: &str, target: &UniverseTarget, confirm: Confirmation, dry_run: bool, debounce_ms: u64) -> i32 {
+    let assets_dir = match RblxSyncConfig::load(Path::new(config_arg)) {
+        Ok(config) => config.assets_dir,
+        Err(e) => {
+            error!("{:#}", e);
+            return 1;
+        }
+    };
+    let mut watcher = match ChangeWatcher::new(ChangeFilter::new(Path::new(config_arg), Path::new(&assets_dir))) {
+        Ok(watcher) => watcher,
+        Err(e) => {
+            error!("{:#}", e);
+            return 1;
+        }
+    };
+
+    invoke(args, plain_run(dry_run), config_arg, target, confirm, None).await;
+    loop {
+        info!("Watching {} and {}/ for changes (Ctrl-C to stop)", config_arg, assets_dir);
+        match watcher.next_change(std::time::Duration::from_millis(debounce_ms)).await {
+            Ok(paths) => {
+                for path in &paths {
+                    info!("Changed: {}", path.display());
                 }
             }
-            return Ok(());
+            Err(e) => {
+                error!("{:#}", e);
+                return 1;
+            }
         }
-        _ => {}
+        invoke(args, plain_run(dry_run), config_arg, target, confirm, None).await;
     }
+}
 
-    // Load Env Config (API Key)
-    let env_config = match env_config {
+/// Run plan, sync and publish jobs triggered over HTTP, one at a time,
+/// until interrupted
+async fn serve(args: &Cli, config_arg: &str, target: &UniverseTarget, listen: std::net::SocketAddr, token: Option<String>) -> i32 {
+    let token = match token {
+        Some(token) => token,
+        None => match serve::generate_token() {
+            Ok(token) => {
+                // Printed before it's registered for redaction, which would hide it from logs
+                eprintln!("No --token given; requests must send: Authorization: Bearer {}", token);
+                token
+            }
+            Err(e) => {
+                error!("{:#}", e);
+                return 1;
+            }
+        },
+    };
+    let mut jobs = match serve::start(listen, token).await {
+        Ok(jobs) => jobs,
+        Err(e) => {
+            error!("{:#}", e);
+            return 1;
+        }
+    };
+    info!("Serving on http://{} (POST /plan, /sync or /publish; Ctrl-C to stop)", listen);
+    // Nobody is at the terminal to answer prompts
+    let confirm = Confirmation::from_flags(args.yes, true);
+    while let Some(job) = jobs.recv().await {
+        let command = match job.action {
+            Action::Plan => plain_run(true),
+            Action::Sync => plain_run(false),
+            Action::Publish => Commands::Publish { wait_live: commands::VERIFY_WAIT_SECS, no_verify: false, promote: false },
+        };
+        let code = invoke(args, command, config_arg, target, confirm, Some(job.events.clone())).await;
+        info!("HTTP-triggered {} finished with exit code {}", job.action.name(), code);
+    }
+    0
+}
+
+/// Run `command` once per selected workspace project, continuing past
+/// failures, and return the exit code
+async fn run_workspace(args: &Cli, command: Commands, target: &UniverseTarget, confirm: Confirmation) -> anyhow::Result<i32> {
+    if args.config.is_some() {
+        return Err(anyhow!("--config can't be combined with --all or --project; projects use the configs listed in {}", WORKSPACE_FILE));
+    }
+    if args.all && !args.project.is_empty() {
+        return Err(anyhow!("Use either --all or --project, not both"));
+    }
+    if let Commands::Watch { .. } | Commands::Serve { .. } = command {
+        return Err(anyhow!("{} runs a single project; run it from the project's directory instead of using --all or --project", command.name()));
+    }
+    let cwd = std::env::current_dir()?;
+    let workspace = Workspace::discover(&cwd)?.ok_or_else(|| anyhow!("--all and --project need a {} in this or a parent directory", WORKSPACE_FILE))?;
+    let projects = workspace.select(&args.project)?;
+    if target.id.is_some() && projects.len() > 1 {
+        return Err(anyhow!("--universe-id can only be used with a single --project"));
+    }
+
+    let mut failed = Vec::new();
+    for project in &projects {
+        info!("==> Project {}", project.name);
+        // Each project runs from its own directory, like a single project
+        let entered = workspace.config_path(project).and_then(|config_path| {
+            let root = config_path.parent().unwrap_or(&workspace.root);
+            std::env::set_current_dir(root).with_context(|| format!("Failed to enter {}", root.display()))?;
+            Ok(config_path.file_name().unwrap_or_default().to_string_lossy().into_owned())
+        });
+        let code = match entered {
+            Ok(config_arg) => invoke(args, command.clone(), &config_arg, target, confirm, None).await,
+            Err(e) => {
+                error!("{:#}", e);
+                1
+            }
+        };
+        if code != 0 {
+            failed.push(project.name.as_str());
+        }
+    }
+    if failed.is_empty() {
+        info!("All {} project(s) succeeded", projects.len());
+        Ok(0)
+    } else {
+        error!("{} of {} project(s) failed: {}", failed.len(), projects.len(), failed.join(", "));
+        Ok(1)
+    }
+}
+
+/// Set once the end-of-run security audit has run in this process
+static SECURITY_AUDITED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+/// Run `command` against one project config, then audit it and write its
+/// run manifest and any requested reports. Report events are also sent to
+/// `events` when given. Returns the exit code.
+async fn invoke(args: &Cli, command: Commands, config_arg: &str, target: &UniverseTarget, confirm: Confirmation, events: Option<tokio::sync::mpsc::UnboundedSender<serde_json::Value>>) -> i32 {
+    let is_run = matches!(command, Commands::Run { .. });
+    let summary_file = match &command {
+        Commands::Run { report: Some(ReportFormat::Json), report_file, .. } => Some(report_file.clone()),
+        _ => None,
+    };
+    let markdown_file = match &command {
+        Commands::Run { markdown, .. } => markdown.clone(),
+        _ => None,
+    };
+
+    // Trace level keeps the command's name out of every log line; it's
+    // still recorded when telemetry is exported
+    let span = tracing::trace_span!("command", command = command.name());
+    let mut report = RunReport::new(command.name());
+    report.set_output(args.output);
+    report.actor = Some(actor::current());
+    if let Some(events) = events {
+        report.stream_to(events);
+    }
+    // The directory state is loaded from and saved to; a bare file name has an empty parent
+    let project_root = Path::new(config_arg).parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    // Held until the manifest is written
+    let lock = match command.needs_lock() {
+        true => RunLock::acquire(project_root, command.name(), std::time::Duration::from_secs(args.lock_timeout)).await.map(Some),
+        false => Ok(None),
+    };
+    let mut result = match lock {
+        Ok(_) => execute_with_state(config_arg, command, target, confirm, &mut report).instrument(span).await,
+        Err(e) => Err(e),
+    };
+
+    // Once per process, not after every run of `watch` or `serve`
+    let findings = if SECURITY_AUDITED.swap(true, std::sync::atomic::Ordering::Relaxed) {
+        Vec::new()
+    } else {
+        security::audit(project_root, Path::new(config_arg))
+    };
+    for finding in &findings {
+        // Shown once, not after every command of a project that keeps it
+        match finding == security::NOT_GITIGNORED && security::reported_before(project_root, finding) {
+            true => debug!("Security: {}", finding),
+            false => warn!("Security: {}", finding),
+        }
+    }
+    if args.strict_security && !findings.is_empty() && result.is_ok() {
+        result = Err(anyhow!("{} security issue(s) found (--strict-security)", findings.len()));
+    }
+    report.security_warnings = findings;
+
+    // Write the run manifest regardless of outcome
+    report.finish(&result);
+    if let Err(e) = rblxsync::audit::append(project_root, &report) {
+        warn!("Failed to write audit log: {:#}", e);
+    }
+    if let Err(e) = report.save(project_root) {
+        warn!("Failed to write run manifest: {}", e);
+    }
+    #[cfg(feature = "otel")]
+    rblxsync::telemetry::export(&report).await;
+
+    if is_run {
+        let summary = report.summary();
+        if args.output == OutputMode::Text {
+            for line in summary.lines() {
+                info!("{}", line);
+            }
+        }
+        if let Some(path) = summary_file {
+            let written = serde_json::to_string_pretty(&summary).map_err(anyhow::Error::from).and_then(|json| Ok(std::fs::write(&path, json)?));
+            match written {
+                Ok(()) => info!("Wrote summary report to {}", path),
+                Err(e) => warn!("Failed to write summary report: {}", e),
+            }
+        }
+        if let Some(path) = markdown_file {
+            match std::fs::write(&path, report.markdown()) {
+                Ok(()) => info!("Wrote markdown plan to {}", path),
+                Err(e) => warn!("Failed to write markdown plan: {}", e),
+            }
+        }
+    }
+
+    match result {
+        Ok(code) => code,
+        Err(e) => {
+            error!("{:#}", e);
+            1
+        }
+    }
+}
+
+/// Run `command` between downloading and uploading the remote lock file,
+/// when a `state_backend` is configured
+async fn execute_with_state(config_arg: &str, command: Commands, target: &UniverseTarget, confirm: Confirmation, report: &mut RunReport) -> anyhow::Result<i32> {
+    let setup = ApiSetup::get();
+    let remote = match command.uses_state() {
+        // A replay must not read or overwrite the shared copy with state
+        // built from recorded responses; the local lock file is used as is
+        true if setup.replay => {
+            info!("Replaying: not using the remote state backend");
+            None
+        }
+        true => RemoteState::pull(Path::new(config_arg), target.id, &setup.options).await?,
+        false => None,
+    };
+    let result = execute(config_arg, command, target, confirm, report).await;
+    // Uploaded after failures too: the lock file records what was applied
+    if let Some(remote) = remote {
+        if let Err(e) = remote.push().await {
+            match result {
+                Ok(_) => return Err(e),
+                Err(_) => error!("{:#}", e),
+            }
+        }
+    }
+    result
+}
+
+/// Run `command`, returning the process exit code
+async fn execute(config_arg: &str, command: Commands, target: &UniverseTarget, confirm: Confirmation, report: &mut RunReport) -> anyhow::Result<i32> {
+    if let Commands::Validate = command {
+        let path = Path::new(config_arg);
+        if !path.exists() {
+            return Err(anyhow!("Config file not found: {}", config_arg));
+        }
+        let problems = commands::lint(path)
+            .map_err(|e| anyhow!("Config validation failed: {:#}", e))?;
+        if !problems.is_empty() {
+            for problem in &problems {
+                error!("{}", problem);
+                report.error(problem.clone());
+            }
+            return Err(anyhow!("Config validation failed: {} problem(s) found", problems.len()));
+        }
+        info!("Config file is valid.");
+        return Ok(0);
+    }
+
+    if let Commands::Init { creator_id, creator_type, from_remote, force } = command {
+        let opts = commands::InitOptions {
+            universe_id: target.id.or(target.profile_default),
+            creator: creator_id.map(|id| CreatorConfig { id, creator_type }),
+            from_remote,
+            force,
+            interactive: confirm.interactive,
+        };
+        let client = Config::from_env(Path::new(config_arg)).ok().map(|c| api_client(c.api_key));
+        commands::init(Path::new(config_arg), opts, client, report).await?;
+        return Ok(0);
+    }
+
+    if let Commands::Migrate { source, state, environment, force, .. } = command {
+        let universe_id = target.id.or(target.profile_default);
+        commands::migrate_from_mantle(Path::new(config_arg), Path::new(&source), state.as_deref().map(Path::new), environment.as_deref(), universe_id, force, report).await?;
+        return Ok(0);
+    }
+
+    if let Commands::Config = command {
+        commands::show_config(Path::new(config_arg), report)?;
+        return Ok(0);
+    }
+
+    if let Commands::Scopes { read_only } = command {
+        let config = RblxSyncConfig::load(Path::new(config_arg))?;
+        commands::scopes(&config, read_only);
+        return Ok(0);
+    }
+
+    if let Commands::Login { stdin } = command {
+        commands::login(rblxsync::credentials::selected_profile(), stdin || !confirm.interactive).await?;
+        return Ok(0);
+    }
+    if let Commands::Logout = command {
+        commands::logout(rblxsync::credentials::selected_profile())?;
+        return Ok(0);
+    }
+
+    let state_root = Path::new(config_arg).parent().unwrap_or(Path::new("."));
+    if let Commands::Status = command {
+        commands::status(&SyncState::load(state_root)?);
+        return Ok(0);
+    }
+    if let Commands::History { name, resource_type, field, actor, limit } = command {
+        let filter = rblxsync::audit::HistoryFilter { name, resource_type, field, actor };
+        commands::history(state_root, &filter, limit, report)?;
+        return Ok(0);
+    }
+    if let Commands::Icons { action: IconsCommand::Import { lockfile, dry_run } } = &command {
+        let config = RblxSyncConfig::load(Path::new(config_arg))?;
+        commands::icons_import(&config, state_root, Path::new(lockfile), *dry_run, report).await?;
+        return Ok(0);
+    }
+    if let Commands::State { action } = &command {
+        let state = || SyncState::load(state_root);
+        match action {
+            StateCommand::List => commands::state_list(&state()?),
+            StateCommand::Show { kind, name } => commands::state_show(&state()?, *kind, name)?,
+            StateCommand::Rm { kind, name } => commands::state_rm(state_root, state()?, *kind, name, confirm)?,
+            StateCommand::Mv { old, new, kind } => commands::state_mv(state_root, state()?, *kind, old, new)?,
+            StateCommand::Rollback => commands::state_rollback(state_root)?,
+            StateCommand::Refresh { .. } => {} // Needs the API key
+        }
+        if !matches!(action, StateCommand::Refresh { .. }) {
+            return Ok(0);
+        }
+    }
+
+    // Load Env Config (API Key). Commands above don't need it, so an
+    // api_key_command only runs when a key is actually used.
+    let env_config = match Config::from_env(Path::new(config_arg)) {
         Ok(c) => c,
+        // Replayed requests never reach Roblox, so no key is needed
+        Err(_) if ApiSetup::get().replay => Config { api_key: "replay".to_string(), roblox_cookie: None },
         Err(e) => {
-            error!("Failed to load environment: {}", e);
             error!("Ensure ROBLOX_API_KEY is set.");
-            std::process::exit(1);
+            return Err(anyhow!("Failed to load environment: {}", e));
         }
     };
 
-    let client = RobloxClient::new(env_config.api_key);
+    report.key_fingerprint = Some(rblxsync::credentials::fingerprint(&env_config.api_key));
+    let client = api_client(env_config.api_key);
 
     match command {
-        Commands::Run { dry_run } => {
+        Commands::Run { dry_run, no_create, use_ids, only, filter, targets, allow_protected, confirm_price_changes, overwrite_remote_changes, no_cache, .. } => {
             if dry_run {
-                info!("Dry-run mode enabled.");
+                info!("{}", Msg::DryRunEnabled.text());
             }
-            let config_path = Path::new(&args.config);
-            let config = RblxSyncConfig::load(config_path)?;
+            let config_path = Path::new(config_arg);
+            let config = load_config(config_path, target, &client, !dry_run).await?;
             let root = config_path.parent().unwrap_or(Path::new("."));
             let state = SyncState::load(root)?;
             
+            let opts = commands::SyncOptions {
+                dry_run,
+                no_create,
+                actor: actor::current(),
+                use_ids,
+                confirm,
+                only,
+                filter,
+                targets,
+                allow_protected,
+                confirm_price_changes,
+                overwrite_remote_changes,
+                no_cache,
+                root: root.to_path_buf(),
+            };
+
             // Check if universe settings are defined and require ROBLOX_COOKIE
-            let cookie_client = if config.universe.has_settings() {
+            let cookie_client = if config.universe.has_settings() && opts.syncs(SyncSection::Universe) {
                 match &env_config.roblox_cookie {
                     Some(cookie) => {
                         info!("Universe settings detected, using cookie authentication for develop.roblox.com API");
                         Some(RobloxCookieClient::new(cookie.clone()))
                     }
                     None => {
-                        error!("Universe settings are defined in {} but ROBLOX_COOKIE is not set.", args.config);
+                        error!("Universe settings are defined in {} but ROBLOX_COOKIE is not set.", config_arg);
                         error!("");
                         error!("To update universe settings (name, description, etc.), you must provide your");
                         error!(".ROBLOSECURITY cookie. Add the following to your .env file:");
@@ -122,25 +1341,219 @@ async fn main() -> anyhow::Result<()> {
                         error!("  3. Copy the value of .ROBLOSECURITY");
                         error!("");
                         error!("WARNING: Keep this cookie secret! Anyone with it can access your account.");
-                        std::process::exit(1);
+                        return Err(anyhow!("ROBLOX_COOKIE is not set"));
                     }
                 }
             } else {
                 None
             };
             
-            commands::run(config, state, client, cookie_client, dry_run).await?;
+            commands::run(config, state, client, cookie_client, &opts, report).await?;
+        }
+        Commands::Promote { from, to, with_settings, dry_run } => {
+            let config_path = Path::new(config_arg);
+            // Named universes resolve through the config; bare IDs are used as is
+            let universe_id = |universe: &str| match universe.parse::<u64>() {
+                Ok(id) => Ok(id),
+                Err(_) => RblxSyncConfig::load_universe(config_path, universe).map(|c| c.universe.id),
+            };
+            let (from_id, to_id) = (universe_id(&from)?, universe_id(&to)?);
+            // Creator and badge payment settings come from the target's config
+            let config = match to.parse::<u64>() {
+                Ok(_) => RblxSyncConfig::load(config_path)?,
+                Err(_) => RblxSyncConfig::load_universe(config_path, &to)?,
+            };
+            // The target follows the source, whatever was edited there
+            let opts = commands::SyncOptions { dry_run, actor: actor::current(), confirm, overwrite_remote_changes: true, root: state_root.to_path_buf(), ..Default::default() };
+            let cookie_client = if with_settings {
+                Some(env_config.roblox_cookie.clone().map(RobloxCookieClient::new).ok_or_else(|| anyhow!("--with-settings needs ROBLOX_COOKIE"))?)
+            } else {
+                None
+            };
+            commands::promote(&config, state_root, client, cookie_client, from_id, to_id, &opts, report).await?;
+        }
+        Commands::Backup { out, places } => {
+            let config = load_config(Path::new(config_arg), target, &client, false).await?;
+            commands::backup(&config, state_root, client, Path::new(&out), places, report).await?;
+        }
+        Commands::Restore { snapshot, with_icons, places, dry_run } => {
+            let config = load_config(Path::new(config_arg), target, &client, !dry_run).await?;
+            let cookie_client = env_config.roblox_cookie.clone().map(RobloxCookieClient::new);
+            let opts = commands::SyncOptions { dry_run, actor: actor::current(), confirm, overwrite_remote_changes: true, root: state_root.to_path_buf(), ..Default::default() };
+            commands::restore(&config, state_root, client, cookie_client, Path::new(&snapshot), with_icons, places, &opts, report).await?;
+        }
+        Commands::Publish { wait_live, no_verify, promote } => {
+            let config = load_config(Path::new(config_arg), target, &client, true).await?;
+            let opts = commands::PublishOptions { verify: (!no_verify).then_some(wait_live), promote, confirm };
+            commands::publish(config, state_root, client, opts, report).await?;
+        }
+        Commands::RestartServers => {
+            let config = load_config(Path::new(config_arg), target, &client, true).await?;
+            confirm.require(&format!("Restart every server of universe {}", config.universe.id))?;
+            commands::restart_servers(&config, &client, report).await?;
+        }
+        Commands::RunScript { file, place, version, timeout } => {
+            let config = load_config(Path::new(config_arg), target, &client, true).await?;
+            commands::run_script(&config, &client, Path::new(&file), place, version, timeout, report).await?;
+        }
+        Commands::Notify { users, message_id, params, launch_data, category, dry_run } => {
+            let config = load_config(Path::new(config_arg), target, &client, !dry_run).await?;
+            let notification = commands::Notification { message_id, parameters: params, launch_data, category };
+            commands::notify(&config, &client, &users, &notification, dry_run, report).await?;
+        }
+        Commands::Stats { format, out } => {
+            let config = load_config(Path::new(config_arg), target, &client, false).await?;
+            commands::stats(&config, &client, format, out.as_deref().map(Path::new), report).await?;
         }
-        Commands::Publish => {
-            let config = RblxSyncConfig::load(Path::new(&args.config))?;
-            commands::publish(config, client).await?;
+        Commands::Message { topic, message } => {
+            let config = load_config(Path::new(config_arg), target, &client, true).await?;
+            commands::message(&config, &client, &topic, &message, report).await?;
         }
-        Commands::Export { output, lua } => {
-            let config = RblxSyncConfig::load(Path::new(&args.config))?;
-            commands::export(config, client, output, lua).await?;
+        Commands::Export { out, lua, resume } => {
+            let config_path = Path::new(config_arg);
+            let config = load_config(config_path, target, &client, false).await?;
+            let state = SyncState::load(config_path.parent().unwrap_or(Path::new(".")))?;
+            commands::export(config, &state, client, out, lua, resume, report).await?;
         }
-        Commands::Validate => unreachable!(), // Handled above
+        Commands::Pull { dry_run } => {
+            report.dry_run = dry_run;
+            let config_path = Path::new(config_arg);
+            let config = load_config(config_path, target, &client, !dry_run).await?;
+            commands::pull(config_path, config, client, dry_run, report).await?;
+        }
+        Commands::Check => {
+            let config = load_config(Path::new(config_arg), target, &client, false).await?;
+            if commands::check(config, state_root, client, report).await? {
+                return Ok(commands::DRIFT_EXIT_CODE);
+            }
+        }
+        Commands::State { action: StateCommand::Refresh { dry_run } } => {
+            let config = load_config(Path::new(config_arg), target, &client, !dry_run).await?;
+            commands::state_refresh(config, state_root, client, dry_run, confirm, report).await?;
+        }
+        Commands::Icons { action: IconsCommand::Download { force, dry_run } } => {
+            let config = load_config(Path::new(config_arg), target, &client, false).await?;
+            let state = SyncState::load(state_root)?;
+            commands::icons_download(config, &state, client, force, dry_run, report).await?;
+        }
+        Commands::Assets { action: AssetsCommand::Gc { dry_run } } => {
+            let config = load_config(Path::new(config_arg), target, &client, !dry_run).await?;
+            commands::assets_gc(&config, state_root, &client, dry_run, confirm, report).await?;
+        }
+        Commands::Badges { action } => {
+            let (selection, enabled) = match action {
+                BadgesCommand::Enable(selection) => (selection, true),
+                BadgesCommand::Disable(selection) => (selection, false),
+            };
+            let config = load_config(Path::new(config_arg), target, &client, !selection.dry_run).await?;
+            commands::badges_set_enabled(&config, state_root, &client, selection.filter.as_ref(), enabled, selection.dry_run, confirm, report).await?;
+        }
+        Commands::Universe { action } => {
+            let config = load_config(Path::new(config_arg), target, &client, true).await?;
+            let cookie = env_config.roblox_cookie.clone().ok_or_else(|| anyhow!("ROBLOX_COOKIE is not set; activating or deactivating a universe requires the .ROBLOSECURITY cookie"))?;
+            let active = matches!(action, UniverseCommand::Activate);
+            commands::universe_set_active(&config, state_root, RobloxCookieClient::new(cookie), active, confirm, report).await?;
+        }
+        Commands::Restrictions { action } => {
+            let dry_run = match &action {
+                RestrictionsCommand::List { .. } => true,
+                RestrictionsCommand::Ban { dry_run, .. } | RestrictionsCommand::Unban { dry_run, .. } => *dry_run,
+            };
+            let config = load_config(Path::new(config_arg), target, &client, !dry_run).await?;
+            match action {
+                RestrictionsCommand::List { place } => commands::restrictions_list(&config, &client, place, report).await?,
+                RestrictionsCommand::Ban { user_id, place, duration, reason, private_reason, ban_alt_accounts, .. } => {
+                    let restriction = RestrictionConfig {
+                        user_id,
+                        place_id: place,
+                        active: true,
+                        duration,
+                        display_reason: reason,
+                        private_reason,
+                        ban_alt_accounts,
+                    };
+                    commands::restrictions_apply(&config, &client, &restriction, dry_run, confirm, report).await?
+                }
+                RestrictionsCommand::Unban { user_id, place, .. } => {
+                    let restriction = RestrictionConfig {
+                        user_id,
+                        place_id: place,
+                        active: false,
+                        duration: None,
+                        display_reason: None,
+                        private_reason: None,
+                        ban_alt_accounts: false,
+                    };
+                    commands::restrictions_apply(&config, &client, &restriction, dry_run, confirm, report).await?
+                }
+            }
+        }
+        Commands::Datastore { action } => {
+            let read_only = matches!(action, DatastoreCommand::Get { .. } | DatastoreCommand::ListEntries { .. } | DatastoreCommand::Export { .. });
+            let config = load_config(Path::new(config_arg), target, &client, !read_only).await?;
+            match action {
+                DatastoreCommand::Get { datastore, key, scope } => commands::datastore_get(&config, &client, &datastore, scope.as_deref(), &key, report).await?,
+                DatastoreCommand::Set { datastore, key, value, scope, dry_run } => {
+                    commands::datastore_set(&config, &client, &datastore, scope.as_deref(), &key, &value, dry_run, report).await?
+                }
+                DatastoreCommand::ListEntries { datastore, prefix, scope } => {
+                    commands::datastore_list(&config, &client, &datastore, scope.as_deref(), prefix.as_deref(), report).await?
+                }
+                DatastoreCommand::Export { datastore, out, scope, resume } => {
+                    commands::datastore_export(&config, &client, &datastore, scope.as_deref(), Path::new(&out), resume, report).await?
+                }
+                DatastoreCommand::Import { datastore, file, scope, dry_run } => {
+                    commands::datastore_import(&config, &client, &datastore, scope.as_deref(), Path::new(&file), dry_run, confirm, report).await?
+                }
+                DatastoreCommand::Delete { datastore, key, scope, dry_run } => {
+                    commands::datastore_delete(&config, &client, &datastore, scope.as_deref(), &key, dry_run, confirm, report).await?
+                }
+            }
+        }
+        Commands::Whoami { mut universe_ids } => {
+            if universe_ids.is_empty() {
+                // Works without a config; the universe check is skipped then
+                match target.id {
+                    Some(id) => universe_ids.push(id),
+                    None => universe_ids.extend(RblxSyncConfig::load(Path::new(config_arg)).ok().map(|c| c.universe.id).or(target.profile_default)),
+                }
+            }
+            commands::whoami(&client, &universe_ids, report).await?;
+        }
+        Commands::VerifyPending => {
+            let config = load_config(Path::new(config_arg), target, &client, true).await?;
+            let mut state = SyncState::load(state_root)?;
+            commands::verify_pending(config.universe.id, state_root, &mut state, &client, report).await?;
+            state.save(state_root)?;
+        }
+        Commands::Init { .. } | Commands::Migrate { .. } | Commands::Validate | Commands::Scopes { .. } | Commands::Config | Commands::Watch { .. } | Commands::Serve { .. } | Commands::Completions { .. } | Commands::Login { .. } | Commands::Logout | Commands::State { .. } | Commands::Status | Commands::History { .. } | Commands::Icons { action: IconsCommand::Import { .. } } => unreachable!(), // Handled above
     }
 
-    Ok(())
+    Ok(0)
+}
+
+/// Load the config, applying any `--universe-id` override. Commands that
+/// write (remotely or to the lock file/config) must confirm the override,
+/// and can't target a universe other than the one the lock file belongs to.
+async fn load_config(path: &Path, target: &UniverseTarget, client: &RobloxClient, writes: bool) -> anyhow::Result<RblxSyncConfig> {
+    let mut config = RblxSyncConfig::load(path)?;
+    if writes {
+        let state = SyncState::load(path.parent().unwrap_or(Path::new(".")))?;
+        commands::check_lock_universe(&state, config.universe.id, target.id.unwrap_or(config.universe.id))?;
+    }
+    if let Some(id) = target.id.filter(|id| *id != config.universe.id) {
+        if writes {
+            commands::confirm_universe_override(client, config.universe.id, id, target.confirm, target.interactive).await?;
+        }
+        warn!("Targeting universe {} instead of configured universe {}", id, config.universe.id);
+        config.universe.id = id;
+    }
+    Ok(config)
+}
+
+/// Parse a `key=value` argument
+fn parse_key_value(arg: &str) -> Result<(String, String), String> {
+    arg.split_once('=')
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .ok_or_else(|| format!("expected key=value, got '{}'", arg))
 }
@@ -0,0 +1,358 @@
+//! Translation of a Mantle project for `rblxsync migrate --from mantle`.
+//!
+//! Mantle describes one experience under `target.experience`, with passes,
+//! products, badges and places keyed by a label, and records what it
+//! deployed per environment in `.mantle-state.yml`, as resources with IDs
+//! such as `pass_<label>`. The config is converted field by field; the IDs
+//! from the state file pin each resource so the first `run` adopts it
+//! instead of creating a duplicate. Settings rblxsync has no equivalent for
+//! are reported rather than silently dropped.
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde_yaml::{Mapping, Value};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// What Mantle deployed to one environment
+#[derive(Debug, Default)]
+pub struct MantleState {
+    /// Environment the IDs were read from
+    pub environment: String,
+    pub universe_id: Option<u64>,
+    /// Remote ID and icon asset ID by Mantle resource ID (`pass_vip`)
+    resources: HashMap<String, (u64, Option<u64>)>,
+}
+
+impl MantleState {
+    /// Read `environment` from a `.mantle-state.yml`, or its only environment
+    pub fn load(path: &Path, environment: Option<&str>) -> Result<Self> {
+        let content = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let doc: Value = serde_yaml::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))?;
+        let environments = doc["environments"].as_mapping().ok_or_else(|| anyhow!("{} has no environments", path.display()))?;
+        let labels: Vec<&str> = environments.keys().filter_map(Value::as_str).collect();
+        let environment = match (environment, labels.as_slice()) {
+            (Some(label), _) => label.to_string(),
+            (None, [only]) => only.to_string(),
+            (None, _) => return Err(anyhow!("{} has environments {}; pick one with --environment", path.display(), labels.join(", "))),
+        };
+        let resources = environments
+            .get(environment.as_str())
+            .and_then(Value::as_sequence)
+            .ok_or_else(|| anyhow!("{} has no environment '{}'", path.display(), environment))?;
+
+        let mut state = Self { environment, ..Default::default() };
+        let mut icons = HashMap::new();
+        for resource in resources {
+            let Some(id) = resource["id"].as_str() else { continue };
+            // Outputs are tagged by resource type: `outputs: { pass: { assetId: 1 } }`
+            let Some(outputs) = resource["outputs"].as_mapping().and_then(|m| m.values().next()) else { continue };
+            let asset_id = outputs["assetId"].as_u64();
+            match id.split_once('_') {
+                Some(("experience", _)) => state.universe_id = asset_id,
+                // Older states keep icons as separate resources
+                Some((kind, label)) if kind.ends_with("Icon") => {
+                    if let Some(asset_id) = asset_id {
+                        icons.insert(format!("{}_{}", kind.trim_end_matches("Icon"), label), asset_id);
+                    }
+                }
+                _ => {
+                    // A product's `productId` is the ID the Open Cloud API uses
+                    let remote_id = outputs["productId"].as_u64().or(asset_id);
+                    let icon = outputs["iconAssetId"].as_u64().or(outputs["initialIconAssetId"].as_u64());
+                    if let Some(remote_id) = remote_id {
+                        state.resources.insert(id.to_string(), (remote_id, icon));
+                    }
+                }
+            }
+        }
+        for (id, icon) in icons {
+            if let Some(entry) = state.resources.get_mut(&id) {
+                entry.1 = entry.1.or(Some(icon));
+            }
+        }
+        Ok(state)
+    }
+
+    /// Remote ID and icon asset ID of the resource with `label` of `kind`
+    /// (`pass`, `product`, `badge`, `place`)
+    pub fn resource(&self, kind: &str, label: &str) -> Option<(u64, Option<u64>)> {
+        self.resources.get(&format!("{}_{}", kind, label)).copied()
+    }
+}
+
+/// A Mantle config translated into rblxsync's layout
+#[derive(Debug)]
+pub struct Migration {
+    /// Config document, with icon and place paths relative to `base`
+    pub config: Value,
+    /// Icon asset IDs by `(section, name)`, e.g. `("game_passes", "VIP")`
+    pub icon_asset_ids: HashMap<(&'static str, String), u64>,
+    /// Mantle settings that weren't migrated
+    pub skipped: Vec<String>,
+}
+
+/// Convert the Mantle config `doc`. Files Mantle resolves against its
+/// config's directory are made relative to `base`.
+pub fn convert(doc: &Value, base: &str, state: Option<&MantleState>, universe_id: Option<u64>) -> Result<Migration> {
+    let experience = &doc["target"]["experience"];
+    if !experience.is_mapping() {
+        return Err(anyhow!("not a Mantle config: target.experience is missing"));
+    }
+    let universe_id = state
+        .and_then(|s| s.universe_id)
+        .or(universe_id)
+        .ok_or_else(|| anyhow!("no universe ID; pass --state with Mantle's state file or --universe-id"))?;
+
+    let mut migration = Migration { config: Value::Null, icon_asset_ids: HashMap::new(), skipped: Vec::new() };
+    let mut config = Mapping::new();
+    config.insert("assets_dir".into(), base.into());
+
+    match &doc["owner"] {
+        Value::Mapping(owner) => {
+            if let Some(group) = owner.get("group").and_then(Value::as_u64) {
+                config.insert("creator".into(), Value::Mapping(mapping([("id", group.to_string().into()), ("type", "group".into())])));
+            }
+        }
+        Value::String(owner) if owner == "personal" => migration.skipped.push("owner: personal (set creator.id to your user ID to upload icons)".to_string()),
+        _ => {}
+    }
+    if !doc["environments"].is_null() {
+        migration.skipped.push("environments (migrate each with --environment into its own config, or see `universes`)".to_string());
+    }
+
+    let universe = convert_universe(experience, universe_id, &mut migration.skipped);
+    config.insert("universe".into(), Value::Mapping(universe));
+
+    let mut places = Vec::new();
+    for (label, place) in entries(&experience["places"]) {
+        let Some(file) = place["file"].as_str() else {
+            migration.skipped.push(format!("target.experience.places.{} (no file)", label));
+            continue;
+        };
+        let Some((place_id, _)) = state.and_then(|s| s.resource("place", label)) else {
+            migration.skipped.push(format!("target.experience.places.{} (not deployed yet, so its place ID is unknown)", label));
+            continue;
+        };
+        places.push(Value::Mapping(mapping([("place_id", place_id.into()), ("file_path", join(base, file).into()), ("publish", true.into())])));
+    }
+    config.insert("places".into(), Value::Sequence(places));
+
+    for (mantle_key, kind, section) in [("passes", "pass", "game_passes"), ("products", "product", "developer_products"), ("badges", "badge", "badges")] {
+        let mut resources = Vec::new();
+        for (label, resource) in entries(&experience[mantle_key]) {
+            let Some(name) = resource["name"].as_str() else { continue };
+            let mut entry = mapping([("name", name.into())]);
+            let deployed = state.and_then(|s| s.resource(kind, label));
+            if let Some((id, icon_asset_id)) = deployed {
+                entry.insert("id".into(), id.into());
+                if let Some(icon_asset_id) = icon_asset_id {
+                    migration.icon_asset_ids.insert((section, name.to_string()), icon_asset_id);
+                }
+            }
+            for (from, to) in [("description", "description"), ("price", "price"), ("enabled", "is_enabled")] {
+                if !resource[from].is_null() {
+                    entry.insert(to.into(), resource[from].clone());
+                }
+            }
+            if let Some(icon) = resource["icon"].as_str() {
+                entry.insert("icon".into(), icon.into());
+            }
+            // Mantle doesn't require one, but rblxsync can't make one up
+            if kind == "product" && !entry.contains_key("price") {
+                bail!("Mantle product '{}' (target.experience.products.{}) has no price; developer products need one, so set it and migrate again", name, label);
+            }
+            resources.push(Value::Mapping(entry));
+        }
+        config.insert(section.into(), Value::Sequence(resources));
+    }
+
+    for (key, _) in experience.as_mapping().into_iter().flatten() {
+        let key = key.as_str().unwrap_or_default();
+        if !matches!(key, "configuration" | "places" | "passes" | "products" | "badges") {
+            migration.skipped.push(format!("target.experience.{}", key));
+        }
+    }
+    migration.config = Value::Mapping(config);
+    Ok(migration)
+}
+
+/// `universe` from the experience's configuration and its start place
+fn convert_universe(experience: &Value, universe_id: u64, skipped: &mut Vec<String>) -> Mapping {
+    let mut universe = mapping([("id", universe_id.into())]);
+    let start = &experience["places"]["start"]["configuration"];
+    for (from, to) in [("name", "name"), ("description", "description"), ("maxPlayerCount", "max_players")] {
+        if !start[from].is_null() {
+            universe.insert(to.into(), start[from].clone());
+        }
+    }
+
+    for (key, value) in experience["configuration"].as_mapping().into_iter().flatten() {
+        let key = key.as_str().unwrap_or_default();
+        let converted = match key {
+            "genre" => value.as_str().map(|g| ("genre", g.to_lowercase().into())),
+            "playableDevices" => Some(("playable_devices", value.clone())),
+            "enableStudioAccessToApis" => Some(("studio_api_access", value.clone())),
+            "playability" => match value.as_str() {
+                Some("public") => Some(("active", true.into())),
+                Some("private") => Some(("active", false.into())),
+                _ => None,
+            },
+            "privateServers" => match value {
+                Value::String(s) if s == "disabled" => Some(("private_server_cost", "disabled".into())),
+                Value::String(s) if s == "free" => Some(("private_server_cost", 0.into())),
+                Value::Mapping(m) => m.get("price").map(|price| ("private_server_cost", price.clone())),
+                _ => None,
+            },
+            "paidAccess" => match value {
+                Value::String(s) if s == "disabled" => Some(("paid_access", 0.into())),
+                Value::Mapping(m) => m.get("price").map(|price| ("paid_access", price.clone())),
+                _ => None,
+            },
+            "avatarType" => value.as_str().map(|t| ("avatar_type", snake_case(t).into())),
+            "avatarAnimationType" => value.as_str().map(|t| ("avatar_animation", snake_case(t).into())),
+            "avatarCollisionType" => value.as_str().map(|t| ("avatar_collision", snake_case(t).into())),
+            _ => None,
+        };
+        match converted {
+            Some((field, value)) => {
+                universe.insert(field.into(), value);
+            }
+            None => skipped.push(format!("target.experience.configuration.{}", key)),
+        }
+    }
+    universe
+}
+
+/// Label and body of each entry of a Mantle map such as `passes`
+fn entries(value: &Value) -> impl Iterator<Item = (&str, &Value)> {
+    value.as_mapping().into_iter().flatten().filter_map(|(k, v)| Some((k.as_str()?, v)))
+}
+
+fn mapping<const N: usize>(pairs: [(&str, Value); N]) -> Mapping {
+    pairs.into_iter().map(|(k, v)| (Value::from(k), v)).collect()
+}
+
+fn join(base: &str, path: &str) -> String {
+    if base == "." {
+        path.to_string()
+    } else {
+        format!("{}/{}", base.trim_end_matches('/'), path)
+    }
+}
+
+/// `playerChoice` -> `player_choice`
+fn snake_case(value: &str) -> String {
+    let mut out = String::new();
+    for c in value.chars() {
+        if c.is_ascii_uppercase() {
+            out.push('_');
+        }
+        out.push(c.to_ascii_lowercase());
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{AvatarType, PrivateServerCost, RblxSyncConfig};
+
+    const CONFIG: &str = r#"
+owner:
+  group: 42
+target:
+  experience:
+    configuration:
+      genre: sciFi
+      playableDevices: [computer, phone]
+      privateServers:
+        price: 100
+      avatarType: playerChoice
+      allowThirdPartySales: true
+    places:
+      start:
+        file: game.rbxlx
+        configuration:
+          name: Space Game
+          maxPlayerCount: 20
+    passes:
+      vip:
+        name: VIP
+        price: 100
+        icon: icons/vip.png
+    products:
+      coins:
+        name: 100 Coins
+        price: 10
+    badges:
+      welcome:
+        name: Welcome
+        icon: icons/welcome.png
+        enabled: false
+    socialLinks: []
+"#;
+
+    const STATE: &str = r#"
+version: "6"
+environments:
+  production:
+    - id: experience_singleton
+      outputs:
+        experience:
+          assetId: 1000
+          startPlaceId: 2000
+    - id: place_start
+      outputs:
+        place:
+          assetId: 2000
+    - id: pass_vip
+      outputs:
+        pass:
+          assetId: 3000
+          iconAssetId: 3001
+    - id: product_coins
+      outputs:
+        product:
+          assetId: 4000
+          productId: 4100
+    - id: productIcon_coins
+      outputs:
+        productIcon:
+          assetId: 4001
+"#;
+
+    #[test]
+    fn test_convert_mantle_project() {
+        let dir = std::env::temp_dir().join(format!("rblxsync-mantle-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("state.yml"), STATE).unwrap();
+        let state = MantleState::load(&dir.join("state.yml"), None).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!((state.environment.as_str(), state.universe_id), ("production", Some(1000)));
+        assert_eq!(state.resource("product", "coins"), Some((4100, Some(4001))));
+
+        let doc: Value = serde_yaml::from_str(CONFIG).unwrap();
+        let migration = convert(&doc, "mantle", Some(&state), None).unwrap();
+        let config: RblxSyncConfig = serde_yaml::from_value(migration.config).unwrap();
+        assert_eq!(config.universe.id, 1000);
+        assert_eq!(config.universe.name.as_deref(), Some("Space Game"));
+        assert_eq!(config.universe.genre.as_deref(), Some("scifi"));
+        assert_eq!(config.universe.max_players, Some(20));
+        assert_eq!(config.universe.private_server_cost, Some(PrivateServerCost::Paid(100)));
+        assert_eq!(config.universe.avatar_type, Some(AvatarType::PlayerChoice));
+        assert_eq!(config.creator.unwrap().creator_type, "group");
+        assert_eq!((config.places[0].place_id, config.places[0].file_path.as_str()), (2000, "mantle/game.rbxlx"));
+        assert_eq!((config.game_passes[0].id, config.game_passes[0].icon.as_deref()), (Some(3000), Some("icons/vip.png")));
+        assert_eq!((config.developer_products[0].id, config.developer_products[0].price), (Some(4100), 10));
+        assert_eq!((config.badges[0].id, config.badges[0].is_enabled), (None, Some(false)));
+        assert_eq!(migration.icon_asset_ids[&("developer_products", "100 Coins".to_string())], 4001);
+        assert_eq!(migration.skipped, ["target.experience.configuration.allowThirdPartySales", "target.experience.socialLinks"]);
+
+        assert!(convert(&doc, ".", None, None).is_err());
+
+        let priceless = CONFIG.replace("        price: 10\n", "");
+        let doc: Value = serde_yaml::from_str(&priceless).unwrap();
+        let error = convert(&doc, "mantle", Some(&state), None).unwrap_err();
+        assert!(error.to_string().contains("products.coins) has no price"), "{}", error);
+    }
+}
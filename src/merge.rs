@@ -0,0 +1,95 @@
+//! Field-level three-way merge for resources edited both in config and
+//! outside rblxsync (e.g. on the Creator Dashboard) since the last sync.
+//!
+//! The lock file holds each field as of the last sync, the base. A field
+//! only config changed is applied, a field only edited remotely is left as
+//! it is, and a field changed differently on both sides is a conflict that
+//! needs `--overwrite-remote-changes`. Fields config doesn't declare are
+//! never treated as changed there.
+
+use serde::Serialize;
+use serde_json::Value;
+
+/// One field of a resource as of the last sync, in config and remotely
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeField {
+    /// Field name, as in config and change sets
+    pub field: &'static str,
+    pub base: Value,
+    pub config: Value,
+    pub remote: Value,
+}
+
+impl MergeField {
+    pub fn new(field: &'static str, base: impl Serialize, config: impl Serialize, remote: impl Serialize) -> Self {
+        Self { field, base: value(base), config: value(config), remote: value(remote) }
+    }
+
+    fn config_changed(&self) -> bool {
+        !self.config.is_null() && self.config != self.base
+    }
+
+    /// A field missing from the remote listing is treated as unchanged
+    fn remote_changed(&self) -> bool {
+        !self.remote.is_null() && self.remote != self.base
+    }
+}
+
+/// Empty strings are stored as a missing value (e.g. a description)
+pub fn value(v: impl Serialize) -> Value {
+    match serde_json::to_value(v).unwrap_or_default() {
+        Value::String(s) if s.is_empty() => Value::Null,
+        v => v,
+    }
+}
+
+/// How a resource's fields merge
+#[derive(Debug, Default, PartialEq)]
+pub struct Merge<'a> {
+    /// Fields edited remotely that config didn't change, or changed to the
+    /// same value, so their remote value stays
+    pub keep_remote: Vec<&'a MergeField>,
+    /// Fields changed differently in config and remotely
+    pub conflicts: Vec<&'a MergeField>,
+}
+
+pub fn three_way(fields: &[MergeField]) -> Merge<'_> {
+    let mut merge = Merge::default();
+    for field in fields.iter().filter(|f| f.remote_changed()) {
+        if !field.config_changed() || field.config == field.remote {
+            merge.keep_remote.push(field);
+        } else {
+            merge.conflicts.push(field);
+        }
+    }
+    merge
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_three_way() {
+        let fields = [
+            // Edited on the dashboard only
+            MergeField::new("description", "Old", "Old", "Tweaked"),
+            // Changed in config only
+            MergeField::new("price", 100, 150, 100),
+            // Changed on both sides
+            MergeField::new("name", "VIP", "VIP+", "Super VIP"),
+            // Changed to the same value on both sides
+            MergeField::new("is_for_sale", true, false, false),
+            // Not declared in config, or missing remotely
+            MergeField::new("description", "Old", None::<String>, "New"),
+            MergeField::new("price", 100, 200, None::<u64>),
+        ];
+        let merge = three_way(&fields);
+        let keep: Vec<_> = merge.keep_remote.iter().map(|f| f.field).collect();
+        let conflicts: Vec<_> = merge.conflicts.iter().map(|f| f.field).collect();
+        assert_eq!(keep, ["description", "is_for_sale", "description"]);
+        assert_eq!(conflicts, ["name"]);
+
+        assert!(three_way(&[MergeField::new("description", None::<String>, None::<String>, "")]).keep_remote.is_empty());
+    }
+}
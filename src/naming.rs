@@ -0,0 +1,117 @@
+//! Naming convention enforcement for resource names.
+//!
+//! The `naming` config block sets a regex and/or case style per resource
+//! type. Violations are reported together by `validate` (and therefore by
+//! every `run`, including `--dry-run`).
+
+use crate::config::{CaseStyle, NamePattern, NamingRule, RblxSyncConfig};
+use anyhow::{anyhow, Result};
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+/// Every case style's pattern, compiled once
+static CASE_PATTERNS: LazyLock<HashMap<CaseStyle, Regex>> = LazyLock::new(|| {
+    [CaseStyle::Title, CaseStyle::Sentence, CaseStyle::Pascal, CaseStyle::Camel, CaseStyle::Snake, CaseStyle::ScreamingSnake, CaseStyle::Kebab]
+        .into_iter()
+        .map(|case| (case, Regex::new(case.pattern()).expect("case style patterns are valid")))
+        .collect()
+});
+
+impl CaseStyle {
+    fn pattern(&self) -> &'static str {
+        match self {
+            CaseStyle::Title => r"^[A-Z0-9][^\s]*( [A-Z0-9][^\s]*)*$",
+            // Words after the first start lowercase (or with a digit)
+            CaseStyle::Sentence => r"^[A-Z0-9][^\s]*( [^\sA-Z][^\s]*)*$",
+            CaseStyle::Pascal => r"^[A-Z][A-Za-z0-9]*$",
+            CaseStyle::Camel => r"^[a-z][A-Za-z0-9]*$",
+            CaseStyle::Snake => r"^[a-z0-9]+(_[a-z0-9]+)*$",
+            CaseStyle::ScreamingSnake => r"^[A-Z0-9]+(_[A-Z0-9]+)*$",
+            CaseStyle::Kebab => r"^[a-z0-9]+(-[a-z0-9]+)*$",
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            CaseStyle::Title => "Title Case",
+            CaseStyle::Sentence => "Sentence case",
+            CaseStyle::Pascal => "PascalCase",
+            CaseStyle::Camel => "camelCase",
+            CaseStyle::Snake => "snake_case",
+            CaseStyle::ScreamingSnake => "SCREAMING_SNAKE_CASE",
+            CaseStyle::Kebab => "kebab-case",
+        }
+    }
+
+    pub fn matches(&self, name: &str) -> bool {
+        CASE_PATTERNS[self].is_match(name)
+    }
+}
+
+/// Check every resource name against the configured rules
+pub fn check(config: &RblxSyncConfig) -> Result<()> {
+    let Some(naming) = &config.naming else {
+        return Ok(());
+    };
+
+    let mut violations = Vec::new();
+    let sections: [(&str, &Option<NamingRule>, Vec<&str>); 4] = [
+        ("game pass", &naming.game_passes, config.game_passes.iter().map(|p| p.name.as_str()).collect()),
+        ("developer product", &naming.developer_products, config.developer_products.iter().map(|p| p.name.as_str()).collect()),
+        ("badge", &naming.badges, config.badges.iter().map(|b| b.name.as_str()).collect()),
+        ("asset", &naming.assets, config.assets.iter().map(|a| a.name.as_str()).collect()),
+    ];
+
+    for (resource_type, rule, names) in sections {
+        let Some(rule) = rule else { continue };
+        for name in names {
+            if let Some(NamePattern(re)) = &rule.pattern {
+                if !re.is_match(name) {
+                    violations.push(format!("{} '{}' does not match pattern {}", resource_type, name, re.as_str()));
+                }
+            }
+            if let Some(case) = &rule.case {
+                if !case.matches(name) {
+                    violations.push(format!("{} '{}' is not {}", resource_type, name, case.label()));
+                }
+            }
+        }
+    }
+
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow!("Naming convention violations:\n  {}", violations.join("\n  ")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_naming_check() {
+        let config: RblxSyncConfig = serde_yaml::from_str(
+            "universe: { id: 1 }\n\
+             naming:\n  developer_products: { pattern: '^[A-Z][A-Za-z0-9 ]+$' }\n  badges: { case: title }\n\
+             developer_products: [{ name: 100 Coins, price: 10 }, { name: Gems Pack, price: 5 }]\n\
+             badges: [{ name: First Win }, { name: second win }]\n",
+        )
+        .unwrap();
+
+        let err = check(&config).unwrap_err().to_string();
+        assert!(err.contains("developer product '100 Coins'"));
+        assert!(err.contains("badge 'second win' is not Title Case"));
+        assert!(!err.contains("Gems Pack"));
+        assert!(!err.contains("First Win"));
+
+        assert!(CaseStyle::Snake.matches("double_jump"));
+        assert!(CaseStyle::Sentence.matches("Gems pack 2"));
+        assert!(!CaseStyle::Sentence.matches("Gems Pack"));
+        assert!(!CaseStyle::Pascal.matches("Double Jump"));
+
+        let invalid = serde_yaml::from_str::<RblxSyncConfig>("universe: { id: 1 }\nnaming:\n  badges: { pattern: '[' }\n");
+        assert!(invalid.unwrap_err().to_string().contains("invalid naming pattern"));
+    }
+}
@@ -3,9 +3,10 @@
 //! This module converts the `rblxsync-lock.yml` state into a Luau module
 //! that can be used in-game to reference resource IDs and metadata.
 
+use crate::config::SplitOutputConfig;
 use crate::state::SyncState;
 use anyhow::Result;
-use log::info;
+use tracing::info;
 use std::path::Path;
 
 /// Generate a Luau configuration file from the sync state.
@@ -15,9 +16,23 @@ use std::path::Path;
 /// * `universe_id` - The universe ID to include in the output
 /// * `output_path` - Path where the output file will be written
 pub fn generate_config(state: &SyncState, universe_id: u64, output_path: &str) -> Result<()> {
-    let path = Path::new(output_path);
-    let content = generate_luau_content(state, universe_id);
+    write_module(Path::new(output_path), &generate_luau_content(state, universe_id, None))
+}
+
+/// Generate `{name}.server.luau` with every field and `{name}.client.luau`
+/// with only client-visible fields. Returns the paths written.
+pub fn generate_split(state: &SyncState, universe_id: u64, split: &SplitOutputConfig) -> Result<Vec<String>> {
+    let dir = Path::new(&split.dir);
+    let server_path = dir.join(format!("{}.server.luau", split.name));
+    let client_path = dir.join(format!("{}.client.luau", split.name));
+
+    write_module(&server_path, &generate_luau_content(state, universe_id, None))?;
+    write_module(&client_path, &generate_luau_content(state, universe_id, Some(split)))?;
+
+    Ok(vec![server_path.to_string_lossy().to_string(), client_path.to_string_lossy().to_string()])
+}
 
+fn write_module(path: &Path, content: &str) -> Result<()> {
     // Create parent directories if they don't exist
     if let Some(parent) = path.parent() {
         if !parent.as_os_str().is_empty() {
@@ -25,20 +40,26 @@ pub fn generate_config(state: &SyncState, universe_id: u64, output_path: &str) -
         }
     }
 
-    std::fs::write(path, &content)?;
-    info!("Generated config file: {}", output_path);
+    std::fs::write(path, content)?;
+    info!("Generated config file: {}", path.display());
 
     Ok(())
 }
 
-/// Generate the Luau content string from state.
-fn generate_luau_content(state: &SyncState, universe_id: u64) -> String {
+/// Generate the Luau content string from state. With `client`, fields that
+/// are server-only under its visibility rules are left out.
+fn generate_luau_content(state: &SyncState, universe_id: u64, client: Option<&SplitOutputConfig>) -> String {
     let mut output = String::new();
+    let show = |field: &str| client.map(|c| c.is_client_visible(field)).unwrap_or(true);
 
     // Header comment
     output.push_str("--!strict\n");
     output.push_str("-- Auto-generated by rblxsync. Do not edit manually.\n");
-    output.push_str("-- This file is regenerated each time `rblxsync run` completes.\n\n");
+    output.push_str("-- This file is regenerated each time `rblxsync run` completes.\n");
+    if client.is_some() {
+        output.push_str("-- Client-safe module: server-only fields are omitted.\n");
+    }
+    output.push('\n');
 
     // Type definitions
     output.push_str("export type Universe = {\n");
@@ -52,25 +73,30 @@ fn generate_luau_content(state: &SyncState, universe_id: u64) -> String {
     output.push_str("}\n\n");
 
     output.push_str("export type GamePass = {\n");
-    output.push_str("\tId: number,\n");
-    output.push_str("\tName: string,\n");
-    output.push_str("\tDescription: string?,\n");
-    output.push_str("\tPrice: number?,\n");
-    output.push_str("\tIsForSale: boolean?,\n");
+    push_type_field(&mut output, show("id"), "Id: number");
+    push_type_field(&mut output, show("name"), "Name: string");
+    push_type_field(&mut output, show("description"), "Description: string?");
+    push_type_field(&mut output, show("price"), "Price: number?");
+    push_type_field(&mut output, show("is_for_sale"), "IsForSale: boolean?");
     output.push_str("}\n\n");
 
     output.push_str("export type DeveloperProduct = {\n");
-    output.push_str("\tId: number,\n");
-    output.push_str("\tName: string,\n");
-    output.push_str("\tDescription: string?,\n");
-    output.push_str("\tPrice: number?,\n");
+    push_type_field(&mut output, show("id"), "Id: number");
+    push_type_field(&mut output, show("name"), "Name: string");
+    push_type_field(&mut output, show("description"), "Description: string?");
+    push_type_field(&mut output, show("price"), "Price: number?");
     output.push_str("}\n\n");
 
     output.push_str("export type Badge = {\n");
+    push_type_field(&mut output, show("id"), "Id: number");
+    push_type_field(&mut output, show("name"), "Name: string");
+    push_type_field(&mut output, show("description"), "Description: string?");
+    push_type_field(&mut output, show("is_enabled"), "IsEnabled: boolean?");
+    output.push_str("}\n\n");
+
+    output.push_str("export type Asset = {\n");
     output.push_str("\tId: number,\n");
-    output.push_str("\tName: string,\n");
-    output.push_str("\tDescription: string?,\n");
-    output.push_str("\tIsEnabled: boolean?,\n");
+    output.push_str("\tType: string,\n");
     output.push_str("}\n\n");
 
     output.push_str("return {\n");
@@ -114,15 +140,19 @@ fn generate_luau_content(state: &SyncState, universe_id: u64) -> String {
     game_passes.sort_by_key(|(id, _)| *id);
     for (id, resource) in game_passes {
         output.push_str("\t\t{\n");
-        output.push_str(&format!("\t\t\tId = {},\n", id));
-        output.push_str(&format!("\t\t\tName = \"{}\",\n", escape_luau_string(&resource.name)));
-        if let Some(ref description) = resource.description {
+        if show("id") {
+            output.push_str(&format!("\t\t\tId = {},\n", id));
+        }
+        if show("name") {
+            output.push_str(&format!("\t\t\tName = \"{}\",\n", escape_luau_string(&resource.name)));
+        }
+        if let Some(description) = resource.description.as_ref().filter(|_| show("description")) {
             output.push_str(&format!("\t\t\tDescription = \"{}\",\n", escape_luau_string(description)));
         }
-        if let Some(price) = resource.price {
+        if let Some(price) = resource.price.filter(|_| show("price")) {
             output.push_str(&format!("\t\t\tPrice = {},\n", price));
         }
-        if let Some(is_for_sale) = resource.is_for_sale {
+        if let Some(is_for_sale) = resource.is_for_sale.filter(|_| show("is_for_sale")) {
             output.push_str(&format!("\t\t\tIsForSale = {},\n", is_for_sale));
         }
         output.push_str("\t\t},\n");
@@ -135,12 +165,16 @@ fn generate_luau_content(state: &SyncState, universe_id: u64) -> String {
     products.sort_by_key(|(id, _)| *id);
     for (id, resource) in products {
         output.push_str("\t\t{\n");
-        output.push_str(&format!("\t\t\tId = {},\n", id));
-        output.push_str(&format!("\t\t\tName = \"{}\",\n", escape_luau_string(&resource.name)));
-        if let Some(ref description) = resource.description {
+        if show("id") {
+            output.push_str(&format!("\t\t\tId = {},\n", id));
+        }
+        if show("name") {
+            output.push_str(&format!("\t\t\tName = \"{}\",\n", escape_luau_string(&resource.name)));
+        }
+        if let Some(description) = resource.description.as_ref().filter(|_| show("description")) {
             output.push_str(&format!("\t\t\tDescription = \"{}\",\n", escape_luau_string(description)));
         }
-        if let Some(price) = resource.price {
+        if let Some(price) = resource.price.filter(|_| show("price")) {
             output.push_str(&format!("\t\t\tPrice = {},\n", price));
         }
         output.push_str("\t\t},\n");
@@ -153,25 +187,44 @@ fn generate_luau_content(state: &SyncState, universe_id: u64) -> String {
     badges.sort_by_key(|(id, _)| *id);
     for (id, resource) in badges {
         output.push_str("\t\t{\n");
-        output.push_str(&format!("\t\t\tId = {},\n", id));
-        output.push_str(&format!("\t\t\tName = \"{}\",\n", escape_luau_string(&resource.name)));
-        if let Some(ref description) = resource.description {
+        if show("id") {
+            output.push_str(&format!("\t\t\tId = {},\n", id));
+        }
+        if show("name") {
+            output.push_str(&format!("\t\t\tName = \"{}\",\n", escape_luau_string(&resource.name)));
+        }
+        if let Some(description) = resource.description.as_ref().filter(|_| show("description")) {
             output.push_str(&format!("\t\t\tDescription = \"{}\",\n", escape_luau_string(description)));
         }
-        if let Some(is_enabled) = resource.is_enabled {
+        if let Some(is_enabled) = resource.is_enabled.filter(|_| show("is_enabled")) {
             output.push_str(&format!("\t\t\tIsEnabled = {},\n", is_enabled));
         }
         output.push_str("\t\t},\n");
     }
-    output.push_str("\t} :: { Badge },\n");
+    output.push_str("\t} :: { Badge },\n\n");
+
+    // Assets, keyed by config name for direct lookup
+    output.push_str("\tAssets = {\n");
+    let mut assets: Vec<_> = state.assets.iter().collect();
+    assets.sort_by_key(|(name, _)| name.as_str());
+    for (name, asset) in assets {
+        output.push_str(&format!("\t\t[\"{}\"] = {{ Id = {}, Type = \"{}\" }},\n", escape_luau_string(name), asset.asset_id, asset.asset_type));
+    }
+    output.push_str("\t} :: { [string]: Asset },\n");
 
     output.push_str("}\n");
 
     output
 }
 
+fn push_type_field(output: &mut String, visible: bool, field: &str) {
+    if visible {
+        output.push_str(&format!("\t{},\n", field));
+    }
+}
+
 /// Escape special characters in a Luau string.
-fn escape_luau_string(s: &str) -> String {
+pub(crate) fn escape_luau_string(s: &str) -> String {
     s.replace('\\', "\\\\")
         .replace('"', "\\\"")
         .replace('\n', "\\n")
@@ -182,7 +235,7 @@ fn escape_luau_string(s: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::state::{ResourceState, UniverseState};
+    use crate::state::{AssetState, ResourceState, UniverseState};
 
     #[test]
     fn test_escape_luau_string() {
@@ -192,6 +245,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::field_reassign_with_default)]
     fn test_generate_luau_content() {
         let mut state = SyncState::default();
         state.universe = Some(UniverseState {
@@ -201,6 +255,7 @@ mod tests {
             playable_devices: Some(vec!["computer".to_string(), "phone".to_string()]),
             max_players: Some(50),
             private_server_cost: Some("disabled".to_string()),
+            ..Default::default()
         });
         state.game_passes.insert(
             123,
@@ -209,13 +264,24 @@ mod tests {
                 description: Some("VIP access".to_string()),
                 price: Some(100),
                 is_for_sale: Some(true),
+                is_active: None,
                 is_enabled: None,
+                regional_pricing_enabled: None,
                 icon_hash: None,
-                icon_asset_id: None,
+                icon_moderation_state: None,
+                icon_moderation_checked: None,
+                last_synced: None,
+                remote_updated: None,
+                kept_remote: Default::default(),
             },
         );
 
-        let content = generate_luau_content(&state, 12345);
+        state.assets.insert(
+            "Sword".to_string(),
+            AssetState { asset_id: 555, hash: "abc".to_string(), asset_type: "Model".to_string(), last_synced: None },
+        );
+
+        let content = generate_luau_content(&state, 12345, None);
         assert!(content.contains("Id = 12345"));
         assert!(content.contains("Name = \"Test Game\""));
         assert!(content.contains("MaxPlayers = 50"));
@@ -224,5 +290,40 @@ mod tests {
         assert!(content.contains("IsForSale = true"));
         assert!(content.contains(":: Universe"));
         assert!(content.contains("GamePass"));
+        assert!(content.contains("[\"Sword\"] = { Id = 555, Type = \"Model\" }"));
+    }
+
+    #[test]
+    fn test_client_module_omits_server_fields() {
+        let mut state = SyncState::default();
+        state.developer_products.insert(
+            7,
+            ResourceState {
+                name: "Coins".to_string(),
+                description: Some("Internal SKU".to_string()),
+                price: Some(25),
+                is_for_sale: None,
+                is_active: None,
+                is_enabled: None,
+                regional_pricing_enabled: None,
+                icon_hash: None,
+                icon_moderation_state: None,
+                icon_moderation_checked: None,
+                last_synced: None,
+                remote_updated: None,
+                kept_remote: Default::default(),
+            },
+        );
+        let split: SplitOutputConfig = serde_yaml::from_str("dir: out\nvisibility: { description: server }\n").unwrap();
+
+        let server = generate_luau_content(&state, 1, None);
+        assert!(server.contains("Price = 25"));
+        assert!(server.contains("Description = \"Internal SKU\""));
+
+        let client = generate_luau_content(&state, 1, Some(&split));
+        assert!(client.contains("Id = 7"));
+        assert!(client.contains("Name = \"Coins\""));
+        assert!(!client.contains("Price"));
+        assert!(!client.contains("Internal SKU"));
     }
 }
@@ -0,0 +1,187 @@
+//! Progress bars for long operations: listings that span several pages,
+//! icon uploads, place uploads (in bytes) and asset operation polling.
+//!
+//! Bars are drawn with indicatif on stderr only once [`enable`]d, which
+//! `main` does when stderr is a terminal, logs are text and output isn't
+//! `--json`; otherwise they go to a hidden draw target. Progress then falls
+//! back to plain log lines: a line per quarter for byte transfers, and debug
+//! lines for the rest. Log lines written through [`suspend`] go above the bars.
+
+use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+
+/// Spinners advance this often
+const TICK_INTERVAL: Duration = Duration::from_millis(100);
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Every bar, drawn together so several don't overwrite each other
+static BARS: LazyLock<MultiProgress> = LazyLock::new(|| MultiProgress::with_draw_target(ProgressDrawTarget::hidden()));
+
+/// Draw bars on stderr instead of logging progress
+pub fn enable(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+    BARS.set_draw_target(if enabled { ProgressDrawTarget::stderr() } else { ProgressDrawTarget::hidden() });
+}
+
+/// Run `write` with the bars cleared and redraw them after, so log lines
+/// written to stderr don't run into them
+pub fn suspend<R>(write: impl FnOnce() -> R) -> R {
+    BARS.suspend(write)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Unit {
+    Items,
+    Bytes,
+    /// No count, only a message
+    None,
+}
+
+/// Progress of one operation. Clones share it; the bar is cleared once the
+/// last clone is dropped or [`finish`](Progress::finish) is called.
+#[derive(Clone)]
+pub struct Progress(Arc<State>);
+
+struct State {
+    bar: ProgressBar,
+    label: String,
+    unit: Unit,
+    /// Quarters already logged, when not drawing
+    logged_quarters: AtomicU64,
+    finished: AtomicBool,
+}
+
+impl Progress {
+    fn new(label: impl Into<String>, unit: Unit, total: Option<u64>) -> Self {
+        let label = label.into();
+        let bar = BARS.add(match total {
+            Some(total) => ProgressBar::new(total),
+            None => ProgressBar::no_length(),
+        });
+        bar.set_style(style(unit, total.is_some()));
+        bar.set_prefix(label.clone());
+        if total.is_none() && ENABLED.load(Ordering::Relaxed) {
+            bar.enable_steady_tick(TICK_INTERVAL);
+        }
+        Self(Arc::new(State { bar, label, unit, logged_quarters: AtomicU64::new(0), finished: AtomicBool::new(false) }))
+    }
+
+    /// Count of items, e.g. fetched from a paginated listing
+    pub fn items(label: impl Into<String>, total: Option<u64>) -> Self {
+        Self::new(label, Unit::Items, total)
+    }
+
+    /// Bytes of a transfer of `total` bytes
+    pub fn bytes(label: impl Into<String>, total: u64) -> Self {
+        Self::new(label, Unit::Bytes, Some(total))
+    }
+
+    /// An operation without a count, e.g. waiting for an upload
+    pub fn spinner(label: impl Into<String>) -> Self {
+        Self::new(label, Unit::None, None)
+    }
+
+    pub fn inc(&self, amount: u64) {
+        self.0.bar.inc(amount);
+        if !ENABLED.load(Ordering::Relaxed) {
+            self.log_quarter();
+        }
+    }
+
+    /// Text shown after the bar, e.g. the current attempt
+    pub fn set_message(&self, message: impl Into<String>) {
+        let message = message.into();
+        if !ENABLED.load(Ordering::Relaxed) {
+            tracing::debug!("{}: {}", self.0.label, message);
+        }
+        self.0.bar.set_message(format!(" ({})", message));
+    }
+
+    /// Clear the bar, logging how far the operation got
+    pub fn finish(&self) {
+        if self.0.finished.swap(true, Ordering::Relaxed) {
+            return;
+        }
+        self.0.bar.finish_and_clear();
+        let position = self.0.bar.position();
+        let elapsed = self.0.bar.elapsed().as_secs_f64();
+        match self.0.unit {
+            Unit::Bytes => tracing::debug!("{}: {} in {:.1}s", self.0.label, format_bytes(position), elapsed),
+            Unit::Items => tracing::debug!("{}: {} in {:.1}s", self.0.label, position, elapsed),
+            Unit::None => tracing::debug!("{}: done in {:.1}s", self.0.label, elapsed),
+        }
+    }
+
+    /// Log transfers at each quarter, for logs without bars (e.g. on CI)
+    fn log_quarter(&self) {
+        let (Unit::Bytes, Some(total)) = (self.0.unit, self.0.bar.length().filter(|&t| t > 0)) else {
+            return;
+        };
+        let position = self.0.bar.position().min(total);
+        let quarter = position * 4 / total;
+        if quarter > 0 && self.0.logged_quarters.fetch_max(quarter, Ordering::Relaxed) < quarter {
+            tracing::info!("{}: {}% ({} of {})", self.0.label, quarter * 25, format_bytes(position), format_bytes(total));
+        }
+    }
+}
+
+impl Drop for State {
+    fn drop(&mut self) {
+        self.bar.finish_and_clear();
+        BARS.remove(&self.bar);
+    }
+}
+
+/// How a bar of `unit` is drawn
+fn style(unit: Unit, counted: bool) -> ProgressStyle {
+    let template = match (unit, counted) {
+        (Unit::None, _) => "{spinner} {prefix}{msg}",
+        (Unit::Bytes, true) => "{prefix} [{bar:30}] {percent:>3}% {bytes} / {total_bytes}{msg}",
+        (Unit::Items, true) => "{prefix} [{bar:30}] {percent:>3}% {pos}/{len}{msg}",
+        (Unit::Bytes, false) => "{spinner} {prefix} {bytes}{msg}",
+        (Unit::Items, false) => "{spinner} {prefix} {pos}{msg}",
+    };
+    ProgressStyle::with_template(template).expect("valid progress template").progress_chars("#>-").tick_chars("|/-\\ ")
+}
+
+/// Size in binary units, e.g. `12.5 MB`
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: &[&str] = &["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    match unit {
+        0 => format!("{} B", bytes),
+        _ => format!("{:.1} {}", size, UNITS[unit]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(3 * 1024 * 1024 / 2), "1.5 MB");
+
+        // Not enabled: drawn to a hidden target, counts still kept
+        let upload = Progress::bytes("Uploading place 1", 4 * 1024 * 1024);
+        upload.clone().inc(1024 * 1024);
+        assert_eq!((upload.0.bar.position(), upload.0.logged_quarters.load(Ordering::Relaxed)), (1024 * 1024, 1));
+        assert!(upload.0.bar.is_hidden());
+        upload.finish();
+        assert!(upload.0.bar.is_finished());
+
+        let listing = Progress::items("Listing badges", None);
+        listing.inc(100);
+        listing.set_message("page 2");
+        assert_eq!((listing.0.bar.position(), listing.0.bar.message()), (100, " (page 2)".to_string()));
+    }
+}
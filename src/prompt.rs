@@ -0,0 +1,110 @@
+//! Interactive terminal prompts.
+//!
+//! Prompts are only shown when stdin is a terminal, no CI environment is
+//! detected and `--non-interactive` isn't set; callers must fall back to a
+//! flag-driven path otherwise. Destructive or costly operations go through
+//! `Confirmation::require`, which honours the global `--yes` flag.
+
+use anyhow::{anyhow, Result};
+use std::io::{self, BufRead, IsTerminal, Write};
+
+/// Whether prompts can be shown
+pub fn is_interactive() -> bool {
+    io::stdin().is_terminal() && std::env::var_os("CI").is_none()
+}
+
+/// How confirmations of destructive or costly operations are answered
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Confirmation {
+    /// `--yes`: approve without asking
+    pub yes: bool,
+    /// Whether prompts may be shown
+    pub interactive: bool,
+}
+
+impl Confirmation {
+    pub fn from_flags(yes: bool, non_interactive: bool) -> Self {
+        Self { yes, interactive: !non_interactive && is_interactive() }
+    }
+
+    /// Approve `action` (e.g. "Lower the price of 2 item(s)"): passes with
+    /// `--yes`, asks y/N in a terminal, and fails otherwise
+    pub fn require(&self, action: &str) -> Result<()> {
+        if self.yes {
+            return Ok(());
+        }
+        if !self.interactive {
+            return Err(anyhow!("{} requires confirmation; pass --yes to proceed", action));
+        }
+        if confirm(&format!("{}?", action))? {
+            Ok(())
+        } else {
+            Err(anyhow!("Aborted: {}", action.to_lowercase()))
+        }
+    }
+}
+
+/// Ask the user to pick one of `options`, returning its index
+pub fn choose(question: &str, options: &[String]) -> Result<usize> {
+    let mut stderr = io::stderr();
+    writeln!(stderr, "{}", question)?;
+    for (i, option) in options.iter().enumerate() {
+        writeln!(stderr, "  [{}] {}", i + 1, option)?;
+    }
+
+    loop {
+        write!(stderr, "Choose 1-{} (or q to abort): ", options.len())?;
+        stderr.flush()?;
+
+        let mut line = String::new();
+        if io::stdin().lock().read_line(&mut line)? == 0 {
+            return Err(anyhow!("Aborted"));
+        }
+        let answer = line.trim();
+        if answer.eq_ignore_ascii_case("q") {
+            return Err(anyhow!("Aborted"));
+        }
+        match answer.parse::<usize>() {
+            Ok(n) if (1..=options.len()).contains(&n) => return Ok(n - 1),
+            _ => writeln!(stderr, "Please enter a number between 1 and {}", options.len())?,
+        }
+    }
+}
+
+/// Ask the user to type `expected` exactly, returning whether they did
+pub fn confirm_typed(question: &str, expected: &str) -> Result<bool> {
+    let mut stderr = io::stderr();
+    write!(stderr, "{} ", question)?;
+    stderr.flush()?;
+
+    let mut line = String::new();
+    io::stdin().lock().read_line(&mut line)?;
+    Ok(line.trim() == expected)
+}
+
+/// Ask a y/N question, defaulting to no
+pub fn confirm(question: &str) -> Result<bool> {
+    let mut stderr = io::stderr();
+    write!(stderr, "{} [y/N] ", question)?;
+    stderr.flush()?;
+
+    let mut line = String::new();
+    io::stdin().lock().read_line(&mut line)?;
+    Ok(matches!(line.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// Ask a free-form question, returning the trimmed answer (empty if skipped)
+pub fn ask(question: &str) -> Result<String> {
+    let mut stderr = io::stderr();
+    write!(stderr, "{} ", question)?;
+    stderr.flush()?;
+
+    let mut line = String::new();
+    io::stdin().lock().read_line(&mut line)?;
+    Ok(line.trim().to_string())
+}
+
+/// Ask for a secret without echoing it, returning the trimmed answer
+pub fn ask_secret(question: &str) -> Result<String> {
+    Ok(rpassword::prompt_password(format!("{} ", question))?.trim().to_string())
+}
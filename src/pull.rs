@@ -0,0 +1,257 @@
+//! Reconciles remote resource values back into `rblxsync.yml`.
+//!
+//! Also used by `check`, which runs the same comparison without writing to
+//! report drift between the config and the live universe.
+//!
+//! The config is edited as a generic YAML document so unknown keys and key
+//! order survive the round trip. Comments are not preserved by the YAML
+//! serializer, which is why `pull` previews its diff before writing.
+
+use crate::fetch::ResourceKind;
+use serde_yaml::{Mapping, Value};
+
+/// A config value that differs from the remote resource
+#[derive(Debug, Clone, PartialEq)]
+pub struct PullChange {
+    pub section: String,
+    pub name: String,
+    pub field: String,
+    pub old: Option<Value>,
+    pub new: Value,
+    /// The config value is a template or comes from a file, so it was left
+    /// as is for the user to reconcile
+    pub conflict: bool,
+}
+
+impl std::fmt::Display for PullChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let old = self.old.as_ref().map(display_value).unwrap_or_else(|| "(unset)".to_string());
+        write!(f, "{}[\"{}\"].{}: {} -> {}", self.section, self.name, self.field, old, display_value(&self.new))
+    }
+}
+
+fn display_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => format!("{:?}", s),
+        other => serde_yaml::to_string(other).unwrap_or_default().trim().to_string(),
+    }
+}
+
+/// How a config field is read from a remote API item
+pub struct FieldMapping {
+    pub field: &'static str,
+    pub extract: fn(&serde_json::Value) -> Option<Value>,
+}
+
+/// Fields pulled for each config section
+pub fn mappings(section: &str) -> Vec<FieldMapping> {
+    match section {
+        "game_passes" => vec![
+            FieldMapping { field: "description", extract: remote_description },
+            FieldMapping { field: "price", extract: remote_price },
+            FieldMapping { field: "is_for_sale", extract: |item| remote_bool(item, &["isForSale"]) },
+            FieldMapping { field: "regional_pricing_enabled", extract: |item| remote_bool(item, &["isRegionalPricingEnabled"]) },
+        ],
+        "developer_products" => vec![
+            FieldMapping { field: "description", extract: remote_description },
+            FieldMapping { field: "price", extract: remote_price },
+            FieldMapping { field: "is_active", extract: |item| remote_bool(item, &["isForSale", "isActive"]) },
+            FieldMapping { field: "regional_pricing_enabled", extract: |item| remote_bool(item, &["isRegionalPricingEnabled"]) },
+        ],
+        "badges" => vec![
+            FieldMapping { field: "description", extract: remote_description },
+            FieldMapping { field: "is_enabled", extract: |item| remote_bool(item, &["enabled", "isEnabled"]) },
+        ],
+        _ => Vec::new(),
+    }
+}
+
+fn remote_description(item: &serde_json::Value) -> Option<Value> {
+    item["description"]
+        .as_str()
+        .filter(|d| !d.is_empty())
+        .map(|d| Value::String(d.to_string()))
+}
+
+fn remote_price(item: &serde_json::Value) -> Option<Value> {
+    price_of(item).map(|p| Value::Number(p.into()))
+}
+
+fn remote_bool(item: &serde_json::Value, keys: &[&str]) -> Option<Value> {
+    bool_of(item, keys).map(Value::Bool)
+}
+
+/// Price of a remote item, whichever API shape it came from
+pub fn price_of(item: &serde_json::Value) -> Option<u64> {
+    item["price"].as_u64()
+        .or_else(|| item["priceInRobux"].as_u64())
+        .or_else(|| item["priceInformation"]["defaultPriceInRobux"].as_u64())
+}
+
+/// When a remote item was last modified, whichever API shape it came from
+pub fn updated_of(item: &serde_json::Value) -> Option<String> {
+    item["updatedTimestamp"].as_str().or_else(|| item["updated"].as_str()).map(|t| t.to_string())
+}
+
+/// Asset ID of a remote item's current icon, whichever API shape it came from
+pub fn icon_asset_id_of(item: &serde_json::Value) -> Option<u64> {
+    ["iconAssetId", "iconImageAssetId", "iconImageId", "displayIconImageId"]
+        .iter()
+        .find_map(|k| item[*k].as_u64().or_else(|| item[*k].as_str().and_then(|s| s.parse().ok())))
+        .filter(|id| *id != 0)
+}
+
+/// First boolean found under any of `keys`
+pub fn bool_of(item: &serde_json::Value, keys: &[&str]) -> Option<bool> {
+    keys.iter().find_map(|k| item[*k].as_bool())
+}
+
+/// Update entries of `section` in the config document to match `remote`
+/// items, matching by pinned ID or else by name or alias (case-insensitive).
+pub fn apply_remote(doc: &mut Value, section: &str, remote: &[serde_json::Value]) -> Vec<PullChange> {
+    let mut changes = Vec::new();
+    let fields = mappings(section);
+
+    let Some(entries) = doc.get_mut(section).and_then(|v| v.as_sequence_mut()) else {
+        return changes;
+    };
+
+    for entry in entries.iter_mut() {
+        let Some(map) = entry.as_mapping_mut() else { continue };
+        let Some(name) = map.get("name").and_then(|n| n.as_str()).map(|n| n.to_string()) else { continue };
+        let Some(item) = find_item(remote, section, entry_id(map), &name, &entry_aliases(map)) else { continue };
+
+        for mapping in &fields {
+            let Some(new) = (mapping.extract)(item) else { continue };
+            let key = Value::String(mapping.field.to_string());
+            let old = map.get(&key).cloned();
+            if old.as_ref() == Some(&new) {
+                continue;
+            }
+            let conflict = is_source_managed(map, mapping.field);
+            changes.push(PullChange {
+                section: section.to_string(),
+                name: name.clone(),
+                field: mapping.field.to_string(),
+                old,
+                new: new.clone(),
+                conflict,
+            });
+            if !conflict {
+                map.insert(key, new);
+            }
+        }
+    }
+
+    changes
+}
+
+/// Whether `field` of a config entry holds a `{{variable}}` template or is
+/// read from a file (`<field>_file`), so the rendered remote value can't
+/// replace it without losing the source
+fn is_source_managed(map: &serde_yaml::Mapping, field: &str) -> bool {
+    let templated = map.get(field).and_then(|v| v.as_str()).is_some_and(|v| v.contains("{{"));
+    templated || map.contains_key(format!("{}_file", field).as_str())
+}
+
+/// Names of entries in `section` that have no matching remote item
+pub fn missing_remote(doc: &Value, section: &str, remote: &[serde_json::Value]) -> Vec<String> {
+    let Some(entries) = doc.get(section).and_then(|v| v.as_sequence()) else {
+        return Vec::new();
+    };
+    entries
+        .iter()
+        .filter_map(|entry| entry.as_mapping())
+        .filter_map(|map| {
+            let name = map.get("name").and_then(|n| n.as_str())?;
+            find_item(remote, section, entry_id(map), name, &entry_aliases(map)).is_none().then(|| name.to_string())
+        })
+        .collect()
+}
+
+/// Remote item with the pinned ID, or else named `name` or one of `aliases`
+/// (case-insensitive). `section` selects how IDs are read from items.
+pub fn find_item<'a>(remote: &'a [serde_json::Value], section: &str, pinned: Option<u64>, name: &str, aliases: &[String]) -> Option<&'a serde_json::Value> {
+    if let Some(pinned) = pinned {
+        let kind = ResourceKind::ALL.into_iter().find(|k| k.key() == section)?;
+        return remote.iter().find(|item| kind.item_id(item) == Some(pinned));
+    }
+    remote.iter().find(|item| {
+        item["name"].as_str().map(|remote_name| {
+            let remote_name = remote_name.to_lowercase();
+            remote_name == name.to_lowercase() || aliases.iter().any(|a| a.to_lowercase() == remote_name)
+        }).unwrap_or(false)
+    })
+}
+
+fn entry_id(map: &Mapping) -> Option<u64> {
+    map.get("id").and_then(|id| id.as_u64())
+}
+
+fn entry_aliases(map: &Mapping) -> Vec<String> {
+    map.get("aliases")
+        .and_then(|a| a.as_sequence())
+        .map(|seq| seq.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_apply_remote() {
+        let mut doc: Value = serde_yaml::from_str(
+            "universe: { id: 1 }\n\
+             game_passes:\n  - name: VIP\n    price: 100\n    extra: keep\n  - name: Other\n    aliases: [Old]\n",
+        )
+        .unwrap();
+        let remote = vec![
+            json!({ "name": "vip", "price": 150, "description": "Now cheaper!", "isForSale": true }),
+            json!({ "name": "Old", "price": 10 }),
+        ];
+
+        let changes = apply_remote(&mut doc, "game_passes", &remote);
+        assert_eq!(changes.len(), 4);
+        assert_eq!(changes[1].to_string(), "game_passes[\"VIP\"].price: 100 -> 150");
+
+        let vip = &doc["game_passes"][0];
+        assert_eq!(vip["price"], Value::Number(150.into()));
+        assert_eq!(vip["is_for_sale"], Value::Bool(true));
+        assert_eq!(vip["extra"], Value::String("keep".to_string()));
+        assert_eq!(doc["game_passes"][1]["price"], Value::Number(10.into()));
+
+        // A second pull is a no-op
+        assert!(apply_remote(&mut doc, "game_passes", &remote).is_empty());
+
+        let remote = vec![json!({ "name": "VIP" })];
+        assert_eq!(missing_remote(&doc, "game_passes", &remote), vec!["Other".to_string()]);
+
+        // A pinned ID wins over a name match
+        let mut doc: Value = serde_yaml::from_str("game_passes:\n  - name: VIP\n    id: 42\n").unwrap();
+        let remote = vec![json!({ "id": 7, "name": "VIP", "price": 1 }), json!({ "id": 42, "name": "Renamed", "price": 2 })];
+        apply_remote(&mut doc, "game_passes", &remote);
+        assert_eq!(doc["game_passes"][0]["price"], Value::Number(2.into()));
+    }
+
+    #[test]
+    fn test_apply_remote_keeps_templates() {
+        let mut doc: Value = serde_yaml::from_str(
+            "game_passes:\n  - name: VIP\n    description: \"Season {{season}}\"\n    price: 100\n\
+             \x20 - name: Boost\n    description_file: boost.md\n",
+        )
+        .unwrap();
+        let remote = vec![
+            json!({ "name": "VIP", "description": "Season 3", "price": 150 }),
+            json!({ "name": "Boost", "description": "Faster" }),
+        ];
+
+        let changes = apply_remote(&mut doc, "game_passes", &remote);
+        let conflicts: Vec<_> = changes.iter().filter(|c| c.conflict).map(|c| (c.name.as_str(), c.field.as_str())).collect();
+        assert_eq!(conflicts, [("VIP", "description"), ("Boost", "description")]);
+        assert_eq!(doc["game_passes"][0]["description"], Value::String("Season {{season}}".to_string()));
+        assert_eq!(doc["game_passes"][0]["price"], Value::Number(150.into()));
+        assert!(doc["game_passes"][1].get("description").is_none());
+    }
+}
@@ -0,0 +1,72 @@
+//! Keeps secrets out of logs, reports and error messages.
+//!
+//! API keys, the `.ROBLOSECURITY` cookie and CSRF tokens are registered
+//! when a client is created with them; [`redact`] replaces every registered
+//! secret with `<redacted>`. The logger (see [`logging`](crate::logging))
+//! applies it to every line, so `-vv` output with request and response
+//! bodies is safe to paste into a bug report. Roblox cookies are recognised
+//! by their warning prefix even when they were never registered.
+
+use std::borrow::Cow;
+use std::sync::RwLock;
+
+const REDACTED: &str = "<redacted>";
+
+/// Start of every `.ROBLOSECURITY` cookie value
+const COOKIE_PREFIX: &str = "_|WARNING:-DO-NOT-SHARE-THIS.";
+
+/// Shorter values aren't registered, as redacting them would mangle
+/// unrelated text (e.g. the placeholder key used with `--replay`)
+const MIN_SECRET_LEN: usize = 8;
+
+static SECRETS: RwLock<Vec<String>> = RwLock::new(Vec::new());
+
+/// Redact `secret` from everything logged or reported from now on
+pub fn register(secret: &str) {
+    let secret = secret.trim();
+    if secret.len() < MIN_SECRET_LEN {
+        return;
+    }
+    let mut secrets = SECRETS.write().unwrap();
+    if !secrets.iter().any(|s| s == secret) {
+        secrets.push(secret.to_string());
+        // Longest first, so a secret containing another is replaced whole
+        secrets.sort_by_key(|s| std::cmp::Reverse(s.len()));
+    }
+}
+
+/// `text` with every registered secret and Roblox cookie replaced
+pub fn redact(text: &str) -> Cow<'_, str> {
+    let secrets = SECRETS.read().unwrap();
+    if !text.contains(COOKIE_PREFIX) && !secrets.iter().any(|s| text.contains(s.as_str())) {
+        return Cow::Borrowed(text);
+    }
+    let mut text = text.to_string();
+    for secret in secrets.iter() {
+        text = text.replace(secret.as_str(), REDACTED);
+    }
+    while let Some(start) = text.find(COOKIE_PREFIX) {
+        let rest = &text[start..];
+        let end = rest.find(|c: char| c.is_whitespace() || matches!(c, '"' | '\'' | ';' | ',')).unwrap_or(rest.len());
+        text.replace_range(start..start + end, REDACTED);
+    }
+    Cow::Owned(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact() {
+        register("test-secret-key-123");
+        register("short");
+        assert_eq!(redact("x-api-key: test-secret-key-123, body: {}"), "x-api-key: <redacted>, body: {}");
+        assert_eq!(redact("a short note"), "a short note");
+        assert!(matches!(redact("nothing to hide"), Cow::Borrowed(_)));
+
+        let cookie = format!("{}--Sharing-this-will-allow-someone-to-log-in-as-you.|_ABC123", COOKIE_PREFIX);
+        assert_eq!(redact(&format!(r#"{{"cookie":"{}"}}"#, cookie)), r#"{"cookie":"<redacted>"}"#);
+        assert_eq!(redact(&format!(".ROBLOSECURITY={}; path=/", cookie)), ".ROBLOSECURITY=<redacted>; path=/");
+    }
+}
@@ -0,0 +1,446 @@
+//! Remote state backends.
+//!
+//! With `state_backend` configured, the lock file is shared through a data
+//! store entry in the universe, an S3-compatible bucket or a git branch
+//! instead of being committed. Commands that use state download it over the
+//! local lock file first and upload it again afterwards if it changed, so
+//! the rest of rblxsync keeps reading and writing the local file. Uploads
+//! are conditional on the version that was downloaded (or create-only when
+//! there was none): when another runner saved state in between, the upload
+//! fails instead of discarding its changes.
+//!
+//! The content last downloaded or uploaded is kept in
+//! `.rblxsync/remote-base.yml`. A local lock file that changed since then,
+//! e.g. after such a failed upload, is merged with the remote copy entry by
+//! entry rather than overwritten, so the IDs it recorded aren't lost.
+
+use crate::api::{ClientOptions, RobloxClient};
+use crate::config::{Config, RblxSyncConfig, StateBackendConfig};
+use crate::state::{replace_file, SyncState};
+use anyhow::{anyhow, bail, Context, Result};
+use hmac::{Hmac, Mac};
+use serde_yaml::{Mapping, Value};
+use tracing::{info, warn};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// The lock file's shared copy, downloaded for the current command
+pub struct RemoteState {
+    store: Store,
+    /// Local lock file
+    local: PathBuf,
+    /// Copy of the content last synced with the remote
+    base: PathBuf,
+    /// Name of the remote copy (prefix and lock file name)
+    name: String,
+    /// Content as downloaded; None when there was no remote copy yet
+    pulled: Option<String>,
+    /// Version the upload is conditional on
+    version: Option<String>,
+}
+
+enum Store {
+    Datastore { client: RobloxClient, universe_id: u64, datastore: String },
+    S3 { bucket: String, region: String, endpoint: String },
+    Git { remote: String, branch: String },
+}
+
+impl RemoteState {
+    /// Download the remote lock file of the config at `config_path` over the
+    /// local one. None when the config has no `state_backend` (or doesn't
+    /// load; the command reports that itself). A data store backend is
+    /// reached with a client set up from `options`, so its requests go
+    /// through the same middleware as the command's.
+    pub async fn pull(config_path: &Path, universe_override: Option<u64>, options: &ClientOptions) -> Result<Option<Self>> {
+        let Ok(config) = RblxSyncConfig::load(config_path) else { return Ok(None) };
+        let Some(backend) = config.state_backend.clone() else { return Ok(None) };
+        let root = config_path.parent().unwrap_or(Path::new("."));
+        let local = SyncState::get_state_path(root);
+        let base = base_path(root);
+        let file_name = local.file_name().unwrap_or_default().to_string_lossy().into_owned();
+        let (store, prefix) = match backend {
+            StateBackendConfig::Datastore { datastore, prefix } => {
+                let env = Config::from_env(config_path)?;
+                let universe_id = universe_override.unwrap_or(config.universe.id);
+                (Store::Datastore { client: RobloxClient::with_options(env.api_key, options.clone()), universe_id, datastore }, prefix)
+            }
+            StateBackendConfig::S3 { bucket, region, endpoint, prefix } => {
+                let endpoint = endpoint.unwrap_or_else(|| format!("https://s3.{}.amazonaws.com", region));
+                (Store::S3 { bucket, region, endpoint }, prefix)
+            }
+            StateBackendConfig::Git { branch, remote, prefix } => (Store::Git { remote, branch }, prefix),
+        };
+        let name = format!("{}{}", prefix, file_name);
+
+        let (pulled, version) = store.fetch(&name).await.with_context(|| format!("Failed to read remote state from {}", store.describe(&name)))?;
+        match &pulled {
+            Some(content) => {
+                let current = fs::read_to_string(&local).ok();
+                let previous = fs::read_to_string(&base).ok();
+                let merged = match &current {
+                    // Changed locally since the last sync: merge rather than lose it
+                    Some(current) if current != content && previous.as_ref() != Some(current) => {
+                        warn!("{} has changes that were never uploaded; merging them with {}", local.display(), store.describe(&name));
+                        merge(previous.as_deref(), current, content).with_context(|| format!("Failed to merge {} with the remote state", local.display()))?
+                    }
+                    _ => content.clone(),
+                };
+                replace_file(&local, &merged).with_context(|| format!("Failed to write {}", local.display()))?;
+                save_base(&base, content)?;
+                info!("Loaded state from {}", store.describe(&name));
+            }
+            None if local.exists() => info!("No state in {} yet; {} will be uploaded", store.describe(&name), local.display()),
+            None => info!("No state in {} yet", store.describe(&name)),
+        }
+        Ok(Some(Self { store, local, base, name, pulled, version }))
+    }
+
+    /// Upload the local lock file if the command changed it
+    pub async fn push(self) -> Result<()> {
+        let Ok(content) = fs::read_to_string(&self.local) else { return Ok(()) };
+        if self.pulled.as_ref() == Some(&content) {
+            return Ok(());
+        }
+        self.store
+            .save(&self.name, &content, self.version.as_deref())
+            .await
+            .with_context(|| format!("Failed to save state to {}", self.store.describe(&self.name)))?;
+        save_base(&self.base, &content)?;
+        info!("Saved state to {}", self.store.describe(&self.name));
+        Ok(())
+    }
+}
+
+fn base_path(project_root: &Path) -> PathBuf {
+    project_root.join(".rblxsync").join("remote-base.yml")
+}
+
+fn save_base(path: &Path, content: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = crate::security::private_file().write(true).create(true).truncate(true).open(path)?;
+    file.write_all(content.as_bytes()).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Lock file sections merged entry by entry; the rest are merged whole
+const KEYED_SECTIONS: [&str; 7] = ["game_passes", "developer_products", "badges", "assets", "icons", "icon_sources", "test_publishes"];
+
+/// Three-way merge of lock files: each entry (or other top-level field) the
+/// local copy changed since `base` is taken from it, the rest from `remote`.
+/// Without a base, every local entry counts as changed.
+fn merge(base: Option<&str>, local: &str, remote: &str) -> Result<String> {
+    let parse = |content: &str| -> Result<Mapping> {
+        let content = serde_yaml::to_string(&SyncState::parse(content)?)?;
+        Ok(serde_yaml::from_str(&content)?)
+    };
+    let base = base.map(parse).transpose()?.unwrap_or_default();
+    let local = parse(local)?;
+    let mut merged = parse(remote)?;
+
+    let keys: Vec<Value> = local.keys().chain(base.keys()).cloned().collect();
+    for key in keys {
+        let (b, l) = (base.get(&key), local.get(&key));
+        if !KEYED_SECTIONS.contains(&key.as_str().unwrap_or_default()) {
+            pick(&mut merged, &key, b, l);
+            continue;
+        }
+        let empty = Mapping::new();
+        let (b, l) = (b.and_then(Value::as_mapping).unwrap_or(&empty), l.and_then(Value::as_mapping).unwrap_or(&empty));
+        let section = merged.entry(key).or_insert_with(|| Value::Mapping(Mapping::new()));
+        let Some(section) = section.as_mapping_mut() else { continue };
+        for entry in l.keys().chain(b.keys()).cloned().collect::<Vec<_>>() {
+            pick(section, &entry, b.get(&entry), l.get(&entry));
+        }
+    }
+    let merged: SyncState = serde_yaml::from_value(Value::Mapping(merged))?;
+    Ok(serde_yaml::to_string(&merged)?)
+}
+
+/// Take `key` from the local copy into `merged` if it changed since the base
+fn pick(merged: &mut Mapping, key: &Value, base: Option<&Value>, local: Option<&Value>) {
+    if local == base {
+        return;
+    }
+    match local {
+        Some(value) => {
+            merged.insert(key.clone(), value.clone());
+        }
+        None => {
+            merged.remove(key);
+        }
+    }
+}
+
+impl Store {
+    fn describe(&self, name: &str) -> String {
+        match self {
+            Store::Datastore { universe_id, datastore, .. } => format!("data store '{}' entry '{}' of universe {}", datastore, name, universe_id),
+            Store::S3 { bucket, .. } => format!("s3://{}/{}", bucket, name),
+            Store::Git { remote, branch } => format!("{}/{}:{}", remote, branch, name),
+        }
+    }
+
+    /// The remote content, if any, and the version to make the next save
+    /// conditional on
+    async fn fetch(&self, name: &str) -> Result<(Option<String>, Option<String>)> {
+        match self {
+            Store::Datastore { client, universe_id, datastore } => {
+                let Some(entry) = client.get_datastore_entry(*universe_id, datastore, None, name).await? else { return Ok((None, None)) };
+                let content = entry["value"].as_str().ok_or_else(|| anyhow!("entry '{}' doesn't hold a lock file", name))?;
+                Ok((Some(content.to_string()), entry["etag"].as_str().map(str::to_string)))
+            }
+            Store::S3 { bucket, region, endpoint } => {
+                let response = s3_request(reqwest::Method::GET, endpoint, region, bucket, name, None, &[]).await?;
+                if response.status() == reqwest::StatusCode::NOT_FOUND {
+                    return Ok((None, None));
+                }
+                let status = response.status();
+                let etag = response.headers().get(reqwest::header::ETAG).and_then(|v| v.to_str().ok()).map(str::to_string);
+                let text = response.text().await.unwrap_or_default();
+                if !status.is_success() {
+                    bail!("S3 request failed: {} - {}", status, text);
+                }
+                Ok((Some(text), etag))
+            }
+            Store::Git { remote, branch } => {
+                let heads = git(&["ls-remote", "--heads", remote, &format!("refs/heads/{}", branch)], None, None)?;
+                if heads.trim().is_empty() {
+                    return Ok((None, None));
+                }
+                git(&["fetch", "--quiet", remote, &format!("refs/heads/{}", branch)], None, None)?;
+                let commit = git(&["rev-parse", "FETCH_HEAD"], None, None)?.trim().to_string();
+                let object = format!("{}:{}", commit, name);
+                if git(&["cat-file", "-e", &object], None, None).is_err() {
+                    return Ok((None, Some(commit)));
+                }
+                Ok((Some(git(&["show", &object], None, None)?), Some(commit)))
+            }
+        }
+    }
+
+    /// Save `content`, failing if the remote changed since `version` was read
+    async fn save(&self, name: &str, content: &str, version: Option<&str>) -> Result<()> {
+        match self {
+            Store::Datastore { client, universe_id, datastore } => {
+                let value = serde_json::json!(content);
+                let result = match version {
+                    Some(etag) => client.set_datastore_entry(*universe_id, datastore, None, name, &value, Some(etag)).await,
+                    // Create-only, so a first save can't overwrite one made meanwhile
+                    None => client.create_datastore_entry(*universe_id, datastore, None, name, &value).await,
+                };
+                match result {
+                    Err(e) if matches!(crate::api::ApiError::status_of(&e), Some(reqwest::StatusCode::CONFLICT | reqwest::StatusCode::PRECONDITION_FAILED)) => {
+                        bail!("another run saved state since it was read; run the command again to sync against it")
+                    }
+                    result => result.map(|_| ()),
+                }
+            }
+            Store::S3 { bucket, region, endpoint } => {
+                // Supported by AWS and most S3-compatible stores
+                let condition = match version {
+                    Some(etag) => ("if-match", etag),
+                    None => ("if-none-match", "*"),
+                };
+                let response = s3_request(reqwest::Method::PUT, endpoint, region, bucket, name, Some(content), &[condition]).await?;
+                let status = response.status();
+                if status == reqwest::StatusCode::PRECONDITION_FAILED || status == reqwest::StatusCode::CONFLICT {
+                    bail!("another run saved state since it was read; run the command again to sync against it");
+                }
+                if !status.is_success() {
+                    bail!("S3 request failed: {} - {}", status, response.text().await.unwrap_or_default());
+                }
+                Ok(())
+            }
+            Store::Git { remote, branch } => {
+                let blob = git(&["hash-object", "-w", "--stdin"], Some(content), None)?.trim().to_string();
+                // A private index keeps the user's staged changes untouched
+                let index = std::env::temp_dir().join(format!("rblxsync-state-index-{}", std::process::id()));
+                let _ = fs::remove_file(&index);
+                if let Some(parent) = version {
+                    git(&["read-tree", parent], None, Some(&index))?;
+                }
+                git(&["update-index", "--add", "--cacheinfo", &format!("100644,{},{}", blob, name)], None, Some(&index))?;
+                let tree = git(&["write-tree"], None, Some(&index))?.trim().to_string();
+                let _ = fs::remove_file(&index);
+
+                let message = format!("Update {} ({})", name, crate::actor::current());
+                let mut args = Vec::new();
+                if git(&["config", "user.email"], None, None).is_err() {
+                    args.extend(["-c", "user.name=rblxsync", "-c", "user.email=rblxsync@localhost"]);
+                }
+                args.extend(["commit-tree", tree.as_str(), "-m", message.as_str()]);
+                if let Some(parent) = version {
+                    args.extend(["-p", parent]);
+                }
+                let commit = git(&args, None, None)?.trim().to_string();
+                // Rejected unless it fast-forwards from the fetched commit
+                git(&["push", "--quiet", remote, &format!("{}:refs/heads/{}", commit, branch)], None, None)
+                    .context("the push was rejected; if another run saved state since it was read, run the command again")?;
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Run git in the project directory, returning its stdout
+fn git(args: &[&str], stdin: Option<&str>, index: Option<&Path>) -> Result<String> {
+    let mut command = Command::new("git");
+    command.args(args).stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::piped());
+    if let Some(index) = index {
+        command.env("GIT_INDEX_FILE", index);
+    }
+    let mut child = command.spawn().context("Failed to run git")?;
+    if let Some(mut pipe) = child.stdin.take() {
+        pipe.write_all(stdin.unwrap_or_default().as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        bail!("git {} failed: {}", args.join(" "), String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+// --- S3 (AWS Signature Version 4) ---
+
+/// Send a path-style S3 request for `key` in `bucket`, signed with the
+/// credentials in `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY` and
+/// optionally `AWS_SESSION_TOKEN`
+async fn s3_request(
+    method: reqwest::Method,
+    endpoint: &str,
+    region: &str,
+    bucket: &str,
+    key: &str,
+    body: Option<&str>,
+    headers: &[(&str, &str)],
+) -> Result<reqwest::Response> {
+    let access_key = std::env::var("AWS_ACCESS_KEY_ID").context("AWS_ACCESS_KEY_ID is not set")?;
+    let secret_key = std::env::var("AWS_SECRET_ACCESS_KEY").context("AWS_SECRET_ACCESS_KEY is not set")?;
+    let session_token = std::env::var("AWS_SESSION_TOKEN").ok();
+
+    let path = format!("/{}/{}", uri_encode(bucket), key.split('/').map(uri_encode).collect::<Vec<_>>().join("/"));
+    let url = reqwest::Url::parse(&format!("{}{}", endpoint.trim_end_matches('/'), path))?;
+    let host = match (url.host_str(), url.port()) {
+        (Some(host), Some(port)) => format!("{}:{}", host, port),
+        (Some(host), None) => host.to_string(),
+        (None, _) => bail!("Invalid S3 endpoint: {}", endpoint),
+    };
+    let payload_hash = crate::assets::hash_bytes(body.unwrap_or_default().as_bytes());
+    let amz_date = amz_date(crate::actor::now());
+
+    let mut signed = vec![("host", host.as_str()), ("x-amz-content-sha256", payload_hash.as_str()), ("x-amz-date", amz_date.as_str())];
+    if let Some(token) = &session_token {
+        signed.push(("x-amz-security-token", token.as_str()));
+    }
+    let authorization = authorization(method.as_str(), &path, &signed, &payload_hash, &amz_date, region, &access_key, &secret_key);
+
+    let mut request = reqwest::Client::new().request(method, url).header("authorization", authorization);
+    for (name, value) in signed.iter().filter(|(name, _)| *name != "host").chain(headers) {
+        request = request.header(*name, *value);
+    }
+    if let Some(body) = body {
+        request = request.body(body.to_string());
+    }
+    Ok(request.send().await?)
+}
+
+/// `Authorization` header for a request whose `signed` headers are sorted
+/// by name
+#[allow(clippy::too_many_arguments)]
+fn authorization(method: &str, path: &str, signed: &[(&str, &str)], payload_hash: &str, amz_date: &str, region: &str, access_key: &str, secret_key: &str) -> String {
+    let canonical_headers: String = signed.iter().map(|(name, value)| format!("{}:{}\n", name, value.trim())).collect();
+    let signed_names = signed.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(";");
+    let canonical_request = format!("{}\n{}\n\n{}\n{}\n{}", method, path, canonical_headers, signed_names, payload_hash);
+    let date = &amz_date[..8];
+    let credential_scope = format!("{}/{}/s3/aws4_request", date, region);
+    let string_to_sign = format!("AWS4-HMAC-SHA256\n{}\n{}\n{:x}", amz_date, credential_scope, Sha256::digest(canonical_request.as_bytes()));
+    let signature: String = hmac(&signing_key(secret_key, date, region, "s3"), string_to_sign.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect();
+    format!("AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}", access_key, credential_scope, signed_names, signature)
+}
+
+fn signing_key(secret_key: &str, date: &str, region: &str, service: &str) -> Vec<u8> {
+    let key = hmac(format!("AWS4{}", secret_key).as_bytes(), date.as_bytes());
+    let key = hmac(&key, region.as_bytes());
+    let key = hmac(&key, service.as_bytes());
+    hmac(&key, b"aws4_request")
+}
+
+fn hmac(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// `YYYYMMDDTHHMMSSZ`
+fn amz_date(secs: u64) -> String {
+    let (y, m, d) = crate::template::civil_from_days((secs / 86_400) as i64);
+    let s = secs % 86_400;
+    format!("{:04}{:02}{:02}T{:02}{:02}{:02}Z", y, m, d, s / 3600, s % 3600 / 60, s % 60)
+}
+
+/// Percent-encode everything but unreserved characters, as SigV4 requires
+fn uri_encode(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sigv4() {
+        // Example from the AWS Signature Version 4 documentation
+        let key = signing_key("wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY", "20120215", "us-east-1", "iam");
+        let hex: String = key.iter().map(|b| format!("{:02x}", b)).collect();
+        assert_eq!(hex, "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d");
+
+        assert_eq!(amz_date(1_329_264_000 + 3_723), "20120215T010203Z");
+        assert_eq!(uri_encode("rblxsync-lock.main.yml"), "rblxsync-lock.main.yml");
+        assert_eq!(uri_encode("a b+c"), "a%20b%2Bc");
+    }
+
+    #[test]
+    fn test_merge() {
+        let base = "game_passes:\n  1:\n    name: VIP\n    price: 100\nicons:\n  a: 10\n";
+        // This run created a pass and uploaded an icon; another one changed a price
+        let local = "game_passes:\n  1:\n    name: VIP\n    price: 100\n  2:\n    name: Boost\nicons:\n  a: 10\n  b: 20\n";
+        let remote = "game_passes:\n  1:\n    name: VIP\n    price: 150\n  3:\n    name: Other\nicons:\n  a: 10\n  c: 30\n";
+
+        let merged = SyncState::parse(&merge(Some(base), local, remote).unwrap()).unwrap();
+        assert_eq!(merged.game_passes[&1].price, Some(150));
+        assert!(merged.game_passes.contains_key(&2) && merged.game_passes.contains_key(&3));
+        assert_eq!(merged.icons.len(), 3);
+
+        // Entries removed locally stay removed
+        let local = "game_passes:\n  2:\n    name: Boost\nicons:\n  a: 10\n";
+        let merged = SyncState::parse(&merge(Some(base), local, remote).unwrap()).unwrap();
+        assert!(!merged.game_passes.contains_key(&1));
+
+        // Without a base, local entries win and remote ones are added
+        let merged = SyncState::parse(&merge(None, local, remote).unwrap()).unwrap();
+        assert_eq!(merged.game_passes.keys().copied().collect::<std::collections::BTreeSet<_>>(), [1, 2, 3].into());
+    }
+
+    #[test]
+    fn test_state_backend_config() {
+        let parse = |yaml: &str| serde_yaml::from_str::<StateBackendConfig>(yaml);
+        assert_eq!(
+            parse("type: datastore").unwrap(),
+            StateBackendConfig::Datastore { datastore: "rblxsync-state".to_string(), prefix: String::new() }
+        );
+        assert!(matches!(parse("type: s3\nbucket: studio-state\nprefix: obby/").unwrap(), StateBackendConfig::S3 { region, .. } if region == "us-east-1"));
+        assert!(matches!(parse("type: git").unwrap(), StateBackendConfig::Git { branch, remote, .. } if branch == "rblxsync-state" && remote == "origin"));
+        assert!(parse("type: s3").is_err());
+        assert!(parse("type: git\nbranchh: x").is_err());
+    }
+}
@@ -0,0 +1,444 @@
+//! Machine-readable record of a single rblxsync invocation.
+//!
+//! Every command fills in a `RunReport` as it goes; at exit the report is
+//! written to `.rblxsync/last-run.json` so downstream tooling can consume
+//! the outcome without parsing logs. The JSON layout is a stable contract:
+//! add fields, never rename or remove them.
+//!
+//! With `--output json`, the report also streams each change, skip, error
+//! and artifact to stdout as a newline-delimited JSON event, followed by a
+//! final `summary` event.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// Version of the manifest layout, bumped on incompatible changes
+pub const MANIFEST_VERSION: u32 = 1;
+
+/// How commands report progress on stdout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum OutputMode {
+    /// Human-readable log lines
+    #[default]
+    Text,
+    /// Newline-delimited JSON events
+    Json,
+}
+
+/// What happened to a resource during a run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeAction {
+    Create,
+    Update,
+    Upload,
+    Publish,
+    /// Remote differs from config (reported by `check`, never applied)
+    Drift,
+    /// Remote file saved locally (e.g. `icons download`)
+    Download,
+    /// Asset no longer in use archived (e.g. `assets gc`)
+    Archive,
+}
+
+/// A single field that differs between the previous and desired state
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub old: serde_json::Value,
+    pub new: serde_json::Value,
+}
+
+/// A resource that was (or in dry-run mode, would be) changed
+#[derive(Debug, Clone, Serialize)]
+pub struct ResourceChange {
+    pub resource_type: String,
+    pub name: String,
+    pub id: Option<u64>,
+    pub action: ChangeAction,
+    pub fields: Vec<FieldChange>,
+}
+
+/// Totals across all resource types
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Counts {
+    pub created: u32,
+    pub updated: u32,
+    pub skipped: u32,
+    pub uploaded: u32,
+    pub published: u32,
+    pub drifted: u32,
+    pub downloaded: u32,
+    pub archived: u32,
+    pub failed: u32,
+}
+
+/// One row of the end-of-run summary table
+#[derive(Debug, Clone, Serialize)]
+pub struct SummaryRow {
+    pub resource_type: String,
+    pub created: u32,
+    /// Updates, uploads, publishes and archives
+    pub updated: u32,
+    pub skipped: u32,
+    pub failed: u32,
+}
+
+/// End-of-run summary, printed after `run` and written by `run --report json`
+#[derive(Debug, Clone, Serialize)]
+pub struct Summary {
+    pub rows: Vec<SummaryRow>,
+    pub duration_ms: u128,
+    pub api_calls: u64,
+}
+
+impl Summary {
+    /// Render as an aligned text table
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = vec![format!("{:<20} {:>8} {:>8} {:>8} {:>8}", "RESOURCE", "CREATED", "UPDATED", "SKIPPED", "FAILED")];
+        for row in &self.rows {
+            lines.push(format!("{:<20} {:>8} {:>8} {:>8} {:>8}", row.resource_type, row.created, row.updated, row.skipped, row.failed));
+        }
+        lines.push(format!("Finished in {:.1}s with {} API call(s)", self.duration_ms as f64 / 1000.0, self.api_calls));
+        lines
+    }
+}
+
+/// The set of field changes detected for one resource
+#[derive(Debug, Clone, Default)]
+pub struct ChangeSet {
+    pub fields: Vec<FieldChange>,
+}
+
+impl ChangeSet {
+    /// Record that `field` changes from `old` to `new`
+    pub fn push<O: Serialize, N: Serialize>(&mut self, field: &str, old: O, new: N) {
+        self.fields.push(FieldChange {
+            field: field.to_string(),
+            old: serde_json::to_value(old).unwrap_or_default(),
+            new: serde_json::to_value(new).unwrap_or_default(),
+        });
+    }
+
+    /// Build a change set for a newly created resource from its serialized
+    /// config, with every non-null field changing from `null`
+    pub fn created<T: Serialize>(config: &T) -> Self {
+        let mut set = Self::default();
+        if let Ok(serde_json::Value::Object(map)) = serde_json::to_value(config) {
+            for (field, value) in map {
+                if !value.is_null() {
+                    set.push(&field, serde_json::Value::Null, value);
+                }
+            }
+        }
+        set
+    }
+
+    pub fn contains(&self, field: &str) -> bool {
+        self.fields.iter().any(|f| f.field == field)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    /// Comma-separated list of changed field names, for log output
+    pub fn join(&self, sep: &str) -> String {
+        self.fields.iter().map(|f| f.field.as_str()).collect::<Vec<_>>().join(sep)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct RunReport {
+    pub manifest_version: u32,
+    pub command: String,
+    pub profile: Option<String>,
+    /// Who ran the command (see `actor::current`)
+    pub actor: Option<String>,
+    /// Fingerprint of the API key used (see `credentials::fingerprint`)
+    pub key_fingerprint: Option<String>,
+    pub dry_run: bool,
+    pub success: bool,
+    /// Unix timestamp (seconds) when the command started
+    pub started_at: u64,
+    pub duration_ms: u128,
+    pub counts: Counts,
+    /// Counts per resource type
+    pub counts_by_type: BTreeMap<String, Counts>,
+    /// Roblox API requests sent during the command
+    pub api_calls: u64,
+    pub changes: Vec<ResourceChange>,
+    pub errors: Vec<String>,
+    /// Findings from the end-of-run security audit
+    pub security_warnings: Vec<String>,
+    /// Duration of each phase in milliseconds
+    pub timings: BTreeMap<String, u128>,
+    /// Files written by the command
+    pub artifacts: Vec<String>,
+    #[serde(skip)]
+    started: Instant,
+    /// API calls made by the process before this command started (watch
+    /// and workspace runs share one process)
+    #[serde(skip)]
+    calls_at_start: u64,
+    #[serde(skip)]
+    output: OutputMode,
+    /// Also sends every event here, e.g. to an HTTP client of `serve`
+    #[serde(skip)]
+    events: Option<tokio::sync::mpsc::UnboundedSender<serde_json::Value>>,
+}
+
+impl RunReport {
+    pub fn new(command: &str) -> Self {
+        Self {
+            manifest_version: MANIFEST_VERSION,
+            command: command.to_string(),
+            profile: None,
+            actor: None,
+            key_fingerprint: None,
+            dry_run: false,
+            success: false,
+            started_at: SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0),
+            duration_ms: 0,
+            counts: Counts::default(),
+            counts_by_type: BTreeMap::new(),
+            api_calls: 0,
+            changes: Vec::new(),
+            errors: Vec::new(),
+            security_warnings: Vec::new(),
+            timings: BTreeMap::new(),
+            artifacts: Vec::new(),
+            started: Instant::now(),
+            calls_at_start: crate::api::api_calls(),
+            output: OutputMode::Text,
+            events: None,
+        }
+    }
+
+    /// Stream events to stdout as they are recorded when `output` is JSON
+    pub fn set_output(&mut self, output: OutputMode) {
+        self.output = output;
+    }
+
+    pub fn output(&self) -> OutputMode {
+        self.output
+    }
+
+    /// Send every event to `events` too, whatever the output mode
+    pub fn stream_to(&mut self, events: tokio::sync::mpsc::UnboundedSender<serde_json::Value>) {
+        self.events = Some(events);
+    }
+
+    fn emit(&self, event: serde_json::Value) {
+        if self.output == OutputMode::Json {
+            println!("{}", event);
+        }
+        if let Some(events) = &self.events {
+            // The client may have disconnected; the command carries on
+            let _ = events.send(event);
+        }
+    }
+
+    /// Record a created/updated/uploaded/published/downloaded/archived resource and bump its counter
+    pub fn record(&mut self, resource_type: &str, name: &str, id: Option<u64>, action: ChangeAction, changes: ChangeSet) {
+        let by_type = self.counts_by_type.entry(resource_type.to_string()).or_default();
+        for counts in [&mut self.counts, by_type] {
+            match action {
+                ChangeAction::Create => counts.created += 1,
+                ChangeAction::Update => counts.updated += 1,
+                ChangeAction::Upload => counts.uploaded += 1,
+                ChangeAction::Publish => counts.published += 1,
+                ChangeAction::Drift => counts.drifted += 1,
+                ChangeAction::Download => counts.downloaded += 1,
+                ChangeAction::Archive => counts.archived += 1,
+            }
+        }
+        let change = ResourceChange {
+            resource_type: resource_type.to_string(),
+            name: name.to_string(),
+            id: id.filter(|id| *id != 0),
+            action,
+            fields: changes.fields,
+        };
+        self.emit(serde_json::json!({ "event": "change", "dry_run": self.dry_run, "change": &change }));
+        self.changes.push(change);
+    }
+
+    pub fn skip(&mut self, resource_type: &str) {
+        self.counts.skipped += 1;
+        self.counts_by_type.entry(resource_type.to_string()).or_default().skipped += 1;
+        self.emit(serde_json::json!({ "event": "skip", "resource_type": resource_type }));
+    }
+
+    pub fn error(&mut self, message: impl Into<String>) {
+        let message = crate::redact::redact(&message.into()).into_owned();
+        self.emit(serde_json::json!({ "event": "error", "message": &message }));
+        self.errors.push(message);
+    }
+
+    /// Record an error attributed to one resource, counting it as failed
+    pub fn fail(&mut self, resource_type: &str, message: impl Into<String>) {
+        self.counts.failed += 1;
+        self.counts_by_type.entry(resource_type.to_string()).or_default().failed += 1;
+        self.error(message);
+    }
+
+    pub fn artifact(&mut self, path: impl AsRef<Path>) {
+        let path = path.as_ref().to_string_lossy().to_string();
+        self.emit(serde_json::json!({ "event": "artifact", "path": &path }));
+        self.artifacts.push(path);
+    }
+
+    /// Record how long a phase took, measured from `since`
+    pub fn timing(&mut self, phase: &str, since: Instant) {
+        self.timings.insert(phase.to_string(), since.elapsed().as_millis());
+    }
+
+    /// Mark the report complete with the command's final result
+    pub fn finish<T>(&mut self, result: &Result<T>) {
+        self.duration_ms = self.started.elapsed().as_millis();
+        self.api_calls = crate::api::api_calls() - self.calls_at_start;
+        self.success = result.is_ok() && self.errors.is_empty();
+        if let Err(e) = result {
+            self.error(format!("{:#}", e));
+        }
+        self.emit(serde_json::json!({
+            "event": "summary",
+            "command": &self.command,
+            "success": self.success,
+            "counts": &self.counts,
+            "duration_ms": self.duration_ms,
+            "api_calls": self.api_calls,
+        }));
+    }
+
+    /// Per-type totals for the end-of-run summary
+    pub fn summary(&self) -> Summary {
+        let rows = self
+            .counts_by_type
+            .iter()
+            .map(|(resource_type, c)| SummaryRow {
+                resource_type: resource_type.clone(),
+                created: c.created,
+                updated: c.updated + c.uploaded + c.published + c.archived,
+                skipped: c.skipped,
+                failed: c.failed,
+            })
+            .collect();
+        Summary { rows, duration_ms: self.duration_ms, api_calls: self.api_calls }
+    }
+
+    /// Render the changes as a markdown table (resource, field, old → new),
+    /// e.g. for a CI bot to post as a pull request comment
+    pub fn markdown(&self) -> String {
+        let mut out = String::from(if self.dry_run { "### rblxsync plan\n\n" } else { "### rblxsync changes\n\n" });
+        if self.changes.is_empty() {
+            out.push_str("No changes.\n");
+        } else {
+            out.push_str("| Resource | Action | Field | Change |\n|----------|--------|-------|--------|\n");
+            for change in &self.changes {
+                let resource = match change.id {
+                    Some(id) => format!("{} `{}` ({})", change.resource_type, markdown_escape(&change.name), id),
+                    None => format!("{} `{}`", change.resource_type, markdown_escape(&change.name)),
+                };
+                let action = serde_json::to_value(change.action).ok().and_then(|v| v.as_str().map(str::to_string)).unwrap_or_default();
+                if change.fields.is_empty() {
+                    out.push_str(&format!("| {} | {} | | |\n", resource, action));
+                }
+                for field in &change.fields {
+                    let diff = match &field.old {
+                        serde_json::Value::Null => markdown_value(&field.new),
+                        old => format!("{} → {}", markdown_value(old), markdown_value(&field.new)),
+                    };
+                    out.push_str(&format!("| {} | {} | {} | {} |\n", resource, action, field.field, diff));
+                }
+            }
+        }
+        out.push_str(&format!(
+            "\n{} created, {} updated, {} uploaded, {} published, {} skipped, {} failed\n",
+            self.counts.created, self.counts.updated, self.counts.uploaded, self.counts.published, self.counts.skipped, self.counts.failed
+        ));
+        if !self.errors.is_empty() {
+            out.push_str("\n**Errors**\n\n");
+            for error in &self.errors {
+                out.push_str(&format!("- {}\n", markdown_escape(error)));
+            }
+        }
+        out
+    }
+
+    /// Write the report to `.rblxsync/last-run.json` under `project_root`
+    pub fn save(&self, project_root: &Path) -> Result<PathBuf> {
+        let path = Self::manifest_path(project_root);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = crate::security::private_file().write(true).create(true).truncate(true).open(&path)?;
+        file.write_all(serde_json::to_string_pretty(self)?.as_bytes())?;
+        Ok(path)
+    }
+
+    pub fn manifest_path(project_root: &Path) -> PathBuf {
+        project_root.join(".rblxsync").join("last-run.json")
+    }
+}
+
+/// Escape characters that would break a markdown table cell
+fn markdown_escape(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+/// A field value as inline code, or an em dash when unset
+fn markdown_value(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Null => "—".to_string(),
+        serde_json::Value::String(s) => format!("`{}`", markdown_escape(s)),
+        other => format!("`{}`", markdown_escape(&other.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_counts_and_fields() {
+        let mut report = RunReport::new("run");
+        let mut changes = ChangeSet::default();
+        changes.push("price", Some(100), Some(150));
+        assert!(changes.contains("price"));
+        assert_eq!(changes.join(", "), "price");
+
+        report.record("game_pass", "VIP", Some(1), ChangeAction::Update, changes);
+        report.record("badge", "First Win", Some(0), ChangeAction::Create, ChangeSet::default());
+        report.skip("badge");
+        report.fail("badge", "icon rejected");
+        report.finish(&Ok(()));
+
+        assert_eq!(report.counts.updated, 1);
+        assert_eq!(report.counts.created, 1);
+        assert_eq!(report.counts.skipped, 1);
+        assert_eq!(report.changes[1].id, None);
+        assert!(!report.success);
+
+        let summary = report.summary();
+        assert_eq!(summary.rows.len(), 2);
+        let badge = &summary.rows[0];
+        assert_eq!((badge.resource_type.as_str(), badge.created, badge.skipped, badge.failed), ("badge", 1, 1, 1));
+        assert_eq!(summary.lines().len(), 4);
+
+        let markdown = report.markdown();
+        assert!(markdown.contains("| game_pass `VIP` (1) | update | price | `100` → `150` |"));
+        assert!(markdown.contains("| badge `First Win` | create | | |"));
+        assert!(markdown.contains("- icon rejected"));
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["changes"][0]["fields"][0]["new"], 150);
+        assert_eq!(json["changes"][0]["action"], "update");
+    }
+}
@@ -0,0 +1,101 @@
+//! Exclusive lock held while a command changes the universe or the lock
+//! file, so two CI jobs can't interleave PATCHes or overwrite each other's
+//! state.
+//!
+//! The lock is an OS file lock on `.rblxsync/lock`, released when the
+//! process exits, even if it crashes, so a stale lock can't block later
+//! runs. The holder writes who it is into the file for the error message
+//! of anyone who has to wait.
+
+use anyhow::{bail, Context, Result};
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::io::{Read, Seek, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// How often a waiting process retries
+const RETRY_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Held until dropped
+#[derive(Debug)]
+pub struct RunLock {
+    _file: File,
+}
+
+impl RunLock {
+    pub fn path(project_root: &Path) -> PathBuf {
+        project_root.join(".rblxsync").join("lock")
+    }
+
+    /// Take the lock for `command`, waiting up to `timeout` for another
+    /// process to release it
+    pub async fn acquire(project_root: &Path, command: &str, timeout: Duration) -> Result<Self> {
+        let path = Self::path(project_root);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // Not truncated on open: the holder's details stay readable until we own it
+        let mut file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .read(true)
+            .write(true)
+            .open(&path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+
+        let started = Instant::now();
+        let mut logged = false;
+        loop {
+            match file.try_lock() {
+                Ok(()) => break,
+                Err(TryLockError::WouldBlock) => {
+                    let holder = holder(&mut file);
+                    if started.elapsed() >= timeout {
+                        bail!(
+                            "Another rblxsync process is running here ({}); wait for it to finish or pass --lock-timeout <SECONDS> to wait",
+                            holder
+                        );
+                    }
+                    if !logged {
+                        tracing::info!("Waiting up to {}s for another rblxsync process ({})", timeout.as_secs(), holder);
+                        logged = true;
+                    }
+                    tokio::time::sleep(RETRY_INTERVAL.min(timeout.saturating_sub(started.elapsed()))).await;
+                }
+                Err(TryLockError::Error(e)) => return Err(e).with_context(|| format!("Failed to lock {}", path.display())),
+            }
+        }
+
+        let info = format!("pid {}, {} by {}, since {}", std::process::id(), command, crate::actor::current(), crate::template::format_timestamp(crate::actor::now()));
+        file.set_len(0)?;
+        file.rewind()?;
+        file.write_all(info.as_bytes())?;
+        Ok(Self { _file: file })
+    }
+}
+
+/// Details the current holder wrote, or a placeholder when they can't be read
+fn holder(file: &mut File) -> String {
+    let mut info = String::new();
+    match file.rewind().and_then(|_| file.read_to_string(&mut info)) {
+        Ok(_) if !info.trim().is_empty() => info.trim().to_string(),
+        _ => "holder unknown".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_run_lock() {
+        let root = std::env::temp_dir().join(format!("rblxsync-runlock-{}", std::process::id()));
+        let lock = RunLock::acquire(&root, "run", Duration::ZERO).await.unwrap();
+        let err = RunLock::acquire(&root, "publish", Duration::from_millis(100)).await.unwrap_err().to_string();
+        assert!(err.contains("run by"), "{}", err);
+
+        drop(lock);
+        RunLock::acquire(&root, "publish", Duration::ZERO).await.unwrap();
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,118 @@
+//! Works out which Open Cloud API key scopes a config needs.
+//!
+//! Used by `rblxsync scopes` so admins can mint a correctly scoped key
+//! before the first sync instead of discovering missing permissions from
+//! 403 responses.
+
+use crate::config::{RblxSyncConfig, StateBackendConfig};
+
+/// A single API key permission required by the config
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScopeRequirement {
+    /// API system as shown in the Creator Dashboard
+    pub api: &'static str,
+    /// Open Cloud scope name
+    pub scope: &'static str,
+    /// Why rblxsync needs it
+    pub reason: &'static str,
+}
+
+impl ScopeRequirement {
+    const fn new(api: &'static str, scope: &'static str, reason: &'static str) -> Self {
+        Self { api, scope, reason }
+    }
+}
+
+/// Collect the scopes needed to run `config`. With `read_only`, only the
+/// scopes used by `export` and `run --dry-run` are returned.
+pub fn required_scopes(config: &RblxSyncConfig, read_only: bool) -> Vec<ScopeRequirement> {
+    let mut scopes = Vec::new();
+    let has_icons = config.game_passes.iter().any(|p| p.icon.is_some() || p.icon_template.is_some())
+        || config.developer_products.iter().any(|p| p.icon.is_some() || p.icon_template.is_some());
+
+    // Export always lists passes and products, so they are needed even when empty in config
+    scopes.push(ScopeRequirement::new("game-passes", "game-pass:read", "List existing game passes"));
+    scopes.push(ScopeRequirement::new("developer-products", "developer-product:read", "List existing developer products"));
+
+    if !config.restrictions.is_empty() {
+        scopes.push(ScopeRequirement::new("universe.user-restriction", "universe.user-restriction:read", "Compare bans with the live ones"));
+    }
+    if config.creator.as_ref().is_some_and(|c| c.creator_type == "group") {
+        scopes.push(ScopeRequirement::new("groups", "group:read", "Check the key owner's group role permissions"));
+    }
+    if has_icons {
+        scopes.push(ScopeRequirement::new("assets", "asset:read", "Check moderation state of uploaded icons"));
+    }
+    let datastore_state = matches!(config.state_backend, Some(StateBackendConfig::Datastore { .. }));
+    if datastore_state {
+        scopes.push(ScopeRequirement::new("universe-datastores.objects", "universe-datastores.objects:read", "Download the lock file from state_backend"));
+    }
+    if config.places.iter().any(|p| p.publish) {
+        scopes.push(ScopeRequirement::new("assets", "asset:read", "Check place versions when publishing"));
+    }
+
+    if read_only {
+        return scopes;
+    }
+
+    if !config.game_passes.is_empty() {
+        scopes.push(ScopeRequirement::new("game-passes", "game-pass:write", "Create and update game passes"));
+    }
+    if !config.developer_products.is_empty() {
+        scopes.push(ScopeRequirement::new("developer-products", "developer-product:write", "Create and update developer products"));
+    }
+    if !config.badges.is_empty() {
+        scopes.push(ScopeRequirement::new("legacy-universe.badge", "legacy-universe.badge:write", "Update badges and badge icons"));
+        scopes.push(ScopeRequirement::new("legacy-universe.badge", "legacy-universe.badge:manage-and-spend-robux", "Create badges (costs Robux)"));
+    }
+    if has_icons || !config.assets.is_empty() {
+        scopes.push(ScopeRequirement::new("assets", "asset:write", "Upload icons and assets"));
+    }
+    if config.places.iter().any(|p| p.publish) {
+        scopes.push(ScopeRequirement::new("universe-places", "universe-place:write", "Publish place files"));
+    }
+    if !config.restrictions.is_empty() {
+        scopes.push(ScopeRequirement::new("universe.user-restriction", "universe.user-restriction:write", "Ban and unban users"));
+    }
+    if config.places.iter().any(|p| p.publish) && config.hooks.as_ref().is_some_and(|h| h.publish_message.is_some()) {
+        scopes.push(ScopeRequirement::new("universe-messaging-service", "universe-messaging-service:publish", "Notify live servers after publishing"));
+    }
+    if datastore_state {
+        scopes.push(ScopeRequirement::new("universe-datastores.objects", "universe-datastores.objects:create", "Create the lock file in state_backend"));
+        scopes.push(ScopeRequirement::new("universe-datastores.objects", "universe-datastores.objects:update", "Upload the lock file to state_backend"));
+    }
+    if config.places.iter().any(|p| p.publish && p.restart_after_publish) {
+        scopes.push(ScopeRequirement::new("universe", "universe:write", "Restart servers after publishing"));
+    }
+
+    scopes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(yaml: &str) -> RblxSyncConfig {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn test_required_scopes() {
+        let cfg = config(
+            "universe: { id: 1 }\n\
+             game_passes: [{ name: VIP, price: 100, icon: vip.png }]\n\
+             places: [{ place_id: 2, file_path: a.rbxl, publish: true, restart_after_publish: true }]\n",
+        );
+
+        let full: Vec<&str> = required_scopes(&cfg, false).iter().map(|s| s.scope).collect();
+        assert!(full.contains(&"game-pass:write"));
+        assert!(full.contains(&"asset:write"));
+        assert!(full.contains(&"universe-place:write"));
+        assert!(full.contains(&"universe:write"));
+        assert!(!full.contains(&"developer-product:write"));
+
+        let read: Vec<&str> = required_scopes(&cfg, true).iter().map(|s| s.scope).collect();
+        assert!(read.contains(&"game-pass:read"));
+        assert!(!read.iter().any(|s| s.ends_with(":write")));
+    }
+}
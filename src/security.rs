@@ -0,0 +1,164 @@
+//! End-of-run security checks.
+//!
+//! rblxsync handles an API key (and sometimes a `.ROBLOSECURITY` cookie) and
+//! writes state that maps resource names to IDs. These checks catch the
+//! common ways such secrets leak: hardcoding them in the committed config,
+//! committing the `.rblxsync/` working directory, or leaving `.env` and the
+//! credentials file readable by every user on a shared CI machine.
+
+use crate::credentials;
+use crate::report::RunReport;
+use std::fs::{self, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Prefix of every `.ROBLOSECURITY` cookie value
+const COOKIE_MARKER: &str = "_|WARNING:-DO-NOT-SHARE-THIS";
+
+/// Config keys that suggest a credential was pasted into the config
+const SECRET_KEYS: [&str; 4] = ["api_key", "apikey", "roblox_api_key", "roblox_cookie"];
+
+/// Finding for a `.rblxsync/` that would be committed
+pub const NOT_GITIGNORED: &str = ".rblxsync/ is not gitignored; add it to .gitignore so cached data and run manifests aren't committed";
+
+/// Options that create a file only its owner can read, for the state and
+/// run records rblxsync writes. Existing files keep their permissions.
+pub fn private_file() -> OpenOptions {
+    let mut options = OpenOptions::new();
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options
+}
+
+/// Whether `finding` was already reported by the previous run, per its
+/// manifest, so it only needs logging once
+pub fn reported_before(project_root: &Path, finding: &str) -> bool {
+    let Ok(content) = fs::read_to_string(RunReport::manifest_path(project_root)) else { return false };
+    let Ok(manifest) = serde_json::from_str::<serde_json::Value>(&content) else { return false };
+    manifest["security_warnings"].as_array().is_some_and(|warnings| warnings.iter().any(|w| w == finding))
+}
+
+/// Run every check, returning a human-readable message per finding
+pub fn audit(project_root: &Path, config_path: &Path) -> Vec<String> {
+    let mut findings = Vec::new();
+
+    if let Ok(content) = fs::read_to_string(config_path) {
+        let secrets: Vec<String> = ["ROBLOX_API_KEY", "ROBLOX_COOKIE"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .collect();
+        let secrets: Vec<&str> = secrets.iter().map(|s| s.as_str()).collect();
+        for finding in find_hardcoded_secrets(&content, &secrets) {
+            findings.push(format!("{} {}; load it from the environment or .env instead", config_path.display(), finding));
+        }
+    }
+
+    if is_git_tracked_dir(project_root) {
+        findings.push(NOT_GITIGNORED.to_string());
+    }
+
+    for path in sensitive_files(project_root) {
+        if is_world_readable(&path) {
+            findings.push(format!("{} is world-readable; restrict it with `chmod o-r {}`", path.display(), path.display()));
+        }
+    }
+
+    findings
+}
+
+/// Look for credentials in config file contents. `secrets` are the values
+/// of the credentials currently in the environment.
+pub fn find_hardcoded_secrets(content: &str, secrets: &[&str]) -> Vec<String> {
+    let mut findings = Vec::new();
+
+    // Short values would match unrelated text
+    if secrets.iter().any(|s| s.len() >= 16 && content.contains(s)) {
+        findings.push("contains the API key or cookie from the environment".to_string());
+    }
+    if content.contains(COOKIE_MARKER) {
+        findings.push("contains a .ROBLOSECURITY cookie".to_string());
+    }
+    for line in content.lines() {
+        // `key: value` in YAML, `key = value` in TOML, `"key": value` in JSON
+        let line = line.trim_start().trim_start_matches('"').to_lowercase();
+        let key = line.split([':', '=', '"']).next().unwrap_or("").trim_end();
+        if SECRET_KEYS.contains(&key) && line.len() > key.len() {
+            findings.push(format!("sets `{}`, which looks like a hardcoded credential", key));
+        }
+    }
+
+    findings
+}
+
+/// Files holding credentials: the project's `.env` and the global
+/// credentials file. These should only be readable by their owner; the lock
+/// file and run records hold no secrets, and the lock file is committed.
+fn sensitive_files(project_root: &Path) -> Vec<PathBuf> {
+    std::iter::once(project_root.join(".env"))
+        .chain(credentials::credentials_path())
+        .filter(|p| p.exists())
+        .collect()
+}
+
+/// Whether `.rblxsync/` would be committed. Returns false outside a git
+/// work tree or when git is unavailable.
+fn is_git_tracked_dir(project_root: &Path) -> bool {
+    let Ok(output) = Command::new("git")
+        .args(["check-ignore", "-q", ".rblxsync/"])
+        .current_dir(project_root)
+        .output()
+    else {
+        return false;
+    };
+    // 0 = ignored, 1 = not ignored, 128 = not a repository
+    output.status.code() == Some(1)
+}
+
+#[cfg(unix)]
+fn is_world_readable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path).map(|m| m.permissions().mode() & 0o004 != 0).unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_world_readable(_path: &Path) -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_hardcoded_secrets() {
+        let clean = "universe:\n  id: 1\n  description: \"Use the api_key from the dashboard\"\n";
+        assert!(find_hardcoded_secrets(clean, &["abcdefghijklmnopqrstuvwxyz"]).is_empty());
+
+        let leaked = "universe:\n  id: 1\napi_key: abcdefghijklmnopqrstuvwxyz\n";
+        let findings = find_hardcoded_secrets(leaked, &["abcdefghijklmnopqrstuvwxyz"]);
+        assert_eq!(findings.len(), 2);
+
+        assert_eq!(find_hardcoded_secrets("cookie: _|WARNING:-DO-NOT-SHARE-THIS.--abc", &[]).len(), 1);
+        assert_eq!(find_hardcoded_secrets("roblox_cookie = \"x\"\n", &[]).len(), 1);
+        assert_eq!(find_hardcoded_secrets("{\n  \"api_key\": \"x\"\n}", &[]).len(), 1);
+        assert!(find_hardcoded_secrets("api_keys_doc: x\n", &[]).is_empty());
+    }
+
+    #[test]
+    fn test_credential_files_checked() {
+        let root = std::env::temp_dir().join(format!("rblxsync-security-{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        let mut report = RunReport::new("run");
+        report.security_warnings.push(NOT_GITIGNORED.to_string());
+        report.save(&root).unwrap();
+
+        // The lock file and run records aren't secrets
+        assert!(sensitive_files(&root).iter().all(|p| !p.starts_with(&root)));
+        fs::write(root.join(".env"), "ROBLOX_API_KEY=x\n").unwrap();
+        assert!(sensitive_files(&root).contains(&root.join(".env")));
+        assert!(reported_before(&root, NOT_GITIGNORED));
+        assert!(!reported_before(&root, "other"));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,213 @@
+//! HTTP trigger API for `rblxsync serve`.
+//!
+//! Dashboards and chatops bots start a plan, sync or publish with a POST
+//! and read its progress from the response: the same events `--output json`
+//! prints, as server-sent events when the request accepts
+//! `text/event-stream` and as newline-delimited JSON otherwise. Commands
+//! share the process's working directory and lock file, so one job runs at
+//! a time and triggers sent meanwhile get `409 Conflict`.
+//!
+//! Every request needs the bearer token. Requests from browsers (with an
+//! `Origin` header) and triggers that aren't `application/json` are
+//! rejected, so a web page can't reach a server on localhost through the
+//! user's browser or DNS rebinding.
+
+use anyhow::{Context, Result};
+use axum::body::Body;
+use axum::extract::State;
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde_json::Value;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use subtle::ConstantTimeEq;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio_stream::wrappers::UnboundedReceiverStream;
+use tokio_stream::StreamExt;
+
+/// What a trigger asks for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// `run --dry-run`
+    Plan,
+    /// `run`
+    Sync,
+    /// `publish`
+    Publish,
+}
+
+impl Action {
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Plan => "plan",
+            Action::Sync => "sync",
+            Action::Publish => "publish",
+        }
+    }
+}
+
+/// A triggered command waiting to run. Report events sent to `events`
+/// stream to the client; the response ends once the job is dropped.
+pub struct Job {
+    pub action: Action,
+    // Declared first so the server is free again before the stream ends
+    _busy: BusyGuard,
+    pub events: UnboundedSender<Value>,
+}
+
+/// Marks the server idle when the job is dropped
+struct BusyGuard(Arc<AtomicBool>);
+
+impl Drop for BusyGuard {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::SeqCst);
+    }
+}
+
+struct Server {
+    jobs: UnboundedSender<Job>,
+    busy: Arc<AtomicBool>,
+    token: String,
+}
+
+/// A random token for servers started without one
+pub fn generate_token() -> Result<String> {
+    let mut bytes = [0u8; 24];
+    getrandom::fill(&mut bytes).map_err(|e| anyhow::anyhow!("Failed to generate a token: {}", e))?;
+    Ok(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// Bind `listen` and serve the trigger API in the background, returning the
+/// queue of triggered jobs. Requests must send `Authorization: Bearer <token>`.
+pub async fn start(listen: SocketAddr, token: String) -> Result<UnboundedReceiver<Job>> {
+    crate::redact::register(&token);
+    let listener = tokio::net::TcpListener::bind(listen).await.with_context(|| format!("Failed to listen on {}", listen))?;
+    let (jobs, queue) = mpsc::unbounded_channel();
+    let app = router(Arc::new(Server { jobs, busy: Arc::new(AtomicBool::new(false)), token }));
+    tokio::spawn(async move {
+        if let Err(e) = axum::serve(listener, app).await {
+            tracing::error!("HTTP server stopped: {}", e);
+        }
+    });
+    Ok(queue)
+}
+
+fn router(server: Arc<Server>) -> Router {
+    Router::new()
+        .route("/status", get(status))
+        .route("/plan", post(|state, headers| trigger(state, headers, Action::Plan)))
+        .route("/sync", post(|state, headers| trigger(state, headers, Action::Sync)))
+        .route("/publish", post(|state, headers| trigger(state, headers, Action::Publish)))
+        .with_state(server)
+}
+
+fn authorized(server: &Server, headers: &HeaderMap) -> bool {
+    let given = headers.get(header::AUTHORIZATION).and_then(|v| v.to_str().ok()).and_then(|v| v.strip_prefix("Bearer "));
+    given.is_some_and(|given| bool::from(given.as_bytes().ct_eq(server.token.as_bytes())))
+}
+
+/// Why a request must be refused before its token is even checked, if it
+/// must: browsers send `Origin`, and triggers must declare a JSON body
+fn rejected(headers: &HeaderMap, trigger: bool) -> Option<Response> {
+    if headers.contains_key(header::ORIGIN) {
+        return Some(error(StatusCode::FORBIDDEN, "requests from browsers are not allowed"));
+    }
+    let json = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split(';').next())
+        .is_some_and(|v| v.trim().eq_ignore_ascii_case("application/json"));
+    if trigger && !json {
+        return Some(error(StatusCode::UNSUPPORTED_MEDIA_TYPE, "Content-Type must be application/json"));
+    }
+    None
+}
+
+fn error(status: StatusCode, message: &str) -> Response {
+    (status, Json(serde_json::json!({ "error": message }))).into_response()
+}
+
+async fn status(State(server): State<Arc<Server>>, headers: HeaderMap) -> Response {
+    if let Some(response) = rejected(&headers, false) {
+        return response;
+    }
+    if !authorized(&server, &headers) {
+        return error(StatusCode::UNAUTHORIZED, "missing or wrong bearer token");
+    }
+    Json(serde_json::json!({ "busy": server.busy.load(Ordering::SeqCst) })).into_response()
+}
+
+async fn trigger(State(server): State<Arc<Server>>, headers: HeaderMap, action: Action) -> Response {
+    if let Some(response) = rejected(&headers, true) {
+        return response;
+    }
+    if !authorized(&server, &headers) {
+        return error(StatusCode::UNAUTHORIZED, "missing or wrong bearer token");
+    }
+    if server.busy.compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst).is_err() {
+        return error(StatusCode::CONFLICT, "another job is running");
+    }
+    let busy = BusyGuard(server.busy.clone());
+    let (events, stream) = mpsc::unbounded_channel();
+    if server.jobs.send(Job { action, _busy: busy, events }).is_err() {
+        return error(StatusCode::SERVICE_UNAVAILABLE, "server is shutting down");
+    }
+    tracing::info!("Triggered {} over HTTP", action.name());
+
+    let stream = UnboundedReceiverStream::new(stream);
+    let wants_sse = headers
+        .get(header::ACCEPT)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|accept| accept.contains("text/event-stream"));
+    if wants_sse {
+        let events = stream.map(|event: Value| {
+            let name = event["event"].as_str().unwrap_or("message").to_string();
+            Ok::<_, Infallible>(Event::default().event(name).data(event.to_string()))
+        });
+        Sse::new(events).keep_alive(KeepAlive::default()).into_response()
+    } else {
+        let lines = stream.map(|event: Value| Ok::<_, Infallible>(format!("{}\n", event)));
+        ([(header::CONTENT_TYPE, "application/x-ndjson")], Body::from_stream(lines)).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_authorized() {
+        let (jobs, _queue) = mpsc::unbounded_channel();
+        let server = Server { jobs, busy: Arc::new(AtomicBool::new(false)), token: "s3cret".to_string() };
+        let mut headers = HeaderMap::new();
+        assert!(!authorized(&server, &headers));
+        headers.insert(header::AUTHORIZATION, "Bearer wrong".parse().unwrap());
+        assert!(!authorized(&server, &headers));
+        headers.insert(header::AUTHORIZATION, "Bearer s3cret".parse().unwrap());
+        assert!(authorized(&server, &headers));
+        headers.insert(header::AUTHORIZATION, "Bearer s3cret2".parse().unwrap());
+        assert!(!authorized(&server, &headers));
+
+        assert_eq!(generate_token().unwrap().len(), 48);
+        assert_ne!(generate_token().unwrap(), generate_token().unwrap());
+    }
+
+    #[test]
+    fn test_rejected() {
+        let mut headers = HeaderMap::new();
+        assert!(rejected(&headers, false).is_none());
+        assert_eq!(rejected(&headers, true).unwrap().status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        headers.insert(header::CONTENT_TYPE, "text/plain".parse().unwrap());
+        assert_eq!(rejected(&headers, true).unwrap().status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        headers.insert(header::CONTENT_TYPE, "application/json; charset=utf-8".parse().unwrap());
+        assert!(rejected(&headers, true).is_none());
+        headers.insert(header::ORIGIN, "https://example.com".parse().unwrap());
+        assert_eq!(rejected(&headers, true).unwrap().status(), StatusCode::FORBIDDEN);
+        assert_eq!(rejected(&headers, false).unwrap().status(), StatusCode::FORBIDDEN);
+    }
+}
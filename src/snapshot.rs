@@ -0,0 +1,166 @@
+//! Universe snapshots for `rblxsync backup` and `rblxsync restore`.
+//!
+//! A snapshot is a zstd-compressed tarball. `snapshot.json` holds the raw
+//! remote game passes, developer products and badges plus the universe's
+//! public details; rendered icons are stored as `icons/<kind>/<id>.png` and,
+//! when requested, local place files as `places/<place id>.<ext>`.
+
+use crate::fetch::ResourceKind;
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::path::Path;
+
+/// Format version written to `snapshot.json`
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// Name of the manifest inside the archive
+const MANIFEST: &str = "snapshot.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub version: u32,
+    pub universe_id: u64,
+    /// Unix timestamp (seconds) when the backup was taken
+    pub created_at: u64,
+    /// Universe details from the games API, as returned
+    #[serde(default)]
+    pub universe: Option<serde_json::Value>,
+    #[serde(default)]
+    pub game_passes: Vec<serde_json::Value>,
+    #[serde(default)]
+    pub developer_products: Vec<serde_json::Value>,
+    #[serde(default)]
+    pub badges: Vec<serde_json::Value>,
+    /// Archive path of each backed up place file, by place ID
+    #[serde(default)]
+    pub places: BTreeMap<u64, String>,
+}
+
+impl Snapshot {
+    pub fn new(universe_id: u64, created_at: u64) -> Self {
+        Self {
+            version: SNAPSHOT_VERSION,
+            universe_id,
+            created_at,
+            universe: None,
+            game_passes: Vec::new(),
+            developer_products: Vec::new(),
+            badges: Vec::new(),
+            places: BTreeMap::new(),
+        }
+    }
+
+    pub fn items(&self, kind: ResourceKind) -> &[serde_json::Value] {
+        match kind {
+            ResourceKind::GamePasses => &self.game_passes,
+            ResourceKind::DeveloperProducts => &self.developer_products,
+            ResourceKind::Badges => &self.badges,
+        }
+    }
+
+    pub fn items_mut(&mut self, kind: ResourceKind) -> &mut Vec<serde_json::Value> {
+        match kind {
+            ResourceKind::GamePasses => &mut self.game_passes,
+            ResourceKind::DeveloperProducts => &mut self.developer_products,
+            ResourceKind::Badges => &mut self.badges,
+        }
+    }
+
+    /// Archive path of a resource's icon
+    pub fn icon_path(kind: ResourceKind, id: u64) -> String {
+        format!("icons/{}/{}.png", kind.key(), id)
+    }
+}
+
+/// Streams files into a new snapshot archive
+pub struct SnapshotWriter {
+    builder: tar::Builder<zstd::Encoder<'static, File>>,
+}
+
+impl SnapshotWriter {
+    pub fn create(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        let file = File::create(path).with_context(|| format!("Failed to create {}", path.display()))?;
+        Ok(Self { builder: tar::Builder::new(zstd::Encoder::new(file, 0)?) })
+    }
+
+    pub fn add_bytes(&mut self, name: &str, data: &[u8]) -> Result<()> {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        self.builder.append_data(&mut header, name, data)?;
+        Ok(())
+    }
+
+    pub fn add_file(&mut self, name: &str, path: &Path) -> Result<()> {
+        let mut file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+        self.builder.append_file(name, &mut file)?;
+        Ok(())
+    }
+
+    /// Write the manifest and close the archive
+    pub fn finish(mut self, snapshot: &Snapshot) -> Result<()> {
+        self.add_bytes(MANIFEST, &serde_json::to_vec_pretty(snapshot)?)?;
+        self.builder.into_inner()?.finish()?;
+        Ok(())
+    }
+}
+
+/// Unpack a snapshot archive into `dest` and read its manifest
+pub fn extract(path: &Path, dest: &Path) -> Result<Snapshot> {
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    fs::create_dir_all(dest)?;
+    tar::Archive::new(zstd::Decoder::new(file)?)
+        .unpack(dest)
+        .with_context(|| format!("{} is not a valid snapshot", path.display()))?;
+
+    let manifest = dest.join(MANIFEST);
+    let content = fs::read_to_string(&manifest).with_context(|| format!("{} has no {}", path.display(), MANIFEST))?;
+    let snapshot: Snapshot = serde_json::from_str(&content)?;
+    if snapshot.version > SNAPSHOT_VERSION {
+        return Err(anyhow!(
+            "{} was written by a newer rblxsync (snapshot version {}); upgrade to restore it",
+            path.display(),
+            snapshot.version
+        ));
+    }
+    Ok(snapshot)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let dir = std::env::temp_dir().join(format!("rblxsync-snapshot-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let archive = dir.join("out").join("snapshot.tar.zst");
+
+        let mut snapshot = Snapshot::new(42, 1_700_000_000);
+        snapshot.universe = Some(serde_json::json!({ "name": "My Game" }));
+        snapshot.items_mut(ResourceKind::Badges).push(serde_json::json!({ "id": 5, "name": "Welcome" }));
+        snapshot.places.insert(7, "places/7.rbxl".to_string());
+        fs::write(dir.join("place.rbxl"), b"place").unwrap();
+
+        let mut writer = SnapshotWriter::create(&archive).unwrap();
+        writer.add_bytes(&Snapshot::icon_path(ResourceKind::Badges, 5), b"png").unwrap();
+        writer.add_file("places/7.rbxl", &dir.join("place.rbxl")).unwrap();
+        writer.finish(&snapshot).unwrap();
+
+        let restored = extract(&archive, &dir.join("extracted")).unwrap();
+        assert_eq!(restored.universe_id, 42);
+        assert_eq!(restored.items(ResourceKind::Badges).len(), 1);
+        assert_eq!(restored.places.get(&7).map(|p| p.as_str()), Some("places/7.rbxl"));
+        assert_eq!(fs::read(dir.join("extracted/icons/badges/5.png")).unwrap(), b"png");
+        assert_eq!(fs::read(dir.join("extracted/places/7.rbxl")).unwrap(), b"place");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,11 +1,23 @@
-use anyhow::Result;
+use anyhow::{anyhow, bail, Context, Result};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
+use std::io::Write;
 use std::path::{Path, PathBuf};
 
+/// Layout version written to new lock files. Bump it and add a step to
+/// `migrate` whenever the layout changes in a way older files don't parse as.
+pub const STATE_VERSION: u32 = 2;
+
 #[derive(Debug, Default, Deserialize, Serialize, Clone)]
 pub struct SyncState {
+    /// Layout version of the file this state was read from
+    #[serde(default)]
+    pub version: StateVersion,
+    /// Universe the recorded IDs belong to. Lock files written before it was
+    /// recorded belong to the configured universe.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub universe_id: Option<u64>,
     /// Universe settings state
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub universe: Option<UniverseState>,
@@ -18,6 +30,83 @@ pub struct SyncState {
     /// Badges keyed by their Roblox ID
     #[serde(default)]
     pub badges: HashMap<u64, ResourceState>,
+    /// Standalone assets keyed by their config name
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub assets: HashMap<String, AssetState>,
+    /// Asset IDs of uploaded icons keyed by content hash (SHA-256), so an
+    /// icon shared by several resources is uploaded once
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub icons: HashMap<String, u64>,
+    /// Where each asset in `icons` came from, by the same hash. Icons
+    /// recorded before sources were kept have none.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub icon_sources: HashMap<String, IconSource>,
+    /// Changes accepted by Roblox but still awaiting moderation, re-checked on each run
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pending: Vec<PendingVerification>,
+    /// Unix timestamps of badges created in the last 24 hours, to estimate the free daily quota
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub badge_creations: Vec<u64>,
+    /// Last build published to each place's test place, keyed by the
+    /// production place ID, so `publish --promote` ships the same file
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub test_publishes: HashMap<u64, TestPublish>,
+    /// Time-limited bans applied by `run`, keyed by user ID (and `@` place
+    /// ID), so one that has run out isn't applied again
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub timed_bans: HashMap<String, TimedBan>,
+}
+
+/// When a time-limited ban started and how long it lasts
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+pub struct TimedBan {
+    /// Unix timestamp of when the ban was applied
+    pub started: u64,
+    /// Length in seconds
+    pub duration: u64,
+}
+
+impl TimedBan {
+    /// Whether the ban has run out by `now`
+    pub fn expired(&self, now: u64) -> bool {
+        self.started.saturating_add(self.duration) <= now
+    }
+}
+
+/// A place file published to a test place
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct TestPublish {
+    pub test_place_id: u64,
+    /// SHA-256 of the place file
+    pub hash: String,
+    /// Version of the test place it was published as
+    pub version: u64,
+}
+
+/// Where the asset recorded for an icon came from. Only assets this project
+/// uploaded are its to archive (`assets gc`) or replace (`run --no-cache`).
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IconSource {
+    /// Uploaded by this project
+    Uploaded,
+    /// Reused from the asset cache, i.e. uploaded by another project
+    Cache,
+    /// Recorded from a Tarmac, Asphalt or Mantle lockfile
+    Imported,
+    /// The live icon of a resource, adopted because it showed the same picture
+    Live,
+}
+
+/// Lock file layout version; files without one predate versioning (0)
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct StateVersion(pub u32);
+
+impl Default for StateVersion {
+    fn default() -> Self {
+        Self(STATE_VERSION)
+    }
 }
 
 #[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
@@ -35,9 +124,12 @@ pub struct UniverseState {
     /// Private server cost state: None = not set, Some("disabled") = disabled, Some("0") = free, Some("X") = paid
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub private_server_cost: Option<String>,
+    /// Values of the other universe settings, by config field
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub settings: BTreeMap<String, serde_json::Value>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub struct ResourceState {
     pub name: String,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -46,12 +138,166 @@ pub struct ResourceState {
     pub price: Option<u64>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub is_for_sale: Option<bool>,
+    /// Whether a developer product is on sale
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_active: Option<bool>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub is_enabled: Option<bool>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub regional_pricing_enabled: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub icon_hash: Option<String>,
+    /// Moderation state of the uploaded icon asset ("Approved", "Reviewing", "Rejected")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon_moderation_state: Option<String>,
+    /// When `icon_moderation_state` was last read from Roblox (Unix seconds)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon_moderation_checked: Option<u64>,
+    /// Who last changed this resource, and when
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub icon_asset_id: Option<u64>,
+    pub last_synced: Option<SyncStamp>,
+    /// Remote `updated` timestamp as of the last sync, to notice edits made
+    /// outside rblxsync (e.g. on the Creator Dashboard)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote_updated: Option<String>,
+    /// Fields left as edited outside rblxsync because config didn't change
+    /// them, with the config value they were kept over. The fields above
+    /// hold the remote value; config is applied again once it changes.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub kept_remote: BTreeMap<String, serde_json::Value>,
+}
+
+impl ResourceState {
+    /// Value of a mergeable field (see `merge`) by its config name
+    pub fn field(&self, field: &str) -> serde_json::Value {
+        let value = match field {
+            "description" => serde_json::to_value(&self.description),
+            "price" => serde_json::to_value(self.price),
+            "is_for_sale" => serde_json::to_value(self.is_for_sale),
+            "is_active" => serde_json::to_value(self.is_active),
+            "is_enabled" => serde_json::to_value(self.is_enabled),
+            "regional_pricing_enabled" => serde_json::to_value(self.regional_pricing_enabled),
+            _ => Ok(serde_json::Value::Null),
+        };
+        value.unwrap_or_default()
+    }
+
+    /// Set a mergeable field by its config name
+    pub fn set_field(&mut self, field: &str, value: &serde_json::Value) {
+        match field {
+            "description" => self.description = value.as_str().map(|s| s.to_string()),
+            "price" => self.price = value.as_u64(),
+            "is_for_sale" => self.is_for_sale = value.as_bool(),
+            "is_active" => self.is_active = value.as_bool(),
+            "is_enabled" => self.is_enabled = value.as_bool(),
+            "regional_pricing_enabled" => self.regional_pricing_enabled = value.as_bool(),
+            _ => {}
+        }
+    }
+
+    /// Value of `field` as of the last sync to merge against: for a field
+    /// kept as edited remotely, the config value it was kept over
+    pub fn merge_base(&self, field: &str) -> serde_json::Value {
+        self.kept_remote.get(field).cloned().unwrap_or_else(|| self.field(field))
+    }
+}
+
+/// Who made the last change to a resource and when
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct SyncStamp {
+    /// Actor string: `RBLXSYNC_ACTOR`, CI job or OS username
+    pub by: String,
+    /// Unix timestamp (seconds)
+    pub at: u64,
+}
+
+impl SyncStamp {
+    pub fn now(actor: &str) -> Self {
+        Self { by: actor.to_string(), at: crate::actor::now() }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct AssetState {
+    pub asset_id: u64,
+    /// SHA-256 hash of the uploaded file
+    pub hash: String,
+    pub asset_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_synced: Option<SyncStamp>,
+}
+
+/// A change that was applied but can't be confirmed until moderation finishes
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct PendingVerification {
+    pub kind: EntryKind,
+    pub id: u64,
+    pub name: String,
+    #[serde(flatten)]
+    pub check: PendingCheck,
+    /// Unix timestamp when the change was queued
+    pub since: u64,
+    /// Number of verification attempts so far
+    #[serde(default)]
+    pub attempts: u32,
+}
+
+/// What must be confirmed for a pending change
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(tag = "check", rename_all = "snake_case")]
+pub enum PendingCheck {
+    /// An uploaded icon awaiting asset moderation
+    Icon { asset_id: u64 },
+    /// Name/description text that must read back unfiltered
+    Text { expected_name: String, expected_description: Option<String> },
+}
+
+impl PendingCheck {
+    fn same_kind(&self, other: &PendingCheck) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+}
+
+/// Kinds of entries stored in the lock file, as named on the command line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, clap::ValueEnum, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EntryKind {
+    GamePass,
+    DeveloperProduct,
+    Badge,
+    Asset,
+}
+
+impl EntryKind {
+    pub const ALL: [EntryKind; 4] = [EntryKind::GamePass, EntryKind::DeveloperProduct, EntryKind::Badge, EntryKind::Asset];
+
+    /// Resource type as named in the run manifest
+    pub fn resource_type(&self) -> &'static str {
+        match self {
+            EntryKind::GamePass => "game_pass",
+            EntryKind::DeveloperProduct => "developer_product",
+            EntryKind::Badge => "badge",
+            EntryKind::Asset => "asset",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            EntryKind::GamePass => "game-pass",
+            EntryKind::DeveloperProduct => "developer-product",
+            EntryKind::Badge => "badge",
+            EntryKind::Asset => "asset",
+        }
+    }
+}
+
+/// A single lock file entry, for listing
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateEntry {
+    pub kind: EntryKind,
+    pub id: u64,
+    pub name: String,
+    pub last_synced: Option<SyncStamp>,
 }
 
 impl SyncState {
@@ -62,7 +308,45 @@ impl SyncState {
         }
 
         let content = fs::read_to_string(&state_path)?;
-        let state: SyncState = serde_yaml::from_str(&content)?;
+        Self::parse(&content).with_context(|| format!("Failed to read {}", state_path.display()))
+    }
+
+    /// Parse lock file content of any known layout version, migrating older
+    /// layouts. Fields this version doesn't know are an error rather than
+    /// being dropped on the next save.
+    pub fn parse(content: &str) -> Result<Self> {
+        let mut doc: serde_yaml::Value = serde_yaml::from_str(content)?;
+        if doc.is_null() {
+            return Ok(Self::default());
+        }
+        let version = match doc.get("version") {
+            None => 0,
+            Some(v) => v
+                .as_u64()
+                .and_then(|v| u32::try_from(v).ok())
+                .ok_or_else(|| anyhow!("`version` must be a whole number, found {:?}", v))?,
+        };
+        if version > STATE_VERSION {
+            bail!(
+                "lock file layout version {} is newer than this rblxsync supports ({}); upgrade rblxsync to use it",
+                version,
+                STATE_VERSION
+            );
+        }
+        if version < STATE_VERSION {
+            migrate(&mut doc, version)?;
+            tracing::info!("Migrated lock file layout from version {} to {}; it's rewritten on the next save", version, STATE_VERSION);
+        }
+
+        let mut unknown = Vec::new();
+        let mut state: SyncState = serde_ignored::deserialize(doc, |path| unknown.push(path.to_string()))?;
+        if !unknown.is_empty() {
+            bail!(
+                "unknown field(s) {}; they would be lost on the next save. Remove them, or upgrade rblxsync if a newer version wrote them",
+                unknown.join(", ")
+            );
+        }
+        state.version = StateVersion::default();
         Ok(state)
     }
 
@@ -73,12 +357,239 @@ impl SyncState {
         }
 
         let content = serde_yaml::to_string(self)?;
-        fs::write(state_path, content)?;
-        Ok(())
+        replace_file(&state_path, &content).with_context(|| format!("Failed to write {}", state_path.display()))
+    }
+
+    /// The lock file's content before the last save changed it
+    pub fn backup_path(project_root: &Path) -> PathBuf {
+        with_suffix(&Self::get_state_path(project_root), ".bak")
+    }
+
+    /// Restore the lock file from its backup. The current content becomes
+    /// the backup, so rolling back again undoes the rollback.
+    pub fn rollback(project_root: &Path) -> Result<Self> {
+        let backup_path = Self::backup_path(project_root);
+        if !backup_path.exists() {
+            bail!("No backup at {}; one is kept once the lock file has changed", backup_path.display());
+        }
+        let content = fs::read_to_string(&backup_path)?;
+        let state = Self::parse(&content).with_context(|| format!("Failed to read {}", backup_path.display()))?;
+        let state_path = Self::get_state_path(project_root);
+        replace_file(&state_path, &content).with_context(|| format!("Failed to write {}", state_path.display()))?;
+        Ok(state)
+    }
+
+    /// `rblxsync-lock.yml`, or `rblxsync-lock.<name>.yml` when a named
+    /// universe is selected with `--universe`
+    pub fn get_state_path(project_root: &Path) -> PathBuf {
+        match crate::config::selected_universe() {
+            Some(name) => project_root.join(format!("rblxsync-lock.{}.yml", name)),
+            None => project_root.join("rblxsync-lock.yml"),
+        }
     }
 
-    fn get_state_path(project_root: &Path) -> PathBuf {
-        project_root.join("rblxsync-lock.yml")
+    /// Queue a change for verification, replacing any older check of the same
+    /// kind for the same resource
+    pub fn queue_pending(&mut self, kind: EntryKind, id: u64, name: &str, check: PendingCheck) {
+        self.pending.retain(|p| !(p.kind == kind && p.id == id && p.check.same_kind(&check)));
+        self.pending.push(PendingVerification {
+            kind,
+            id,
+            name: name.to_string(),
+            check,
+            since: crate::actor::now(),
+            attempts: 0,
+        });
+    }
+
+    /// Universe whose IDs this lock file holds: the recorded one, else
+    /// `configured` when it tracks anything. None for an empty lock file.
+    pub fn owner(&self, configured: u64) -> Option<u64> {
+        self.universe_id.or_else(|| (self.universe.is_some() || !self.entries().is_empty()).then_some(configured))
+    }
+
+    /// Every resource and asset entry, sorted by kind then name
+    pub fn entries(&self) -> Vec<StateEntry> {
+        let mut entries = Vec::new();
+        for kind in EntryKind::ALL {
+            match self.resources(kind) {
+                Some(map) => entries.extend(map.iter().map(|(id, r)| StateEntry { kind, id: *id, name: r.name.clone(), last_synced: r.last_synced.clone() })),
+                None => entries.extend(self.assets.iter().map(|(name, a)| StateEntry { kind, id: a.asset_id, name: name.clone(), last_synced: a.last_synced.clone() })),
+            }
+        }
+        entries.sort_by_key(|e| (e.kind as u8, e.name.to_lowercase()));
+        entries
+    }
+
+    /// Find the entry of `kind` whose name (case-insensitive) or ID is `key`,
+    /// returning its state serialized as YAML
+    pub fn show(&self, kind: EntryKind, key: &str) -> Result<String> {
+        let content = match self.resources(kind) {
+            Some(map) => {
+                let id = Self::resolve(map, kind, key)?;
+                let mut entry = serde_yaml::Mapping::new();
+                entry.insert("id".into(), id.into());
+                if let serde_yaml::Value::Mapping(fields) = serde_yaml::to_value(&map[&id])? {
+                    entry.extend(fields);
+                }
+                serde_yaml::to_string(&entry)?
+            }
+            None => {
+                let name = self.resolve_asset(kind, key)?;
+                let mut entry = serde_yaml::Mapping::new();
+                entry.insert("name".into(), name.clone().into());
+                if let serde_yaml::Value::Mapping(fields) = serde_yaml::to_value(&self.assets[&name])? {
+                    entry.extend(fields);
+                }
+                serde_yaml::to_string(&entry)?
+            }
+        };
+        Ok(content)
+    }
+
+    /// Remove the entry of `kind` matching `key`, returning its name
+    pub fn remove(&mut self, kind: EntryKind, key: &str) -> Result<String> {
+        match self.resources(kind) {
+            Some(map) => {
+                let id = Self::resolve(map, kind, key)?;
+                let map = self.resources_mut(kind).expect("resource kind");
+                Ok(map.remove(&id).map(|r| r.name).unwrap_or_default())
+            }
+            None => {
+                let name = self.resolve_asset(kind, key)?;
+                self.assets.remove(&name);
+                Ok(name)
+            }
+        }
+    }
+
+    /// Rename the entry matching `old` to `new`. Without `kind`, every kind
+    /// is searched and the name must be unambiguous.
+    pub fn rename(&mut self, kind: Option<EntryKind>, old: &str, new: &str) -> Result<EntryKind> {
+        let kind = match kind {
+            Some(kind) => kind,
+            None => {
+                let matches: Vec<EntryKind> = EntryKind::ALL
+                    .into_iter()
+                    .filter(|k| match self.resources(*k) {
+                        Some(map) => Self::resolve(map, *k, old).is_ok(),
+                        None => self.resolve_asset(*k, old).is_ok(),
+                    })
+                    .collect();
+                match matches.as_slice() {
+                    [kind] => *kind,
+                    [] => return Err(anyhow!("No state entry named '{}'", old)),
+                    _ => return Err(anyhow!(
+                        "'{}' matches several kinds ({}); pass --type to choose one",
+                        old,
+                        matches.iter().map(|k| k.label()).collect::<Vec<_>>().join(", ")
+                    )),
+                }
+            }
+        };
+
+        match self.resources(kind) {
+            Some(map) => {
+                let id = Self::resolve(map, kind, old)?;
+                if map.iter().any(|(other, r)| *other != id && r.name.to_lowercase() == new.to_lowercase()) {
+                    return Err(anyhow!("A {} named '{}' already exists in state", kind.label(), new));
+                }
+                let map = self.resources_mut(kind).expect("resource kind");
+                if let Some(entry) = map.get_mut(&id) {
+                    entry.name = new.to_string();
+                }
+            }
+            None => {
+                let name = self.resolve_asset(kind, old)?;
+                if self.assets.contains_key(new) && name != new {
+                    return Err(anyhow!("An asset named '{}' already exists in state", new));
+                }
+                if let Some(asset) = self.assets.remove(&name) {
+                    self.assets.insert(new.to_string(), asset);
+                }
+            }
+        }
+        Ok(kind)
+    }
+
+    /// Badges created within the last 24 hours as of `now`, dropping older records
+    pub fn recent_badge_creations(&mut self, now: u64) -> usize {
+        self.badge_creations.retain(|at| now.saturating_sub(*at) < 24 * 60 * 60);
+        self.badge_creations.len()
+    }
+
+    /// ID-keyed resource map for `kind`, or None for assets (keyed by name)
+    pub fn resources(&self, kind: EntryKind) -> Option<&HashMap<u64, ResourceState>> {
+        match kind {
+            EntryKind::GamePass => Some(&self.game_passes),
+            EntryKind::DeveloperProduct => Some(&self.developer_products),
+            EntryKind::Badge => Some(&self.badges),
+            EntryKind::Asset => None,
+        }
+    }
+
+    pub fn resources_mut(&mut self, kind: EntryKind) -> Option<&mut HashMap<u64, ResourceState>> {
+        match kind {
+            EntryKind::GamePass => Some(&mut self.game_passes),
+            EntryKind::DeveloperProduct => Some(&mut self.developer_products),
+            EntryKind::Badge => Some(&mut self.badges),
+            EntryKind::Asset => None,
+        }
+    }
+
+    fn resolve(map: &HashMap<u64, ResourceState>, kind: EntryKind, key: &str) -> Result<u64> {
+        if let Ok(id) = key.parse::<u64>() {
+            if map.contains_key(&id) {
+                return Ok(id);
+            }
+        }
+        map.iter()
+            .find(|(_, r)| r.name.to_lowercase() == key.to_lowercase())
+            .map(|(id, _)| *id)
+            .ok_or_else(|| anyhow!("No {} named '{}' in state", kind.label(), key))
+    }
+
+    fn resolve_asset(&self, kind: EntryKind, key: &str) -> Result<String> {
+        self.assets
+            .iter()
+            .find(|(name, a)| name.to_lowercase() == key.to_lowercase() || a.asset_id.to_string() == key)
+            .map(|(name, _)| name.clone())
+            .ok_or_else(|| anyhow!("No {} named '{}' in state", kind.label(), key))
+    }
+
+    /// Asset ID of the icon `entry` was last synced with
+    pub fn icon_asset_id(&self, entry: &ResourceState) -> Option<u64> {
+        entry.icon_hash.as_ref().and_then(|hash| self.icons.get(hash)).copied()
+    }
+
+    /// Note where the asset recorded for the icon with `hash` came from
+    pub fn set_icon_source(&mut self, hash: &str, source: IconSource) {
+        self.icon_sources.insert(hash.to_string(), source);
+    }
+
+    /// Where the asset recorded for the icon with `hash` came from, if known
+    pub fn icon_source(&self, hash: &str) -> Option<IconSource> {
+        self.icon_sources.get(hash).copied()
+    }
+
+    /// Asset to reuse for the icon with `hash`. Without `use_cache`, one
+    /// that came from the asset cache is uploaded again instead.
+    pub fn reusable_icon(&self, hash: &str, use_cache: bool) -> Option<u64> {
+        let from_cache = self.icon_source(hash) == Some(IconSource::Cache);
+        self.icons.get(hash).copied().filter(|_| use_cache || !from_cache)
+    }
+
+    /// Stop tracking the asset recorded for the icon with `hash`
+    pub fn forget_icon(&mut self, hash: &str) {
+        self.icons.remove(hash);
+        self.icon_sources.remove(hash);
+    }
+
+    /// Remember the asset an icon was uploaded as
+    fn record_icon(&mut self, hash: Option<&str>, asset_id: Option<u64>) {
+        if let (Some(hash), Some(asset_id)) = (hash, asset_id.filter(|id| *id != 0)) {
+            self.icons.insert(hash.to_string(), asset_id);
+        }
     }
 
     /// Find a game pass by name (case-insensitive) and return (id, state)
@@ -88,6 +599,7 @@ impl SyncState {
             .map(|(id, state)| (*id, state))
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn update_game_pass(
         &mut self, 
         id: u64, 
@@ -98,14 +610,21 @@ impl SyncState {
         icon_hash: Option<String>, 
         icon_asset_id: Option<u64>
     ) {
+        self.record_icon(icon_hash.as_deref(), icon_asset_id);
         self.game_passes.insert(id, ResourceState { 
             name, 
             description,
             price,
             is_for_sale,
+            is_active: None,
             is_enabled: None,
+            regional_pricing_enabled: None,
             icon_hash, 
-            icon_asset_id 
+            icon_moderation_state: None,
+            icon_moderation_checked: None,
+            last_synced: None,
+            remote_updated: None,
+            kept_remote: Default::default(),
         });
     }
     
@@ -116,23 +635,32 @@ impl SyncState {
             .map(|(id, state)| (*id, state))
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn update_developer_product(
         &mut self, 
         id: u64, 
         name: String, 
         description: Option<String>,
         price: Option<u64>,
+        is_active: Option<bool>,
         icon_hash: Option<String>, 
         icon_asset_id: Option<u64>
     ) {
+        self.record_icon(icon_hash.as_deref(), icon_asset_id);
         self.developer_products.insert(id, ResourceState { 
             name, 
             description,
             price,
             is_for_sale: None,
+            is_active,
             is_enabled: None,
+            regional_pricing_enabled: None,
             icon_hash, 
-            icon_asset_id 
+            icon_moderation_state: None,
+            icon_moderation_checked: None,
+            last_synced: None,
+            remote_updated: None,
+            kept_remote: Default::default(),
         });
     }
 
@@ -143,23 +671,30 @@ impl SyncState {
             .map(|(id, state)| (*id, state))
     }
 
+    /// Badge icons are uploaded with the badge itself, so they have no asset
+    /// ID to share
     pub fn update_badge(
         &mut self, 
         id: u64, 
         name: String, 
         description: Option<String>,
         is_enabled: Option<bool>,
-        icon_hash: Option<String>, 
-        icon_asset_id: Option<u64>
+        icon_hash: Option<String>
     ) {
         self.badges.insert(id, ResourceState { 
             name, 
             description,
             price: None,
             is_for_sale: None,
+            is_active: None,
             is_enabled,
+            regional_pricing_enabled: None,
             icon_hash, 
-            icon_asset_id 
+            icon_moderation_state: None,
+            icon_moderation_checked: None,
+            last_synced: None,
+            remote_updated: None,
+            kept_remote: Default::default(),
         });
     }
 
@@ -179,7 +714,278 @@ impl SyncState {
             playable_devices,
             max_players,
             private_server_cost,
+            settings: self.universe.take().map(|u| u.settings).unwrap_or_default(),
         });
     }
 }
 
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(suffix);
+    path.into()
+}
+
+/// Write `content` over `path` so a crash never leaves it half-written: it
+/// goes to a temp file that's renamed over the old file once it's on disk.
+/// The old content is kept as `<path>.bak`. Unchanged content isn't
+/// rewritten, so the backup keeps the last real change.
+pub(crate) fn replace_file(path: &Path, content: &str) -> Result<()> {
+    let previous = fs::read_to_string(path).ok();
+    if previous.as_deref() == Some(content) {
+        return Ok(());
+    }
+    let tmp = with_suffix(path, ".tmp");
+    let _ = fs::remove_file(&tmp);
+    let mut file = crate::security::private_file().write(true).create_new(true).open(&tmp)?;
+    file.write_all(content.as_bytes())?;
+    file.sync_all()?;
+    drop(file);
+    if let Some(previous) = previous {
+        let backup = with_suffix(path, ".bak");
+        crate::security::private_file().write(true).create(true).truncate(true).open(&backup)?.write_all(previous.as_bytes())?;
+        // Keep permissions restricted with `chmod` (see security checks)
+        let permissions = fs::metadata(path)?.permissions();
+        fs::set_permissions(&backup, permissions.clone())?;
+        fs::set_permissions(&tmp, permissions)?;
+    }
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// Upgrade a lock file document from layout `version` to `STATE_VERSION`,
+/// one version at a time
+fn migrate(doc: &mut serde_yaml::Value, version: u32) -> Result<()> {
+    for from in version..STATE_VERSION {
+        match from {
+            0 => {
+                for section in ["game_passes", "developer_products", "badges"] {
+                    if let Some(entries) = doc.get_mut(section) {
+                        rekey_by_id(section, entries)?;
+                    }
+                }
+            }
+            1 => collect_icon_asset_ids(doc),
+            _ => unreachable!("no migration from lock file layout version {}", from),
+        }
+    }
+    if let Some(doc) = doc.as_mapping_mut() {
+        doc.insert("version".into(), STATE_VERSION.into());
+    }
+    Ok(())
+}
+
+/// Version 1 files kept each resource's icon asset ID next to its hash, and
+/// shared ones under `uploaded_icons`; version 2 keeps a single `icons` map
+/// from hash to asset ID
+fn collect_icon_asset_ids(doc: &mut serde_yaml::Value) {
+    let Some(doc) = doc.as_mapping_mut() else { return };
+    let mut icons = match doc.remove("uploaded_icons") {
+        Some(serde_yaml::Value::Mapping(icons)) => icons,
+        _ => serde_yaml::Mapping::new(),
+    };
+    for section in ["game_passes", "developer_products", "badges"] {
+        let Some(entries) = doc.get_mut(section).and_then(|e| e.as_mapping_mut()) else { continue };
+        for entry in entries.values_mut().filter_map(|e| e.as_mapping_mut()) {
+            let asset_id = entry.remove("icon_asset_id");
+            if let (Some(hash), Some(asset_id)) = (entry.get("icon_hash").cloned(), asset_id) {
+                icons.entry(hash).or_insert(asset_id);
+            }
+        }
+    }
+    if !icons.is_empty() {
+        doc.insert("icons".into(), icons.into());
+    }
+}
+
+/// Version 0 files written by older releases keyed resources by name with
+/// the ID inside each entry; version 1 keys them by ID with the name inside
+fn rekey_by_id(section: &str, entries: &mut serde_yaml::Value) -> Result<()> {
+    let Some(map) = entries.as_mapping_mut() else { return Ok(()) };
+    let mut rekeyed = serde_yaml::Mapping::new();
+    for (key, mut entry) in std::mem::take(map) {
+        let numeric = key.as_u64().or_else(|| key.as_str().and_then(|k| k.parse().ok()));
+        let key = match numeric {
+            Some(id) => serde_yaml::Value::from(id),
+            None => {
+                let name = key.as_str().map(|k| k.to_string()).unwrap_or_else(|| format!("{:?}", key));
+                let id = entry
+                    .as_mapping_mut()
+                    .and_then(|e| e.remove("id"))
+                    .and_then(|id| id.as_u64())
+                    .ok_or_else(|| anyhow!("can't migrate {} entry '{}': it has no numeric `id`. Run `rblxsync state refresh` to rebuild the lock file", section, name))?;
+                if let Some(e) = entry.as_mapping_mut() {
+                    e.entry("name".into()).or_insert(name.into());
+                }
+                serde_yaml::Value::from(id)
+            }
+        };
+        if rekeyed.contains_key(&key) {
+            bail!("can't migrate {}: ID {:?} appears more than once", section, key);
+        }
+        rekeyed.insert(key, entry);
+    }
+    *map = rekeyed;
+    Ok(())
+}
+
+/// `rblxsync-promote.<from>-<to>.yml`: lock state for resources `promote`
+/// mirrored into the target universe, and the source resource each mirrors
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct PromotionState {
+    pub from: u64,
+    pub to: u64,
+    /// Source ID -> target ID, per resource type (e.g. `game_pass`)
+    #[serde(default)]
+    pub ids: BTreeMap<String, BTreeMap<u64, u64>>,
+    /// Lock state of the mirrored resources in the target universe
+    #[serde(default)]
+    pub target: SyncState,
+}
+
+impl PromotionState {
+    pub fn load(project_root: &Path, from: u64, to: u64) -> Result<Self> {
+        let path = Self::path(project_root, from, to);
+        if !path.exists() {
+            return Ok(Self { from, to, ..Self::default() });
+        }
+        Ok(serde_yaml::from_str(&fs::read_to_string(&path)?)?)
+    }
+
+    pub fn save(&self, project_root: &Path) -> Result<PathBuf> {
+        let path = Self::path(project_root, self.from, self.to);
+        fs::write(&path, serde_yaml::to_string(self)?)?;
+        Ok(path)
+    }
+
+    pub fn path(project_root: &Path, from: u64, to: u64) -> PathBuf {
+        project_root.join(format!("rblxsync-promote.{}-{}.yml", from, to))
+    }
+
+    /// Target ID mirroring source resource `source_id`
+    pub fn target_id(&self, kind: EntryKind, source_id: u64) -> Option<u64> {
+        self.ids.get(kind.resource_type())?.get(&source_id).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_entry_commands() {
+        let mut state = SyncState::default();
+        state.update_game_pass(1, "VIP".to_string(), None, Some(100), None, None, None);
+        state.update_badge(2, "VIP".to_string(), None, Some(true), None);
+        state.update_game_pass(3, "Gold".to_string(), None, None, None, None, None);
+
+        assert_eq!(state.entries().len(), 3);
+        assert!(state.show(EntryKind::GamePass, "1").unwrap().contains("price: 100"));
+
+        // Ambiguous without a kind, and renames can't collide
+        assert!(state.rename(None, "vip", "Premium").is_err());
+        assert!(state.rename(Some(EntryKind::GamePass), "vip", "gold").is_err());
+        assert_eq!(state.rename(Some(EntryKind::GamePass), "vip", "Premium").unwrap(), EntryKind::GamePass);
+        assert_eq!(state.game_passes[&1].name, "Premium");
+
+        assert_eq!(state.remove(EntryKind::Badge, "VIP").unwrap(), "VIP");
+        assert!(state.badges.is_empty());
+        assert!(state.remove(EntryKind::Badge, "VIP").is_err());
+    }
+
+    #[test]
+    fn test_reusable_icon() {
+        let mut state = SyncState::default();
+        state.icons.insert("own".to_string(), 1);
+        state.set_icon_source("own", IconSource::Uploaded);
+        state.icons.insert("cached".to_string(), 2);
+        state.set_icon_source("cached", IconSource::Cache);
+        assert_eq!(state.reusable_icon("own", false), Some(1));
+        assert_eq!(state.reusable_icon("cached", true), Some(2));
+        assert_eq!(state.reusable_icon("cached", false), None);
+        assert_eq!(state.reusable_icon("missing", true), None);
+    }
+
+    #[test]
+    fn test_queue_pending_round_trip() {
+        let mut state = SyncState::default();
+        state.queue_pending(EntryKind::GamePass, 1, "VIP", PendingCheck::Icon { asset_id: 10 });
+        state.queue_pending(EntryKind::GamePass, 1, "VIP", PendingCheck::Text { expected_name: "VIP".to_string(), expected_description: None });
+        // A newer upload replaces the older icon check
+        state.queue_pending(EntryKind::GamePass, 1, "VIP", PendingCheck::Icon { asset_id: 11 });
+        assert_eq!(state.pending.len(), 2);
+
+        let yaml = serde_yaml::to_string(&state).unwrap();
+        assert!(yaml.contains("check: icon"));
+        assert!(yaml.contains("kind: game-pass"));
+        let loaded: SyncState = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(loaded.pending, state.pending);
+        assert!(loaded.pending.iter().any(|p| p.check == PendingCheck::Icon { asset_id: 11 }));
+    }
+
+    #[test]
+    fn test_state_migration() {
+        let legacy = "game_passes:\n  VIP:\n    id: 42\n    price: 100\nbadges:\n  7:\n    name: Winner\n";
+        let state = SyncState::parse(legacy).unwrap();
+        assert_eq!(state.version, StateVersion(STATE_VERSION));
+        assert_eq!(state.game_passes[&42].name, "VIP");
+        assert_eq!(state.game_passes[&42].price, Some(100));
+        assert_eq!(state.badges[&7].name, "Winner");
+        assert!(serde_yaml::to_string(&state).unwrap().starts_with("version: 2\n"));
+
+        let v1 = "version: 1\ngame_passes:\n  42:\n    name: VIP\n    icon_hash: abc\n    icon_asset_id: 9\nuploaded_icons:\n  def: 10\n";
+        let state = SyncState::parse(v1).unwrap();
+        assert_eq!(state.icons, HashMap::from([("abc".to_string(), 9), ("def".to_string(), 10)]));
+        assert_eq!(state.icon_asset_id(&state.game_passes[&42]), Some(9));
+
+        assert!(SyncState::parse("game_passes:\n  VIP:\n    price: 100\n").unwrap_err().to_string().contains("no numeric `id`"));
+        assert!(SyncState::parse("version: 99\n").unwrap_err().to_string().contains("upgrade rblxsync"));
+        let err = SyncState::parse("version: 2\nbadges:\n  7:\n    name: Winner\n    shiny: true\n").unwrap_err().to_string();
+        assert!(err.contains("badges.7.shiny"), "{}", err);
+        assert!(SyncState::parse("").unwrap().game_passes.is_empty());
+    }
+
+    #[test]
+    fn test_save_backup_and_rollback() {
+        let dir = std::env::temp_dir().join(format!("rblxsync-rollback-{}", std::process::id()));
+        let mut state = SyncState::default();
+        assert!(SyncState::rollback(&dir).is_err());
+        state.update_badge(1, "Winner".to_string(), None, Some(true), None);
+        state.save(&dir).unwrap();
+        assert!(!SyncState::backup_path(&dir).exists());
+
+        state.update_badge(2, "Veteran".to_string(), None, Some(true), None);
+        state.save(&dir).unwrap();
+        // Saving unchanged state keeps the backup of the last real change
+        state.save(&dir).unwrap();
+        assert_eq!(SyncState::rollback(&dir).unwrap().badges.len(), 1);
+        assert_eq!(SyncState::load(&dir).unwrap().badges.len(), 1);
+        assert_eq!(SyncState::rollback(&dir).unwrap().badges.len(), 2);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_recent_badge_creations() {
+        let now = 1_000_000;
+        let mut state = SyncState { badge_creations: vec![now - 25 * 60 * 60, now - 60, now], ..Default::default() };
+        assert_eq!(state.recent_badge_creations(now), 2);
+        assert_eq!(state.badge_creations, vec![now - 60, now]);
+    }
+
+    #[test]
+    fn test_promotion_state_round_trip() {
+        let dir = std::env::temp_dir().join(format!("rblxsync-promote-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let mut promotion = PromotionState::load(&dir, 1, 2).unwrap();
+        assert_eq!((promotion.from, promotion.to), (1, 2));
+        promotion.ids.entry("game_pass".to_string()).or_default().insert(10, 20);
+        promotion.target.update_badge(30, "Winner".to_string(), None, Some(true), None);
+        let path = promotion.save(&dir).unwrap();
+        assert!(path.ends_with("rblxsync-promote.1-2.yml"));
+
+        let loaded = PromotionState::load(&dir, 1, 2).unwrap();
+        assert_eq!(loaded.target_id(EntryKind::GamePass, 10), Some(20));
+        assert_eq!(loaded.target_id(EntryKind::Badge, 10), None);
+        assert_eq!(loaded.target.badges[&30].name, "Winner");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,177 @@
+//! OpenTelemetry export, behind the `otel` feature. When
+//! `OTEL_EXPORTER_OTLP_ENDPOINT` is set, every command sends its trace (the
+//! command, resource and HTTP call spans, turned into OpenTelemetry spans by
+//! [`layer`] at every log level) and the process's metrics to an OTLP/HTTP
+//! collector as JSON, through `opentelemetry-otlp`:
+//!
+//! - `rblxsync.command.duration`: command duration in seconds, by command
+//!   and outcome
+//! - `http.client.request.duration`: Open Cloud latency in seconds, by
+//!   method, endpoint (the path with IDs replaced by `{id}`) and status
+//! - `rblxsync.http.retries`: requests sent again, by reason
+//!
+//! Export happens once the command finishes; a collector that can't be
+//! reached is logged and otherwise ignored. The exporters read the standard
+//! `OTEL_EXPORTER_OTLP_*` variables (endpoints, headers, timeout) themselves.
+
+use crate::report::RunReport;
+use opentelemetry::metrics::{Counter, Histogram, MeterProvider as _};
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::{MetricExporter, Protocol, SpanExporter, WithExportConfig};
+use opentelemetry_sdk::metrics::{PeriodicReader, SdkMeterProvider};
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use opentelemetry_sdk::Resource;
+use std::sync::OnceLock;
+use std::time::Duration;
+use tracing_subscriber::{Layer, Registry};
+
+/// Upper bounds of the latency histograms' buckets, in seconds (the
+/// OpenTelemetry HTTP semantic conventions' defaults)
+const BUCKETS: &[f64] = &[0.005, 0.01, 0.025, 0.05, 0.075, 0.1, 0.25, 0.5, 0.75, 1.0, 2.5, 5.0, 7.5, 10.0];
+
+/// Providers exporting to the collector, and the instruments recording into them
+struct Telemetry {
+    tracer: SdkTracerProvider,
+    meter: SdkMeterProvider,
+    commands: Histogram<f64>,
+    requests: Histogram<f64>,
+    retries: Counter<u64>,
+}
+
+impl Telemetry {
+    fn from_env() -> Option<Self> {
+        if !enabled(|name| std::env::var(name).ok()) {
+            return None;
+        }
+        match Self::build() {
+            Ok(telemetry) => Some(telemetry),
+            Err(e) => {
+                tracing::warn!("OpenTelemetry export is off: {}", e);
+                None
+            }
+        }
+    }
+
+    fn build() -> anyhow::Result<Self> {
+        let service_name = std::env::var("OTEL_SERVICE_NAME").ok().filter(|v| !v.trim().is_empty()).unwrap_or_else(|| "rblxsync".to_string());
+        let resource = Resource::builder().with_service_name(service_name).with_attribute(KeyValue::new("service.version", env!("CARGO_PKG_VERSION"))).build();
+
+        let spans = SpanExporter::builder().with_http().with_protocol(Protocol::HttpJson).build()?;
+        let tracer = SdkTracerProvider::builder().with_batch_exporter(spans).with_resource(resource.clone()).build();
+        let metrics = MetricExporter::builder().with_http().with_protocol(Protocol::HttpJson).build()?;
+        let meter = SdkMeterProvider::builder().with_reader(PeriodicReader::builder(metrics).build()).with_resource(resource).build();
+
+        let instruments = meter.meter("rblxsync");
+        Ok(Self {
+            commands: instruments
+                .f64_histogram("rblxsync.command.duration")
+                .with_description("Duration of rblxsync commands")
+                .with_unit("s")
+                .with_boundaries(BUCKETS.to_vec())
+                .build(),
+            requests: instruments
+                .f64_histogram("http.client.request.duration")
+                .with_description("Duration of Open Cloud requests")
+                .with_unit("s")
+                .with_boundaries(BUCKETS.to_vec())
+                .build(),
+            retries: instruments.u64_counter("rblxsync.http.retries").with_description("Requests sent again").with_unit("{retry}").build(),
+            tracer,
+            meter,
+        })
+    }
+}
+
+/// Whether the `OTEL_*` variables read by `var` ask for export: an endpoint
+/// for both signals, and the SDK not disabled
+fn enabled(var: impl Fn(&str) -> Option<String>) -> bool {
+    let var = |name: &str| var(name).filter(|v| !v.trim().is_empty());
+    if var("OTEL_SDK_DISABLED").is_some_and(|v| v.eq_ignore_ascii_case("true")) {
+        return false;
+    }
+    var("OTEL_EXPORTER_OTLP_ENDPOINT").is_some()
+        || (var("OTEL_EXPORTER_OTLP_TRACES_ENDPOINT").is_some() && var("OTEL_EXPORTER_OTLP_METRICS_ENDPOINT").is_some())
+}
+
+fn telemetry() -> Option<&'static Telemetry> {
+    static TELEMETRY: OnceLock<Option<Telemetry>> = OnceLock::new();
+    TELEMETRY.get_or_init(Telemetry::from_env).as_ref()
+}
+
+/// Whether telemetry is exported
+pub fn active() -> bool {
+    telemetry().is_some()
+}
+
+/// Layer turning every span, whatever the log level, into an OpenTelemetry span
+pub(crate) fn layer() -> Box<dyn Layer<Registry> + Send + Sync> {
+    let tracer = telemetry().map(|t| t.tracer.tracer("rblxsync"));
+    Box::new(tracer.map(|tracer| tracing_opentelemetry::layer().with_tracer(tracer)))
+}
+
+/// Record the latency of one Open Cloud request
+pub fn record_request(method: &reqwest::Method, url: &reqwest::Url, status: u16, elapsed: Duration) {
+    let Some(telemetry) = telemetry() else { return };
+    let attributes = [
+        KeyValue::new("http.request.method", method.as_str().to_string()),
+        KeyValue::new("http.response.status_code", i64::from(status)),
+        KeyValue::new("server.address", url.host_str().unwrap_or_default().to_string()),
+        KeyValue::new("url.template", endpoint(url.path())),
+    ];
+    telemetry.requests.record(elapsed.as_secs_f64(), &attributes);
+}
+
+/// Count a request that had to be sent again, e.g. for a new CSRF token
+pub fn count_retry(reason: &str) {
+    if let Some(telemetry) = telemetry() {
+        telemetry.retries.add(1, &[KeyValue::new("reason", reason.to_string())]);
+    }
+}
+
+/// `path` with numeric segments replaced by `{id}`, to group requests by endpoint
+fn endpoint(path: &str) -> String {
+    path.split('/').map(|segment| if !segment.is_empty() && segment.bytes().all(|b| b.is_ascii_digit()) { "{id}" } else { segment }).collect::<Vec<_>>().join("/")
+}
+
+/// Record `report`'s command and send everything collected so far
+pub async fn export(report: &RunReport) {
+    let Some(telemetry) = telemetry() else {
+        return;
+    };
+    let attributes = [
+        KeyValue::new("command", report.command.clone()),
+        KeyValue::new("dry_run", report.dry_run),
+        KeyValue::new("success", report.success),
+    ];
+    telemetry.commands.record(report.duration_ms as f64 / 1000.0, &attributes);
+
+    // The exporters block on their HTTP client, so flush off the runtime
+    let flushed = tokio::task::spawn_blocking(|| (telemetry.tracer.force_flush(), telemetry.meter.force_flush())).await;
+    match flushed {
+        Ok((Ok(()), Ok(()))) => tracing::debug!("Exported traces and metrics"),
+        Ok((traces, metrics)) => {
+            for e in [traces.err(), metrics.err()].into_iter().flatten() {
+                tracing::warn!("Failed to export telemetry: {}", e);
+            }
+        }
+        Err(e) => tracing::warn!("Failed to export telemetry: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_enabled_and_endpoint() {
+        let env = |vars: &'static [(&'static str, &'static str)]| move |name: &str| vars.iter().find(|(n, _)| *n == name).map(|(_, v)| v.to_string());
+        assert!(!enabled(env(&[])));
+        assert!(enabled(env(&[("OTEL_EXPORTER_OTLP_ENDPOINT", "http://collector:4318")])));
+        assert!(!enabled(env(&[("OTEL_EXPORTER_OTLP_ENDPOINT", "http://collector:4318"), ("OTEL_SDK_DISABLED", "TRUE")])));
+        assert!(!enabled(env(&[("OTEL_EXPORTER_OTLP_TRACES_ENDPOINT", "http://collector:4318/v1/traces")])));
+        assert!(enabled(env(&[("OTEL_EXPORTER_OTLP_TRACES_ENDPOINT", "http://c/v1/traces"), ("OTEL_EXPORTER_OTLP_METRICS_ENDPOINT", "http://c/v1/metrics")])));
+
+        assert_eq!(endpoint("/game-passes/v1/universes/123/game-passes/45"), "/game-passes/v1/universes/{id}/game-passes/{id}");
+    }
+}
@@ -0,0 +1,150 @@
+//! Template variable substitution for descriptions.
+//!
+//! Descriptions may reference `{{variable}}` placeholders which are resolved
+//! at sync time. Built-in variables are `version` (from `git describe` in the project root),
+//! `date` (UTC, `YYYY-MM-DD`), `universe.id` and `universe.name`; anything
+//! else comes from the top-level `variables` map in `rblxsync.yml`.
+
+use crate::config::RblxSyncConfig;
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Build the variable table used to render templates for `config`, whose
+/// project lives in `root`.
+pub fn variables(config: &RblxSyncConfig, root: &Path) -> HashMap<String, String> {
+    let mut vars = HashMap::new();
+    vars.insert("date".to_string(), today());
+    vars.insert("universe.id".to_string(), config.universe.id.to_string());
+    if let Some(name) = &config.universe.name {
+        vars.insert("universe.name".to_string(), name.clone());
+    }
+    if let Some(version) = git_version(root) {
+        vars.insert("version".to_string(), version);
+    }
+    // User-defined variables take precedence over built-ins
+    for (key, value) in &config.variables {
+        vars.insert(key.clone(), value.clone());
+    }
+    vars
+}
+
+/// Render every templated description in the config.
+pub fn render_config(mut config: RblxSyncConfig, root: &Path) -> Result<RblxSyncConfig> {
+    let vars = variables(&config, root);
+
+    if let Some(desc) = &config.universe.description {
+        config.universe.description = Some(render(desc, &vars)?);
+    }
+    for pass in &mut config.game_passes {
+        if let Some(desc) = &pass.description {
+            pass.description = Some(render(desc, &vars)?);
+        }
+    }
+    for prod in &mut config.developer_products {
+        if let Some(desc) = &prod.description {
+            prod.description = Some(render(desc, &vars)?);
+        }
+    }
+    for badge in &mut config.badges {
+        if let Some(desc) = &badge.description {
+            badge.description = Some(render(desc, &vars)?);
+        }
+    }
+
+    Ok(config)
+}
+
+/// Replace `{{name}}` placeholders in `input`. Unknown variables are an error.
+pub fn render(input: &str, vars: &HashMap<String, String>) -> Result<String> {
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after
+            .find("}}")
+            .ok_or_else(|| anyhow!("Unclosed template variable in: {}", input))?;
+        let name = after[..end].trim();
+        let value = vars
+            .get(name)
+            .ok_or_else(|| anyhow!("Unknown template variable '{{{{{}}}}}' (define it under `variables`)", name))?;
+        output.push_str(value);
+        rest = &after[end + 2..];
+    }
+    output.push_str(rest);
+
+    Ok(output)
+}
+
+/// Latest git tag (or short commit hash) of the project in `root`, if any
+fn git_version(root: &Path) -> Option<String> {
+    // A bare config file name has an empty parent
+    let dir = if root.as_os_str().is_empty() { Path::new(".") } else { root };
+    let output = Command::new("git")
+        .args(["describe", "--tags", "--always"])
+        .current_dir(dir)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if version.is_empty() { None } else { Some(version) }
+}
+
+/// Today's UTC date as `YYYY-MM-DD`
+fn today() -> String {
+    let secs = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    let (y, m, d) = civil_from_days((secs / 86_400) as i64);
+    format!("{:04}-{:02}-{:02}", y, m, d)
+}
+
+/// Format a Unix timestamp as `YYYY-MM-DD HH:MM UTC`
+pub fn format_timestamp(secs: u64) -> String {
+    let (y, m, d) = civil_from_days((secs / 86_400) as i64);
+    let minutes = (secs % 86_400) / 60;
+    format!("{:04}-{:02}-{:02} {:02}:{:02} UTC", y, m, d, minutes / 60, minutes % 60)
+}
+
+/// Convert days since the Unix epoch to a (year, month, day) civil date
+pub(crate) fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let y = yoe + era * 400 + if m <= 2 { 1 } else { 0 };
+    (y, m, d)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let mut vars = HashMap::new();
+        vars.insert("season".to_string(), "Winter".to_string());
+        vars.insert("universe.name".to_string(), "My Game".to_string());
+        assert_eq!(
+            render("Updated for the {{season}} event in {{ universe.name }}!", &vars).unwrap(),
+            "Updated for the Winter event in My Game!"
+        );
+        assert_eq!(render("No variables", &vars).unwrap(), "No variables");
+        assert!(render("{{missing}}", &vars).is_err());
+        assert!(render("{{season", &vars).is_err());
+    }
+
+    #[test]
+    fn test_civil_from_days() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(19_782), (2024, 2, 29));
+    }
+}
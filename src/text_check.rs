@@ -0,0 +1,105 @@
+//! Pre-check of names and descriptions for text Roblox's filter is likely to
+//! replace with `#`.
+//!
+//! Roblox offers no text filtering API for API keys, so this is a local
+//! heuristic covering the usual causes: links, contact details, long numbers
+//! and mentions of off-platform services, plus any `text_check.blocked_words`.
+//! Filtered text is otherwise only noticed after the sync, when it reads back
+//! as `####` (see `verify_pending`).
+
+use crate::config::{TextCheckConfig, TextCheckLevel};
+use anyhow::{anyhow, Result};
+use tracing::warn;
+use regex::Regex;
+
+/// A name or description about to be created or changed
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedText<'a> {
+    pub resource_type: &'static str,
+    pub name: &'a str,
+    /// `name` or `description`
+    pub field: &'static str,
+    pub text: &'a str,
+}
+
+/// Patterns the filter removes, with what they look like to a reader
+const PATTERNS: [(&str, &str); 6] = [
+    (r"(?i)\b[\w.+-]+@[\w-]+\.[a-z]{2,}\b", "an email address"),
+    (r"(?i)\bhttps?://|\bwww\.|\b[a-z0-9-]+\.(com|net|org|gg|io|xyz|me|ly|tv|co)\b", "a link"),
+    (r"(?:^|\s)@\w{2,}", "a social media handle"),
+    (r"(?i)\b(discord|twitter|instagram|tiktok|snapchat|youtube|twitch|whatsapp|telegram)\b", "an off-platform service"),
+    (r"\d{3}[\s.-]\d{3}[\s.-]\d{4}", "a phone number"),
+    (r"\d{7,}", "a long number"),
+];
+
+/// Why `text` is likely to be filtered; empty when it looks fine
+pub fn problems(text: &str, blocked_words: &[String]) -> Vec<String> {
+    let mut problems: Vec<String> = PATTERNS
+        .iter()
+        .filter(|(pattern, _)| Regex::new(pattern).is_ok_and(|re| re.is_match(text)))
+        .map(|(_, reason)| reason.to_string())
+        .collect();
+    let lower = text.to_lowercase();
+    let words: Vec<&str> = lower.split(|c: char| !c.is_alphanumeric()).collect();
+    for blocked in blocked_words {
+        if words.contains(&blocked.to_lowercase().as_str()) {
+            problems.push(format!("the blocked word '{}'", blocked));
+        }
+    }
+    problems
+}
+
+/// Warn about, or with `level: error` fail on, planned text that's likely to
+/// be filtered
+pub fn check(config: Option<&TextCheckConfig>, planned: &[PlannedText]) -> Result<()> {
+    let default = TextCheckConfig::default();
+    let config = config.unwrap_or(&default);
+    if config.level == TextCheckLevel::Off {
+        return Ok(());
+    }
+    let findings: Vec<String> = planned
+        .iter()
+        .filter_map(|t| {
+            let problems = problems(t.text, &config.blocked_words);
+            (!problems.is_empty()).then(|| format!("{} '{}' {} contains {}", t.resource_type, t.name, t.field, problems.join(" and ")))
+        })
+        .collect();
+    if findings.is_empty() {
+        return Ok(());
+    }
+    match config.level {
+        TextCheckLevel::Error => Err(anyhow!(
+            "Text likely to be filtered by Roblox (set text_check.level to warn to sync anyway):\n  {}",
+            findings.join("\n  ")
+        )),
+        _ => {
+            for finding in &findings {
+                warn!("Text may be filtered by Roblox: {}", finding);
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_problems() {
+        assert!(problems("Double coins forever!", &[]).is_empty());
+        assert!(problems("Costs 1000000 coins... just kidding, 2024 edition", &[]).contains(&"a long number".to_string()));
+        assert_eq!(problems("Join our discord.gg/abc", &[]), ["a link", "an off-platform service"]);
+        assert_eq!(problems("Mail dev@example.com", &[]), ["an email address", "a link"]);
+        assert_eq!(problems("Follow @studio", &[]), ["a social media handle"]);
+        assert_eq!(problems("Call 555-123-4567", &[]), ["a phone number"]);
+        assert_eq!(problems("Super Noob Pack", &["noob".to_string()]), ["the blocked word 'noob'"]);
+        assert!(problems("Noobish", &["noob".to_string()]).is_empty());
+
+        let planned = [PlannedText { resource_type: "badge", name: "Link", field: "description", text: "www.example.com" }];
+        assert!(check(None, &planned).is_ok());
+        let strict = TextCheckConfig { level: TextCheckLevel::Error, blocked_words: Vec::new() };
+        let err = check(Some(&strict), &planned).unwrap_err().to_string();
+        assert!(err.contains("badge 'Link' description contains a link"), "{}", err);
+    }
+}
@@ -0,0 +1,110 @@
+//! File watching for `rblxsync watch`.
+//!
+//! The config file and `assets_dir` are watched; bursts of events (editors
+//! often write a file several times when saving) are collapsed into one
+//! change once the files have been quiet for the debounce period. Files
+//! rblxsync writes itself, such as the lock file, are outside the watched
+//! set, so a sync never triggers the next one.
+
+use anyhow::{Context, Result};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+
+/// Which changed paths should trigger a sync
+#[derive(Debug, Clone)]
+pub struct ChangeFilter {
+    config: PathBuf,
+    assets_dir: PathBuf,
+}
+
+impl ChangeFilter {
+    /// Paths are made absolute so they compare with the watcher's events
+    pub fn new(config: &Path, assets_dir: &Path) -> Self {
+        Self { config: absolute(config), assets_dir: absolute(assets_dir) }
+    }
+
+    pub fn matches(&self, path: &Path) -> bool {
+        path == self.config || path.starts_with(&self.assets_dir)
+    }
+}
+
+fn absolute(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| std::env::current_dir().map(|cwd| cwd.join(path)).unwrap_or_else(|_| path.to_path_buf()))
+}
+
+/// Watches the config and assets directory, queueing their events
+pub struct ChangeWatcher {
+    // Dropping the watcher stops the events
+    _watcher: RecommendedWatcher,
+    events: UnboundedReceiver<notify::Result<Event>>,
+    filter: ChangeFilter,
+}
+
+impl ChangeWatcher {
+    pub fn new(filter: ChangeFilter) -> Result<Self> {
+        let (tx, events) = mpsc::unbounded_channel();
+        let mut watcher = notify::recommended_watcher(move |event| {
+            let _ = tx.send(event);
+        })
+        .context("Failed to start the file watcher")?;
+        // Editors often save by replacing the file, so watch its directory
+        let config_dir = filter.config.parent().unwrap_or(Path::new("/"));
+        watcher
+            .watch(config_dir, RecursiveMode::NonRecursive)
+            .with_context(|| format!("Failed to watch {}", config_dir.display()))?;
+        if filter.assets_dir.is_dir() {
+            watcher
+                .watch(&filter.assets_dir, RecursiveMode::Recursive)
+                .with_context(|| format!("Failed to watch {}", filter.assets_dir.display()))?;
+        }
+        Ok(Self { _watcher: watcher, events, filter })
+    }
+
+    /// Wait for a relevant change, then until nothing has changed for
+    /// `debounce`, returning every path changed meanwhile
+    pub async fn next_change(&mut self, debounce: Duration) -> Result<Vec<PathBuf>> {
+        let mut changed = BTreeSet::new();
+        while changed.is_empty() {
+            let event = self.events.recv().await.context("File watcher stopped")?;
+            self.collect(event, &mut changed);
+        }
+        while let Ok(Some(event)) = tokio::time::timeout(debounce, self.events.recv()).await {
+            self.collect(event, &mut changed);
+        }
+        Ok(changed.into_iter().collect())
+    }
+
+    fn collect(&self, event: notify::Result<Event>, changed: &mut BTreeSet<PathBuf>) {
+        match event {
+            Ok(event) if !matches!(event.kind, EventKind::Access(_)) => {
+                changed.extend(event.paths.into_iter().filter(|p| self.filter.matches(p)));
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!("File watcher error: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_change_filter() {
+        let root = std::env::temp_dir().join(format!("rblxsync-watch-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("assets/icons")).unwrap();
+        std::fs::write(root.join("rblxsync.yml"), "").unwrap();
+        let filter = ChangeFilter::new(&root.join("rblxsync.yml"), &root.join("assets"));
+
+        let root = root.canonicalize().unwrap();
+        assert!(filter.matches(&root.join("rblxsync.yml")));
+        assert!(filter.matches(&root.join("assets/icons/vip.png")));
+        assert!(!filter.matches(&root.join("rblxsync-lock.yml")));
+        assert!(!filter.matches(&root.join(".rblxsync/last-run.json")));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
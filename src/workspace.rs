@@ -0,0 +1,123 @@
+//! Monorepo workspaces: one repository holding a project config per
+//! experience.
+//!
+//! `rblxsync-workspace.yml`, found in the current or a parent directory,
+//! lists the projects. `--all` or `--project <name>` runs a command once per
+//! project, each with its own config, lock file and `.rblxsync/` directory.
+
+use crate::config::CONFIG_FILE_NAMES;
+use anyhow::{anyhow, bail, Context, Result};
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Workspace file name
+pub const WORKSPACE_FILE: &str = "rblxsync-workspace.yml";
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Project {
+    pub name: String,
+    /// Project directory, or its config file, relative to the workspace file
+    pub path: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct WorkspaceFile {
+    projects: Vec<Project>,
+}
+
+#[derive(Debug)]
+pub struct Workspace {
+    /// Directory holding the workspace file
+    pub root: PathBuf,
+    pub projects: Vec<Project>,
+}
+
+impl Workspace {
+    /// Load the workspace file in `start` or the nearest parent directory
+    pub fn discover(start: &Path) -> Result<Option<Self>> {
+        match start.ancestors().map(|dir| dir.join(WORKSPACE_FILE)).find(|path| path.is_file()) {
+            Some(path) => Self::load(&path).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let root = path.parent().unwrap_or(Path::new("")).to_path_buf();
+        Self::parse(&content, root).with_context(|| format!("Invalid workspace file {}", path.display()))
+    }
+
+    fn parse(content: &str, root: PathBuf) -> Result<Self> {
+        let file: WorkspaceFile = serde_yaml::from_str(content)?;
+        if file.projects.is_empty() {
+            bail!("projects is empty");
+        }
+        for (i, project) in file.projects.iter().enumerate() {
+            if project.name.is_empty() || !project.name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+                bail!("Invalid project name '{}': use letters, digits, '-' and '_'", project.name);
+            }
+            if file.projects[..i].iter().any(|p| p.name == project.name) {
+                bail!("Project '{}' is listed twice", project.name);
+            }
+        }
+        Ok(Self { root, projects: file.projects })
+    }
+
+    /// The projects named in `names`, in workspace order, or every project
+    /// when `names` is empty
+    pub fn select(&self, names: &[String]) -> Result<Vec<&Project>> {
+        if let Some(unknown) = names.iter().find(|name| !self.projects.iter().any(|p| &p.name == *name)) {
+            let known: Vec<&str> = self.projects.iter().map(|p| p.name.as_str()).collect();
+            return Err(anyhow!("Unknown project '{}'; the workspace lists: {}", unknown, known.join(", ")));
+        }
+        Ok(self.projects.iter().filter(|p| names.is_empty() || names.contains(&p.name)).collect())
+    }
+
+    /// Config file of `project`: its `path`, or the config inside that
+    /// directory
+    pub fn config_path(&self, project: &Project) -> Result<PathBuf> {
+        let path = self.root.join(&project.path);
+        if !path.is_dir() {
+            return Ok(path);
+        }
+        CONFIG_FILE_NAMES
+            .iter()
+            .map(|name| path.join(name))
+            .find(|config| config.is_file())
+            .ok_or_else(|| anyhow!("Project '{}' has no rblxsync config in {}", project.name, path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_workspace() {
+        let root = std::env::temp_dir().join(format!("rblxsync-workspace-{}", std::process::id()));
+        fs::create_dir_all(root.join("games/obby")).unwrap();
+        fs::write(root.join("games/obby/rblxsync.toml"), "").unwrap();
+        fs::write(
+            root.join(WORKSPACE_FILE),
+            "projects:\n  - { name: obby, path: games/obby }\n  - { name: tycoon, path: games/tycoon.yml }\n",
+        )
+        .unwrap();
+
+        let workspace = Workspace::discover(&root.join("games/obby")).unwrap().unwrap();
+        assert_eq!(workspace.root, root);
+        let all: Vec<&str> = workspace.select(&[]).unwrap().iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(all, vec!["obby", "tycoon"]);
+        let obby = workspace.select(&["obby".to_string()]).unwrap();
+        assert_eq!(workspace.config_path(obby[0]).unwrap(), root.join("games/obby/rblxsync.toml"));
+        assert_eq!(workspace.config_path(&workspace.projects[1]).unwrap(), root.join("games/tycoon.yml"));
+        assert!(workspace.select(&["racing".to_string()]).unwrap_err().to_string().contains("obby, tycoon"));
+
+        assert!(Workspace::parse("projects: [{ name: a, path: x }, { name: a, path: y }]", PathBuf::new()).is_err());
+        assert!(Workspace::parse("projects: []", PathBuf::new()).is_err());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}